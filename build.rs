@@ -0,0 +1,9 @@
+// The gRPC service is generated from proto/analysis.proto, protox compiles it so protoc isn't needed
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/analysis.proto");
+        let descriptors = protox::compile(["proto/analysis.proto"], ["proto"]).expect("proto/analysis.proto is invalid");
+        tonic_build::configure().build_client(false).compile_fds(descriptors).expect("unable to generate the gRPC service");
+    }
+}
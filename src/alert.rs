@@ -0,0 +1,129 @@
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::follow::{FollowEvent, FollowEventKind};
+use crate::report::Report;
+
+// The window --alert-rate counts allocations in, and the least time between two size alerts of a file
+const WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy)]
+enum Breach {
+    // Allocations within a minute
+    Rate(u64),
+    // Bytes of a single allocation
+    Size(u64),
+}
+
+#[derive(Default)]
+struct FileState {
+    recent: VecDeque<Instant>,
+    // Set while the rate stays above the threshold, so a storm is reported once rather than per allocation
+    rate_breached: bool,
+    last_size_alert: Option<Instant>,
+}
+
+// POSTs a JSON description of each breach of the --alert-rate or --alert-size thresholds to --alert-webhook,
+// a failed post is logged and the alert dropped
+pub struct Alerter {
+    url: String,
+    slack: bool,
+    rate: Option<u64>,
+    size: Option<u64>,
+    files: HashMap<String, FileState>,
+}
+
+impl Alerter {
+    pub fn new(url: &str, slack: bool, rate: Option<u64>, size: Option<u64>) -> Alerter {
+        Alerter { url: url.to_string(), slack, rate, size, files: HashMap::new() }
+    }
+
+    // While following, the rate is of the allocations read in the last minute
+    pub fn observe(&mut self, event: &FollowEvent) {
+        let FollowEventKind::HumongousAllocation(bytes) = event.kind else { return };
+        let now = Instant::now();
+        let state = self.files.entry(event.file.clone()).or_default();
+        let mut breaches = Vec::new();
+        if let Some(rate) = self.rate {
+            state.recent.push_back(now);
+            while state.recent.front().is_some_and(|x| now.duration_since(*x) > WINDOW) {
+                state.recent.pop_front();
+            }
+            let breached = state.recent.len() as u64 > rate;
+            if breached && !state.rate_breached {
+                breaches.push((Breach::Rate(rate), state.recent.len() as u64));
+            }
+            state.rate_breached = breached;
+        }
+        if let Some(size) = self.size.filter(|x| bytes >= *x) {
+            if state.last_size_alert.is_none_or(|x| now.duration_since(x) >= WINDOW) {
+                state.last_size_alert = Some(now);
+                breaches.push((Breach::Size(size), bytes));
+            }
+        }
+        for (breach, value) in breaches {
+            self.send(&event.file, breach, value);
+        }
+    }
+
+    // A new file of --watch, by its busiest minute of jvm uptime and its largest allocation
+    pub fn check_reports(&self, reports: &[Report]) {
+        for file in reports.iter().flat_map(|x| &x.retained) {
+            if let Some(rate) = self.rate {
+                let mut uptimes: Vec<f64> = file.allocations.iter().filter_map(|x| x.uptime).collect();
+                uptimes.sort_by(f64::total_cmp);
+                let mut start = 0;
+                let mut busiest = 0;
+                for end in 0..uptimes.len() {
+                    while uptimes[end] - uptimes[start] > WINDOW.as_secs_f64() {
+                        start += 1;
+                    }
+                    busiest = busiest.max(end - start + 1);
+                }
+                if busiest as u64 > rate {
+                    self.send(&file.name, Breach::Rate(rate), busiest as u64);
+                }
+            }
+            if let Some(size) = self.size {
+                if let Some(largest) = file.allocations.iter().map(|x| x.bytes).max().filter(|x| *x >= size) {
+                    self.send(&file.name, Breach::Size(size), largest);
+                }
+            }
+        }
+    }
+
+    fn payload(&self, file: &str, breach: Breach, value: u64) -> Value {
+        let (kind, threshold, message) = match breach {
+            Breach::Rate(rate) => ("rate", rate, format!("{} humongous allocations within a minute in {}, above the threshold of {}", value, file, rate)),
+            Breach::Size(size) => ("size", size, format!("Humongous allocation of {} bytes in {}, at or above the threshold of {} bytes", value, file, size)),
+        };
+        match self.slack {
+            true => json!({ "text": format!(":warning: {}", message) }),
+            false => json!({ "alert": kind, "file": file, "value": value, "threshold": threshold, "message": message }),
+        }
+    }
+
+    fn send(&self, file: &str, breach: Breach, value: u64) {
+        let payload = self.payload(file, breach, value);
+        log::info!("Alert: {}", payload);
+        if let Err(e) = self.post(&payload) {
+            log::warn!("Unable to send the alert to {}: {}", self.url, e);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn post(&self, payload: &Value) -> Result<(), String> {
+        ureq::post(&self.url)
+            .timeout(Duration::from_secs(10))
+            .set("Content-Type", "application/json")
+            .send_string(&payload.to_string())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn post(&self, _: &Value) -> Result<(), String> {
+        Err("webhooks are not available in the WebAssembly build".to_string())
+    }
+}
@@ -0,0 +1,87 @@
+use crate::bins::human_size;
+use crate::heap_occupancy::parse_decimal_size;
+use crate::numbers::grouped;
+use crate::AllocationTime;
+
+// Humongous share of the allocated bytes below which the region size hardly matters, and above which the
+// humongous allocations drive the old generation
+const MINOR_SHARE_PERCENT: f64 = 1.0;
+const MAJOR_SHARE_PERCENT: f64 = 10.0;
+
+// Eden used when a young collection started, all of it allocated since the previous one
+pub struct EdenSample {
+    pub uptime: f64,
+    pub bytes: u64,
+}
+
+// JDK 8:  "   [Eden: 400.0M(408.0M)->0.0B(400.0M) Survivors: 8192.0K->16.0M Heap: 5000.0M(8192.0M)->4700.0M(8192.0M)]"
+// JDK 9+: "[10.289s][info][gc,heap] GC(5) Eden regions: 25->0(24)"
+// Like the heap occupancy, the JDK 8 line belongs to the pause logged just before it
+pub fn parse_eden(line: &str, uptime: Option<f64>, region_size: u64) -> Option<EdenSample> {
+    let bytes = if let Some((_, eden)) = line.split_once("[Eden: ") {
+        parse_decimal_size(eden.split('(').next()?)?
+    } else {
+        let (_, regions) = line.split_once(" Eden regions: ")?;
+        regions.split("->").next()?.trim().parse::<u64>().ok()? * region_size
+    };
+    Some(EdenSample { uptime: uptime?, bytes })
+}
+
+// How much of everything the application allocated went to humongous objects, from the eden consumed between
+// young collections. Only the span from the first to the last young collection of each file is measured.
+#[derive(Default)]
+pub struct AllocationRate {
+    pub collections: u64,
+    pub eden_bytes: u64,
+    pub humongous_bytes: u64,
+    pub seconds: f64,
+}
+
+impl AllocationRate {
+    pub fn record(&mut self, samples: &[EdenSample], allocations: &[AllocationTime]) {
+        let (Some(first), Some(last)) = (samples.first(), samples.last()) else { return };
+        if last.uptime <= first.uptime {
+            return;
+        }
+        // The first collection's eden was allocated before the span
+        self.collections += samples.len() as u64 - 1;
+        self.eden_bytes += samples[1..].iter().map(|x| x.bytes).sum::<u64>();
+        self.humongous_bytes += allocations.iter().filter(|x| x.uptime > first.uptime && x.uptime <= last.uptime).map(|x| x.bytes).sum::<u64>();
+        self.seconds += last.uptime - first.uptime;
+    }
+
+    // Of the bytes allocated in eden and as humongous objects
+    pub fn humongous_percentage(&self) -> f64 {
+        self.humongous_bytes as f64 * 100.0 / (self.eden_bytes + self.humongous_bytes).max(1) as f64
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let rate = |bytes: u64| format!("{}/s", human_size((bytes as f64 / self.seconds) as u64));
+        vec![
+            ("young collections", grouped(&self.collections)),
+            ("measured", format!("{:.0}s", self.seconds)),
+            ("allocated in eden", human_size(self.eden_bytes)),
+            ("allocated as humongous objects", human_size(self.humongous_bytes)),
+            ("total allocation rate", rate(self.eden_bytes + self.humongous_bytes)),
+            ("humongous allocation rate", rate(self.humongous_bytes)),
+            ("humongous share of allocated bytes", format!("{:.2}%", self.humongous_percentage())),
+        ]
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        let percentage = self.humongous_percentage();
+        if percentage < MINOR_SHARE_PERCENT {
+            Some(format!(
+                "Only {:.2}% of the allocated bytes are humongous: the young generation allocation drives the collections, a larger region size would hardly change the GC behavior",
+                percentage
+            ))
+        } else if percentage >= MAJOR_SHARE_PERCENT {
+            Some(format!(
+                "{:.1}% of the allocated bytes are humongous and bypass the young generation straight into the old one: a larger region size or smaller arrays at the allocation sites are the main lever",
+                percentage
+            ))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::report::Report;
+
+// FNV-1a, unlike the std hashers its output is the same in every run and release, so a file keeps its
+// label across reports
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, x| (hash ^ *x as u64).wrapping_mul(0x100000001b3))
+}
+
+// "gc-log-1f2e3d4c", which says nothing of the host or directory the log came from
+pub fn label(file: &str) -> String {
+    format!("gc-log-{:08x}", fnv1a(file.as_bytes()) >> 32)
+}
+
+// Only numbers, sizes and enabled / disabled are kept, anything else given to a flag could be a path or a name
+fn is_safe_value(value: &str) -> bool {
+    matches!(value, "enabled" | "disabled") || (value.starts_with(|x: char| x.is_ascii_digit()) && value.chars().all(|x| x.is_ascii_alphanumeric() || x == '.'))
+}
+
+// Replaces the file names and --input labels of the reports with their labels and drops the flag values that aren't plain
+// numbers, the counts and sizes are left as they are
+pub fn scrub(reports: &mut [Report]) {
+    let relabel = |name: &mut String| *name = label(name);
+    for report in reports {
+        for summary in &mut report.file_summaries {
+            // A title naming the file, with --group-by file
+            if report.title.as_ref() == Some(&summary.file) {
+                report.title = Some(label(&summary.file));
+            }
+            if let Some(error) = &mut summary.error {
+                *error.path_mut() = label(&error.path_mut().display().to_string()).into();
+            }
+            summary.jvm_configuration.retain(|(_, value)| is_safe_value(value));
+            relabel(&mut summary.file);
+            summary.label.iter_mut().for_each(relabel);
+        }
+        report.full_gc_rows.iter_mut().for_each(|x| relabel(&mut x.file));
+        report.heap_occupancy_rows.iter_mut().for_each(|x| relabel(&mut x.file));
+        report.safepoint_rows.iter_mut().for_each(|x| relabel(&mut x.file));
+        report.burst_rows.iter_mut().for_each(|x| relabel(&mut x.file));
+        report.timeline_rows.iter_mut().for_each(|x| relabel(&mut x.file));
+        report.retained.iter_mut().for_each(|x| {
+            relabel(&mut x.name);
+            x.label.iter_mut().for_each(relabel);
+        });
+    }
+}
@@ -0,0 +1,199 @@
+use flate2::read::GzDecoder;
+use glob::Pattern;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::throttle::Throttled;
+use crate::{encoding, remote};
+
+// Gc logs inside an archive are addressed as if the archive was a directory, e.g. bundle.zip/logs/gc.log.0
+
+thread_local! {
+    // Gc logs handed over as text rather than read from disk, e.g. by the WebAssembly build
+    static IN_MEMORY: RefCell<HashMap<PathBuf, Arc<[u8]>>> = RefCell::new(HashMap::new());
+}
+
+pub fn register(path: &Path, contents: Arc<[u8]>) {
+    IN_MEMORY.with(|x| x.borrow_mut().insert(path.to_path_buf(), contents));
+}
+
+pub fn unregister(path: &Path) {
+    IN_MEMORY.with(|x| x.borrow_mut().remove(path));
+}
+
+pub fn is_in_memory(path: &Path) -> bool {
+    IN_MEMORY.with(|x| x.borrow().contains_key(path))
+}
+
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+#[derive(Clone, Copy)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else {
+        None
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    archive_kind(path).is_some()
+}
+
+fn tar_archive(path: &Path, kind: ArchiveKind) -> io::Result<tar::Archive<Box<dyn Read>>> {
+    let file = Throttled::new(File::open(path)?);
+    let reader: Box<dyn Read> = match kind {
+        ArchiveKind::TarGz => Box::new(GzDecoder::new(file)),
+        _ => Box::new(file),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+// Tarballs created with `tar -C dir .` prefix every member with ./
+fn entry_name<R: Read>(entry: &tar::Entry<R>) -> io::Result<String> {
+    let path = entry.path()?;
+    Ok(path.strip_prefix("./").unwrap_or(&path).to_string_lossy().into_owned())
+}
+
+fn entries(archive: &Path, kind: ArchiveKind) -> io::Result<Vec<String>> {
+    match kind {
+        ArchiveKind::Zip => {
+            let archive = zip::ZipArchive::new(File::open(archive)?)?;
+            Ok(archive.file_names().filter(|x| !x.ends_with('/')).map(|x| x.to_string()).collect())
+        }
+        ArchiveKind::Tar | ArchiveKind::TarGz => {
+            let mut names = Vec::new();
+            for entry in tar_archive(archive, kind)?.entries()? {
+                let entry = entry?;
+                if entry.header().entry_type().is_file() {
+                    names.push(entry_name(&entry)?);
+                }
+            }
+            Ok(names)
+        }
+    }
+}
+
+// Replaces every archive with the gc logs inside of it whose path matches the pattern
+pub fn expand(files: &[PathBuf], pattern: &Pattern) -> Vec<PathBuf> {
+    let mut expanded = Vec::new();
+    for file in files {
+        let Some(kind) = archive_kind(file) else {
+            expanded.push(file.clone());
+            continue;
+        };
+        match entries(file, kind) {
+            Ok(mut entries) => {
+                entries.retain(|x| pattern.matches(x));
+                entries.sort();
+                if entries.is_empty() {
+                    log::warn!("{}: no entries match {}", file.display(), pattern);
+                }
+                log::debug!("{}: analyzing {}", file.display(), entries.join(", "));
+                expanded.extend(entries.iter().map(|x| file.join(x)));
+            }
+            Err(e) => log::error!("Skipping {}: unable to read the archive: {}", file.display(), e),
+        }
+    }
+    expanded
+}
+
+// Archive members are decompressed into memory, since the gc log is read more than once. Tarballs
+// are streamed through until the member is found, without extracting anything else.
+fn read_entry(archive: &Path, kind: ArchiveKind, name: &str) -> io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    match kind {
+        ArchiveKind::Zip => {
+            let mut archive = zip::ZipArchive::new(Throttled::new(File::open(archive)?))?;
+            archive.by_name(name)?.read_to_end(&mut contents)?;
+        }
+        ArchiveKind::Tar | ArchiveKind::TarGz => {
+            let mut tarball = tar_archive(archive, kind)?;
+            let mut entries = tarball.entries()?;
+            let mut entry = loop {
+                match entries.next() {
+                    Some(entry) => {
+                        let entry = entry?;
+                        if entry_name(&entry)? == name {
+                            break entry;
+                        }
+                    }
+                    None => return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} is not in {}", name, archive.display()))),
+                }
+            };
+            entry.read_to_end(&mut contents)?;
+        }
+    }
+    Ok(contents)
+}
+
+// A remote gc log read as it's downloaded, with a size of 0 when it isn't known up front. UTF-16 logs are
+// transcoded into memory and registered like local ones.
+pub fn open_stream(path: &Path, mut stream: remote::Stream) -> io::Result<(Box<dyn ReadSeek>, u64)> {
+    let Some(utf16) = encoding::detect(stream.head()) else {
+        let len = stream.len.unwrap_or(0);
+        return Ok((Box::new(stream), len));
+    };
+    log::debug!("{}: transcoding from UTF-16 ({:?})", path.display(), utf16);
+    let mut contents = Vec::new();
+    stream.read_to_end(&mut contents)?;
+    let contents: Arc<[u8]> = Arc::from(encoding::to_utf8(&contents, utf16));
+    register(path, contents.clone());
+    let len = contents.len() as u64;
+    Ok((Box::new(Cursor::new(contents)), len))
+}
+
+// Opens a gc log along with its size, either a regular file, a member of an archive, a registered in-memory log
+// or a URL, which is requested again each time it's opened
+pub fn open(path: &Path) -> io::Result<(Box<dyn ReadSeek>, u64)> {
+    if let Some(contents) = IN_MEMORY.with(|x| x.borrow().get(path).cloned()) {
+        let len = contents.len() as u64;
+        return Ok((Box::new(Cursor::new(contents)), len));
+    }
+    if remote::is_remote(path) {
+        return open_stream(path, remote::open(path)?);
+    }
+    for archive in path.ancestors().skip(1) {
+        if let Some(kind) = archive_kind(archive).filter(|_| archive.is_file()) {
+            let entry = path.strip_prefix(archive).unwrap();
+            let entry: Vec<_> = entry.iter().map(|x| x.to_string_lossy()).collect();
+            let contents = encoding::transcode(read_entry(archive, kind, &entry.join("/"))?);
+            let len = contents.len() as u64;
+            return Ok((Box::new(Cursor::new(contents)), len));
+        }
+    }
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut file = Throttled::new(file);
+    // UTF-16 logs are transcoded into memory once, later opens find them registered
+    let mut head = Vec::with_capacity(encoding::HEAD_LEN);
+    (&mut file).take(encoding::HEAD_LEN as u64).read_to_end(&mut head)?;
+    if let Some(utf16) = encoding::detect(&head) {
+        log::debug!("{}: transcoding from UTF-16 ({:?})", path.display(), utf16);
+        let mut contents = head;
+        file.read_to_end(&mut contents)?;
+        let contents: Arc<[u8]> = Arc::from(encoding::to_utf8(&contents, utf16));
+        register(path, contents.clone());
+        let len = contents.len() as u64;
+        return Ok((Box::new(Cursor::new(contents)), len));
+    }
+    file.rewind()?;
+    Ok((Box::new(file), len))
+}
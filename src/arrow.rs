@@ -0,0 +1,15 @@
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::ArrowError;
+use std::fs::File;
+use std::path::Path;
+
+use crate::columnar;
+use crate::report::Report;
+
+// The same allocations table as the Parquet export, uncompressed so notebooks can memory map it
+pub fn export(path: &Path, reports: &[Report]) -> Result<(), ArrowError> {
+    let batch = columnar::allocations(reports)?;
+    let mut writer = FileWriter::try_new(File::create(path)?, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()
+}
@@ -0,0 +1,86 @@
+use cli_table::format::Justify;
+use std::hint::black_box;
+use std::io::{self, Read, Write};
+use std::num::ParseIntError;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::numbers::grouped;
+use crate::report::{table_style, write_rows, Record};
+use crate::Cli;
+
+// Custom patterns are meant to match lines the built in parsers don't, so their count isn't compared
+const PATTERN_PARSER: &str = "with --pattern";
+
+type LineParser<'a> = Box<dyn Fn(&str) -> Option<Result<u64, ParseIntError>> + 'a>;
+
+pub struct BenchRow {
+    pub parser: &'static str,
+    pub allocations: usize,
+    pub best: String,
+    pub mean: String,
+    pub lines_per_second: String,
+    pub throughput: String,
+}
+
+impl Record for BenchRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Parser", Justify::Left),
+        ("Allocations", Justify::Right),
+        ("Best", Justify::Right),
+        ("Mean", Justify::Right),
+        ("Lines/s", Justify::Right),
+        ("MB/s", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.parser.to_string(), grouped(&self.allocations), self.best.clone(), self.mean.clone(), self.lines_per_second.clone(), self.throughput.clone()]
+    }
+}
+
+// Times every humongous allocation line parser over the lines of the file. The file is read into
+// memory first so only the parsing is measured, not the disk or the rest of the analysis.
+pub fn run(file: &Path, iterations: u32, args: &Cli, out: &mut dyn Write) -> io::Result<()> {
+    let mut contents = Vec::new();
+    crate::archive::open(file)?.0.read_to_end(&mut contents)?;
+    let text = String::from_utf8_lossy(&contents);
+    let lines: Vec<&str> = text.lines().collect();
+
+    let mut parsers: Vec<(&'static str, LineParser)> = vec![
+        ("string parsing (default)", Box::new(crate::parse_humongous_object_allocation)),
+        ("regex find", Box::new(crate::parse_humongous_object_allocation_with_regex_find)),
+        ("regex captures", Box::new(crate::parse_humongous_object_allocation_with_regex_captures)),
+    ];
+    if !args.pattern.is_empty() {
+        parsers.push((PATTERN_PARSER, Box::new(|line| crate::parse_allocation(line, &args.pattern))));
+    }
+
+    let mut rows: Vec<BenchRow> = Vec::new();
+    for (name, parser) in &parsers {
+        let mut times = Vec::new();
+        let mut allocations = 0;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            allocations = lines.iter().filter(|line| matches!(black_box(parser(line)), Some(Ok(_)))).count();
+            times.push(start.elapsed());
+        }
+        let best = times.iter().min().copied().unwrap_or_default();
+        let mean = times.iter().sum::<Duration>() / iterations;
+        if let Some(default) = rows.first().filter(|x| x.allocations != allocations && *name != PATTERN_PARSER) {
+            log::warn!("{} found {} allocations, {} found {}", name, allocations, default.parser, default.allocations);
+        }
+        rows.push(BenchRow {
+            parser: name,
+            allocations,
+            best: format!("{:.1?}", best),
+            mean: format!("{:.1?}", mean),
+            lines_per_second: format!("{:.0}", lines.len() as f64 / best.as_secs_f64()),
+            throughput: format!("{:.1}", text.len() as f64 / (1024.0 * 1024.0) / best.as_secs_f64()),
+        });
+    }
+
+    writeln!(out, "{}: {} lines, {:.1}MB, best of {} runs", file.display(), lines.len(), text.len() as f64 / (1024.0 * 1024.0), iterations)?;
+    let style = table_style(args);
+    write_rows(out, &rows, style)?;
+    out.flush()
+}
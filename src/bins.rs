@@ -0,0 +1,98 @@
+use cli_table::format::Justify;
+
+use crate::buckets::MB;
+use crate::numbers::grouped;
+use crate::report::Record;
+
+pub struct SizeBinRow {
+    pub range: String,
+    pub allocations: u64,
+    pub percentage: String,
+}
+
+impl Record for SizeBinRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Allocation Size", Justify::Left),
+        ("Number of Allocations", Justify::Right),
+        ("% of Allocations", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.range.clone(), grouped(&self.allocations), self.percentage.clone()]
+    }
+}
+
+// Fixed width size bins for a finer grained distribution than the region size buckets
+#[derive(Default)]
+pub struct SizeBins {
+    counts: Vec<u64>,
+    // At or above --bins-max
+    overflow: u64,
+}
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes.is_multiple_of(MB) {
+        format!("{}MB", bytes / MB)
+    } else if bytes.is_multiple_of(1024) {
+        format!("{}KB", bytes / 1024)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+// "42.3MB", rounded unlike format_size as allocations are rarely a whole number of KB
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let (mut size, mut unit) = (bytes as f64, 0);
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{}B", bytes),
+        _ => format!("{:.1}{}", size, UNITS[unit]),
+    }
+}
+
+// A size in the text, markdown and html reports, the byte count with --raw-bytes
+pub fn display_size(bytes: u64, raw: bool) -> String {
+    match raw {
+        true => grouped(&bytes),
+        false => human_size(bytes),
+    }
+}
+
+impl SizeBins {
+    pub fn record(&mut self, bytes: u64, width: u64, max: u64) {
+        if bytes >= max {
+            self.overflow += 1;
+            return;
+        }
+        let index = (bytes / width) as usize;
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty() && self.overflow == 0
+    }
+
+    // Every bin up to the last one holding an allocation, followed by the overflow
+    pub fn rows(&self, width: u64, max: u64) -> Vec<SizeBinRow> {
+        let total = (self.counts.iter().sum::<u64>() + self.overflow).max(1) as f64;
+        let row = |range: String, allocations: u64| SizeBinRow { range, allocations, percentage: format!("{:.2}%", allocations as f64 * 100.0 / total) };
+        let mut rows: Vec<SizeBinRow> = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(index, count)| {
+                let start = index as u64 * width;
+                row(format!("{} - {}", format_size(start), format_size((start + width).min(max))), *count)
+            })
+            .collect();
+        rows.push(row(format!(">= {}", format_size(max)), self.overflow));
+        rows
+    }
+}
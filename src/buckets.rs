@@ -0,0 +1,150 @@
+use cli_table::{format::Justify, Cell, CellStruct, Color, Style, Table, TableStruct};
+
+use crate::bins::display_size;
+use crate::numbers::grouped;
+use crate::report::{title_row, Record, Records};
+
+// Region sizes G1 accepts: 1MB..32MB, and up to 512MB since JDK 18
+pub const MB: u64 = 1024 * 1024;
+pub const DEFAULT_REGION_SIZES: [u64; 9] = [2 * MB, 4 * MB, 8 * MB, 16 * MB, 32 * MB, 64 * MB, 128 * MB, 256 * MB, 512 * MB];
+// Buckets up to this size are always shown, larger default buckets only when they hold allocations
+const ALWAYS_SHOWN_REGION_SIZE: u64 = 32 * MB;
+
+// Anything at or below half of the smallest G1 region size (1MB) can never be humongous
+pub const MIN_HUMONGOUS_ALLOCATION: u64 = 524288;
+
+pub struct G1RegionBucket {
+    pub region_size: String,
+    pub max_size: u64,
+    pub num_allocations: u32,
+    pub percentage: String,
+    pub cumulative_percentage: String,
+}
+
+pub struct G1RegionBucketRow {
+    pub region_size: String,
+    pub max_size: String,
+    pub num_allocations: u32,
+    pub percentage: String,
+    pub cumulative_percentage: String,
+}
+
+impl Record for G1RegionBucketRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Region Size", Justify::Right),
+        ("Max Allocation Size (50%)", Justify::Left),
+        ("Number of Allocations", Justify::Left),
+        ("% of Allocations", Justify::Right),
+        ("Cumulative %", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.region_size.clone(), self.max_size.clone(), grouped(&self.num_allocations), self.percentage.clone(), self.cumulative_percentage.clone()]
+    }
+}
+
+// The buckets as printed in the text, markdown and html reports
+pub fn rows(buckets: &[G1RegionBucket], raw_bytes: bool) -> Vec<G1RegionBucketRow> {
+    buckets
+        .iter()
+        .map(|x| G1RegionBucketRow {
+            region_size: x.region_size.clone(),
+            max_size: display_size(x.max_size, raw_bytes),
+            num_allocations: x.num_allocations,
+            percentage: x.percentage.clone(),
+            cumulative_percentage: x.cumulative_percentage.clone(),
+        })
+        .collect()
+}
+
+// The text table of the buckets, those with more than highlight_count allocations in red and the one of the
+// recommended region size in green
+pub fn table(buckets: &[G1RegionBucket], raw_bytes: bool, highlight_count: Option<u32>, recommended: Option<u64>) -> (TableStruct, Records) {
+    let recommended = recommended.map(format_region_size);
+    let colors = buckets.iter().map(|x| {
+        if highlight_count.is_some_and(|count| x.num_allocations > count) {
+            Some(Color::Red)
+        } else if recommended.as_ref() == Some(&x.region_size) {
+            Some(Color::Green)
+        } else {
+            None
+        }
+    });
+    let records = Records::of(&rows(buckets, raw_bytes));
+    let cells: Vec<Vec<CellStruct>> = records
+        .rows
+        .iter()
+        .zip(colors)
+        .map(|(row, color)| row.iter().zip(G1RegionBucketRow::COLUMNS).map(|(cell, column)| cell.cell().justify(column.1).foreground_color(color)).collect())
+        .collect();
+    (cells.table().title(title_row(G1RegionBucketRow::COLUMNS)), records)
+}
+
+pub fn format_region_size(bytes: u64) -> String {
+    if bytes.is_multiple_of(MB) {
+        format!("{}MB", bytes / MB)
+    } else {
+        format!("{}KB", bytes / 1024)
+    }
+}
+
+// One bucket per candidate region size, counting the allocations that stop being humongous at that
+// region size (i.e. those larger than 50% of the previous region size but no larger than 50% of this one),
+// followed by an Overflow bucket for everything larger than 50% of the biggest region size
+pub fn build_buckets(region_sizes: &[u64]) -> Vec<G1RegionBucket> {
+    region_sizes
+        .iter()
+        .map(|x| G1RegionBucket { region_size: format_region_size(*x), max_size: x / 2, num_allocations: 0, percentage: String::new(), cumulative_percentage: String::new() })
+        .chain(std::iter::once(G1RegionBucket { region_size: "Overflow".to_string(), max_size: u32::MAX as u64, num_allocations: 0, percentage: String::new(), cumulative_percentage: String::new() }))
+        .collect()
+}
+
+// Drops the empty default buckets above 32MB that sit directly below the Overflow bucket, so
+// deployments that never come near large regions keep the familiar 2MB..32MB table
+pub fn trim_large_buckets(buckets: &mut Vec<G1RegionBucket>) {
+    let overflow = buckets.pop().unwrap();
+    while let Some(last) = buckets.last() {
+        if last.num_allocations > 0 || last.max_size * 2 <= ALWAYS_SHOWN_REGION_SIZE {
+            break;
+        }
+        buckets.pop();
+    }
+    buckets.push(overflow);
+}
+
+// Fills in each bucket's share of all allocations and the running total up to and including it
+pub fn compute_percentages(buckets: &mut [G1RegionBucket]) {
+    let total: u32 = buckets.iter().map(|x| x.num_allocations).sum();
+    if total == 0 {
+        return;
+    }
+    let mut cumulative = 0;
+    for bucket in buckets.iter_mut() {
+        cumulative += bucket.num_allocations;
+        bucket.percentage = format!("{:.2}%", bucket.num_allocations as f64 * 100.0 / total as f64);
+        bucket.cumulative_percentage = format!("{:.2}%", cumulative as f64 * 100.0 / total as f64);
+    }
+}
+
+// Returns None for allocations that can never be humongous
+pub fn bucket_index(buckets: &[G1RegionBucket], bytes: u64) -> Option<usize> {
+    if bytes <= MIN_HUMONGOUS_ALLOCATION {
+        return None;
+    }
+    let region_buckets = &buckets[..buckets.len() - 1];
+    Some(region_buckets.partition_point(|x| x.max_size < bytes))
+}
+
+// Bucket edges must be strictly increasing region sizes of at least 1MB
+pub fn validate_region_sizes(region_sizes: &[u64]) -> Result<(), String> {
+    if region_sizes.is_empty() {
+        return Err("at least one bucket is required".to_string());
+    }
+    if region_sizes[0] < MB {
+        return Err(format!("bucket {} is smaller than the minimum region size of 1MB", format_region_size(region_sizes[0])));
+    }
+    match region_sizes.windows(2).find(|x| x[0] >= x[1]) {
+        Some(x) => Err(format!("buckets must be strictly increasing, {} is followed by {}", format_region_size(x[0]), format_region_size(x[1]))),
+        None => Ok(()),
+    }
+}
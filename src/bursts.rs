@@ -0,0 +1,118 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::timestamp::format_wall_clock;
+use crate::AllocationTime;
+
+pub struct BurstRow {
+    pub file: String,
+    pub start: String,
+    pub start_time: String,
+    pub duration: String,
+    pub allocations: u64,
+    pub bytes: u64,
+    pub factor: String,
+}
+
+impl Record for BurstRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Start (s)", Justify::Right),
+        ("Start Time", Justify::Left),
+        ("Duration (s)", Justify::Right),
+        ("Humongous Allocations", Justify::Right),
+        ("Humongous Bytes", Justify::Right),
+        ("x Average", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.file.clone(),
+            self.start.clone(),
+            self.start_time.clone(),
+            self.duration.clone(),
+            grouped(&self.allocations),
+            grouped(&self.bytes),
+            self.factor.clone(),
+        ]
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Window {
+    allocations: u64,
+    bytes: u64,
+}
+
+// Allocations of a single file per `interval` window of uptime, from the first window with an allocation
+// to the last, along with the index of the first
+fn windows(allocations: &[AllocationTime], interval: f64) -> Option<(usize, Vec<Window>)> {
+    let indexes = allocations.iter().map(|x| (x.uptime / interval) as usize);
+    let first = indexes.clone().min()?;
+    let last = indexes.max().unwrap_or(first);
+    let mut windows = vec![Window::default(); last - first + 1];
+    for allocation in allocations {
+        let window = &mut windows[(allocation.uptime / interval) as usize - first];
+        window.allocations += 1;
+        window.bytes += allocation.bytes;
+    }
+    Some((first, windows))
+}
+
+// Runs of consecutive windows whose allocation count exceeds `factor` times the average per window, along
+// with the average
+fn runs(windows: &[Window], factor: f64) -> (f64, Vec<Range<usize>>) {
+    // A burst needs quiet windows around it to stand out from
+    if windows.len() < 3 {
+        return (0.0, Vec::new());
+    }
+    let average = windows.iter().map(|x| x.allocations).sum::<u64>() as f64 / windows.len() as f64;
+    let threshold = average * factor;
+
+    let mut runs = Vec::new();
+    let mut index = 0;
+    while index < windows.len() {
+        if windows[index].allocations as f64 <= threshold {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < windows.len() && windows[index].allocations as f64 > threshold {
+            index += 1;
+        }
+        runs.push(start..index);
+    }
+    (average, runs)
+}
+
+// Runs of consecutive `interval` windows of a single file whose allocation count exceeds `factor` times the
+// average per window over the whole log, e.g. the nightly batch job among an otherwise quiet day. The
+// datestamps are those of the first allocation of each window that has one.
+pub fn detect_bursts(file: &str, allocations: &[AllocationTime], dates: &BTreeMap<u64, i64>, interval: f64, factor: f64) -> Vec<BurstRow> {
+    let Some((first, windows)) = windows(allocations, interval) else { return Vec::new() };
+    let (average, runs) = runs(&windows, factor);
+    runs.into_iter()
+        .map(|run| {
+            let burst = &windows[run.clone()];
+            let allocations = burst.iter().map(|x| x.allocations).sum::<u64>();
+            BurstRow {
+                file: file.to_string(),
+                start: format!("{:.0}", (first + run.start) as f64 * interval),
+                start_time: (first + run.start..first + run.end).find_map(|x| dates.get(&(x as u64))).map_or("-".to_string(), |x| format_wall_clock(*x)),
+                duration: format!("{:.0}", burst.len() as f64 * interval),
+                allocations,
+                bytes: burst.iter().map(|x| x.bytes).sum(),
+                factor: format!("{:.1}", allocations as f64 / burst.len() as f64 / average),
+            }
+        })
+        .collect()
+}
+
+// The indexes of the `interval` windows of uptime that detect_bursts reports as bursts
+pub fn burst_windows(allocations: &[AllocationTime], interval: f64, factor: f64) -> Vec<Range<u64>> {
+    let Some((first, windows)) = windows(allocations, interval) else { return Vec::new() };
+    runs(&windows, factor).1.into_iter().map(|x| (first + x.start) as u64..(first + x.end) as u64).collect()
+}
@@ -0,0 +1,59 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::timestamp::{self, format_wall_clock};
+
+pub struct CalendarRow {
+    pub window_start: String,
+    pub allocations: u64,
+    pub bytes: u64,
+    pub p99: u64,
+}
+
+impl Record for CalendarRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Window Start", Justify::Left),
+        ("Humongous Allocations", Justify::Right),
+        ("Humongous Bytes", Justify::Right),
+        ("p99 Size", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.window_start.clone(), grouped(&self.allocations), grouped(&self.bytes), grouped(&self.p99)]
+    }
+}
+
+// Allocation sizes per calendar aligned window (e.g. each hour of the day) of the datestamps, across all files
+#[derive(Default)]
+pub struct CalendarWindows {
+    windows: BTreeMap<i64, Vec<u64>>,
+    // Allocations whose line had no datestamp (-XX:+PrintGCDateStamps)
+    pub undated: u64,
+}
+
+impl CalendarWindows {
+    pub fn record(&mut self, wall_clock: Option<i64>, interval: u64, bytes: u64) {
+        match wall_clock {
+            Some(seconds) => self.windows.entry(timestamp::window_start(seconds, interval as i64)).or_default().push(bytes),
+            None => self.undated += 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    pub fn rows(&self) -> Vec<CalendarRow> {
+        self.windows
+            .iter()
+            .map(|(start, sizes)| {
+                let mut sizes = sizes.clone();
+                sizes.sort_unstable();
+                let p99 = sizes[((sizes.len() as f64 * 0.99).ceil() as usize).max(1) - 1];
+                CalendarRow { window_start: format_wall_clock(*start), allocations: sizes.len() as u64, bytes: sizes.iter().sum(), p99 }
+            })
+            .collect()
+    }
+}
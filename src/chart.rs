@@ -0,0 +1,92 @@
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::bins::format_size;
+use crate::report::Report;
+
+const PANEL_WIDTH: u32 = 640;
+const PANEL_HEIGHT: u32 = 400;
+
+// Allocation counts by the power of two their size rounds up to, e.g. 8MB for every size above 4MB up to 8MB,
+// from the smallest to the largest with the empty ones in between
+fn size_distribution(report: &Report) -> Vec<(String, u64)> {
+    let mut buckets: BTreeMap<u32, u64> = BTreeMap::new();
+    for allocation in report.retained.iter().flat_map(|x| &x.allocations) {
+        *buckets.entry(allocation.bytes.max(1).next_power_of_two().trailing_zeros()).or_insert(0) += 1;
+    }
+    let (Some(first), Some(last)) = (buckets.keys().next(), buckets.keys().next_back()) else { return Vec::new() };
+    (*first..=*last).map(|power| (format!("≤{}", format_size(1 << power)), buckets.get(&power).copied().unwrap_or(0))).collect()
+}
+
+fn draw_distribution<DB: DrawingBackend>(area: &DrawingArea<DB, Shift>, title: &str, report: &Report) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let buckets = size_distribution(report);
+    let max = buckets.iter().map(|x| x.1).max().unwrap_or(0).max(1);
+    let mut chart = ChartBuilder::on(area)
+        .caption(format!("{}Allocation Sizes", title), ("sans-serif", 18))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d((0..buckets.len().saturating_sub(1)).into_segmented(), 0..max + max / 10 + 1)?;
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_desc("Size")
+        .y_desc("Allocations")
+        .x_label_formatter(&|x| match x {
+            SegmentValue::CenterOf(index) => buckets.get(*index).map_or(String::new(), |x| x.0.clone()),
+            _ => String::new(),
+        })
+        .draw()?;
+    chart.draw_series(Histogram::vertical(&chart).style(BLUE.mix(0.6).filled()).margin(4).data(buckets.iter().enumerate().map(|(index, x)| (index, x.1))))?;
+    Ok(())
+}
+
+// A line per file of the allocations per --timeline-interval window
+fn draw_timeline<DB: DrawingBackend>(area: &DrawingArea<DB, Shift>, title: &str, report: &Report, interval: f64) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let mut files: BTreeMap<&str, Vec<(f64, u64)>> = BTreeMap::new();
+    for row in &report.timeline_rows {
+        let start = row.window_start.parse::<f64>().unwrap_or(0.0);
+        files.entry(&row.file).or_default().push((start, row.allocations));
+    }
+    let end = files.values().flatten().map(|x| x.0).fold(0.0, f64::max) + interval;
+    let max = files.values().flatten().map(|x| x.1).max().unwrap_or(0).max(1);
+    let mut chart = ChartBuilder::on(area)
+        .caption(format!("{}Allocations over Time ({}s windows)", title, interval), ("sans-serif", 18))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..end, 0..max + max / 10 + 1)?;
+    chart.configure_mesh().x_desc("Uptime (s)").y_desc("Allocations").draw()?;
+    for (index, (file, points)) in files.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+        chart.draw_series(LineSeries::new(points.iter().copied(), color.stroke_width(2)))?.label(*file).legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    if files.len() > 1 {
+        chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+    }
+    Ok(())
+}
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, reports: &[Report], interval: f64) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    let rows = root.split_evenly((reports.len(), 2));
+    for (report, panels) in reports.iter().zip(rows.chunks(2)) {
+        let title = report.title.as_ref().map_or(String::new(), |x| format!("{}: ", x));
+        draw_distribution(&panels[0], &title, report)?;
+        draw_timeline(&panels[1], &title, report, interval)?;
+    }
+    root.present()
+}
+
+// --chart, the size distribution and the allocations over time of each report side by side, a row per report.
+// A .png path gets a bitmap, anything else an SVG.
+pub fn write_chart(path: &Path, reports: &[Report], interval: f64) -> Result<(), String> {
+    let size = (PANEL_WIDTH * 2, PANEL_HEIGHT * reports.len().max(1) as u32);
+    let result = match path.extension().and_then(|x| x.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("png") => draw(BitMapBackend::new(path, size).into_drawing_area(), reports, interval).map_err(|e| e.to_string()),
+        _ => draw(SVGBackend::new(path, size).into_drawing_area(), reports, interval).map_err(|e| e.to_string()),
+    };
+    result.map_err(|e| format!("Unable to write the chart to {}: {}", path.display(), e))
+}
@@ -0,0 +1,221 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::HumongousAllocation;
+
+// How often the progress of a file is written to the --checkpoint file while it is parsed
+const INTERVAL: Duration = Duration::from_secs(30);
+
+// How far a gc log was parsed and the allocations found up to there
+pub struct FileCheckpoint {
+    pub first_line: String,
+    // End of the last line parsed
+    pub offset: u64,
+    // Number of the last line parsed
+    pub line: usize,
+    pub complete: bool,
+    pub allocations: Vec<HumongousAllocation>,
+}
+
+// The --checkpoint file, a json document keyed by the path of each gc log like the --state file
+#[derive(Default)]
+pub struct Checkpoints {
+    pub files: BTreeMap<String, FileCheckpoint>,
+}
+
+fn allocation_value(allocation: &HumongousAllocation) -> Value {
+    json!([allocation.line, allocation.uptime, allocation.wall_clock, allocation.bytes])
+}
+
+fn parse_allocation(value: &Value) -> Option<HumongousAllocation> {
+    let [line, uptime, wall_clock, bytes] = value.as_array()?.as_slice() else { return None };
+    Some(HumongousAllocation { line: line.as_u64()? as usize, uptime: uptime.as_f64(), wall_clock: wall_clock.as_i64(), bytes: bytes.as_u64()? })
+}
+
+impl Checkpoints {
+    // A checkpoint file that doesn't exist yet has no checkpoints
+    pub fn load(path: &Path) -> Result<Checkpoints, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Checkpoints::default()),
+            Err(e) => return Err(format!("Unable to read {}: {}", path.display(), e)),
+        };
+        let invalid = || format!("Invalid checkpoint file {}", path.display());
+        let document: Value = serde_json::from_str(&text).map_err(|e| format!("{}: {}", invalid(), e))?;
+        let mut checkpoints = Checkpoints::default();
+        for (name, file) in document.get("files").and_then(Value::as_object).ok_or_else(invalid)? {
+            let allocations = file.get("allocations").and_then(Value::as_array).ok_or_else(invalid)?;
+            checkpoints.files.insert(
+                name.clone(),
+                FileCheckpoint {
+                    first_line: file.get("first_line").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    offset: file.get("offset").and_then(Value::as_u64).ok_or_else(invalid)?,
+                    line: file.get("line").and_then(Value::as_u64).ok_or_else(invalid)? as usize,
+                    complete: file.get("complete").and_then(Value::as_bool).unwrap_or(false),
+                    allocations: allocations.iter().map(parse_allocation).collect::<Option<_>>().ok_or_else(invalid)?,
+                },
+            );
+        }
+        Ok(checkpoints)
+    }
+
+    // Written next to the checkpoint file and renamed over it, so an interrupted save leaves the previous one
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let files: Map<String, Value> = self
+            .files
+            .iter()
+            .map(|(name, file)| {
+                let allocations: Vec<Value> = file.allocations.iter().map(allocation_value).collect();
+                (name.clone(), json!({ "first_line": file.first_line, "offset": file.offset, "line": file.line, "complete": file.complete, "allocations": allocations }))
+            })
+            .collect();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_string(&json!({ "files": files }))?)?;
+        fs::rename(&temporary, path)
+    }
+
+    // Where the file was left off, unless it was rotated, replaced or truncated since
+    pub fn resume(&mut self, file: &Path, first_line: &str, len: u64) -> Option<FileCheckpoint> {
+        let checkpoint = self.files.remove(&file.display().to_string())?;
+        if checkpoint.first_line != first_line || checkpoint.offset > len {
+            log::info!("{} changed since the checkpoint, reading it from the start", file.display());
+            return None;
+        }
+        Some(checkpoint)
+    }
+}
+
+// Counts the bytes consumed from the buffer, which at the end of a line is the offset of the next one
+pub struct Counted<R> {
+    inner: R,
+    consumed: Arc<AtomicU64>,
+}
+
+impl<R: BufRead> Counted<R> {
+    pub fn new(inner: R, consumed: Arc<AtomicU64>) -> Counted<R> {
+        Counted { inner, consumed }
+    }
+}
+
+impl<R: BufRead> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.consumed.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Counted<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.consumed.fetch_add(amount as u64, Ordering::Relaxed);
+        self.inner.consume(amount)
+    }
+}
+
+// Saves the progress of the file being parsed every INTERVAL, along with the checkpoints of the files before it
+pub struct Checkpointer {
+    path: PathBuf,
+    checkpoints: Checkpoints,
+    name: String,
+    first_line: String,
+    // Where reading started, the consumed bytes count from there
+    start: u64,
+    consumed: Arc<AtomicU64>,
+    // Bytes consumed up to the end of the last line parsed, the line being parsed has already been read
+    parsed: u64,
+    last_save: Instant,
+}
+
+impl Checkpointer {
+    pub fn new(path: &Path, checkpoints: Checkpoints, file: &Path, first_line: String, start: u64, consumed: Arc<AtomicU64>) -> Checkpointer {
+        Checkpointer { path: path.to_path_buf(), checkpoints, name: file.display().to_string(), first_line, start, consumed, parsed: 0, last_save: Instant::now() }
+    }
+
+    // Called once a line was read and before it is parsed, with the number of the line parsed before it
+    pub fn tick(&mut self, parsed_line: usize, allocations: &[HumongousAllocation]) {
+        if self.last_save.elapsed() >= INTERVAL {
+            self.save(parsed_line, allocations, false);
+        }
+        self.parsed = self.consumed.load(Ordering::Relaxed);
+    }
+
+    // Once the whole file is parsed, so that --resume doesn't read it again
+    pub fn finish(&mut self, last_line: usize, allocations: &[HumongousAllocation]) {
+        self.parsed = self.consumed.load(Ordering::Relaxed);
+        self.save(last_line, allocations, true);
+    }
+
+    fn save(&mut self, line: usize, allocations: &[HumongousAllocation], complete: bool) {
+        let checkpoint = FileCheckpoint {
+            first_line: self.first_line.clone(),
+            offset: self.start + self.parsed,
+            line,
+            complete,
+            allocations: allocations.iter().map(|x| HumongousAllocation { ..*x }).collect(),
+        };
+        self.checkpoints.files.insert(self.name.clone(), checkpoint);
+        match self.checkpoints.save(&self.path) {
+            Ok(()) => log::debug!("{}: checkpoint at line {}", self.name, line),
+            Err(e) => log::warn!("Unable to write the checkpoint {}: {}", self.path.display(), e),
+        }
+        self.last_save = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_and_loads_the_checkpoints() {
+        let path = std::env::temp_dir().join(format!("checkpoint-round-trip-{}.json", std::process::id()));
+        let allocations = vec![
+            HumongousAllocation { line: 12, uptime: Some(1.234), wall_clock: Some(1709288100), bytes: 4194304 },
+            HumongousAllocation { line: 40, uptime: None, wall_clock: None, bytes: 33554432 },
+        ];
+        let mut checkpoints = Checkpoints::default();
+        checkpoints.files.insert("/var/log/gc.log".to_string(), FileCheckpoint { first_line: "Java HotSpot(TM) 64-Bit Server VM".to_string(), offset: 2048, line: 41, complete: false, allocations });
+        checkpoints.save(&path).unwrap();
+        let mut loaded = Checkpoints::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let file = &loaded.files["/var/log/gc.log"];
+        assert_eq!((file.first_line.as_str(), file.offset, file.line, file.complete), ("Java HotSpot(TM) 64-Bit Server VM", 2048, 41, false));
+        let allocations: Vec<_> = file.allocations.iter().map(|x| (x.line, x.uptime, x.wall_clock, x.bytes)).collect();
+        assert_eq!(allocations, [(12, Some(1.234), Some(1709288100), 4194304), (40, None, None, 33554432)]);
+        assert!(loaded.resume(Path::new("/var/log/gc.log"), "Java HotSpot(TM) 64-Bit Server VM", 1024).is_none());
+    }
+
+    #[test]
+    fn resumes_only_an_unchanged_file() {
+        let checkpoint = || FileCheckpoint { first_line: "first".to_string(), offset: 100, line: 3, complete: true, allocations: Vec::new() };
+        let mut checkpoints = Checkpoints { files: BTreeMap::from([("gc.log".to_string(), checkpoint())]) };
+        assert_eq!(checkpoints.resume(Path::new("gc.log"), "first", 100).map(|x| x.offset), Some(100));
+        // Taken by the resume
+        assert!(checkpoints.resume(Path::new("gc.log"), "first", 100).is_none());
+        checkpoints.files.insert("gc.log".to_string(), checkpoint());
+        assert!(checkpoints.resume(Path::new("gc.log"), "rotated", 100).is_none());
+    }
+
+    #[test]
+    fn counts_the_consumed_bytes() {
+        let consumed = Arc::new(AtomicU64::new(0));
+        let mut reader = Counted::new(io::Cursor::new(b"first\nsecond\n".to_vec()), consumed.clone());
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(consumed.load(Ordering::Relaxed), 6);
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(consumed.load(Ordering::Relaxed), 13);
+    }
+}
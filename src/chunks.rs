@@ -0,0 +1,59 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::throttle::Throttled;
+
+// Smaller files aren't worth splitting, the threads would spend more time starting than parsing
+const MIN_CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
+// Byte ranges of up to `jobs` chunks of the file, each starting at the beginning of a line
+pub fn split(path: &Path, len: u64, jobs: usize) -> io::Result<Vec<Range<u64>>> {
+    boundaries(path, len, (len / MIN_CHUNK_SIZE).clamp(1, jobs.max(1) as u64))
+}
+
+// Byte ranges of about `size` bytes each, also starting at the beginning of a line, for --sample
+pub fn blocks(path: &Path, len: u64, size: u64) -> io::Result<Vec<Range<u64>>> {
+    boundaries(path, len, len.div_ceil(size).max(1))
+}
+
+fn boundaries(path: &Path, len: u64, chunks: u64) -> io::Result<Vec<Range<u64>>> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut starts = vec![0];
+    for index in 1..chunks {
+        let approximate = len * index / chunks;
+        if approximate <= *starts.last().unwrap() {
+            continue;
+        }
+        // The line the approximate offset falls into belongs to the previous chunk
+        file.seek(SeekFrom::Start(approximate))?;
+        let start = approximate + file.read_until(b'\n', &mut Vec::new())? as u64;
+        if start < len {
+            starts.push(start);
+        }
+    }
+    let ends = starts.iter().skip(1).copied().chain(std::iter::once(len));
+    Ok(starts.iter().zip(ends).map(|(start, end)| *start..end).collect())
+}
+
+pub fn open(path: &Path, range: &Range<u64>) -> io::Result<io::Take<Throttled<File>>> {
+    let mut file = Throttled::new(File::open(path)?);
+    file.seek(SeekFrom::Start(range.start))?;
+    Ok(file.take(range.end - range.start))
+}
+
+// Every chunk but the last ends with a newline, so this is the number of lines in it
+pub fn count_lines(path: &Path, range: &Range<u64>) -> io::Result<usize> {
+    let mut reader = BufReader::new(open(path, range)?);
+    let mut lines = 0;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(lines);
+        }
+        lines += buffer.iter().filter(|x| **x == b'\n').count();
+        let consumed = buffer.len();
+        reader.consume(consumed);
+    }
+}
@@ -0,0 +1,132 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::bins::display_size;
+use crate::numbers::grouped;
+use crate::object_shape::likely_object;
+use crate::report::Record;
+
+pub struct SizeClusterRow {
+    pub size: String,
+    pub range: String,
+    pub allocations: u64,
+    pub percentage: String,
+    pub bytes: String,
+    pub likely_object: String,
+}
+
+impl Record for SizeClusterRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Most Common Size", Justify::Right),
+        ("Size Range", Justify::Right),
+        ("Number of Allocations", Justify::Right),
+        ("% of Allocations", Justify::Right),
+        ("Humongous Bytes", Justify::Right),
+        ("Likely Object", Justify::Left),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.size.clone(), self.range.clone(), grouped(&self.allocations), self.percentage.clone(), self.bytes.clone(), self.likely_object.clone()]
+    }
+}
+
+pub struct SizeFrequencyRow {
+    pub size: String,
+    pub allocations: u64,
+    pub percentage: String,
+}
+
+impl Record for SizeFrequencyRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Allocation Size (bytes)", Justify::Right),
+        ("Number of Allocations", Justify::Right),
+        ("% of Allocations", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.size.clone(), grouped(&self.allocations), self.percentage.clone()]
+    }
+}
+
+// Count of every exact allocation size, clustered when reported
+#[derive(Default)]
+pub struct SizeClusters {
+    sizes: BTreeMap<u64, u64>,
+}
+
+struct Cluster {
+    min: u64,
+    max: u64,
+    // Most frequent exact size and its count
+    mode: (u64, u64),
+    allocations: u64,
+    bytes: u64,
+}
+
+impl SizeClusters {
+    pub fn record(&mut self, bytes: u64) {
+        *self.sizes.entry(bytes).or_insert(0) += 1;
+    }
+
+    // Every exact size with its count
+    pub fn sizes(&self) -> &BTreeMap<u64, u64> {
+        &self.sizes
+    }
+
+    // Sizes within `tolerance` percent above the smallest size of a cluster join it, 0 only groups identical sizes.
+    // The `top` clusters with the most allocations, i.e. the allocation sites worth hunting down first.
+    pub fn rows(&self, tolerance: f64, top: usize, raw_bytes: bool) -> Vec<SizeClusterRow> {
+        let mut clusters: Vec<Cluster> = Vec::new();
+        for (size, count) in &self.sizes {
+            match clusters.last_mut() {
+                Some(cluster) if *size as f64 <= cluster.min as f64 * (1.0 + tolerance / 100.0) => {
+                    cluster.max = *size;
+                    if *count > cluster.mode.1 {
+                        cluster.mode = (*size, *count);
+                    }
+                    cluster.allocations += count;
+                    cluster.bytes += size * count;
+                }
+                _ => clusters.push(Cluster { min: *size, max: *size, mode: (*size, *count), allocations: *count, bytes: size * count }),
+            }
+        }
+        let total = self.sizes.values().sum::<u64>().max(1) as f64;
+        clusters.sort_by(|a, b| b.allocations.cmp(&a.allocations).then(a.min.cmp(&b.min)));
+        clusters
+            .into_iter()
+            .take(top)
+            .map(|x| SizeClusterRow {
+                size: display_size(x.mode.0, raw_bytes),
+                range: if x.min == x.max { "-".to_string() } else { format!("{} - {}", display_size(x.min, raw_bytes), display_size(x.max, raw_bytes)) },
+                allocations: x.allocations,
+                percentage: format!("{:.2}%", x.allocations as f64 * 100.0 / total),
+                bytes: display_size(x.bytes, raw_bytes),
+                likely_object: likely_object(x.mode.0).unwrap_or_else(|| "-".to_string()),
+            })
+            .collect()
+    }
+
+    // The `top` exact sizes with the most allocations, in bytes as they are what the allocating code asked for
+    pub fn frequencies(&self, top: usize) -> (Vec<(u64, u64)>, (usize, u64)) {
+        let mut sizes: Vec<(u64, u64)> = self.sizes.iter().map(|(size, count)| (*size, *count)).collect();
+        sizes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let rest = sizes.split_off(top.min(sizes.len()));
+        (sizes, (rest.len(), rest.iter().map(|x| x.1).sum()))
+    }
+
+    // The top sizes, then the rest rolled up into "N other sizes"
+    pub fn frequency_rows(&self, top: usize) -> Vec<SizeFrequencyRow> {
+        let total = self.sizes.values().sum::<u64>().max(1) as f64;
+        let percentage = |count: u64| format!("{:.2}%", count as f64 * 100.0 / total);
+        let (sizes, (other_sizes, other_allocations)) = self.frequencies(top);
+        let mut rows: Vec<SizeFrequencyRow> = sizes.into_iter().map(|(size, count)| SizeFrequencyRow { size: grouped(&size), allocations: count, percentage: percentage(count) }).collect();
+        if other_sizes > 0 {
+            rows.push(SizeFrequencyRow { size: format!("{} other sizes", grouped(&other_sizes)), allocations: other_allocations, percentage: percentage(other_allocations) });
+        }
+        rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+}
@@ -0,0 +1,96 @@
+use cli_table::format::Justify;
+
+use crate::numbers::grouped;
+use crate::report::Record;
+
+// HeapWords on a 64-bit JVM
+const HEAP_WORD_SIZE: u64 = 8;
+
+pub struct CmsFailureRow {
+    pub event: &'static str,
+    pub count: u64,
+}
+
+impl Record for CmsFailureRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Event", Justify::Left),
+        ("Count", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.event.to_string(), grouped(&self.count)]
+    }
+}
+
+// CMS' counterpart of humongous allocation trouble: objects that can't be promoted into a fragmented old generation
+#[derive(Default)]
+pub struct CmsFailures {
+    pub promotion_failures: u64,
+    pub concurrent_mode_failures: u64,
+    // Objects whose size was reported with -XX:+PrintPromotionFailure
+    pub sized_objects: u64,
+}
+
+pub struct CmsFailure {
+    pub promotion_failed: bool,
+    pub concurrent_mode_failure: bool,
+    // Bytes of each object that failed promotion, one per ParNew worker that failed
+    pub sizes: Vec<u64>,
+}
+
+// "1234.567: [GC (Allocation Failure) 1234.567: [ParNew (0: promotion failure size = 4)  (1: promotion failure size = 2048)  (promotion failed): 1887488K->1887488K(1887488K), 0.4567 secs]..."
+// "[CMS1234.890: [CMS-concurrent-mark: 2.345/3.456 secs] [Times: user=9.11 sys=0.07, real=3.46 secs]"
+// " (concurrent mode failure): 6291455K->3546745K(6291456K), 9.8765432 secs] ..."
+pub fn parse_cms_failure(line: &str) -> Option<CmsFailure> {
+    let promotion_failed = line.contains("(promotion failed)");
+    let concurrent_mode_failure = line.contains("(concurrent mode failure)");
+    if !promotion_failed && !concurrent_mode_failure {
+        return None;
+    }
+    let sizes = line
+        .split("promotion failure size = ")
+        .skip(1)
+        .filter_map(|x| x.split(')').next()?.trim().parse::<u64>().ok())
+        .map(|x| x * HEAP_WORD_SIZE)
+        .collect();
+    Some(CmsFailure { promotion_failed, concurrent_mode_failure, sizes })
+}
+
+impl CmsFailures {
+    pub fn record(&mut self, failure: &CmsFailure) {
+        self.promotion_failures += failure.promotion_failed as u64;
+        self.concurrent_mode_failures += failure.concurrent_mode_failure as u64;
+        self.sized_objects += failure.sizes.len() as u64;
+    }
+
+    pub fn merge(&mut self, other: CmsFailures) {
+        self.promotion_failures += other.promotion_failures;
+        self.concurrent_mode_failures += other.concurrent_mode_failures;
+        self.sized_objects += other.sized_objects;
+    }
+
+    pub fn rows(&self) -> Vec<CmsFailureRow> {
+        vec![
+            CmsFailureRow { event: "Promotion Failed", count: self.promotion_failures },
+            CmsFailureRow { event: "Concurrent Mode Failure", count: self.concurrent_mode_failures },
+            CmsFailureRow { event: "Sized Promotion Failures", count: self.sized_objects },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_promotion_and_concurrent_mode_failures() {
+        let promotion = "1234.567: [GC (Allocation Failure) 1234.567: [ParNew (0: promotion failure size = 4)  (1: promotion failure size = 2048)  (promotion failed): 1887488K->1887488K(1887488K), 0.4567 secs]1235.024: [CMS: 4194303K->3546745K(4194304K), 9.8765432 secs] 6081791K->3546745K(6081792K), [Metaspace: 98765K->98765K(1140736K)], 10.3332432 secs]";
+        let failure = parse_cms_failure(promotion).unwrap();
+        assert!(failure.promotion_failed && !failure.concurrent_mode_failure);
+        assert_eq!(failure.sizes, [32, 16384]);
+        let concurrent_mode = " (concurrent mode failure): 6291455K->3546745K(6291456K), 9.8765432 secs] 8178943K->3546745K(8178944K), [Metaspace: 98765K->98765K(1140736K)], 9.8765432 secs] [Times: user=9.11 sys=0.07, real=9.88 secs]";
+        let failure = parse_cms_failure(concurrent_mode).unwrap();
+        assert!(!failure.promotion_failed && failure.concurrent_mode_failure && failure.sizes.is_empty());
+        assert!(parse_cms_failure("[CMS1234.890: [CMS-concurrent-mark: 2.345/3.456 secs] [Times: user=9.11 sys=0.07, real=3.46 secs]").is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use std::sync::Arc;
+
+use crate::report::Report;
+use crate::timestamp::format_wall_clock;
+use crate::Cli;
+
+// The tables shared by the columnar exports. The report column holds the --group-by title, null when
+// everything is in a single report. Columns are only ever added at the end to keep the schema stable.
+
+pub fn allocations(reports: &[Report]) -> Result<RecordBatch, ArrowError> {
+    let (mut report, mut file, mut line, mut uptime, mut datestamp, mut bytes, mut label) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for r in reports {
+        for retained in &r.retained {
+            for allocation in &retained.allocations {
+                report.push(r.title.clone());
+                file.push(retained.name.clone());
+                line.push(allocation.line as u64);
+                uptime.push(allocation.uptime);
+                datestamp.push(allocation.wall_clock.map(format_wall_clock));
+                bytes.push(allocation.bytes);
+                label.push(retained.label.clone());
+            }
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new("report", DataType::Utf8, true),
+        Field::new("file", DataType::Utf8, false),
+        Field::new("line", DataType::UInt64, false),
+        Field::new("uptime", DataType::Float64, true),
+        Field::new("datestamp", DataType::Utf8, true),
+        Field::new("bytes", DataType::UInt64, false),
+        Field::new("label", DataType::Utf8, true),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(report)),
+        Arc::new(StringArray::from(file)),
+        Arc::new(UInt64Array::from(line)),
+        Arc::new(Float64Array::from(uptime)),
+        Arc::new(StringArray::from(datestamp)),
+        Arc::new(UInt64Array::from(bytes)),
+        Arc::new(StringArray::from(label)),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+#[cfg_attr(not(feature = "parquet"), allow(dead_code))]
+pub fn files(reports: &[Report]) -> Result<RecordBatch, ArrowError> {
+    let (mut report, mut file, mut region_size, mut allocations, mut bytes, mut evacuation_failures, mut status, mut label) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for r in reports {
+        for summary in &r.file_summaries {
+            report.push(r.title.clone());
+            file.push(summary.file.clone());
+            region_size.push(summary.region_size.as_ref().map(|x| x.mb));
+            allocations.push(summary.allocations);
+            bytes.push(summary.allocated_bytes);
+            evacuation_failures.push(summary.evacuation_failures);
+            status.push(summary.row().status);
+            label.push(summary.label.clone());
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new("report", DataType::Utf8, true),
+        Field::new("file", DataType::Utf8, false),
+        Field::new("region_size_mb", DataType::UInt32, true),
+        Field::new("allocations", DataType::UInt64, false),
+        Field::new("allocated_bytes", DataType::UInt64, false),
+        Field::new("evacuation_failures", DataType::UInt64, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("label", DataType::Utf8, true),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(report)),
+        Arc::new(StringArray::from(file)),
+        Arc::new(UInt32Array::from(region_size)),
+        Arc::new(UInt64Array::from(allocations)),
+        Arc::new(UInt64Array::from(bytes)),
+        Arc::new(UInt64Array::from(evacuation_failures)),
+        Arc::new(StringArray::from(status)),
+        Arc::new(StringArray::from(label)),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+// One row per statistic rather than a column each, the requested percentiles vary between runs
+#[cfg_attr(not(feature = "parquet"), allow(dead_code))]
+pub fn statistics(reports: &[Report], args: &Cli) -> Result<RecordBatch, ArrowError> {
+    let (mut report, mut statistic, mut value) = (Vec::new(), Vec::new(), Vec::new());
+    for r in reports {
+        let mut push = |name: String, x: u64| {
+            report.push(r.title.clone());
+            statistic.push(name);
+            value.push(x);
+        };
+        push("allocations".to_string(), r.file_summaries.iter().map(|x| x.allocations).sum());
+        push("allocated_bytes".to_string(), r.file_summaries.iter().map(|x| x.allocated_bytes).sum());
+        push("count".to_string(), r.size_stats.count);
+        push("total_bytes".to_string(), r.size_stats.total_bytes);
+        push("mean_bytes".to_string(), r.size_stats.mean().map_or(0, |x| x.round() as u64));
+        push("stddev_bytes".to_string(), r.size_stats.stddev().map_or(0, |x| x.round() as u64));
+        for (label, bytes) in r.percentiles(&args.percentiles) {
+            push(label, bytes);
+        }
+        for bucket in &r.region_size_array {
+            push(format!("bucket_{}", bucket.region_size), bucket.num_allocations as u64);
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new("report", DataType::Utf8, true),
+        Field::new("statistic", DataType::Utf8, false),
+        Field::new("value", DataType::UInt64, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from(report)), Arc::new(StringArray::from(statistic)), Arc::new(UInt64Array::from(value))];
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
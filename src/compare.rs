@@ -0,0 +1,121 @@
+use cli_table::format::Justify;
+use std::io::{self, Write};
+
+use crate::numbers::grouped;
+use crate::report::{table_style, write_rows, Record, Report};
+use crate::Cli;
+
+pub struct CompareBucketRow {
+    pub region_size: String,
+    pub baseline: u64,
+    pub candidate: u64,
+    pub delta: String,
+}
+
+impl Record for CompareBucketRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Region Size", Justify::Right),
+        ("Baseline", Justify::Right),
+        ("Candidate", Justify::Right),
+        ("Delta", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.region_size.clone(), grouped(&self.baseline), grouped(&self.candidate), self.delta.clone()]
+    }
+}
+
+pub struct CompareStatRow {
+    pub statistic: String,
+    pub baseline: u64,
+    pub candidate: u64,
+    pub delta: String,
+}
+
+impl Record for CompareStatRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Statistic", Justify::Left),
+        ("Baseline", Justify::Right),
+        ("Candidate", Justify::Right),
+        ("Delta", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.statistic.clone(), grouped(&self.baseline), grouped(&self.candidate), self.delta.clone()]
+    }
+}
+
+// "+12 (+5.00%)", without the percentage when the baseline is 0
+fn delta(baseline: u64, candidate: u64) -> String {
+    let difference = candidate as i128 - baseline as i128;
+    if baseline == 0 {
+        grouped(&format!("{:+}", difference))
+    } else {
+        grouped(&format!("{:+} ({:+.2}%)", difference, difference as f64 * 100.0 / baseline as f64))
+    }
+}
+
+// Bucket counts matched by region size, the two reports may have trimmed different numbers of large buckets
+fn bucket_rows(baseline: &Report, candidate: &Report) -> Vec<CompareBucketRow> {
+    let count = |report: &Report, region_size: &str| report.region_size_array.iter().find(|x| x.region_size == region_size).map_or(0, |x| x.num_allocations as u64);
+    let longest = if baseline.region_size_array.len() >= candidate.region_size_array.len() { baseline } else { candidate };
+    longest
+        .region_size_array
+        .iter()
+        .map(|bucket| {
+            let (baseline, candidate) = (count(baseline, &bucket.region_size), count(candidate, &bucket.region_size));
+            CompareBucketRow { region_size: bucket.region_size.clone(), baseline, candidate, delta: delta(baseline, candidate) }
+        })
+        .collect()
+}
+
+fn stat_rows(baseline: &Report, candidate: &Report, args: &Cli) -> Vec<CompareStatRow> {
+    let totals = |report: &Report| {
+        let allocations = report.file_summaries.iter().map(|x| x.allocations).sum::<u64>();
+        let bytes = report.file_summaries.iter().map(|x| x.allocated_bytes).sum::<u64>();
+        (allocations, bytes)
+    };
+    let (baseline_allocations, baseline_bytes) = totals(baseline);
+    let (candidate_allocations, candidate_bytes) = totals(candidate);
+    let mut rows = vec![
+        CompareStatRow { statistic: "humongous allocations".to_string(), baseline: baseline_allocations, candidate: candidate_allocations, delta: delta(baseline_allocations, candidate_allocations) },
+        CompareStatRow { statistic: "total humongous bytes".to_string(), baseline: baseline_bytes, candidate: candidate_bytes, delta: delta(baseline_bytes, candidate_bytes) },
+        CompareStatRow { statistic: "wasted bytes".to_string(), baseline: baseline.waste.wasted_bytes, candidate: candidate.waste.wasted_bytes, delta: delta(baseline.waste.wasted_bytes, candidate.waste.wasted_bytes) },
+    ];
+    // Percentiles are only comparable when both sides recorded allocations
+    let candidate_percentiles = candidate.percentiles(&args.percentiles);
+    for ((label, baseline), (_, candidate)) in baseline.percentiles(&args.percentiles).into_iter().zip(candidate_percentiles) {
+        rows.push(CompareStatRow { statistic: label, baseline, candidate, delta: delta(baseline, candidate) });
+    }
+    rows
+}
+
+// The baseline and candidate (two files, or two windows of the same files) bucket tables side by side, followed by the totals and percentiles
+pub fn write_comparison(baseline: &Report, candidate: &Report, (baseline_name, candidate_name): (&str, &str), out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    let style = table_style(args);
+    writeln!(out, "Baseline: {}", baseline_name)?;
+    writeln!(out, "Candidate: {}", candidate_name)?;
+    for (side, report) in [("Baseline", baseline), ("Candidate", candidate)] {
+        for summary in &report.file_summaries {
+            if let Some(region_size) = &summary.region_size {
+                writeln!(out, "{} Region Size: {} - {:?}", side, region_size.describe(), summary.name())?;
+            }
+        }
+    }
+
+    writeln!(out, "\nAllocations by Region Size:")?;
+    write_rows(out, &bucket_rows(baseline, candidate), style)?;
+    writeln!(out, "\nAllocation Statistics:")?;
+    write_rows(out, &stat_rows(baseline, candidate, args), style)?;
+    writeln!(out)?;
+    out.flush()
+}
+
+// The statistics that grew by more than `threshold` percent from the baseline, anything at all from a baseline of 0
+pub fn regressions(baseline: &Report, candidate: &Report, args: &Cli, threshold: f64) -> Vec<String> {
+    let grew = |row: &CompareStatRow| match row.baseline {
+        0 => row.candidate > 0,
+        baseline => (row.candidate as f64 - baseline as f64) * 100.0 / baseline as f64 > threshold,
+    };
+    stat_rows(baseline, candidate, args).into_iter().filter(grew).map(|x| format!("{} went from {} to {}, {}", x.statistic, x.baseline, x.candidate, x.delta)).collect()
+}
@@ -0,0 +1,257 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::bursts;
+use crate::line_parsing::parenthesized;
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::AllocationTime;
+
+pub const HUMONGOUS_TRIGGER: &str = "Humongous Allocation";
+
+pub struct CycleTriggerRow {
+    pub trigger: String,
+    pub cycles: u64,
+}
+
+impl Record for CycleTriggerRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Trigger", Justify::Left),
+        ("Concurrent Cycles", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.trigger.clone(), grouped(&self.cycles)]
+    }
+}
+
+// Tracks the most recent concurrent cycle request so the cycle that follows can be attributed to it
+#[derive(Default)]
+pub struct CycleAttribution {
+    pending_request: Option<String>,
+    pub triggers: BTreeMap<String, u64>,
+}
+
+// JDK 8 (PrintAdaptiveSizePolicy):
+// "[G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, ..., source: end of GC]"
+// "[G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: requested by GC cause, GC cause: System.gc()]"
+fn parse_cycle_request(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("] request concurrent cycle initiation, ").or_else(|| line.split_once(") request concurrent cycle initiation, "))?;
+    match rest.split_once("source: ") {
+        Some((_, source)) => source.strip_suffix(']'),
+        None => rest.split_once("reason: ").map(|x| x.1.split([',', ']']).next().unwrap_or(x.1)),
+    }
+}
+
+// JDK 8:     "[GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0123456 secs]"
+// JDK 9-11:  "[info][gc] GC(5) Pause Initial Mark (G1 Humongous Allocation) 100M->90M(256M) 4.567ms"
+// JDK 12+:   "[info][gc] GC(5) Pause Young (Concurrent Start) (G1 Humongous Allocation) 100M->90M(256M) 4.567ms"
+fn parse_cycle_start(line: &str) -> Option<&str> {
+    if line.contains("(initial-mark)") {
+        line.split_once("[GC pause (").and_then(|x| parenthesized(x.1))
+    } else {
+        line.split_once(" Pause Young (Concurrent Start) (")
+            .or_else(|| line.split_once(" Pause Initial Mark ("))
+            .and_then(|x| parenthesized(x.1))
+    }
+}
+
+fn is_humongous(trigger: &str) -> bool {
+    trigger == "G1 Humongous Allocation" || trigger == "concurrent humongous allocation"
+}
+
+impl CycleAttribution {
+    // Humongous allocation lines are matched by the main parser, and are cycle requests unless G1 logged that it
+    // did not request one, e.g. "do not request concurrent cycle initiation, reason: still doing mixed collections"
+    pub fn record_humongous_request(&mut self, line: &str) {
+        if [") request concurrent cycle initiation", "] request concurrent cycle initiation", "] Request concurrent cycle initiation"].iter().any(|x| line.contains(x)) {
+            self.pending_request = Some(HUMONGOUS_TRIGGER.to_string());
+        }
+    }
+
+    pub fn observe(&mut self, line: &str) {
+        if let Some(source) = parse_cycle_request(line) {
+            self.pending_request = Some(if is_humongous(source) { HUMONGOUS_TRIGGER.to_string() } else { source.to_string() });
+        } else if let Some(cause) = parse_cycle_start(line) {
+            let pending = self.pending_request.take();
+            let trigger = if is_humongous(cause) {
+                HUMONGOUS_TRIGGER.to_string()
+            } else {
+                pending.unwrap_or_else(|| cause.to_string())
+            };
+            *self.triggers.entry(trigger).or_insert(0) += 1;
+        }
+    }
+
+    // A request pending at the end of the earlier lines isn't matched with a cycle at the start of the later ones
+    pub fn merge(&mut self, other: CycleAttribution) {
+        for (trigger, cycles) in other.triggers {
+            *self.triggers.entry(trigger).or_insert(0) += cycles;
+        }
+        self.pending_request = other.pending_request;
+    }
+
+    // Starts a new file, a pending request never carries over between logs
+    pub fn reset(&mut self) {
+        self.pending_request = None;
+    }
+
+    pub fn rows(&self) -> Vec<CycleTriggerRow> {
+        self.triggers.iter().map(|(trigger, cycles)| CycleTriggerRow { trigger: trigger.clone(), cycles: *cycles }).collect()
+    }
+}
+
+// A concurrent cycle from its start up to the end of marking, in seconds of uptime
+pub struct MarkingCycle {
+    pub start: f64,
+    pub duration: f64,
+    pub humongous: bool,
+}
+
+// JDK 9-11: "[info][gc] GC(6) Concurrent Cycle 123.456ms", JDK 12+: "[info][gc] GC(6) Concurrent Mark Cycle 123.456ms",
+// the line logged at the start of the cycle has no duration
+fn parse_cycle_end(line: &str) -> Option<f64> {
+    let (_, rest) = line.split_once(" Concurrent Cycle ").or_else(|| line.split_once(" Concurrent Mark Cycle "))?;
+    Some(rest.trim().strip_suffix("ms")?.parse::<f64>().ok()? / 1000.0)
+}
+
+// Matches the start of each concurrent cycle with its end. JDK 8 logs no duration of the whole cycle, it
+// lasts from the initial-mark pause to the cleanup pause or "[GC concurrent-mark-abort]", or to the last of
+// "[GC concurrent-mark-end, ...]" and "[GC remark ..." when the next cycle starts without a cleanup pause.
+#[derive(Default)]
+pub struct CycleTimer {
+    // Start, whether a humongous allocation triggered it and the end of marking so far
+    started: Option<(f64, bool, Option<f64>)>,
+}
+
+impl CycleTimer {
+    pub fn observe(&mut self, line: &str, uptime: Option<f64>) -> Option<MarkingCycle> {
+        let close = |started: Option<(f64, bool, Option<f64>)>, end: Option<f64>| {
+            let (start, humongous, marked) = started?;
+            end.or(marked).map(|x| MarkingCycle { start, duration: x - start, humongous })
+        };
+        if let Some(cause) = parse_cycle_start(line) {
+            let previous = close(self.started.take(), None);
+            self.started = uptime.map(|x| (x, is_humongous(cause), None));
+            return previous;
+        } else if let Some(duration) = parse_cycle_end(line) {
+            let humongous = self.started.take().is_some_and(|x| x.1);
+            return uptime.map(|x| MarkingCycle { start: x - duration, duration, humongous });
+        } else if line.contains("[GC cleanup ") || line.contains("[GC concurrent-mark-abort]") {
+            return close(self.started.take(), uptime);
+        } else if line.contains("[GC concurrent-mark-end") || line.contains("[GC remark ") {
+            if let Some(started) = self.started.as_mut() {
+                started.2 = uptime.or(started.2);
+            }
+        }
+        None
+    }
+}
+
+// Nearest rank as every cycle is kept
+fn nearest_rank(sorted: &[f64], percentile: f64) -> f64 {
+    sorted[((sorted.len() as f64 * percentile / 100.0).ceil() as usize).clamp(1, sorted.len()) - 1]
+}
+
+// How long marking takes, and whether it keeps up with the humongous allocations: a cycle the humongous
+// allocations start again right after the previous one ended means marking barely finishes in time
+#[derive(Default)]
+pub struct CycleDurations {
+    // Seconds
+    durations: Vec<f64>,
+    pub humongous_cycles: u64,
+    // Cycles during an allocation burst, and humongous triggered cycles that started within the duration of
+    // the previous cycle after its end
+    pub during_bursts: u64,
+    pub back_to_back: u64,
+    pub allocations_during_marking: u64,
+}
+
+impl CycleDurations {
+    // The cycles and allocations of a single file, the bursts as in the Allocation Bursts section
+    pub fn record(&mut self, cycles: &[MarkingCycle], allocations: &[AllocationTime], interval: f64, factor: f64) {
+        let bursts = bursts::burst_windows(allocations, interval, factor);
+        // Sorted, to count the allocations of each cycle with two binary searches
+        let mut uptimes: Vec<f64> = allocations.iter().map(|x| x.uptime).collect();
+        uptimes.sort_by(f64::total_cmp);
+        let mut previous: Option<&MarkingCycle> = None;
+        for cycle in cycles {
+            let end = cycle.start + cycle.duration;
+            self.durations.push(cycle.duration);
+            self.humongous_cycles += u64::from(cycle.humongous);
+            let windows = (cycle.start / interval) as u64..(end / interval) as u64 + 1;
+            self.during_bursts += u64::from(bursts.iter().any(|x| x.start < windows.end && windows.start < x.end));
+            if let (Some(previous), true) = (previous, cycle.humongous) {
+                self.back_to_back += u64::from(cycle.start - (previous.start + previous.duration) < previous.duration);
+            }
+            self.allocations_during_marking += uptimes.partition_point(|x| *x <= end).saturating_sub(uptimes.partition_point(|x| *x < cycle.start)) as u64;
+            previous = Some(cycle);
+        }
+    }
+
+    pub fn cycles(&self) -> usize {
+        self.durations.len()
+    }
+
+    // p50, p90, p99 and max in seconds
+    pub fn percentiles(&self) -> Vec<(String, f64)> {
+        let mut sorted = self.durations.clone();
+        sorted.sort_by(f64::total_cmp);
+        let Some(max) = sorted.last() else { return Vec::new() };
+        let mut values: Vec<(String, f64)> = [50.0, 90.0, 99.0].into_iter().map(|x| (format!("p{}", x), nearest_rank(&sorted, x))).collect();
+        values.push(("max".to_string(), *max));
+        values
+    }
+
+    pub fn stats(&self) -> Vec<(String, String)> {
+        let mut stats = vec![("initiated by humongous allocation".to_string(), grouped(&self.humongous_cycles))];
+        stats.extend(self.percentiles().into_iter().map(|(label, seconds)| (format!("{} duration", label), format!("{:.3}s", seconds))));
+        stats.push(("during allocation bursts".to_string(), format!("{} of {}", grouped(&self.during_bursts), grouped(&self.cycles()))));
+        stats.push(("humongous allocations during marking".to_string(), grouped(&self.allocations_during_marking)));
+        stats.push(("humongous cycles started back to back".to_string(), grouped(&self.back_to_back)));
+        stats
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        (self.back_to_back > 0).then(|| {
+            format!(
+                "{} humongous allocation cycles started less than a cycle's duration after the previous one ended: marking barely finishes before the humongous allocations cross the threshold again, a region size that keeps them out of the old generation or more -XX:ConcGCThreads shortens the race",
+                self.back_to_back
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_cycles_to_the_humongous_requests() {
+        let requested = "2024-03-01T10:15:00.123+0000: 1.234: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: 3932160000 bytes, allocation request: 16777232 bytes, threshold: 3865470540 bytes (45.00 %), source: concurrent humongous allocation]";
+        let not_requested = "2024-03-01T10:15:01.456+0000: 2.567: [G1Ergonomics (Concurrent Cycles) do not request concurrent cycle initiation, reason: still doing mixed collections, occupancy: 3932160000 bytes, allocation request: 16777232 bytes, threshold: 3865470540 bytes (45.00 %), source: concurrent humongous allocation]";
+        let unified = "[2.672s][debug][gc,ergo,ihop] Request concurrent cycle initiation (occupancy higher than threshold) occupancy: 2038681733B allocation request: 11265758B threshold: 1932735283B (45.00) source: concurrent humongous allocation";
+        let cycle = "2024-03-01T10:15:02.000+0000: 3.000: [GC pause (G1 Evacuation Pause) (young) (initial-mark), 0.0123456 secs]";
+        let mut attribution = CycleAttribution::default();
+        attribution.record_humongous_request(not_requested);
+        attribution.observe(cycle);
+        attribution.record_humongous_request(requested);
+        attribution.observe(cycle);
+        attribution.record_humongous_request(unified);
+        attribution.observe(cycle);
+        assert_eq!(attribution.triggers.get("G1 Evacuation Pause"), Some(&1));
+        assert_eq!(attribution.triggers.get(HUMONGOUS_TRIGGER), Some(&2));
+    }
+
+    #[test]
+    fn counts_the_allocations_during_each_cycle() {
+        let allocations: Vec<AllocationTime> = [12.0, 10.0, 10.5, 11.0, 30.0, 20.0].into_iter().map(|uptime| AllocationTime { uptime, bytes: 4194304 }).collect();
+        let cycles = [MarkingCycle { start: 10.0, duration: 1.0, humongous: true }, MarkingCycle { start: 20.0, duration: 0.5, humongous: false }];
+        let mut durations = CycleDurations::default();
+        durations.record(&cycles, &allocations, 60.0, 3.0);
+        assert_eq!(durations.allocations_during_marking, 4);
+        // A cycle whose end comes before its start, across a JVM restart, holds none
+        durations.record(&[MarkingCycle { start: 25.0, duration: -10.0, humongous: false }], &allocations, 60.0, 3.0);
+        assert_eq!(durations.allocations_during_marking, 4);
+    }
+}
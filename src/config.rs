@@ -0,0 +1,91 @@
+use clap::{Arg, ArgAction, ArgMatches, Command, ValueSource};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+// $XDG_CONFIG_HOME/gc-ho-parser/config.toml, falling back to ~/.config
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| env::var_os("HOME").map(|x| Path::new(&x).join(".config")))?;
+    Some(config_home.join("gc-ho-parser").join("config.toml"))
+}
+
+pub fn load(path: &Path) -> Result<Table, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+    text.parse::<Table>().map_err(|e| format!("Invalid config file {}: {}", path.display(), e))
+}
+
+fn scalar(key: &str, value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(x) => Ok(x.clone()),
+        Value::Integer(x) => Ok(x.to_string()),
+        Value::Float(x) => Ok(x.to_string()),
+        _ => Err(format!("{} must be a string or a number", key)),
+    }
+}
+
+// Globs such as "/var/log/cassandra/gc*.log*" are expanded, anything else is passed on as is
+fn expand_files(values: &[String]) -> Vec<OsString> {
+    let mut files = Vec::new();
+    for value in values {
+        let matches: Vec<PathBuf> = glob::glob(value).map(|paths| paths.flatten().collect()).unwrap_or_default();
+        if matches.is_empty() {
+            files.push(OsString::from(value));
+        } else {
+            files.extend(matches.into_iter().map(PathBuf::into_os_string));
+        }
+    }
+    files
+}
+
+fn setting_args(arg: &Arg, key: &str, value: &Value) -> Result<Vec<String>, String> {
+    let flag = format!("--{}", arg.get_long().unwrap_or(arg.get_id()));
+    let values = match value {
+        Value::Array(items) => items.iter().map(|x| scalar(key, x)).collect::<Result<Vec<_>, _>>()?,
+        Value::Boolean(_) => Vec::new(),
+        x => vec![scalar(key, x)?],
+    };
+    match (arg.get_action(), value) {
+        (ArgAction::Count, Value::Integer(n)) => Ok(vec![flag; (*n).max(0) as usize]),
+        (_, Value::Boolean(set)) if !arg.is_takes_value_set() => Ok(if *set { vec![flag] } else { Vec::new() }),
+        (_, Value::Boolean(_)) => Err(format!("{} takes a value, not true or false", key)),
+        _ if !arg.is_takes_value_set() => Err(format!("{} must be true or false", key)),
+        _ if arg.is_multiple_occurrences_set() => Ok(values.into_iter().map(|x| format!("{}={}", flag, x)).collect()),
+        _ => match arg.get_value_delimiter() {
+            Some(delimiter) => Ok(vec![format!("{}={}", flag, values.join(&delimiter.to_string()))]),
+            None if values.len() == 1 => Ok(vec![format!("{}={}", flag, values[0])]),
+            None => Err(format!("{} takes a single value", key)),
+        },
+    }
+}
+
+// Turns the settings of a config file into command line arguments, keys are the long flag names
+// (with dashes or underscores). Settings whose flag was given on the command line are left out so
+// the command line wins, and the files are only used when none were given.
+pub fn config_args(command: &Command, config: &Table, matches: &ArgMatches) -> Result<(Vec<OsString>, Vec<OsString>), String> {
+    let (mut options, mut files) = (Vec::new(), Vec::new());
+    for (key, value) in config {
+        let name = key.replace('_', "-");
+        let arg = command
+            .get_arguments()
+            .find(|x| x.get_long() == Some(name.as_str()) || x.get_id() == key)
+            .filter(|x| !matches!(x.get_id(), "config" | "help" | "version"))
+            .ok_or_else(|| format!("unknown setting {}", key))?;
+        // Global options given after a subcommand are only in its matches
+        let given = |matches: &ArgMatches| matches.value_source(arg.get_id()) == Some(ValueSource::CommandLine);
+        if given(matches) || matches.subcommand().is_some_and(|(_, x)| given(x)) {
+            continue;
+        }
+        if arg.is_positional() {
+            let values = match value {
+                Value::Array(items) => items.iter().map(|x| scalar(key, x)).collect::<Result<Vec<_>, _>>()?,
+                x => vec![scalar(key, x)?],
+            };
+            files = expand_files(&values);
+        } else {
+            options.extend(setting_args(arg, key, value)?.into_iter().map(OsString::from));
+        }
+    }
+    Ok((options, files))
+}
@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+
+use crate::timestamp;
+
+// Gc logs written to stdout in a container reach us wrapped by the container runtime:
+// docker json-file:     {"log":"[0.012s][info][gc] Using G1\n","stream":"stdout","time":"2024-03-01T10:15:00.123456789Z"}
+// containerd / CRI-O:   2024-03-01T10:15:00.123456789Z stdout F [0.012s][info][gc] Using G1
+// kubectl --timestamps: 2024-03-01T10:15:00.123456789Z [0.012s][info][gc] Using G1
+// journalctl -o json:   {"MESSAGE":"[0.012s][info][gc] Using G1","_SYSTEMD_UNIT":"cassandra.service",...}
+// Returns the gc log line inside the wrapper, lines that aren't wrapped are returned as they are
+pub fn strip(line: &str) -> Cow<'_, str> {
+    unwrap(line).0
+}
+
+// The gc log line inside the wrapper and the wall clock seconds the runtime logged it at, for lines whose
+// -Xlog decorations leave the time out
+pub fn unwrap(line: &str) -> (Cow<'_, str>, Option<i64>) {
+    if line.starts_with('{') {
+        return match json_log(line) {
+            Some((log, wall_clock)) => (Cow::Owned(log), wall_clock),
+            None => (Cow::Borrowed(line), None),
+        };
+    }
+    let Some((timestamp, rest)) = strip_timestamp(line) else { return (Cow::Borrowed(line), None) };
+    // The CRI stream is followed by F for a full line or P for part of one, partial lines are rare
+    // enough in gc logs that each part is treated as a line of its own
+    let rest = ["stdout ", "stderr "]
+        .iter()
+        .find_map(|x| rest.strip_prefix(x))
+        .and_then(|x| x.strip_prefix("F ").or_else(|| x.strip_prefix("P ")))
+        .unwrap_or(rest);
+    (Cow::Borrowed(rest), timestamp::parse_wall_clock(timestamp))
+}
+
+fn json_log(line: &str) -> Option<(String, Option<i64>)> {
+    if !line.contains("\"log\"") && !line.contains("\"MESSAGE\"") {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let log = match value.get("log").or_else(|| value.get("MESSAGE"))? {
+        serde_json::Value::String(log) => log.clone(),
+        // journald gives messages that aren't valid UTF-8 as an array of their bytes
+        serde_json::Value::Array(bytes) => String::from_utf8_lossy(&bytes.iter().filter_map(|x| x.as_u64().map(|x| x as u8)).collect::<Vec<_>>()).into_owned(),
+        _ => return None,
+    };
+    // journald's time is in microseconds since the epoch, UTC like docker's
+    let wall_clock = match value.get("time") {
+        Some(time) => time.as_str().and_then(timestamp::parse_wall_clock),
+        None => value.get("__REALTIME_TIMESTAMP").and_then(|x| x.as_str()?.parse::<i64>().ok()).map(|x| x / 1_000_000),
+    };
+    Some((log.trim_end_matches(['\r', '\n']).to_string(), wall_clock))
+}
+
+// An RFC 3339 timestamp ending in Z or a +hh:mm offset and followed by a space, JDK 8 datestamps such as
+// "2024-03-01T10:15:00.123+0000: 1.234: [GC pause ..." are followed by a colon and left alone
+fn strip_timestamp(line: &str) -> Option<(&str, &str)> {
+    let bytes = line.as_bytes();
+    if bytes.len() < 21 || !bytes[0].is_ascii_digit() || bytes[4] != b'-' || bytes[10] != b'T' {
+        return None;
+    }
+    let (timestamp, rest) = line.split_once(' ')?;
+    if !timestamp.is_ascii() {
+        return None;
+    }
+    let time = timestamp.strip_suffix('Z').or_else(|| {
+        let (time, zone) = timestamp.split_at(timestamp.len().checked_sub(6)?);
+        (zone.starts_with(['+', '-']) && zone.as_bytes()[3] == b':').then_some(time)
+    })?;
+    // "10:15:00" with an optional fraction of a second
+    (time.len() >= 19 && time[11..].bytes().all(|x| x.is_ascii_digit() || x == b':' || x == b'.')).then_some((timestamp, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "[0.012s][info][gc] Using G1";
+    // 2024-03-01T10:15:00Z
+    const TIME: i64 = 1709288100;
+
+    #[test]
+    fn unwraps_the_container_runtimes() {
+        let wrapped = [
+            r#"{"log":"[0.012s][info][gc] Using G1\n","stream":"stdout","time":"2024-03-01T10:15:00.123456789Z"}"#,
+            "2024-03-01T10:15:00.123456789Z stdout F [0.012s][info][gc] Using G1",
+            "2024-03-01T10:15:00.123456789Z stderr P [0.012s][info][gc] Using G1",
+            "2024-03-01T10:15:00.123456789Z [0.012s][info][gc] Using G1",
+            "2024-03-01T11:15:00.123+01:00 [0.012s][info][gc] Using G1",
+            r#"{"MESSAGE":"[0.012s][info][gc] Using G1","_SYSTEMD_UNIT":"cassandra.service","__REALTIME_TIMESTAMP":"1709288100123456"}"#,
+        ];
+        for line in wrapped {
+            assert_eq!(unwrap(line), (Cow::Borrowed(LINE), Some(TIME)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn unwraps_journald_messages_of_bytes() {
+        let line = r#"{"MESSAGE":[91,48,46,48,49,50,115,93,32,71,49,255],"__REALTIME_TIMESTAMP":"1709288100123456"}"#;
+        assert_eq!(unwrap(line), (Cow::Borrowed("[0.012s] G1\u{fffd}"), Some(TIME)));
+    }
+
+    #[test]
+    fn leaves_unwrapped_lines_alone() {
+        let lines = [
+            LINE,
+            "2024-03-01T10:15:00.123+0000: 1.234: [GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0123 secs]",
+            r#"{"other":"json"}"#,
+            "",
+        ];
+        for line in lines {
+            assert_eq!(unwrap(line), (Cow::Borrowed(line), None), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use cli_table::format::Justify;
+
+use crate::bins::human_size;
+use crate::numbers::grouped;
+use crate::report::{FileAllocations, Record};
+use crate::timestamp;
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+pub enum DumpOrder {
+    // File by file, as logged
+    Log,
+    // Largest first, allocations of the same size in log order
+    Size,
+}
+
+pub struct DumpRow {
+    pub file: String,
+    pub line: usize,
+    pub uptime: String,
+    pub timestamp: String,
+    pub bytes: u64,
+    pub size: String,
+}
+
+impl Record for DumpRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Line", Justify::Right),
+        ("Uptime (s)", Justify::Right),
+        ("Timestamp", Justify::Left),
+        ("Bytes", Justify::Right),
+        ("Size", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.file.clone(), self.line.to_string(), self.uptime.clone(), self.timestamp.clone(), grouped(&self.bytes), self.size.clone()]
+    }
+}
+
+pub fn rows<'a>(files: impl Iterator<Item = &'a FileAllocations>, order: DumpOrder) -> Vec<DumpRow> {
+    let mut allocations: Vec<_> = files.flat_map(|file| file.allocations.iter().map(move |x| (file.name.as_str(), x))).collect();
+    if let DumpOrder::Size = order {
+        allocations.sort_by_key(|x| std::cmp::Reverse(x.1.bytes));
+    }
+    allocations
+        .into_iter()
+        .map(|(file, allocation)| DumpRow {
+            file: file.to_string(),
+            line: allocation.line,
+            uptime: allocation.uptime.map_or("-".to_string(), |x| format!("{:.3}", x)),
+            timestamp: allocation.wall_clock.map_or("-".to_string(), timestamp::format_wall_clock),
+            bytes: allocation.bytes,
+            size: human_size(allocation.bytes),
+        })
+        .collect()
+}
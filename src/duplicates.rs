@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::{archive, remote};
+
+// FNV-1a of the whole log, streamed so its value doesn't depend on how the reads are split
+fn content_hash(path: &Path) -> io::Result<u64> {
+    let (mut reader, _) = archive::open(path)?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut hash = 0xcbf29ce484222325;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hash);
+        }
+        hash = buffer[..read].iter().fold(hash, |hash, x| (hash ^ *x as u64).wrapping_mul(0x100000001b3));
+    }
+}
+
+// Without downloading URLs just to compare them
+fn size(path: &Path) -> Option<u64> {
+    if remote::is_remote(path) && !archive::is_in_memory(path) {
+        return None;
+    }
+    archive::open(path).ok().map(|x| x.1)
+}
+
+// The same gc log passed twice, e.g. by overlapping globs or as a file and inside a bundle, would count
+// every allocation twice. Files are compared by size and only those of the same size by their contents;
+// the first of identical files is kept.
+pub fn skip_duplicates(files: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut by_size: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        if let Some(size) = size(file) {
+            by_size.entry(size).or_default().push(index);
+        }
+    }
+    let mut skipped = vec![false; files.len()];
+    for indices in by_size.into_values().filter(|x| x.len() > 1) {
+        let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+        for index in indices {
+            if let Ok(hash) = content_hash(&files[index]) {
+                by_hash.entry(hash).or_default().push(index);
+            }
+        }
+        for mut identical in by_hash.into_values().filter(|x| x.len() > 1) {
+            identical.sort();
+            let names: Vec<String> = identical.iter().map(|x| files[*x].display().to_string()).collect();
+            log::warn!("{} have the same contents, only {} is analyzed", names.join(", "), names[0]);
+            identical[1..].iter().for_each(|x| skipped[*x] = true);
+        }
+    }
+    files.into_iter().zip(skipped).filter(|x| !x.1).map(|x| x.0).collect()
+}
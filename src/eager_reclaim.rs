@@ -0,0 +1,94 @@
+use crate::buckets::MB;
+use crate::numbers::grouped;
+
+// Per-collection humongous region counts, used to judge how well eager reclaim keeps up
+#[derive(Default)]
+pub struct EagerReclaim {
+    pending_total: Option<u64>,
+    pub collections: u64,
+    pub regions_before: u64,
+    pub regions_reclaimed: u64,
+    pub max_persisting: u64,
+    // Most humongous regions before a collection, and their size where the region size is known
+    pub max_regions: u64,
+    pub peak_bytes: u64,
+}
+
+fn parse_count(line: &str, label: &str) -> Option<u64> {
+    line.split_once(label)?.1.trim_end().strip_suffix(']')?.trim().parse::<u64>().ok()
+}
+
+// JDK 9+: "[info][gc,heap] GC(5) Humongous regions: 10->2"
+fn parse_unified(line: &str) -> Option<(u64, u64)> {
+    let (_, counts) = line.split_once("Humongous regions: ").or_else(|| line.split_once("Humongous Regions: "))?;
+    let (before, after) = counts.trim().split_once("->")?;
+    Some((before.parse::<u64>().ok()?, after.parse::<u64>().ok()?))
+}
+
+impl EagerReclaim {
+    fn record(&mut self, before: u64, after: u64, region_size: Option<u64>) {
+        self.collections += 1;
+        self.max_regions = self.max_regions.max(before);
+        self.peak_bytes = self.peak_bytes.max(before * region_size.unwrap_or(0));
+        self.regions_before += before;
+        self.regions_reclaimed += before.saturating_sub(after);
+        self.max_persisting = self.max_persisting.max(after);
+    }
+
+    // JDK 8u60+ (PrintGCDetails) reports the counts on separate lines within the pause:
+    //          [Humongous Total: 8]
+    //          [Humongous Candidate: 8]
+    //       [Humongous Reclaim: 0.1 ms]
+    //          [Humongous Reclaimed: 6]
+    pub fn observe(&mut self, line: &str, region_size: Option<u64>) {
+        if let Some((before, after)) = parse_unified(line) {
+            self.record(before, after, region_size);
+        } else if let Some(total) = parse_count(line, "[Humongous Total: ") {
+            self.pending_total = Some(total);
+        } else if let Some(reclaimed) = parse_count(line, "[Humongous Reclaimed: ") {
+            if let Some(total) = self.pending_total.take() {
+                self.record(total, total.saturating_sub(reclaimed), region_size);
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.pending_total = None;
+    }
+
+    pub fn merge(&mut self, other: EagerReclaim) {
+        self.collections += other.collections;
+        self.regions_before += other.regions_before;
+        self.regions_reclaimed += other.regions_reclaimed;
+        self.max_persisting = self.max_persisting.max(other.max_persisting);
+        self.max_regions = self.max_regions.max(other.max_regions);
+        self.peak_bytes = self.peak_bytes.max(other.peak_bytes);
+        self.pending_total = other.pending_total;
+    }
+
+    pub fn regions_persisting(&self) -> u64 {
+        self.regions_before - self.regions_reclaimed
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let collections = self.collections.max(1) as f64;
+        let effectiveness = if self.regions_before > 0 { self.regions_reclaimed as f64 * 100.0 / self.regions_before as f64 } else { 100.0 };
+        let mut stats = vec![
+            ("peak regions before GC", grouped(&self.max_regions)),
+            ("avg regions before GC", format!("{:.1}", self.regions_before as f64 / collections)),
+            ("avg regions reclaimed", format!("{:.1}", self.regions_reclaimed as f64 / collections)),
+            ("avg regions persisting", format!("{:.1}", self.regions_persisting() as f64 / collections)),
+            ("max regions persisting", grouped(&self.max_persisting)),
+            ("reclaim effectiveness", format!("{:.1}%", effectiveness)),
+        ];
+        if self.peak_bytes > 0 {
+            stats.insert(1, ("peak humongous footprint", format!("{}MB", grouped(&(self.peak_bytes / MB)))));
+        }
+        // Little's law: the regions left after each collection over those reclaimed per collection is how many
+        // collections a humongous region survives on average, given as many are allocated as reclaimed
+        if self.regions_reclaimed > 0 {
+            stats.push(("avg persistence (collections survived)", format!("{:.1}", self.regions_persisting() as f64 / self.regions_reclaimed as f64)));
+        }
+        stats
+    }
+}
@@ -0,0 +1,90 @@
+// Enough of the start of a file to tell its encoding
+pub const HEAD_LEN: usize = 16;
+
+// GC logs redirected by PowerShell or copied through some Windows tools end up UTF-16, which reads as a NUL
+// byte between every character and matches nothing
+#[derive(Clone, Copy, Debug)]
+pub enum Utf16 {
+    LittleEndian,
+    BigEndian,
+}
+
+// From the byte order mark, or without one from the NUL bytes of ASCII text in the first characters
+pub fn detect(head: &[u8]) -> Option<Utf16> {
+    match head {
+        [0xFF, 0xFE, ..] => return Some(Utf16::LittleEndian),
+        [0xFE, 0xFF, ..] => return Some(Utf16::BigEndian),
+        _ => {}
+    }
+    let pairs: Vec<&[u8]> = head.chunks_exact(2).collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+    if pairs.iter().all(|x| x[0] != 0 && x[1] == 0) {
+        Some(Utf16::LittleEndian)
+    } else if pairs.iter().all(|x| x[0] == 0 && x[1] != 0) {
+        Some(Utf16::BigEndian)
+    } else {
+        None
+    }
+}
+
+// UTF-8 text of UTF-16 bytes without their byte order mark, with unpaired surrogates replaced
+pub fn to_utf8(bytes: &[u8], encoding: Utf16) -> Vec<u8> {
+    let units = bytes.chunks_exact(2).map(|x| match encoding {
+        Utf16::LittleEndian => u16::from_le_bytes([x[0], x[1]]),
+        Utf16::BigEndian => u16::from_be_bytes([x[0], x[1]]),
+    });
+    let mut text: String = char::decode_utf16(units).map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER)).collect();
+    if text.starts_with('\u{feff}') {
+        text.remove(0);
+    }
+    text.into_bytes()
+}
+
+// Contents as UTF-8, transcoded when they're UTF-16
+pub fn transcode(contents: Vec<u8>) -> Vec<u8> {
+    match detect(&contents[..contents.len().min(HEAD_LEN)]) {
+        Some(encoding) => to_utf8(&contents, encoding),
+        None => contents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "[0.012s][info][gc] Using G1\n";
+
+    fn utf16(text: &str, encoding: Utf16, bom: bool) -> Vec<u8> {
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        units
+            .flat_map(|x| match encoding {
+                Utf16::LittleEndian => x.to_le_bytes(),
+                Utf16::BigEndian => x.to_be_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transcodes_utf16_with_and_without_a_byte_order_mark() {
+        for encoding in [Utf16::LittleEndian, Utf16::BigEndian] {
+            for bom in [true, false] {
+                assert_eq!(transcode(utf16(LINE, encoding, bom)), LINE.as_bytes(), "{:?} with a byte order mark: {}", encoding, bom);
+            }
+        }
+    }
+
+    #[test]
+    fn leaves_utf8_alone() {
+        assert_eq!(transcode(LINE.as_bytes().to_vec()), LINE.as_bytes());
+        assert_eq!(transcode("Größe: 4M\n".as_bytes().to_vec()), "Größe: 4M\n".as_bytes());
+        assert_eq!(transcode(Vec::new()), b"");
+        assert!(detect(b"[").is_none());
+    }
+
+    #[test]
+    fn replaces_unpaired_surrogates() {
+        assert_eq!(to_utf8(&[b'a', 0, 0x00, 0xD8, b'b', 0], Utf16::LittleEndian), "a\u{fffd}b".as_bytes());
+    }
+}
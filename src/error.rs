@@ -0,0 +1,43 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+// Failures that prevent a gc log from being analyzed at all
+// Problems with individual lines are counted in the file summary instead
+#[derive(Debug, Error)]
+pub enum GcLogError {
+    #[error("unable to open {}: {source}", .path.display())]
+    Open { path: PathBuf, source: io::Error },
+    #[error("unable to read {}: {source}", .path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("{} did not contain 3+ lines, does not appear to be a valid gc log", .path.display())]
+    TooShort { path: PathBuf },
+    #[error("no CommandLine flags or logged heap region size found in {}, use --region-size, --flags-file or a larger --header-lines (0 searches the whole file)", .path.display())]
+    MissingFlags { path: PathBuf },
+    #[error("invalid G1HeapRegionSize value {value:?} in {}", .path.display())]
+    InvalidRegionSize { path: PathBuf, value: String },
+    #[error("unable to determine the region size of {}, neither G1HeapRegionSize nor the heap size flags are present", .path.display())]
+    UnknownRegionSize { path: PathBuf },
+    #[error("{} is not a valid flight recording: {message}", .path.display())]
+    InvalidRecording { path: PathBuf, message: String },
+    #[error("{} is a flight recording, which is only supported with --collector g1", .path.display())]
+    UnsupportedRecording { path: PathBuf },
+    #[error("{} is neither --events ndjson nor a CSV with a bytes column", .path.display())]
+    InvalidEvents { path: PathBuf },
+}
+
+impl GcLogError {
+    pub fn path_mut(&mut self) -> &mut PathBuf {
+        match self {
+            GcLogError::Open { path, .. }
+            | GcLogError::Read { path, .. }
+            | GcLogError::TooShort { path }
+            | GcLogError::MissingFlags { path }
+            | GcLogError::InvalidRegionSize { path, .. }
+            | GcLogError::UnknownRegionSize { path }
+            | GcLogError::InvalidRecording { path, .. }
+            | GcLogError::UnsupportedRecording { path }
+            | GcLogError::InvalidEvents { path } => path,
+        }
+    }
+}
@@ -0,0 +1,6 @@
+// JDK 8:    "[GC pause (G1 Evacuation Pause) (young) (to-space exhausted), 0.1234567 secs]"
+// JDK 9-16: "[info][gc] GC(12) To-space exhausted"
+// JDK 17+:  "[info][gc] GC(12) Pause Young (Normal) (G1 Evacuation Pause) (Evacuation Failure) 7934M->7900M(8192M) 123.456ms"
+pub fn is_evacuation_failure(line: &str) -> bool {
+    line.contains("to-space exhausted") || line.contains("To-space exhausted") || line.contains("(Evacuation Failure")
+}
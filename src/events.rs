@@ -0,0 +1,100 @@
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::error::GcLogError;
+use crate::file_summary::FileSummary;
+use crate::{timestamp, HumongousAllocation};
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+pub enum EventsFormat {
+    // One JSON object per line, for jq and other line oriented tools
+    Ndjson,
+}
+
+// Writes the allocation to stdout as soon as it's parsed, each line is flushed so a reader sees it while the
+// rest of the file is still being read
+pub fn emit(file: &str, label: Option<&str>, allocation: &HumongousAllocation) {
+    let event = json!({
+        "file": file,
+        "label": label,
+        "line": allocation.line,
+        "uptime": allocation.uptime,
+        "timestamp": allocation.wall_clock.map(timestamp::format_wall_clock),
+        "bytes": allocation.bytes,
+    });
+    match writeln!(io::stdout().lock(), "{}", event) {
+        // The reader going away (e.g. piped into head) is not an error
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => log::warn!("Unable to write the allocation event: {}", e),
+        _ => {}
+    }
+}
+
+// An event of an --events ndjson line, None when it has no byte count
+fn parse_ndjson(line: &str) -> Option<HumongousAllocation> {
+    let event: Value = serde_json::from_str(line).ok()?;
+    Some(HumongousAllocation {
+        line: event["line"].as_u64().unwrap_or(0) as usize,
+        uptime: event["uptime"].as_f64(),
+        wall_clock: event["timestamp"].as_str().and_then(timestamp::parse_wall_clock),
+        bytes: event["bytes"].as_u64()?,
+    })
+}
+
+// The line, uptime, timestamp and bytes columns of a CSV header, the bytes one being required
+struct CsvColumns {
+    line: Option<usize>,
+    uptime: Option<usize>,
+    timestamp: Option<usize>,
+    bytes: usize,
+}
+
+impl CsvColumns {
+    fn new(header: &str) -> Option<CsvColumns> {
+        let names: Vec<String> = header.split(',').map(|x| x.trim().trim_matches('"').to_ascii_lowercase()).collect();
+        let column = |name: &str| names.iter().position(|x| x == name);
+        Some(CsvColumns { line: column("line"), uptime: column("uptime"), timestamp: column("timestamp"), bytes: column("bytes")? })
+    }
+
+    fn parse(&self, line: &str) -> Option<HumongousAllocation> {
+        let fields: Vec<&str> = line.split(',').map(|x| x.trim().trim_matches('"')).collect();
+        let field = |column: Option<usize>| column.and_then(|x| fields.get(x)).filter(|x| !x.is_empty());
+        Some(HumongousAllocation {
+            line: field(self.line).and_then(|x| x.parse().ok()).unwrap_or(0),
+            uptime: field(self.uptime).and_then(|x| x.parse().ok()),
+            wall_clock: field(self.timestamp).and_then(|x| timestamp::parse_wall_clock(x)),
+            bytes: field(Some(self.bytes))?.parse().ok()?,
+        })
+    }
+}
+
+// The allocations of a file written by --events ndjson, or of a CSV whose header names a bytes column and
+// optionally line, uptime and timestamp ones. Events without a line number are numbered by their line in the file.
+pub fn read(file: &Path, events: impl BufRead, summary: &mut FileSummary) -> Result<Vec<HumongousAllocation>, GcLogError> {
+    let mut allocations = Vec::new();
+    let mut csv = None;
+    for (index, line) in events.lines().enumerate() {
+        let line = line.map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let allocation = match &csv {
+            None if line.starts_with('{') => parse_ndjson(line),
+            None => {
+                let columns = CsvColumns::new(line).ok_or_else(|| GcLogError::InvalidEvents { path: file.to_path_buf() })?;
+                csv = Some(columns);
+                continue;
+            }
+            Some(columns) => columns.parse(line),
+        };
+        match allocation {
+            Some(allocation) => allocations.push(HumongousAllocation { line: if allocation.line == 0 { index + 1 } else { allocation.line }, ..allocation }),
+            None => {
+                summary.warn_at(index + 1, "no byte count in the allocation event".to_string());
+                summary.unparseable_lines += 1;
+            }
+        }
+    }
+    Ok(allocations)
+}
@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+
+use crate::report::Report;
+use crate::Cli;
+
+// Exporters with heavy dependencies are behind a cargo feature of the same name
+#[derive(Clone, Copy, Debug)]
+pub enum ExportFormat {
+    // One row per allocation plus summary tables in a SQLite database
+    Sqlite,
+    // allocations, files and statistics tables as Parquet files in a directory
+    Parquet,
+    // The allocations table in an Arrow IPC (Feather v2) file
+    Arrow,
+    // Whitespace delimited data files and a script plotting them in a directory
+    Gnuplot,
+    // Allocation count and bytes per --interval window as CSV or NDJSON
+    Timeseries,
+    // InfluxDB line protocol to stdout, a file or a write endpoint
+    Influx,
+    // Buckets, percentiles, files and time series sheets of an Excel workbook
+    Xlsx,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExportTarget {
+    pub format: ExportFormat,
+    pub path: PathBuf,
+}
+
+impl ExportFormat {
+    fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Sqlite => "sqlite",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Arrow => "arrow",
+            ExportFormat::Gnuplot => "gnuplot",
+            ExportFormat::Timeseries => "timeseries",
+            ExportFormat::Influx => "influx",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    fn available(self) -> bool {
+        match self {
+            ExportFormat::Sqlite => cfg!(feature = "sqlite"),
+            ExportFormat::Parquet => cfg!(feature = "parquet"),
+            ExportFormat::Arrow => cfg!(feature = "arrow"),
+            ExportFormat::Xlsx => cfg!(feature = "xlsx"),
+            ExportFormat::Gnuplot | ExportFormat::Timeseries | ExportFormat::Influx => true,
+        }
+    }
+
+    fn unavailable(self) -> String {
+        format!("{} export is not available, rebuild with --features {}", self.name(), self.name())
+    }
+}
+
+// "sqlite:out.db", or "influx" alone for stdout
+pub fn parse_export_target(value: &str) -> Result<ExportTarget, String> {
+    if value == "influx" {
+        return Ok(ExportTarget { format: ExportFormat::Influx, path: PathBuf::from("-") });
+    }
+    let (format, path) = value.split_once(':').ok_or_else(|| format!("{} is not a FORMAT:PATH export, e.g. sqlite:humongous.db", value))?;
+    let format = match format {
+        "sqlite" => ExportFormat::Sqlite,
+        "parquet" => ExportFormat::Parquet,
+        "arrow" | "feather" => ExportFormat::Arrow,
+        "gnuplot" => ExportFormat::Gnuplot,
+        "timeseries" => ExportFormat::Timeseries,
+        "influx" => ExportFormat::Influx,
+        "xlsx" => ExportFormat::Xlsx,
+        _ => return Err(format!("unknown export format {}, expected sqlite, parquet, arrow, gnuplot, timeseries, influx or xlsx", format)),
+    };
+    if !format.available() {
+        return Err(format.unavailable());
+    }
+    Ok(ExportTarget { format, path: PathBuf::from(path) })
+}
+
+pub fn export(target: &ExportTarget, reports: &[Report], args: &Cli) -> Result<(), String> {
+    let result = match target.format {
+        ExportFormat::Sqlite => sqlite(&target.path, reports, args),
+        ExportFormat::Parquet => parquet(&target.path, reports, args),
+        ExportFormat::Arrow => arrow(&target.path, reports),
+        ExportFormat::Gnuplot => crate::gnuplot::export(&target.path, reports).map_err(|e| e.to_string()),
+        // Minutes unless --interval says otherwise
+        ExportFormat::Timeseries => crate::timeseries::export(&target.path, reports, args.interval.unwrap_or(60)).map_err(|e| e.to_string()),
+        ExportFormat::Influx => crate::influx::export(&target.path, reports, &args.percentiles, args.interval.unwrap_or(60)),
+        ExportFormat::Xlsx => xlsx(&target.path, reports, args),
+    };
+    result.map_err(|e| format!("Unable to export to {}: {}", target.path.display(), e))
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite(path: &Path, reports: &[Report], args: &Cli) -> Result<(), String> {
+    crate::sqlite::export(path, reports, args).map_err(|e| e.to_string())
+}
+
+// Unavailable targets are already rejected with the arguments
+#[cfg(not(feature = "sqlite"))]
+fn sqlite(_: &Path, _: &[Report], _: &Cli) -> Result<(), String> {
+    Err(ExportFormat::Sqlite.unavailable())
+}
+
+#[cfg(feature = "parquet")]
+fn parquet(dir: &Path, reports: &[Report], args: &Cli) -> Result<(), String> {
+    crate::parquet::export(dir, reports, args).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn parquet(_: &Path, _: &[Report], _: &Cli) -> Result<(), String> {
+    Err(ExportFormat::Parquet.unavailable())
+}
+
+#[cfg(feature = "arrow")]
+fn arrow(path: &Path, reports: &[Report]) -> Result<(), String> {
+    crate::arrow::export(path, reports).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "arrow"))]
+fn arrow(_: &Path, _: &[Report]) -> Result<(), String> {
+    Err(ExportFormat::Arrow.unavailable())
+}
+
+#[cfg(feature = "xlsx")]
+fn xlsx(path: &Path, reports: &[Report], args: &Cli) -> Result<(), String> {
+    crate::xlsx::export(path, reports, args).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn xlsx(_: &Path, _: &[Report], _: &Cli) -> Result<(), String> {
+    Err(ExportFormat::Xlsx.unavailable())
+}
@@ -0,0 +1,181 @@
+use cli_table::format::Justify;
+use std::path::Path;
+
+use crate::error::GcLogError;
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::RegionSize;
+
+// Outcome of analyzing a single gc log, reported at the end of the run
+pub struct FileSummary {
+    pub file: String,
+    // The --input label of the file
+    pub label: Option<String>,
+    pub region_size: Option<RegionSize>,
+    // G1 relevant settings from the CommandLine flags
+    pub jvm_configuration: Vec<(&'static str, String)>,
+    pub initial_heap: Option<u64>,
+    pub max_heap: Option<u64>,
+    // The JDK release the log or the --flags-file names
+    pub jdk_version: Option<u32>,
+    pub error: Option<GcLogError>,
+    pub warnings: Vec<String>,
+    pub allocations: u64,
+    // Sum of the humongous allocation sizes
+    pub allocated_bytes: u64,
+    pub evacuation_failures: u64,
+    // First and last jvm uptime seen in the log
+    pub uptime_range: Option<(f64, f64)>,
+    // Humongous allocation lines whose byte count could not be parsed
+    pub unparseable_lines: u64,
+    // Lines with invalid UTF-8, e.g. interleaved application output, read with the invalid bytes replaced
+    pub undecodable_lines: u64,
+    // Binary garbage such as the NUL bytes a crash leaves behind, skipped
+    pub binary_lines: u64,
+    // Allocations <= 512KB, which can never be humongous
+    pub unexpected_allocations: u64,
+    // Allocations outside of --min-size / --max-size
+    pub filtered_allocations: u64,
+    // Lines of the log read, none for --from-events and flight recordings
+    pub lines_read: u64,
+    // Allocations with a datestamp and with an uptime, of those counted
+    pub dated_allocations: u64,
+    pub uptime_allocations: u64,
+    // Bytes read with --state, from where the previous run left off to the end of the last complete line
+    pub read_range: Option<(u64, u64)>,
+}
+
+pub struct FileSummaryRow {
+    pub file: String,
+    pub status: &'static str,
+    pub allocations: u64,
+    pub skipped_lines: u64,
+    pub details: String,
+}
+
+impl Record for FileSummaryRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Status", Justify::Left),
+        ("Humongous Allocations", Justify::Right),
+        ("Skipped Lines", Justify::Right),
+        ("Details", Justify::Left),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.file.clone(), self.status.to_string(), grouped(&self.allocations), grouped(&self.skipped_lines), self.details.clone()]
+    }
+}
+
+impl FileSummary {
+    pub fn new(file: &Path) -> FileSummary {
+        FileSummary {
+            file: file.display().to_string(),
+            label: None,
+            region_size: None,
+            jvm_configuration: Vec::new(),
+            initial_heap: None,
+            max_heap: None,
+            jdk_version: None,
+            error: None,
+            warnings: Vec::new(),
+            allocations: 0,
+            allocated_bytes: 0,
+            evacuation_failures: 0,
+            uptime_range: None,
+            unparseable_lines: 0,
+            undecodable_lines: 0,
+            binary_lines: 0,
+            unexpected_allocations: 0,
+            filtered_allocations: 0,
+            lines_read: 0,
+            dated_allocations: 0,
+            uptime_allocations: 0,
+            read_range: None,
+        }
+    }
+
+    // Logs the warning as it happens and keeps it for the end of run summary
+    pub fn warn(&mut self, message: String) {
+        log::warn!("{}: {}", self.file, message);
+        self.warnings.push(message);
+    }
+
+    // A warning about a given line, located so the line can be looked up in the gc log
+    pub fn warn_at(&mut self, line: usize, message: String) {
+        log::warn!("{}:{}: {}", self.file, line, message);
+        self.warnings.push(format!("line {}: {}", line, message));
+    }
+
+    pub fn observe_uptime(&mut self, uptime: f64) {
+        let first = self.uptime_range.map_or(uptime, |x| x.0);
+        self.uptime_range = Some((first, uptime));
+    }
+
+    // Adds the line counts of a later chunk of the same file
+    pub fn merge_chunk(&mut self, chunk: FileSummary) {
+        self.warnings.extend(chunk.warnings);
+        self.evacuation_failures += chunk.evacuation_failures;
+        self.unparseable_lines += chunk.unparseable_lines;
+        self.undecodable_lines += chunk.undecodable_lines;
+        self.binary_lines += chunk.binary_lines;
+        self.lines_read += chunk.lines_read;
+        self.uptime_range = match (self.uptime_range, chunk.uptime_range) {
+            (Some((first, _)), Some((_, last))) => Some((first, last)),
+            (range, chunk_range) => range.or(chunk_range),
+        };
+    }
+
+    // Seconds of jvm uptime covered by the log
+    pub fn uptime_span(&self) -> Option<f64> {
+        self.uptime_range.map(|(first, last)| last - first)
+    }
+
+    pub fn details(&self) -> Vec<String> {
+        let mut details: Vec<String> = self.error.iter().map(|x| x.to_string()).collect();
+        details.extend(self.warnings.iter().cloned());
+        if self.unparseable_lines > 0 {
+            details.push(format!("{} humongous allocation lines with an unparseable size", self.unparseable_lines));
+        }
+        if self.undecodable_lines > 0 {
+            details.push(format!("{} lines with invalid UTF-8, read with it replaced", self.undecodable_lines));
+        }
+        if self.binary_lines > 0 {
+            details.push(format!("{} lines of binary data", self.binary_lines));
+        }
+        if self.unexpected_allocations > 0 {
+            details.push(format!("{} unexpected allocations <= 524288 bytes", self.unexpected_allocations));
+        }
+        details
+    }
+
+    // The file under its --input label, e.g. "node1 (us-east/gc.log)"
+    pub fn name(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} ({})", label, self.file),
+            None => self.file.clone(),
+        }
+    }
+
+    pub fn has_issues(&self) -> bool {
+        !self.details().is_empty()
+    }
+
+    pub fn row(&self) -> FileSummaryRow {
+        let details = self.details();
+        let status = if self.error.is_some() {
+            "ERROR"
+        } else if details.is_empty() {
+            "OK"
+        } else {
+            "WARN"
+        };
+        FileSummaryRow {
+            file: self.name(),
+            status,
+            allocations: self.allocations,
+            skipped_lines: self.unparseable_lines + self.binary_lines,
+            details: details.join("\n"),
+        }
+    }
+}
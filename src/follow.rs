@@ -0,0 +1,89 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use crate::pattern::LinePattern;
+use crate::{container, evacuation_failure, full_gc, parse_allocation};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub enum FollowEventKind {
+    HumongousAllocation(u64),
+    FullGc,
+    EvacuationFailure,
+}
+
+pub struct FollowEvent {
+    pub file: String,
+    pub label: Option<String>,
+    pub kind: FollowEventKind,
+}
+
+fn parse_event(line: &str, patterns: &[LinePattern]) -> Option<FollowEventKind> {
+    if let Some(Ok(bytes)) = parse_allocation(line, patterns) {
+        Some(FollowEventKind::HumongousAllocation(bytes))
+    } else if full_gc::parse_full_gc(line, None).is_some() {
+        Some(FollowEventKind::FullGc)
+    } else if evacuation_failure::is_evacuation_failure(line) {
+        Some(FollowEventKind::EvacuationFailure)
+    } else {
+        None
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::ino(metadata)
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> u64 {
+    0
+}
+
+// The log was truncated in place or rotated away (a new file now lives at the path)
+fn was_rotated(path: &Path, position: u64, id: u64) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) => metadata.len() < position || file_id(&metadata) != id,
+        Err(_) => false,
+    }
+}
+
+// Reads the gc log from the start and then keeps polling for appended lines like `tail -F`,
+// reopening it when it gets truncated or rotated. Only returns once the receiver has gone away.
+pub fn follow_file(path: PathBuf, label: Option<String>, patterns: &[LinePattern], events: Sender<FollowEvent>) -> io::Result<()> {
+    let file_name = path.display().to_string();
+    let file = File::open(&path)?;
+    let mut id = file_id(&file.metadata()?);
+    let mut reader = BufReader::new(file);
+    let mut position = 0;
+    let mut line = Vec::new();
+    loop {
+        let read = reader.read_until(b'\n', &mut line)?;
+        position += read as u64;
+        if read == 0 || !line.ends_with(b"\n") {
+            // Keep any partially written line until the rest of it arrives
+            thread::sleep(POLL_INTERVAL);
+            if was_rotated(&path, position, id) {
+                log::info!("{} was rotated, reading it from the start", file_name);
+                let file = File::open(&path)?;
+                id = file_id(&file.metadata()?);
+                reader = BufReader::new(file);
+                reader.seek(SeekFrom::Start(0))?;
+                position = 0;
+                line.clear();
+            }
+            continue;
+        }
+        let text = String::from_utf8_lossy(&line);
+        if let Some(kind) = parse_event(&container::strip(text.trim_end_matches(['\n', '\r'])), patterns) {
+            if events.send(FollowEvent { file: file_name.clone(), label: label.clone(), kind }).is_err() {
+                return Ok(());
+            }
+        }
+        line.clear();
+    }
+}
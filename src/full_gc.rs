@@ -0,0 +1,102 @@
+use cli_table::format::Justify;
+
+use crate::line_parsing::parenthesized;
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::AllocationTime;
+
+#[derive(Debug)]
+pub struct FullGc {
+    pub uptime: Option<f64>,
+    pub cause: String,
+    pub duration: Option<f64>,
+}
+
+pub struct FullGcRow {
+    pub file: String,
+    pub uptime: String,
+    pub cause: String,
+    pub duration: String,
+    pub preceding_allocations: usize,
+}
+
+impl Record for FullGcRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Uptime (s)", Justify::Right),
+        ("Cause", Justify::Left),
+        ("Duration (s)", Justify::Right),
+        ("Preceding Humongous Allocations", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.file.clone(), self.uptime.clone(), self.cause.clone(), self.duration.clone(), grouped(&self.preceding_allocations)]
+    }
+}
+
+// JDK 8:    "2022-01-26T18:54:10.491+0000: 1131.424: [Full GC (Allocation Failure)  7934M->3410M(8192M), 9.8765432 secs]"
+// Unified:  "[1131.424s][info][gc] GC(123) Pause Full (G1 Humongous Allocation) 7934M->3410M(8192M) 9876.543ms"
+// The duration is not always on the same line in JDK 8 logs (ergonomics output can be interleaved),
+// in which case it is left empty rather than guessed
+pub fn parse_full_gc(line: &str, uptime: Option<f64>) -> Option<FullGc> {
+    if let Some((_, rest)) = line.split_once("[Full GC (") {
+        let cause = parenthesized(rest)?;
+        let duration = rest
+            .strip_suffix(" secs]")
+            .and_then(|x| x.rsplit_once(", "))
+            .and_then(|x| x.1.parse::<f64>().ok());
+        Some(FullGc { uptime, cause: cause.to_string(), duration })
+    } else if let Some((_, rest)) = line.split_once(" Pause Full (") {
+        let cause = parenthesized(rest)?;
+        let duration = rest
+            .rsplit_once(' ')
+            .and_then(|x| x.1.strip_suffix("ms"))
+            .and_then(|x| x.parse::<f64>().ok())
+            .map(|x| x / 1000.0);
+        Some(FullGc { uptime, cause: cause.to_string(), duration })
+    } else {
+        None
+    }
+}
+
+// Counts the humongous allocations that occurred within `window` seconds before each Full GC
+// Uptimes are only comparable within a single file, so this must be called per file
+pub fn attribute_full_gcs(file: &str, full_gcs: &[FullGc], allocations: &[AllocationTime], window: f64) -> Vec<FullGcRow> {
+    full_gcs
+        .iter()
+        .map(|full_gc| {
+            let preceding_allocations = match full_gc.uptime {
+                Some(end) => allocations.iter().filter(|x| x.uptime <= end && x.uptime >= end - window).count(),
+                None => 0,
+            };
+            FullGcRow {
+                file: file.to_string(),
+                uptime: full_gc.uptime.map_or("-".to_string(), |x| format!("{:.3}", x)),
+                cause: full_gc.cause.clone(),
+                duration: full_gc.duration.map_or("-".to_string(), |x| format!("{:.3}", x)),
+                preceding_allocations,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(line: &str) -> Option<(String, Option<f64>)> {
+        parse_full_gc(line, Some(1131.424)).map(|x| (x.cause, x.duration))
+    }
+
+    #[test]
+    fn parses_the_full_gc_lines() {
+        let jdk8 = "2022-01-26T18:54:10.491+0000: 1131.424: [Full GC (Allocation Failure)  7934M->3410M(8192M), 9.8765432 secs]";
+        assert_eq!(parsed(jdk8), Some(("Allocation Failure".to_string(), Some(9.8765432))));
+        // Ergonomics output interleaved before the duration
+        let interleaved = "2022-01-26T18:54:10.491+0000: 1131.424: [Full GC (System.gc()) 2022-01-26T18:54:10.492+0000: 1131.425: [G1Ergonomics (Heap Sizing) attempt heap shrinking, reason: capacity higher than max desired capacity after Full GC]";
+        assert_eq!(parsed(interleaved), Some(("System.gc()".to_string(), None)));
+        let unified = "[1131.424s][info][gc] GC(123) Pause Full (G1 Humongous Allocation) 7934M->3410M(8192M) 9876.543ms";
+        assert_eq!(parsed(unified), Some(("G1 Humongous Allocation".to_string(), Some(9.876543))));
+        assert_eq!(parsed("[1131.424s][info][gc] GC(124) Pause Young (Normal) (G1 Evacuation Pause) 4000M->3500M(8192M) 12.345ms"), None);
+    }
+}
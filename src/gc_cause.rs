@@ -0,0 +1,137 @@
+use cli_table::format::Justify;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use crate::gc_cycles::Pause;
+use crate::line_parsing::parenthesized;
+use crate::numbers::{grouped, ExactSum};
+use crate::report::Record;
+
+pub const HUMONGOUS_CAUSE: &str = "G1 Humongous Allocation";
+
+// The pause type that JDK 12+ puts in front of the cause, e.g. "Pause Young (Normal) (G1 Evacuation Pause)"
+const PAUSE_TYPES: [&str; 4] = ["Normal", "Concurrent Start", "Prepare Mixed", "Mixed"];
+
+pub struct GcCauseRow {
+    pub cause: String,
+    pub collections: u64,
+    pub percentage: String,
+}
+
+impl Record for GcCauseRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Cause", Justify::Left),
+        ("Collections", Justify::Right),
+        ("% of Collections", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.cause.clone(), grouped(&self.collections), self.percentage.clone()]
+    }
+}
+
+// JDK 8:    "[GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0123456 secs]", "[Full GC (Allocation Failure) ..."
+//           "[GC (Allocation Failure) ..." from the other collectors
+// JDK 9-11: "[info][gc] GC(5) Pause Initial Mark (G1 Humongous Allocation) 100M->90M(256M) 4.567ms"
+// JDK 12+:  "[info][gc] GC(5) Pause Young (Normal) (G1 Evacuation Pause) 100M->90M(256M) 4.567ms"
+// Pauses without a cause (Remark, Cleanup) are not counted
+pub fn parse_gc_cause(line: &str) -> Option<&str> {
+    for prefix in ["[GC pause (", "[Full GC (", "[GC ("] {
+        if let Some((_, rest)) = line.split_once(prefix) {
+            return parenthesized(rest);
+        }
+    }
+    let (_, rest) = line.split_once(" Pause ")?;
+    let mut rest = &rest[rest.find('(')? + 1..];
+    loop {
+        let group = parenthesized(rest)?;
+        if !PAUSE_TYPES.contains(&group) {
+            return Some(group);
+        }
+        rest = rest[group.len() + 1..].trim_start().strip_prefix('(')?;
+    }
+}
+
+// JDK 8:    "[GC pause (G1 Humongous Allocation) (young)", the young pauses that also start a concurrent cycle included
+// JDK 9-11: "Pause Young (G1 Evacuation Pause)" or "Pause Initial Mark (G1 Humongous Allocation)"
+// JDK 12+:  "Pause Young (Concurrent Start) (G1 Humongous Allocation)"
+fn is_young_pause(line: &str, cause: &str) -> bool {
+    match line.split_once("[GC pause (") {
+        Some((_, rest)) => rest[cause.len() + 1..].trim_start().starts_with("(young)"),
+        None => line.contains(" Pause Young ") || line.contains(" Pause Initial Mark "),
+    }
+}
+
+#[derive(Default)]
+pub struct GcCauses {
+    pub causes: BTreeMap<String, u64>,
+    // Young pauses of the logs (flight recordings have no pause type), and those a humongous allocation triggered
+    pub young_pauses: u64,
+    pub humongous_young_pauses: u64,
+    // Seconds of the young pauses whose duration is on their line, the start lines of -Xlog:gc+start add nothing
+    pub young_pause_time: ExactSum,
+    pub humongous_young_pause_time: ExactSum,
+}
+
+impl GcCauses {
+    // Whether the line was a pause with a cause
+    pub fn observe(&mut self, line: &str) -> bool {
+        let Some(cause) = parse_gc_cause(line) else { return false };
+        self.record(cause);
+        if is_young_pause(line, cause) {
+            self.young_pauses += 1;
+            self.humongous_young_pauses += u64::from(cause == HUMONGOUS_CAUSE);
+            let duration = Pause::parse(line, 0).duration.unwrap_or(0.0);
+            self.young_pause_time += duration;
+            if cause == HUMONGOUS_CAUSE {
+                self.humongous_young_pause_time += duration;
+            }
+        }
+        true
+    }
+
+    pub fn record(&mut self, cause: &str) {
+        *self.causes.entry(cause.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn merge(&mut self, other: GcCauses) {
+        for (cause, collections) in other.causes {
+            *self.causes.entry(cause).or_insert(0) += collections;
+        }
+        self.young_pauses += other.young_pauses;
+        self.humongous_young_pauses += other.humongous_young_pauses;
+        self.young_pause_time += other.young_pause_time;
+        self.humongous_young_pause_time += other.humongous_young_pause_time;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.causes.values().sum()
+    }
+
+    pub fn humongous(&self) -> u64 {
+        self.causes.get(HUMONGOUS_CAUSE).copied().unwrap_or(0)
+    }
+
+    // "12 of 340 (3.5%)", None without young pauses
+    pub fn humongous_young_share(&self) -> Option<String> {
+        (self.young_pauses > 0).then(|| format!("{} of {} ({:.1}%)", grouped(&self.humongous_young_pauses), grouped(&self.young_pauses), self.humongous_young_pauses as f64 * 100.0 / self.young_pauses as f64))
+    }
+
+    // "0.456s of 12.345s (3.7%)", the pause time the humongous allocations cost. None without pause durations.
+    pub fn humongous_young_time_share(&self) -> Option<String> {
+        let (humongous, total) = (self.humongous_young_pause_time.value(), self.young_pause_time.value());
+        (total > 0.0).then(|| format!("{:.3}s of {:.3}s ({:.1}%)", humongous, total, humongous * 100.0 / total))
+    }
+
+    // Most frequent first
+    pub fn rows(&self) -> Vec<GcCauseRow> {
+        let total = self.total().max(1) as f64;
+        let mut rows: Vec<GcCauseRow> = self
+            .causes
+            .iter()
+            .map(|(cause, collections)| GcCauseRow { cause: cause.clone(), collections: *collections, percentage: format!("{:.1}%", *collections as f64 * 100.0 / total) })
+            .collect();
+        rows.sort_by_key(|x| Reverse(x.collections));
+        rows
+    }
+}
@@ -0,0 +1,138 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::numbers::grouped;
+use crate::report::Record;
+
+pub struct CycleRow {
+    pub allocations: String,
+    pub cycles: u64,
+    pub percentage: String,
+}
+
+impl Record for CycleRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Humongous Allocations per Cycle", Justify::Right),
+        ("Cycles", Justify::Right),
+        ("% of Cycles", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.allocations.clone(), grouped(&self.cycles), self.percentage.clone()]
+    }
+}
+
+// A pause with a cause (see GcCauses::observe)
+pub struct Pause {
+    pub line: usize,
+    // The allocations of the file counted before the pause line
+    pub allocations: u64,
+    // "GC(5)" of unified logging, which prints a line at the start of a pause with -Xlog:gc+start as well as
+    // the one at its end
+    pub gc_id: Option<u64>,
+    // Seconds, when it is on the same line
+    pub duration: Option<f64>,
+}
+
+impl Pause {
+    // JDK 8:   "[GC pause (G1 Evacuation Pause) (young), 0.0534567 secs]"
+    // Unified: "[info][gc] GC(5) Pause Young (Normal) (G1 Evacuation Pause) 100M->90M(256M) 4.567ms"
+    pub fn parse(line: &str, line_number: usize) -> Pause {
+        let gc_id = line.split_once("GC(").and_then(|x| x.1.split_once(')')).and_then(|x| x.0.parse::<u64>().ok());
+        let line = line.trim_end();
+        let duration = match line.strip_suffix(" secs]") {
+            Some(rest) => rest.rsplit_once(", ").and_then(|x| x.1.parse::<f64>().ok()),
+            None => line.rsplit_once(' ').and_then(|x| x.1.strip_suffix("ms")).and_then(|x| x.parse::<f64>().ok()).map(|x| x / 1000.0),
+        };
+        Pause { line: line_number, allocations: 0, gc_id, duration }
+    }
+}
+
+// The number of allocations since the previous pause for every pause but the first, the lines of a pause
+// logged at its start and its end count once. An allocation at or before the line of a pause belongs to the
+// cycle it ends, the allocations before the first and after the last pause of the file are left out as
+// their cycle is incomplete.
+pub fn allocations_before_pauses(pauses: &[Pause]) -> Vec<(u64, Option<f64>)> {
+    let mut merged: Vec<(&Pause, Option<f64>)> = Vec::with_capacity(pauses.len());
+    for pause in pauses {
+        match merged.last_mut() {
+            Some((last, duration)) if pause.gc_id.is_some() && last.gc_id == pause.gc_id => *duration = duration.or(pause.duration),
+            _ => merged.push((pause, pause.duration)),
+        }
+    }
+    let mut previous = merged.first().map_or(0, |x| x.0.allocations);
+    let mut cycles = Vec::with_capacity(merged.len());
+    for (pause, duration) in merged.into_iter().skip(1) {
+        cycles.push((pause.allocations - previous, duration));
+        previous = pause.allocations;
+    }
+    cycles
+}
+
+// Allocation counts are grouped in power of two ranges: 0, 1, 2-3, 4-7, ...
+pub fn range_start(allocations: u64) -> u64 {
+    if allocations == 0 { 0 } else { 1 << allocations.ilog2() }
+}
+
+pub fn describe_range(start: u64) -> String {
+    if start < 2 { start.to_string() } else { format!("{}-{}", start, start * 2 - 1) }
+}
+
+// How many humongous allocations happen between consecutive pauses. Eager reclaim can only free the
+// humongous objects that are already dead at the next young collection, so a cycle with many of them
+// tends to leave regions behind.
+#[derive(Default)]
+pub struct AllocationsPerCycle {
+    // Allocations -> cycles with that many
+    pub counts: BTreeMap<u64, u64>,
+}
+
+impl AllocationsPerCycle {
+    pub fn record(&mut self, pauses: &[Pause]) {
+        for (count, _) in allocations_before_pauses(pauses) {
+            *self.counts.entry(count).or_insert(0) += 1;
+        }
+    }
+
+    // No cycle had a humongous allocation, or there were no complete cycles
+    pub fn is_empty(&self) -> bool {
+        self.counts.keys().all(|x| *x == 0)
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    pub fn rows(&self) -> Vec<CycleRow> {
+        let total = self.cycles().max(1) as f64;
+        let mut ranges: BTreeMap<u64, u64> = BTreeMap::new();
+        for (allocations, cycles) in &self.counts {
+            *ranges.entry(range_start(*allocations)).or_insert(0) += cycles;
+        }
+        ranges
+            .into_iter()
+            .map(|(start, cycles)| {
+                CycleRow { allocations: describe_range(start), cycles, percentage: format!("{:.1}%", cycles as f64 * 100.0 / total) }
+            })
+            .collect()
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let cycles = self.cycles();
+        let allocations: u64 = self.counts.iter().map(|(allocations, cycles)| allocations * cycles).sum();
+        let with_allocations = cycles - self.counts.get(&0).copied().unwrap_or(0);
+        // The count at or below which half of the cycles fall
+        let mut seen = 0;
+        let median = self.counts.iter().find(|(_, count)| {
+            seen += **count;
+            seen * 2 >= cycles
+        });
+        vec![
+            ("cycles", grouped(&cycles)),
+            ("cycles with humongous allocations", format!("{} ({:.1}%)", grouped(&with_allocations), with_allocations as f64 * 100.0 / cycles.max(1) as f64)),
+            ("avg allocations per cycle", format!("{:.1}", allocations as f64 / cycles.max(1) as f64)),
+            ("median allocations per cycle", grouped(&median.map_or(0, |x| *x.0))),
+            ("max allocations per cycle", grouped(&self.counts.keys().next_back().copied().unwrap_or(0))),
+        ]
+    }
+}
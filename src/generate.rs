@@ -0,0 +1,242 @@
+use std::io::{self, Write};
+
+use crate::buckets::MB;
+use crate::jvm_flags::format_size;
+use crate::timestamp::format_utc;
+
+// Where the generated logs start, a fixed time so the same --seed gives the same log
+const START: i64 = 1_704_067_200;
+// InitiatingHeapOccupancyPercent in the generated flags and allocation requests
+const IHOP: f64 = 0.45;
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+pub enum LogFormat {
+    // -XX:+PrintGCDetails -XX:+PrintAdaptiveSizePolicy with date and uptime stamps
+    Jdk8,
+    // JDK 17 -Xlog:gc*,gc+ergo+ihop=debug with the time and uptime decorations
+    Unified,
+}
+
+// An exact size, recurring like the arrays of a single allocation site, or a uniform range, each picked in
+// proportion to its weight
+#[derive(Clone, Debug)]
+pub struct SizeComponent {
+    min: u64,
+    max: u64,
+    weight: u64,
+}
+
+// "8m", "1m-32m", or either weighted as "8m:3"
+pub fn parse_size_component(component: &str) -> Result<SizeComponent, String> {
+    let (sizes, weight) = match component.split_once(':') {
+        Some((sizes, weight)) => (sizes, weight.parse::<u64>().ok().filter(|x| *x > 0).ok_or_else(|| format!("{} is not a valid weight", weight))?),
+        None => (component, 1),
+    };
+    let size = |x: &str| crate::jvm_flags::parse_size(x).filter(|x| *x > 0).ok_or_else(|| format!("{} is not a valid size", x));
+    let (min, max) = match sizes.split_once('-') {
+        Some((min, max)) => (size(min)?, size(max)?),
+        None => (size(sizes)?, size(sizes)?),
+    };
+    if min > max {
+        return Err(format!("{} is not a valid size range, the minimum is larger than the maximum", sizes));
+    }
+    Ok(SizeComponent { min, max, weight })
+}
+
+// G1 only requests a concurrent cycle for allocations of more than half a region, so the part of a size range
+// at or below that is left out and a size entirely below it is an error
+pub fn humongous_sizes(sizes: &[SizeComponent], region_size: u64) -> Result<Vec<SizeComponent>, String> {
+    let threshold = region_size / 2;
+    let text = |x: &SizeComponent| match x.min == x.max {
+        true => format_size(x.min),
+        false => format!("{}-{}", format_size(x.min), format_size(x.max)),
+    };
+    sizes
+        .iter()
+        .map(|x| match x.max > threshold {
+            true => Ok(SizeComponent { min: x.min.max(threshold + 1), ..x.clone() }),
+            false => Err(format!("{} is not humongous with {} regions, allocations have to be larger than {}", text(x), format_size(region_size), format_size(threshold))),
+        })
+        .collect()
+}
+
+// SplitMix64, good enough for test data and without a dependency
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // In [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn between(&mut self, min: u64, max: u64) -> u64 {
+        min + self.next() % (max - min + 1)
+    }
+
+    fn size(&mut self, sizes: &[SizeComponent]) -> u64 {
+        let mut pick = self.next() % sizes.iter().map(|x| x.weight).sum::<u64>();
+        for component in sizes {
+            if pick < component.weight {
+                return self.between(component.min, component.max);
+            }
+            pick -= component.weight;
+        }
+        unreachable!("the pick is below the total weight")
+    }
+
+    // Seconds to the next event of a Poisson process
+    fn wait(&mut self, per_second: f64) -> f64 {
+        -(1.0 - self.unit()).ln() / per_second
+    }
+}
+
+pub struct Generator<'a> {
+    pub format: LogFormat,
+    // Humongous allocations per minute on average
+    pub rate: f64,
+    pub sizes: &'a [SizeComponent],
+    // Seconds of uptime covered
+    pub duration: u64,
+    pub heap: u64,
+    pub region_size: u64,
+    pub seed: u64,
+}
+
+impl Generator<'_> {
+    fn datestamp(uptime: f64) -> String {
+        let millis = (uptime * 1000.0).round() as i64;
+        format!("{}.{:03}+0000", format_utc(START + millis / 1000).replace(' ', "T"), millis % 1000)
+    }
+
+    fn header(&self, out: &mut dyn Write) -> io::Result<()> {
+        let (heap_mb, region_mb) = (self.heap / MB, self.region_size / MB);
+        match self.format {
+            LogFormat::Jdk8 => {
+                writeln!(out, "{} GC log file created gc.log", format_utc(START))?;
+                writeln!(out, "OpenJDK 64-Bit Server VM (25.402-b06) for linux-amd64 JRE (1.8.0_402-b06), built on Jan 16 2024 by \"openjdk\" with gcc 7.3.1")?;
+                writeln!(out, "Memory: 4k page, physical 65802328k(2219164k free), swap 0k(0k free)")?;
+                writeln!(
+                    out,
+                    "CommandLine flags: -XX:G1HeapRegionSize={} -XX:InitialHeapSize={} -XX:InitiatingHeapOccupancyPercent={} -XX:MaxGCPauseMillis=500 -XX:MaxHeapSize={} -XX:+PrintAdaptiveSizePolicy -XX:+PrintGCDateStamps -XX:+PrintGCDetails -XX:+PrintGCTimeStamps -XX:+UseG1GC ",
+                    self.region_size,
+                    self.heap,
+                    (IHOP * 100.0) as u32,
+                    self.heap
+                )
+            }
+            LogFormat::Unified => {
+                let stamp = format!("[{}][0.010s]", Self::datestamp(0.01));
+                writeln!(out, "{}[info][gc,init] Version: 17.0.10+7 (release)", stamp)?;
+                writeln!(out, "{}[info][gc] Using G1", stamp)?;
+                writeln!(out, "{}[info][gc,init] Heap Region Size: {}M", stamp, region_mb)?;
+                writeln!(out, "{}[info][gc,init] Heap Min Capacity: {}M", stamp, heap_mb)?;
+                writeln!(out, "{}[info][gc,init] Heap Initial Capacity: {}M", stamp, heap_mb)?;
+                writeln!(out, "{}[info][gc,init] Heap Max Capacity: {}M", stamp, heap_mb)
+            }
+        }
+    }
+
+    fn allocation(&self, out: &mut dyn Write, uptime: f64, bytes: u64, occupancy: u64) -> io::Result<()> {
+        let threshold = (self.heap as f64 * IHOP) as u64;
+        match self.format {
+            LogFormat::Jdk8 => writeln!(
+                out,
+                "{}: {:.3}: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: {} bytes, allocation request: {} bytes, threshold: {} bytes ({:.2} %), source: concurrent humongous allocation]",
+                Self::datestamp(uptime),
+                uptime,
+                occupancy,
+                bytes,
+                threshold,
+                IHOP * 100.0
+            ),
+            LogFormat::Unified => writeln!(
+                out,
+                "[{}][{:.3}s][debug][gc,ergo,ihop] Request concurrent cycle initiation (occupancy higher than threshold) occupancy: {}B allocation request: {}B threshold: {}B ({:.2}) source: concurrent humongous allocation",
+                Self::datestamp(uptime),
+                uptime,
+                occupancy,
+                bytes,
+                threshold,
+                IHOP * 100.0
+            ),
+        }
+    }
+
+    fn pause(&self, out: &mut dyn Write, uptime: f64, number: u64, humongous: bool, (before, after): (u64, u64), millis: f64) -> io::Result<()> {
+        let (before, after, heap) = (before / MB, after / MB, self.heap / MB);
+        match self.format {
+            LogFormat::Jdk8 => {
+                let cause = if humongous { "(G1 Humongous Allocation) (young) (initial-mark)" } else { "(G1 Evacuation Pause) (young)" };
+                writeln!(out, "{}: {:.3}: [GC pause {}, {:.7} secs]", Self::datestamp(uptime), uptime, cause, millis / 1000.0)?;
+                let eden = before - after;
+                writeln!(out, "   [Eden: {}.0M({}.0M)->0.0B({}.0M) Survivors: 16.0M->16.0M Heap: {}.0M({}.0M)->{}.0M({}.0M)]", eden, eden, eden, before, heap, after, heap)
+            }
+            LogFormat::Unified => {
+                let (kind, cause) = if humongous { ("Concurrent Start", "G1 Humongous Allocation") } else { ("Normal", "G1 Evacuation Pause") };
+                writeln!(out, "[{}][{:.3}s][info][gc] GC({}) Pause Young ({}) ({}) {}M->{}M({}M) {:.3}ms", Self::datestamp(uptime), uptime, number, kind, cause, before, after, heap, millis)
+            }
+        }
+    }
+
+    // Allocations arrive at random at --rate, young pauses every 5 to 15 seconds and right after one in five
+    // allocations, with the heap filling up in between and the pauses bringing it back down
+    pub fn write(&self, out: &mut dyn Write) -> io::Result<()> {
+        let mut rng = Rng(self.seed);
+        self.header(out)?;
+        let per_second = self.rate / 60.0;
+        let (floor, threshold, ceiling) = (self.heap / 5, (self.heap as f64 * IHOP) as u64, self.heap * 9 / 10);
+        let mut occupancy = floor;
+        let mut next_allocation = 1.0 + rng.wait(per_second);
+        let mut next_pause = 1.0 + rng.between(5000, 15000) as f64 / 1000.0;
+        // Whether the next pause is one a humongous allocation caused
+        let mut humongous = false;
+        let mut pauses = 0;
+        loop {
+            let uptime = next_allocation.min(next_pause);
+            if uptime > self.duration as f64 {
+                return Ok(());
+            }
+            if next_allocation < next_pause {
+                let bytes = rng.size(self.sizes);
+                // Requests are only logged above the marking threshold
+                occupancy = (occupancy + bytes).max(threshold + rng.between(1, self.heap / 20)).min(ceiling);
+                self.allocation(out, uptime, bytes, occupancy)?;
+                next_allocation = uptime + rng.wait(per_second);
+                if !humongous && rng.between(1, 5) == 1 {
+                    next_pause = uptime + rng.between(1, 50) as f64 / 1000.0;
+                    humongous = true;
+                }
+            } else {
+                let before = (occupancy + rng.between(0, self.heap / 10)).min(ceiling);
+                let after = floor + rng.between(0, self.heap / 10);
+                self.pause(out, uptime, pauses, humongous, (before.max(after), after), rng.between(5000, 80000) as f64 / 1000.0)?;
+                occupancy = after;
+                humongous = false;
+                pauses += 1;
+                next_pause = uptime + rng.between(5000, 15000) as f64 / 1000.0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_humongous_sizes() {
+        let sizes = ["1m-32m:3", "8m"].map(|x| parse_size_component(x).unwrap());
+        let humongous = humongous_sizes(&sizes, 4 * MB).unwrap();
+        assert_eq!((humongous[0].min, humongous[0].max, humongous[0].weight), (2 * MB + 1, 32 * MB, 3));
+        assert_eq!((humongous[1].min, humongous[1].max), (8 * MB, 8 * MB));
+        assert!(humongous_sizes(&sizes, 16 * MB).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::report::Report;
+
+// A string gnuplot reads as a single column or title
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// A block per file (gnuplot's index) of the uptime and size of each allocation, allocations without an
+// uptime can't be placed on the time axis. The blocks are titled by their --input label, if any.
+fn write_allocations(path: &Path, reports: &[Report]) -> io::Result<Vec<String>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut files = Vec::new();
+    for file in reports.iter().flat_map(|x| &x.retained) {
+        if !files.is_empty() {
+            writeln!(out, "\n")?;
+        }
+        writeln!(out, "# {}\n# uptime_seconds bytes", file.name)?;
+        for allocation in &file.allocations {
+            if let Some(uptime) = allocation.uptime {
+                writeln!(out, "{:.3} {}", uptime, allocation.bytes)?;
+            }
+        }
+        files.push(file.label.clone().unwrap_or_else(|| file.name.clone()));
+    }
+    out.flush()?;
+    Ok(files)
+}
+
+// A block per report of the allocation count of each region size bucket
+fn write_buckets(path: &Path, reports: &[Report]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    for (index, report) in reports.iter().enumerate() {
+        if index > 0 {
+            writeln!(out, "\n")?;
+        }
+        writeln!(out, "# {}\n# region_size max_bytes allocations", report.title.as_deref().unwrap_or("all files"))?;
+        for bucket in &report.region_size_array {
+            writeln!(out, "{} {} {}", quote(&bucket.region_size), bucket.max_size, bucket.num_allocations)?;
+        }
+    }
+    out.flush()
+}
+
+// Plots both data files side by side into humongous.png, run from the directory
+fn write_script(path: &Path, reports: &[Report], files: &[String]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "# gnuplot humongous.gp, from this directory")?;
+    writeln!(out, "set terminal png size 1280,480")?;
+    writeln!(out, "set output 'humongous.png'")?;
+    writeln!(out, "set multiplot layout 1,2")?;
+    writeln!(out, "\nset title 'Humongous Allocations over Time'")?;
+    writeln!(out, "set xlabel 'Uptime (s)'\nset ylabel 'Bytes'\nset key outside bottom center")?;
+    let series: Vec<String> = files.iter().enumerate().map(|(index, file)| format!("'allocations.dat' index {} using 1:2 with points pointtype 7 pointsize 0.5 title {}", index, quote(file))).collect();
+    match series.is_empty() {
+        true => writeln!(out, "plot NaN notitle")?,
+        false => writeln!(out, "plot {}", series.join(", \\\n     "))?,
+    }
+    writeln!(out, "\nset title 'Allocations by Region Size'")?;
+    writeln!(out, "set xlabel 'Region Size'\nset ylabel 'Allocations'\nset style fill transparent solid 0.5\nset boxwidth 0.8")?;
+    let series: Vec<String> = reports
+        .iter()
+        .enumerate()
+        .map(|(index, report)| format!("'buckets.dat' index {} using 0:3:xtic(1) with boxes title {}", index, quote(report.title.as_deref().unwrap_or("all files"))))
+        .collect();
+    writeln!(out, "plot {}", series.join(", \\\n     "))?;
+    writeln!(out, "\nunset multiplot")?;
+    out.flush()
+}
+
+// allocations.dat (uptime vs bytes), buckets.dat (region size vs count) and a humongous.gp script plotting
+// them in the directory, replacing earlier exports
+pub fn export(dir: &Path, reports: &[Report]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let files = write_allocations(&dir.join("allocations.dat"), reports)?;
+    write_buckets(&dir.join("buckets.dat"), reports)?;
+    write_script(&dir.join("humongous.gp"), reports, &files)
+}
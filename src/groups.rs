@@ -0,0 +1,52 @@
+use cli_table::format::Justify;
+
+use crate::bins::display_size;
+use crate::buckets::format_region_size;
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::report::{GroupBy, Report};
+use crate::Cli;
+
+pub struct GroupRow {
+    pub group: String,
+    pub files: usize,
+    pub allocations: u64,
+    pub allocated_bytes: String,
+    pub p99: String,
+    pub recommendation: String,
+}
+
+impl Record for GroupRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Group", Justify::Left),
+        ("Files", Justify::Right),
+        ("Humongous Allocations", Justify::Right),
+        ("Allocated Bytes", Justify::Right),
+        ("p99 Size", Justify::Right),
+        ("Recommended Region Size", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.group.clone(), grouped(&self.files), grouped(&self.allocations), self.allocated_bytes.clone(), self.p99.clone(), self.recommendation.clone()]
+    }
+}
+
+// Reports split by region size, whether asked for or because the files differ, compared side by side before
+// their sections so a fleet's groups can be told apart at a glance. Not per file, which has its own summary.
+pub fn is_fleet(reports: &[Report], args: &Cli) -> bool {
+    reports.len() > 1 && !matches!(args.group_by, GroupBy::File)
+}
+
+pub fn rows(reports: &[Report], raw_bytes: bool) -> Vec<GroupRow> {
+    reports
+        .iter()
+        .map(|report| GroupRow {
+            group: report.title.clone().unwrap_or_default(),
+            files: report.file_summaries.len(),
+            allocations: report.size_stats.count,
+            allocated_bytes: display_size(report.size_stats.total_bytes, raw_bytes),
+            p99: report.percentiles(&[99.0]).get(1).map_or("-".to_string(), |x| display_size(x.1, raw_bytes)),
+            recommendation: report.recommendation().map_or("-".to_string(), |x| format_region_size(x.region_size)),
+        })
+        .collect()
+}
@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::upload::{self, Upload};
+use crate::Cli;
+
+mod proto {
+    tonic::include_proto!("gc_ho_parser");
+}
+
+use proto::analysis_server::{Analysis, AnalysisServer};
+use proto::{AnalysisReport, LogChunk};
+
+struct AnalysisService {
+    args: Arc<Cli>,
+}
+
+fn report_message(report: &crate::report::Report, upload: &Upload, args: &Cli) -> proto::Report {
+    let files = report.file_summaries.iter().map(|summary| {
+        let row = summary.row();
+        proto::FileSummary {
+            file: upload.relabel(&summary.file),
+            status: row.status.to_string(),
+            region_size_mb: summary.region_size.as_ref().map(|x| x.mb),
+            allocations: summary.allocations,
+            allocated_bytes: summary.allocated_bytes,
+            details: row.details.lines().map(str::to_string).collect(),
+        }
+    });
+    let buckets = report.region_size_array.iter().map(|x| proto::Bucket { region_size: x.region_size.clone(), max_size: x.max_size, allocations: x.num_allocations as u64 });
+    let percentiles = report.percentiles(&args.percentiles).into_iter().map(|(label, bytes)| proto::Percentile { label, bytes });
+    let recommendation = report.recommendation().map(|x| proto::Recommendation {
+        region_size_bytes: x.region_size,
+        coverage_percentage: x.coverage,
+        meets_target: x.meets_target,
+        summary: x.summary(),
+    });
+    proto::Report {
+        title: report.title.as_deref().map(|x| upload.relabel(x)),
+        files: files.collect(),
+        allocations: report.file_summaries.iter().map(|x| x.allocations).sum(),
+        allocated_bytes: report.file_summaries.iter().map(|x| x.allocated_bytes).sum(),
+        buckets: buckets.collect(),
+        percentiles: percentiles.collect(),
+        recommendation,
+        wasted_bytes: report.waste.wasted_bytes,
+        full_gcs: report.full_gc_rows.len() as u64,
+        evacuation_failures: report.evacuation_failures as u64,
+        mean_bytes: report.size_stats.mean(),
+        stddev_bytes: report.size_stats.stddev(),
+    }
+}
+
+fn analyze_part(part: &Path, name: Option<String>, args: &Cli) -> io::Result<AnalysisReport> {
+    let name = name.filter(|x| !x.is_empty()).unwrap_or_else(|| "gc.log".to_string());
+    let upload = Upload::save(BufReader::new(File::open(part)?), &name, u64::MAX)?;
+    let reports = upload.analyze(args);
+    Ok(AnalysisReport { reports: reports.iter().map(|x| report_message(x, &upload, args)).collect() })
+}
+
+#[tonic::async_trait]
+impl Analysis for AnalysisService {
+    // The chunks are written to a temporary file as they arrive, the analysis itself runs on a blocking thread
+    async fn analyze_log(&self, request: Request<Streaming<LogChunk>>) -> Result<Response<AnalysisReport>, Status> {
+        let mut chunks = request.into_inner();
+        let part = upload::temp_path().with_extension("part");
+        let mut file = File::create(&part).map_err(|e| Status::internal(format!("Unable to store the log: {}", e)))?;
+        let mut name = None;
+        let received = async {
+            while let Some(chunk) = chunks.message().await? {
+                name.get_or_insert(chunk.name);
+                file.write_all(&chunk.data).map_err(|e| Status::internal(format!("Unable to store the log: {}", e)))?;
+            }
+            Ok::<_, Status>(())
+        }
+        .await;
+        drop(file);
+        let args = self.args.clone();
+        let result = match received {
+            Ok(()) => tokio::task::spawn_blocking(move || {
+                let analyzed = analyze_part(&part, name, &args);
+                let _ = fs::remove_file(&part);
+                analyzed
+            })
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(|e| Status::invalid_argument(format!("Unable to read the log: {}", e))),
+            Err(status) => {
+                let _ = fs::remove_file(&part);
+                Err(status)
+            }
+        };
+        result.map(Response::new)
+    }
+}
+
+// Serves the AnalyzeLog service from proto/analysis.proto until the process is stopped
+pub fn serve(address: &str, args: Cli) -> Result<(), Box<dyn Error>> {
+    let address = address.parse()?;
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+    log::info!("Serving the AnalyzeLog gRPC service on {}", address);
+    let service = AnalysisServer::new(AnalysisService { args: Arc::new(args) });
+    runtime.block_on(Server::builder().add_service(service).serve(address))?;
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::buckets::MB;
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::AllocationTime;
+
+pub struct HeapOccupancyRow {
+    pub file: String,
+    pub window_start: String,
+    pub collections: u64,
+    pub max_before: u64,
+    pub avg_after: u64,
+    pub capacity: u64,
+    pub humongous: u64,
+    pub humongous_percentage: String,
+}
+
+impl Record for HeapOccupancyRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Window Start (s)", Justify::Right),
+        ("GCs", Justify::Right),
+        ("Max Heap Before GC (MB)", Justify::Right),
+        ("Avg Heap After GC (MB)", Justify::Right),
+        ("Heap Capacity (MB)", Justify::Right),
+        ("Humongous Allocated (MB)", Justify::Right),
+        ("Humongous % of Live", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.file.clone(),
+            self.window_start.clone(),
+            grouped(&self.collections),
+            grouped(&self.max_before),
+            grouped(&self.avg_after),
+            grouped(&self.capacity),
+            grouped(&self.humongous),
+            self.humongous_percentage.clone(),
+        ]
+    }
+}
+
+pub struct HeapSample {
+    pub uptime: f64,
+    pub before: u64,
+    pub after: u64,
+    pub capacity: u64,
+}
+
+// "5000.0M", "0.0B", "8192.0K" or "1.5G"
+pub fn parse_decimal_size(text: &str) -> Option<u64> {
+    let (number, unit) = text.split_at_checked(text.len().checked_sub(1)?)?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "K" => 1024.0,
+        "M" => MB as f64,
+        "G" => (1024 * MB) as f64,
+        _ => return None,
+    };
+    Some((number.parse::<f64>().ok()? * multiplier) as u64)
+}
+
+// "5000.0M(8192.0M)->4700.0M(8192.0M)" (JDK 8) or "100M->90M(256M)" (JDK 9+)
+fn parse_transition(text: &str) -> Option<(u64, u64, u64)> {
+    let (before, after) = text.split_once("->")?;
+    let before = before.split('(').next()?;
+    let (after, capacity) = after.split_once('(')?;
+    Some((parse_decimal_size(before)?, parse_decimal_size(after)?, parse_decimal_size(capacity.strip_suffix(')')?)?))
+}
+
+// JDK 8:  "   [Eden: 400.0M(408.0M)->0.0B(400.0M) Survivors: 8192.0K->16.0M Heap: 5000.0M(8192.0M)->4700.0M(8192.0M)]"
+// JDK 9+: "[10.289s][info][gc] GC(5) Pause Young (Normal) (G1 Evacuation Pause) 5000M->4700M(8192M) 53.456ms"
+// The JDK 8 line has no timestamp of its own, it belongs to the pause logged just before it
+pub fn parse_heap_occupancy(line: &str, uptime: Option<f64>) -> Option<HeapSample> {
+    let transition = match line.split_once(" Heap: ") {
+        Some((_, heap)) => heap.trim_end().strip_suffix(']')?,
+        None if line.contains(" Pause ") && line.ends_with("ms") => line.rsplit(' ').nth(1)?,
+        None => return None,
+    };
+    let (before, after, capacity) = parse_transition(transition)?;
+    Some(HeapSample { uptime: uptime?, before, after, capacity })
+}
+
+#[derive(Default)]
+struct Window {
+    collections: u64,
+    max_before: u64,
+    after: u64,
+    capacity: u64,
+    humongous: u64,
+}
+
+// Heap occupancy around the collections of a single file in fixed windows of `interval` seconds of uptime,
+// next to the humongous bytes allocated in the same window. Windows without a collection are omitted.
+pub fn build_occupancy_timeline(file: &str, samples: &[HeapSample], allocations: &[AllocationTime], interval: f64) -> Vec<HeapOccupancyRow> {
+    let mut windows: BTreeMap<u64, Window> = BTreeMap::new();
+    for sample in samples {
+        let window = windows.entry((sample.uptime / interval) as u64).or_default();
+        window.collections += 1;
+        window.max_before = window.max_before.max(sample.before);
+        window.after += sample.after;
+        window.capacity = window.capacity.max(sample.capacity);
+    }
+    for allocation in allocations {
+        if let Some(window) = windows.get_mut(&((allocation.uptime / interval) as u64)) {
+            window.humongous += allocation.bytes;
+        }
+    }
+    windows
+        .into_iter()
+        .map(|(index, window)| {
+            let avg_after = window.after / window.collections;
+            HeapOccupancyRow {
+                file: file.to_string(),
+                window_start: format!("{:.0}", index as f64 * interval),
+                collections: window.collections,
+                max_before: window.max_before / MB,
+                avg_after: avg_after / MB,
+                capacity: window.capacity / MB,
+                humongous: window.humongous / MB,
+                humongous_percentage: if avg_after == 0 { "-".to_string() } else { format!("{:.1}%", window.humongous as f64 * 100.0 / avg_after as f64) },
+            }
+        })
+        .collect()
+}
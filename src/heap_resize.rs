@@ -0,0 +1,99 @@
+use crate::bins::human_size;
+use crate::numbers::grouped;
+
+pub enum HeapSizing {
+    Expand(u64),
+    Shrink(u64),
+    // An allocation that found no free regions, G1 tries to expand the heap before collecting
+    HumongousAttempt,
+}
+
+pub struct HeapResize {
+    pub uptime: Option<f64>,
+    pub sizing: HeapSizing,
+}
+
+// Leading digits of a "46137344 bytes" or "46137344B" value after the last occurrence of label
+fn amount_after(line: &str, label: &str) -> Option<u64> {
+    let (_, rest) = line.rsplit_once(label)?;
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..end].parse::<u64>().ok()
+}
+
+// JDK 8:  "[G1Ergonomics (Heap Sizing) expand the heap, requested expansion amount: 44040192 bytes, attempted expansion amount: 46137344 bytes]"
+//         "[G1Ergonomics (Heap Sizing) shrink the heap, requested shrinking amount: ... bytes, aligned shrinking amount: ... bytes, attempted shrinking amount: 2147483648 bytes]"
+//         "[G1Ergonomics (Heap Sizing) attempt heap expansion, reason: humongous allocation request failed, allocation request: 33554448 bytes]"
+// JDK 9+: "[debug][gc,ergo,heap] GC(3) Expand the heap. requested expansion amount: 44040192B expansion amount: 46137344B"
+//         "[debug][gc,ergo,heap] GC(3) Shrink the heap. requested shrinking amount: ...B aligned shrinking amount: ...B attempted shrinking amount: 2147483648B"
+//         "[info][gc,ergo,heap] GC(3) Attempt heap expansion (humongous allocation request failed). Allocation request: 33554448B"
+pub fn parse_heap_resize(line: &str, uptime: Option<f64>) -> Option<HeapResize> {
+    let lower = line.to_ascii_lowercase();
+    let sizing = if lower.contains("expand the heap") && !lower.contains("did not expand the heap") {
+        HeapSizing::Expand(amount_after(line, "expansion amount: ")?)
+    } else if lower.contains("shrink the heap") {
+        HeapSizing::Shrink(amount_after(line, "attempted shrinking amount: ")?)
+    } else if lower.contains("attempt heap expansion") && lower.contains("humongous allocation request failed") {
+        HeapSizing::HumongousAttempt
+    } else {
+        return None;
+    };
+    Some(HeapResize { uptime, sizing })
+}
+
+// How often and how far G1 resized the heap, which with -Xms below -Xmx tends to follow humongous allocations
+#[derive(Default)]
+pub struct HeapResizes {
+    pub expansions: u64,
+    pub expanded_bytes: u64,
+    pub shrinks: u64,
+    pub shrunk_bytes: u64,
+    pub humongous_attempts: u64,
+}
+
+impl HeapResizes {
+    pub fn record(&mut self, resize: &HeapResize) {
+        match resize.sizing {
+            HeapSizing::Expand(bytes) => {
+                self.expansions += 1;
+                self.expanded_bytes += bytes;
+            }
+            HeapSizing::Shrink(bytes) => {
+                self.shrinks += 1;
+                self.shrunk_bytes += bytes;
+            }
+            HeapSizing::HumongousAttempt => self.humongous_attempts += 1,
+        }
+    }
+
+    // Folds in the resizes of the lines that follow
+    pub fn merge(&mut self, other: HeapResizes) {
+        self.expansions += other.expansions;
+        self.expanded_bytes += other.expanded_bytes;
+        self.shrinks += other.shrinks;
+        self.shrunk_bytes += other.shrunk_bytes;
+        self.humongous_attempts += other.humongous_attempts;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expansions + self.shrinks + self.humongous_attempts == 0
+    }
+
+    // "3 expansions (+96.0MB), 1 shrink (-2.0GB), 2 expansion attempts by humongous allocations"
+    pub fn summary(&self) -> String {
+        let plural = |count: u64, word: &str| format!("{} {}{}", grouped(&count), word, if count == 1 { "" } else { "s" });
+        format!(
+            "{} (+{}), {} (-{}), {} by humongous allocations",
+            plural(self.expansions, "expansion"),
+            human_size(self.expanded_bytes),
+            plural(self.shrinks, "shrink"),
+            human_size(self.shrunk_bytes),
+            plural(self.humongous_attempts, "expansion attempt")
+        )
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        (self.expansions > 0 && self.shrinks > 0).then(|| {
+            "The heap both grew and shrank: with -Xms equal to -Xmx G1 keeps the heap at its full size instead of resizing it under humongous pressure".to_string()
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::bins::display_size;
+use crate::buckets::MB;
+use crate::numbers::grouped;
+use crate::report::Report;
+
+fn percent_of(value: u64, whole: u64) -> f64 {
+    value as f64 * 100.0 / whole.max(1) as f64
+}
+
+// The humongous bytes relative to the configured heap (-Xmx), a 2GB footprint means little without knowing
+// whether the heap is 4GB or 64GB
+pub struct HeapShare {
+    pub heap: u64,
+    pub largest: Option<u64>,
+    // The most humongous bytes in use at a collection, from the region counts or the region level trace
+    pub peak: Option<u64>,
+    pub cumulative: u64,
+    // Regions the objects took and the regions of the heap, where the region size is known
+    pub regions: Option<(u64, u64)>,
+}
+
+impl HeapShare {
+    // None when no file has the heap size flags or nothing was humongous
+    pub fn new(report: &Report) -> Option<HeapShare> {
+        let heap = report.max_heap()?;
+        if report.size_stats.count == 0 {
+            return None;
+        }
+        let region_size = report.file_summaries.iter().find_map(|x| x.region_size.as_ref()).map(|x| x.mb.max(1) as u64 * MB);
+        let traced = region_size.map_or(0, |x| report.humongous_trace.peak_regions * x);
+        Some(HeapShare {
+            heap,
+            largest: report.percentiles(&[]).last().map(|x| x.1),
+            peak: Some(report.eager_reclaim.peak_bytes.max(traced)).filter(|x| *x > 0),
+            cumulative: report.size_stats.total_bytes,
+            regions: region_size.filter(|_| report.waste.regions > 0).map(|x| (report.waste.regions, heap / x)),
+        })
+    }
+
+    pub fn percent(&self, bytes: u64) -> f64 {
+        percent_of(bytes, self.heap)
+    }
+
+    pub fn stats(&self, raw_bytes: bool) -> Vec<(&'static str, String)> {
+        let share = |bytes: u64| format!("{} ({:.1}% of the heap)", display_size(bytes, raw_bytes), self.percent(bytes));
+        let mut stats = vec![("max heap size", display_size(self.heap, raw_bytes))];
+        if let Some(largest) = self.largest {
+            stats.push(("largest humongous object", share(largest)));
+        }
+        if let Some(peak) = self.peak {
+            stats.push(("peak humongous footprint", share(peak)));
+        }
+        stats.push(("cumulative humongous bytes", share(self.cumulative)));
+        if let Some((regions, heap_regions)) = self.regions {
+            stats.push(("regions consumed", format!("{} (the heap has {} regions, {:.1}%)", grouped(&regions), grouped(&heap_regions), percent_of(regions, heap_regions))));
+        }
+        stats
+    }
+}
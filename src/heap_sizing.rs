@@ -0,0 +1,140 @@
+use crate::bins::human_size;
+use crate::buckets::format_region_size;
+use crate::jvm_flags::format_size;
+use crate::report::Report;
+
+// Fewer regions than this leave G1 little choice of what to collect, it aims for 2048 when sizing them itself
+const MIN_REGIONS: u64 = 512;
+const DEFAULT_RESERVE_PERCENT: u64 = 10;
+const MAX_RESERVE_PERCENT: u64 = 50;
+// The lowest marking threshold suggested, 10 points below the current one
+const MIN_IHOP_PERCENT: f64 = 10.0;
+
+// A G1 setting from the CommandLine flags of the first file that has it
+fn configured(report: &Report, label: &str) -> Option<u64> {
+    report.file_summaries.iter().flat_map(|x| &x.jvm_configuration).find(|x| x.0 == label).and_then(|x| x.1.parse().ok())
+}
+
+// Whether the recommended region size alone takes care of the humongous allocations: it keeps the coverage
+// target, runs on any JDK and nothing ran out of space
+fn region_size_suffices(report: &Report) -> bool {
+    let recommended = report.recommendation().is_some_and(|x| x.meets_target && !x.requires_jdk18());
+    let full_gcs = report.full_gc_rows.iter().any(|x| x.preceding_allocations > 0);
+    recommended && report.evacuation_failures == 0 && !full_gcs
+}
+
+// A heap sizing change, with the flag that makes it where its value is known
+pub struct Tuning {
+    pub flag: Option<String>,
+    pub suggestion: String,
+}
+
+pub fn suggestions(report: &Report) -> Vec<String> {
+    tuning(report).into_iter().map(|x| x.suggestion).collect()
+}
+
+// Heap sizing changes that complement the region size recommendation, from the heap sizes, the occupancy at the
+// humongous allocations and the events that ran out of space. Empty when the region size is enough by itself.
+pub fn tuning(report: &Report) -> Vec<Tuning> {
+    if region_size_suffices(report) {
+        return Vec::new();
+    }
+    let mut changes = Vec::new();
+    let max_heap = report.max_heap();
+    if let (Some(recommendation), Some(max_heap)) = (report.recommendation(), max_heap) {
+        let regions = max_heap / recommendation.region_size;
+        if regions < MIN_REGIONS {
+            let heap = recommendation.region_size * MIN_REGIONS;
+            let suggestion = format!(
+                "With {} regions the {} heap only has {} of them: a heap of at least {} (-Xmx) keeps {} regions for G1 to choose from",
+                format_region_size(recommendation.region_size),
+                human_size(max_heap),
+                regions,
+                human_size(heap),
+                MIN_REGIONS
+            );
+            changes.push(Tuning { flag: Some(format!("-Xmx{}", format_size(heap))), suggestion });
+        }
+    }
+    let resizing = report.file_summaries.iter().any(|x| matches!((x.initial_heap, x.max_heap), (Some(initial), Some(max)) if initial < max));
+    if resizing || report.heap_resizes.humongous_attempts > 0 {
+        let size = max_heap.map_or(String::new(), |x| format!(" ({})", human_size(x)));
+        let attempts = match report.heap_resizes.humongous_attempts {
+            0 => String::new(),
+            attempts => format!(" ({} humongous allocations did)", attempts),
+        };
+        let suggestion = format!(
+            "Fix the heap size with -Xms equal to -Xmx{}: humongous allocations need contiguous free regions, which a heap below its maximum may have to expand for first{}",
+            size, attempts
+        );
+        changes.push(Tuning { flag: max_heap.map(|x| format!("-Xms{}", format_size(x))), suggestion });
+    }
+    if report.ihop.humongous_requests > 0 && report.ihop.max_humongous_occupancy >= 100.0 {
+        let ihop = report.ihop.threshold_percent.or_else(|| configured(report, "initiating heap occupancy percent").map(|x| x as f64));
+        let flag = ihop.filter(|x| *x > MIN_IHOP_PERCENT).map(|x| format!("-XX:InitiatingHeapOccupancyPercent={:.0}", (x - 10.0).max(MIN_IHOP_PERCENT)));
+        let suggestion = match ihop {
+            Some(ihop) => format!(
+                "Lower -XX:InitiatingHeapOccupancyPercent below {:.0}: humongous allocations take occupancy to {:.0}% of the heap, past the marking threshold, so marking has to start earlier to reclaim them in time",
+                ihop,
+                ihop * report.ihop.max_humongous_occupancy / 100.0
+            ),
+            None => "Lower -XX:InitiatingHeapOccupancyPercent: humongous allocations take occupancy past the marking threshold, so marking has to start earlier to reclaim them in time".to_string(),
+        };
+        changes.push(Tuning { flag, suggestion });
+    }
+    if report.evacuation_failures > 0 {
+        let reserve = configured(report, "reserve percent").unwrap_or(DEFAULT_RESERVE_PERCENT);
+        if reserve < MAX_RESERVE_PERCENT {
+            let raised = (reserve + 10).min(MAX_RESERVE_PERCENT);
+            let suggestion = format!(
+                "Raise -XX:G1ReservePercent from {} to {}: {} to-space exhausted events show evacuation running out of free regions next to the humongous ones",
+                reserve, raised, report.evacuation_failures
+            );
+            changes.push(Tuning { flag: Some(format!("-XX:G1ReservePercent={}", raised)), suggestion });
+        }
+    }
+    changes
+}
+
+// The recommended region size and the heap sizing changes as JVM flags, each after a comment on why, to paste
+// into a deployment config. None without humongous allocations.
+pub fn flags_snippet(report: &Report) -> Option<String> {
+    let recommendation = report.recommendation()?;
+    let mut lines = vec![format!("# {}", recommendation.summary())];
+    let flag = format!("-XX:G1HeapRegionSize={}", format_size(recommendation.region_size));
+    match recommendation.requires_jdk18() && !report.on_jdk18() {
+        true => lines.push(format!("# {} left out, region sizes above 32MB require JDK 18 or newer and the JDK release of the logs is unknown", flag)),
+        false => lines.push(flag),
+    }
+    for change in tuning(report) {
+        lines.push(format!("# {}", change.suggestion));
+        lines.extend(change.flag);
+    }
+    Some(lines.join("\n"))
+}
+
+// The flags alone, the region size only where the JDK takes it
+pub fn flags(report: &Report) -> Vec<String> {
+    let region_size = report.recommendation().filter(|x| !x.requires_jdk18() || report.on_jdk18()).map(|x| format!("-XX:G1HeapRegionSize={}", format_size(x.region_size)));
+    region_size.into_iter().chain(tuning(report).into_iter().filter_map(|x| x.flag)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buckets::MB;
+    use crate::file_summary::FileSummary;
+    use std::path::Path;
+
+    #[test]
+    fn writes_region_sizes_above_32mb_only_for_jdk18() {
+        let mut report = Report::new(&[], None);
+        (0..100).for_each(|_| report.candidates.observe(40 * MB));
+        report.file_summaries.push(FileSummary::new(Path::new("gc.log")));
+        assert!(!flags_snippet(&report).unwrap().lines().any(|x| x.starts_with("-XX:G1HeapRegionSize")));
+        report.file_summaries[0].jdk_version = Some(21);
+        assert!(flags_snippet(&report).unwrap().lines().any(|x| x == "-XX:G1HeapRegionSize=128m"));
+        report.file_summaries[0].jdk_version = Some(17);
+        assert!(flags_snippet(&report).unwrap().lines().any(|x| x == "-XX:G1HeapRegionSize=32m"));
+    }
+}
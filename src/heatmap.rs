@@ -0,0 +1,57 @@
+use cli_table::{format::Justify, Cell, CellStruct, Style, Table, TableStruct};
+use std::collections::BTreeMap;
+
+use crate::buckets::G1RegionBucket;
+use crate::numbers::grouped;
+use crate::report::Records;
+use crate::timestamp;
+
+// Shades of a heatmap cell in the terminal, from empty to the busiest cell
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+// Allocation counts per hour of the day of their datestamps and region size bucket, across all files, to spot
+// sizes that only show up at some hours (e.g. a nightly batch job)
+#[derive(Default)]
+pub struct Heatmap {
+    // Keyed by the bucket's region size, as larger empty buckets are trimmed after the files are read
+    counts: BTreeMap<String, [u64; 24]>,
+    // Allocations whose line had no datestamp (-XX:+PrintGCDateStamps)
+    pub undated: u64,
+}
+
+impl Heatmap {
+    pub fn record(&mut self, wall_clock: Option<i64>, bucket: &G1RegionBucket) {
+        match wall_clock {
+            Some(seconds) => self.counts.entry(bucket.region_size.clone()).or_insert([0; 24])[timestamp::hour_of_day(seconds)] += 1,
+            None => self.undated += 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    // The count of every hour of the day, one column per bucket
+    pub fn hours(&self, buckets: &[G1RegionBucket]) -> Vec<(String, Vec<u64>)> {
+        (0..24).map(|hour| (format!("{:02}:00", hour), buckets.iter().map(|x| self.counts.get(&x.region_size).map_or(0, |counts| counts[hour])).collect())).collect()
+    }
+
+    // Each cell shaded by its count relative to the busiest one
+    pub fn table(&self, buckets: &[G1RegionBucket]) -> (TableStruct, Records) {
+        let hours = self.hours(buckets);
+        let max = hours.iter().flat_map(|x| &x.1).copied().max().unwrap_or(0).max(1);
+        let rows: Vec<(String, Vec<(char, String)>)> = hours
+            .into_iter()
+            .map(|(hour, counts)| (hour, counts.into_iter().map(|count| (SHADES[((count * (SHADES.len() as u64 - 1)).div_ceil(max)) as usize], grouped(&count))).collect()))
+            .collect();
+        let cells: Vec<Vec<CellStruct>> = rows
+            .iter()
+            .map(|(hour, counts)| std::iter::once(hour.cell()).chain(counts.iter().map(|(shade, count)| format!("{}{} {:>5}", shade, shade, count).cell().justify(Justify::Right))).collect())
+            .collect();
+        // Without the padding that lines the counts up
+        let rows = rows.into_iter().map(|(hour, counts)| std::iter::once(hour).chain(counts.into_iter().map(|(shade, count)| format!("{}{} {}", shade, shade, count).trim_start().to_string())).collect()).collect();
+        let titles: Vec<String> = std::iter::once("Hour".to_string()).chain(buckets.iter().map(|x| x.region_size.clone())).collect();
+        let title = titles.iter().map(|x| x.cell().bold(true));
+        (cells.table().title(title), Records { titles, rows })
+    }
+}
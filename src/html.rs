@@ -0,0 +1,481 @@
+use std::io::{self, Write};
+
+use crate::bins::display_size;
+use crate::groups;
+use crate::heap_share::HeapShare;
+use crate::heap_sizing;
+use crate::numbers::grouped;
+use crate::report::{describe_interval, Report};
+use crate::safepoint;
+use crate::{Cli, Collector};
+
+const CHART_WIDTH: f64 = 760.0;
+const CHART_HEIGHT: f64 = 260.0;
+const CHART_MARGIN: f64 = 40.0;
+// Above this many bars only every n-th label is drawn so they don't overlap
+const MAX_CHART_LABELS: usize = 24;
+
+const STYLE: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;margin:2em auto;max-width:960px;color:#222}\
+h1{font-size:1.6em}h2{font-size:1.2em;margin-top:2em;border-bottom:1px solid #ddd;padding-bottom:.2em}\
+table{border-collapse:collapse;margin:.5em 0}th,td{border:1px solid #ccc;padding:.3em .7em;text-align:right}\
+th{background:#f3f3f3}td:first-child,th:first-child{text-align:left}\
+.recommendation{background:#eef7ee;border-left:4px solid #3a3;padding:.6em 1em}\
+svg text{font-size:11px;fill:#444}svg .bar{fill:#4a78b5}";
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut html = String::from("<table>\n<tr>");
+    for header in headers {
+        html.push_str(&format!("<th>{}</th>", escape(header)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", escape(&cell).replace('\n', "<br>")));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+// Inline SVG so the report has no external dependencies
+fn bar_chart(labels: &[String], values: &[f64]) -> String {
+    let max = values.iter().cloned().fold(0.0, f64::max).max(1.0);
+    let plot_width = CHART_WIDTH - 2.0 * CHART_MARGIN;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    let slot = plot_width / values.len().max(1) as f64;
+    let label_step = values.len().div_ceil(MAX_CHART_LABELS).max(1);
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n", CHART_WIDTH, CHART_HEIGHT);
+    svg.push_str(&format!(
+        "<line x1=\"{m}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#888\"/><text x=\"2\" y=\"{t}\">{max}</text><text x=\"2\" y=\"{b}\">0</text>\n",
+        m = CHART_MARGIN,
+        b = CHART_MARGIN + plot_height,
+        r = CHART_MARGIN + plot_width,
+        t = CHART_MARGIN,
+        max = max,
+    ));
+    for (i, (label, value)) in labels.iter().zip(values).enumerate() {
+        let height = value / max * plot_height;
+        let x = CHART_MARGIN + i as f64 * slot;
+        svg.push_str(&format!(
+            "<rect class=\"bar\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\"><title>{}: {}</title></rect>\n",
+            x + slot * 0.1,
+            CHART_MARGIN + plot_height - height,
+            (slot * 0.8).max(1.0),
+            height,
+            escape(label),
+            value,
+        ));
+        if i % label_step == 0 {
+            svg.push_str(&format!("<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n", x, CHART_MARGIN + plot_height + 15.0, escape(label)));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// A single document with one section per report
+pub fn write_html(reports: &[Report], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Humongous Allocation Report</title>\n<style>{}</style>\n</head>\n<body>", STYLE)?;
+    writeln!(out, "<h1>Humongous Allocation Report</h1>")?;
+    if groups::is_fleet(reports, args) {
+        writeln!(out, "<h2>Groups</h2>")?;
+        let rows = groups::rows(reports, args.raw_bytes).into_iter().map(|x| {
+            vec![x.group, grouped(&x.files), grouped(&x.allocations), x.allocated_bytes, x.p99, x.recommendation]
+        });
+        write!(out, "{}", table(&["Group", "Files", "Humongous Allocations", "Allocated Bytes", "p99 Size", "Recommended Region Size"], rows.collect()))?;
+    }
+    for report in reports {
+        write_report(report, out, args)?;
+    }
+    writeln!(out, "</body>\n</html>")?;
+    out.flush()
+}
+
+fn size_stats(out: &mut dyn Write, report: &Report, kind: &str, args: &Cli) -> io::Result<()> {
+    writeln!(out, "<h2>{} Statistics</h2>", kind)?;
+    let rows = report.size_stats.stats(args.raw_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+    write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))
+}
+
+fn write_report(report: &Report, out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    if let Some(title) = &report.title {
+        writeln!(out, "<h1>{}</h1>", escape(title))?;
+    }
+
+    let files = report.file_summaries.iter().map(|x| {
+        vec![x.file.clone(), x.region_size.as_ref().map_or("-".to_string(), |r| r.describe()), grouped(&x.allocations)]
+    });
+    write!(out, "{}", table(&["File", "Region Size", "Humongous Allocations"], files.collect()))?;
+    if let Some(size_filter) = report.size_filter(args) {
+        writeln!(out, "<p>{}</p>", escape(&size_filter))?;
+    }
+    if let Some(sampling) = &report.sampling {
+        writeln!(out, "<h2>Sampling</h2>")?;
+        writeln!(out, "<p>Counts are of the lines read, percentages and percentiles estimate the whole.</p>")?;
+        let rows = sampling.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+    }
+
+    for summary in report.file_summaries.iter().filter(|x| !x.jvm_configuration.is_empty()) {
+        writeln!(out, "<h2>JVM Configuration: {}</h2>", escape(&summary.name()))?;
+        let rows = summary.jvm_configuration.iter().map(|(label, value)| vec![label.to_string(), value.clone()]);
+        write!(out, "{}", table(&["Setting", "Value"], rows.collect()))?;
+    }
+
+    if let Some(recommendation) = report.recommendation() {
+        writeln!(out, "<h2>Recommendation</h2>\n<p class=\"recommendation\">{}</p>", escape(&recommendation.summary()))?;
+        if recommendation.requires_jdk18() {
+            writeln!(out, "<p>Region sizes above 32MB require JDK 18 or newer.</p>")?;
+        }
+    }
+    let suggestions = heap_sizing::suggestions(report);
+    if !suggestions.is_empty() {
+        writeln!(out, "<h2>Heap Sizing</h2>\n<ul>")?;
+        for suggestion in suggestions {
+            writeln!(out, "<li>{}</li>", escape(&suggestion))?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+    if report.candidates.total > 0 {
+        writeln!(out, "<h2>Candidate Region Sizes</h2>")?;
+        let rows = report.candidates.what_if(report.max_heap()).into_iter().map(|x| {
+            vec![x.region_size, grouped(&x.humongous), x.percentage, x.regions, x.notes.to_string()]
+        });
+        write!(out, "{}", table(&["Region Size", "Still Humongous", "% Still Humongous", "Regions in Heap", "Notes"], rows.collect()))?;
+    }
+
+    if let Collector::Cms = args.collector {
+        writeln!(out, "<h2>CMS Promotion Failures</h2>")?;
+        let rows = report.cms_failures.rows().into_iter().map(|x| vec![x.event.to_string(), grouped(&x.count)]);
+        write!(out, "{}", table(&["Event", "Count"], rows.collect()))?;
+        if report.cms_failures.sized_objects > 0 {
+            size_stats(out, report, "Promotion Failure Object Size", args)?;
+            writeln!(out, "<h2>Promotion Failure Object Size Percentiles</h2>")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            write!(out, "{}", table(&["Percentile", "Bytes"], rows.collect()))?;
+        }
+    } else if let Collector::Zgc = args.collector {
+        writeln!(out, "<h2>ZGC Pages per Collection ({} collections)</h2>", grouped(&report.zgc_pages.collections()))?;
+        let rows = report.zgc_pages.rows().into_iter().map(|x| {
+            vec![x.page_type.to_string(), x.avg_pages, grouped(&x.max_pages), x.avg_size, grouped(&x.max_size)]
+        });
+        write!(out, "{}", table(&["Page Type", "Avg Pages", "Max Pages", "Avg Size (MB)", "Max Size (MB)"], rows.collect()))?;
+        if report.zgc_pages.large_pages() > 0 {
+            size_stats(out, report, "Large Page Size", args)?;
+            writeln!(out, "<h2>Large Page Size Percentiles</h2>")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            write!(out, "{}", table(&["Percentile", "Bytes"], rows.collect()))?;
+        }
+    } else if let Collector::Shenandoah = args.collector {
+        writeln!(out, "<h2>Shenandoah Allocation Failures</h2>")?;
+        let rows = report.shenandoah.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if !report.shenandoah.humongous_sizes.is_empty() {
+            let rows = report.shenandoah.rows().into_iter().map(|x| vec![x.region_size, grouped(&x.allocations), x.cumulative_percentage]);
+            write!(out, "{}", table(&["Region Size", "Failed Humongous Allocations", "No Longer Humongous (cumulative %)"], rows.collect()))?;
+            size_stats(out, report, "Failed Humongous Allocation Size", args)?;
+            writeln!(out, "<h2>Failed Humongous Allocation Size Percentiles</h2>")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            write!(out, "{}", table(&["Percentile", "Bytes"], rows.collect()))?;
+        }
+    } else if let Collector::OpenJ9 = args.collector {
+        writeln!(out, "<h2>OpenJ9 Allocation Failures</h2>")?;
+        let rows = report.openj9.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if report.openj9.tenure_failures > 0 {
+            size_stats(out, report, "Tenure Allocation Failure Size", args)?;
+            writeln!(out, "<h2>Tenure Allocation Failure Size Percentiles</h2>")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            write!(out, "{}", table(&["Percentile", "Bytes"], rows.collect()))?;
+        }
+    } else if let Collector::Zing = args.collector {
+        writeln!(out, "<h2>Zing Large Object Allocations</h2>")?;
+        let rows = report.zing.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if report.zing.large_allocations > 0 {
+            size_stats(out, report, "Large Object Size", args)?;
+            writeln!(out, "<h2>Large Object Size Percentiles</h2>")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            write!(out, "{}", table(&["Percentile", "Bytes"], rows.collect()))?;
+        }
+    } else if report.candidates.total > 0 {
+        writeln!(out, "<h2>Allocations by Region Size</h2>")?;
+        let rows = report.region_size_array.iter().map(|x| {
+            vec![x.region_size.clone(), display_size(x.max_size, args.raw_bytes), grouped(&x.num_allocations), x.percentage.clone(), x.cumulative_percentage.clone()]
+        });
+        write!(out, "{}", table(&["Region Size", "Max Allocation Size (50%)", "Number of Allocations", "% of Allocations", "Cumulative %"], rows.collect()))?;
+        let labels: Vec<String> = report.region_size_array.iter().map(|x| x.region_size.clone()).collect();
+        let values: Vec<f64> = report.region_size_array.iter().map(|x| x.num_allocations as f64).collect();
+        write!(out, "{}", bar_chart(&labels, &values))?;
+
+        size_stats(out, report, "Allocation Size", args)?;
+        writeln!(out, "<h2>Allocation Size Percentiles</h2>")?;
+        let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+        write!(out, "{}", table(&["Percentile", "Bytes"], rows.collect()))?;
+    } else {
+        writeln!(out, "<p>No humongous allocations were identified in the provided data set.</p>")?;
+    }
+
+    if !report.timeline_rows.is_empty() {
+        writeln!(out, "<h2>Humongous Allocations over Time ({}s windows)</h2>", args.timeline_interval)?;
+        for summary in &report.file_summaries {
+            let rows: Vec<_> = report.timeline_rows.iter().filter(|x| x.file == summary.file).collect();
+            if rows.is_empty() {
+                continue;
+            }
+            writeln!(out, "<h3>{}</h3>", escape(&summary.name()))?;
+            let labels: Vec<String> = rows.iter().map(|x| format!("{}s", x.window_start)).collect();
+            let values: Vec<f64> = rows.iter().map(|x| x.allocations as f64).collect();
+            write!(out, "{}", bar_chart(&labels, &values))?;
+        }
+        if report.evacuation_failures > 0 || !report.heap_resizes.is_empty() {
+            if report.evacuation_failures > 0 {
+                writeln!(out, "<p>To-space exhausted events: {}</p>", grouped(&report.evacuation_failures))?;
+            }
+            if !report.heap_resizes.is_empty() {
+                writeln!(out, "<p>Heap resizes: {}</p>", escape(&report.heap_resizes.summary()))?;
+                if let Some(hint) = report.heap_resizes.hint() {
+                    writeln!(out, "<p>{}</p>", escape(&hint))?;
+                }
+            }
+            let rows = report.timeline_rows.iter().filter(|x| x.evacuation_failures > 0 || x.expanded > 0 || x.shrunk > 0).map(|x| {
+                vec![x.file.clone(), x.window_start.clone(), grouped(&x.allocations), grouped(&x.bytes), grouped(&x.evacuation_failures), grouped(&x.expanded), grouped(&x.shrunk)]
+            });
+            write!(out, "{}", table(&["File", "Window Start (s)", "Humongous Allocations", "Humongous Bytes", "To-space Exhausted", "Heap Expanded (MB)", "Heap Shrunk (MB)"], rows.collect()))?;
+        }
+    }
+
+    if let (Some(interval), false) = (args.interval, report.calendar.is_empty()) {
+        writeln!(out, "<h2>Humongous Allocations per {}</h2>", describe_interval(interval))?;
+        let rows = report.calendar.rows().into_iter().map(|x| vec![x.window_start, grouped(&x.allocations), grouped(&x.bytes), grouped(&x.p99)]);
+        write!(out, "{}", table(&["Window Start", "Humongous Allocations", "Humongous Bytes", "p99 Size"], rows.collect()))?;
+    }
+
+    if args.heatmap && !report.heatmap.is_empty() {
+        writeln!(out, "<h2>Humongous Allocations per Hour of the Day and Region Size</h2>")?;
+        let headers: Vec<&str> = std::iter::once("Hour").chain(report.region_size_array.iter().map(|x| x.region_size.as_str())).collect();
+        let rows = report.heatmap.hours(&report.region_size_array).into_iter().map(|(hour, counts)| std::iter::once(hour).chain(counts.iter().map(grouped)).collect());
+        write!(out, "{}", table(&headers, rows.collect()))?;
+    }
+
+    if !report.burst_rows.is_empty() {
+        writeln!(out, "<h2>Allocation Bursts (over {}x the average per {}s window)</h2>", args.burst_factor, args.timeline_interval)?;
+        let rows = report.burst_rows.iter().map(|x| {
+            vec![x.file.clone(), x.start.clone(), x.start_time.clone(), x.duration.clone(), grouped(&x.allocations), grouped(&x.bytes), x.factor.clone()]
+        });
+        write!(out, "{}", table(&["File", "Start (s)", "Start Time", "Duration (s)", "Humongous Allocations", "Humongous Bytes", "x Average"], rows.collect()))?;
+    }
+
+    if !report.inter_arrival.is_empty() {
+        writeln!(out, "<h2>Time Between Humongous Allocations</h2>")?;
+        let rows = report.inter_arrival.stats(&args.percentiles).into_iter().map(|(label, value)| vec![label, value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+    }
+
+    if !report.heap_occupancy_rows.is_empty() {
+        writeln!(out, "<h2>Heap Occupancy ({}s windows)</h2>", args.timeline_interval)?;
+        for summary in &report.file_summaries {
+            let rows: Vec<_> = report.heap_occupancy_rows.iter().filter(|x| x.file == summary.file).collect();
+            if rows.is_empty() {
+                continue;
+            }
+            writeln!(out, "<h3>{}: average heap after GC (MB)</h3>", escape(&summary.name()))?;
+            let labels: Vec<String> = rows.iter().map(|x| format!("{}s", x.window_start)).collect();
+            let values: Vec<f64> = rows.iter().map(|x| x.avg_after as f64).collect();
+            write!(out, "{}", bar_chart(&labels, &values))?;
+        }
+        let rows = report.heap_occupancy_rows.iter().map(|x| {
+            vec![
+                x.file.clone(),
+                x.window_start.clone(),
+                grouped(&x.collections),
+                grouped(&x.max_before),
+                grouped(&x.avg_after),
+                grouped(&x.capacity),
+                grouped(&x.humongous),
+                x.humongous_percentage.clone(),
+            ]
+        });
+        write!(out, "{}", table(&["File", "Window Start (s)", "GCs", "Max Heap Before GC (MB)", "Avg Heap After GC (MB)", "Heap Capacity (MB)", "Humongous Allocated (MB)", "Humongous % of Live"], rows.collect()))?;
+    }
+
+    if !report.safepoint_rows.is_empty() {
+        let (spikes, during_bursts) = safepoint::spikes(&report.safepoint_rows);
+        writeln!(out, "<h2>Safepoints ({}s windows)</h2>", args.timeline_interval)?;
+        writeln!(out, "<p>{} time to safepoint spikes over {}x the average, {} during allocation bursts</p>", grouped(&spikes), args.burst_factor, grouped(&during_bursts))?;
+        for summary in &report.file_summaries {
+            let rows: Vec<_> = report.safepoint_rows.iter().filter(|x| x.file == summary.file).collect();
+            if rows.is_empty() {
+                continue;
+            }
+            writeln!(out, "<h3>{}: max time to safepoint (ms)</h3>", escape(&summary.name()))?;
+            let labels: Vec<String> = rows.iter().map(|x| format!("{}s", x.window_start)).collect();
+            let values: Vec<f64> = rows.iter().map(|x| x.max_reaching.parse().unwrap_or(0.0)).collect();
+            write!(out, "{}", bar_chart(&labels, &values))?;
+        }
+        let rows = report.safepoint_rows.iter().map(|x| {
+            vec![
+                x.file.clone(),
+                x.window_start.clone(),
+                grouped(&x.safepoints),
+                x.max_reaching.clone(),
+                x.stopped.clone(),
+                grouped(&x.spikes),
+                grouped(&x.allocations),
+                x.burst.to_string(),
+            ]
+        });
+        write!(out, "{}", table(&["File", "Window Start (s)", "Safepoints", "Max Time to Safepoint (ms)", "Stopped (ms)", "Spikes", "Humongous Allocations", "Allocation Burst"], rows.collect()))?;
+    }
+
+    if args.top_sizes > 0 && !report.size_clusters.is_empty() {
+        writeln!(out, "<h2>Most Common Allocation Sizes</h2>")?;
+        let rows = report.size_clusters.rows(args.size_tolerance, args.top_sizes, args.raw_bytes).into_iter().map(|x| {
+            vec![x.size, x.range, grouped(&x.allocations), x.percentage, x.bytes, x.likely_object]
+        });
+        write!(out, "{}", table(&["Most Common Size", "Size Range", "Number of Allocations", "% of Allocations", "Humongous Bytes", "Likely Object"], rows.collect()))?;
+    }
+
+    if let (Some(top), false) = (args.size_frequency, report.size_clusters.is_empty()) {
+        writeln!(out, "<h2>Allocation Size Frequency</h2>")?;
+        let rows = report.size_clusters.frequency_rows(top).into_iter().map(|x| vec![x.size, grouped(&x.allocations), x.percentage]);
+        write!(out, "{}", table(&["Allocation Size (bytes)", "Number of Allocations", "% of Allocations"], rows.collect()))?;
+    }
+
+    if let (Some(width), false) = (args.bins, report.size_bins.is_empty()) {
+        writeln!(out, "<h2>Allocation Sizes ({} bins)</h2>", crate::bins::format_size(width))?;
+        let rows = report.size_bins.rows(width, args.bins_max);
+        let labels: Vec<String> = rows.iter().map(|x| x.range.clone()).collect();
+        let values: Vec<f64> = rows.iter().map(|x| x.allocations as f64).collect();
+        write!(out, "{}", bar_chart(&labels, &values))?;
+        let rows = rows.into_iter().map(|x| vec![x.range, grouped(&x.allocations), x.percentage]);
+        write!(out, "{}", table(&["Allocation Size", "Number of Allocations", "% of Allocations"], rows.collect()))?;
+    }
+
+    if report.waste.objects > 0 {
+        writeln!(out, "<h2>Humongous Region Waste</h2>")?;
+        let rows = report.waste.stats(args.raw_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+    }
+
+    if let Some(heap_share) = HeapShare::new(report) {
+        writeln!(out, "<h2>Humongous Share of Heap</h2>")?;
+        let rows = heap_share.stats(args.raw_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+    }
+
+    if !report.full_gc_rows.is_empty() {
+        writeln!(out, "<h2>Full GC Events (humongous allocations within {}s before)</h2>", args.full_gc_window)?;
+        let rows = report.full_gc_rows.iter().map(|x| {
+            vec![x.file.clone(), x.uptime.clone(), x.cause.clone(), x.duration.clone(), grouped(&x.preceding_allocations)]
+        });
+        write!(out, "{}", table(&["File", "Uptime (s)", "Cause", "Duration (s)", "Preceding Humongous Allocations"], rows.collect()))?;
+    }
+
+    if !report.gc_causes.causes.is_empty() {
+        writeln!(out, "<h2>GC Causes ({} forced by humongous allocation)</h2>", grouped(&report.gc_causes.humongous()))?;
+        if let Some(share) = report.gc_causes.humongous_young_share() {
+            writeln!(out, "<p>Young pauses triggered by humongous allocation: {}</p>", share)?;
+        }
+        if let Some(share) = report.gc_causes.humongous_young_time_share() {
+            writeln!(out, "<p>Young pause time of those triggered by humongous allocation: {}</p>", share)?;
+        }
+        let rows = report.gc_causes.rows().into_iter().map(|x| vec![x.cause, grouped(&x.collections), x.percentage]);
+        write!(out, "{}", table(&["Cause", "Collections", "% of Collections"], rows.collect()))?;
+    }
+
+    if let Some(cumulative) = &report.cumulative {
+        writeln!(out, "<h2>Cumulative Totals ({} runs)</h2>", grouped(&cumulative.runs))?;
+        let rows = report.cumulative_stats(cumulative, args).into_iter().map(|(label, value)| vec![label, value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+    }
+
+    if !report.allocations_per_cycle.is_empty() {
+        writeln!(out, "<h2>Humongous Allocations per GC Cycle</h2>")?;
+        let rows = report.allocations_per_cycle.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        let rows = report.allocations_per_cycle.rows().into_iter().map(|x| vec![x.allocations, grouped(&x.cycles), x.percentage]);
+        write!(out, "{}", table(&["Humongous Allocations per Cycle", "Cycles", "% of Cycles"], rows.collect()))?;
+    }
+
+    if !report.pause_impact.is_empty() {
+        writeln!(out, "<h2>Pause Impact of Humongous Allocations</h2>")?;
+        let rows = report.pause_impact.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        let rows = report.pause_impact.rows().into_iter().map(|x| vec![x.allocations, grouped(&x.pauses), x.avg_pause, x.max_pause]);
+        write!(out, "{}", table(&["Humongous Allocations Since Previous Pause", "Pauses", "Avg Pause (ms)", "Max Pause (ms)"], rows.collect()))?;
+    }
+
+    if !report.concurrent_cycles.triggers.is_empty() {
+        writeln!(out, "<h2>Concurrent Cycle Triggers</h2>")?;
+        let rows = report.concurrent_cycles.rows().into_iter().map(|x| vec![x.trigger, grouped(&x.cycles)]);
+        write!(out, "{}", table(&["Trigger", "Concurrent Cycles"], rows.collect()))?;
+    }
+
+    if report.cycle_durations.cycles() > 0 {
+        writeln!(out, "<h2>Concurrent Cycle Durations ({} cycles)</h2>", grouped(&report.cycle_durations.cycles()))?;
+        let rows = report.cycle_durations.stats().into_iter().map(|(label, value)| vec![label, value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if let Some(hint) = report.cycle_durations.hint() {
+            writeln!(out, "<p>{}</p>", escape(&hint))?;
+        }
+    }
+
+    if report.ihop.requests > 0 || report.ihop.adaptive_updates > 0 {
+        writeln!(out, "<h2>Marking Threshold (IHOP)</h2>")?;
+        let rows = report.ihop.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if let Some(hint) = report.ihop.hint() {
+            writeln!(out, "<p>{}</p>", escape(&hint))?;
+        }
+    }
+
+    if report.allocation_rate.collections > 0 {
+        writeln!(out, "<h2>Allocation Rate</h2>")?;
+        let rows = report.allocation_rate.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if let Some(hint) = report.allocation_rate.hint() {
+            writeln!(out, "<p>{}</p>", escape(&hint))?;
+        }
+    }
+
+    if report.eager_reclaim.collections > 0 {
+        writeln!(out, "<h2>Humongous Eager Reclaim ({} collections)</h2>", grouped(&report.eager_reclaim.collections))?;
+        let rows = report.eager_reclaim.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+    }
+
+    if report.mixed_collections.collections > 0 {
+        writeln!(out, "<h2>Mixed Collections</h2>")?;
+        let rows = report.mixed_collections.stats(report.size_stats.total_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+        if let Some(hint) = report.mixed_collections.hint(report.size_stats.total_bytes) {
+            writeln!(out, "<p>{}</p>", escape(&hint))?;
+        }
+    }
+
+    if report.humongous_trace.objects > 0 {
+        writeln!(out, "<h2>Humongous Region Trace</h2>\n<p>{}</p>", escape(&report.humongous_trace.summary()))?;
+        let rows = report.humongous_trace.rows().into_iter().map(|x| vec![grouped(&x.regions), grouped(&x.objects)]);
+        write!(out, "{}", table(&["Regions per Object", "Humongous Objects"], rows.collect()))?;
+    }
+
+    writeln!(out, "<h2>Parse Quality</h2>")?;
+    let rows = report.parse_quality(args).stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+    write!(out, "{}", table(&["Statistic", "Value"], rows.collect()))?;
+
+    if report.file_summaries.iter().any(|x| x.has_issues()) {
+        writeln!(out, "<h2>File Summary</h2>")?;
+        let rows = report.file_summaries.iter().map(|x| x.row()).map(|x| {
+            vec![x.file, x.status.to_string(), grouped(&x.allocations), grouped(&x.skipped_lines), x.details]
+        });
+        write!(out, "{}", table(&["File", "Status", "Humongous Allocations", "Skipped Lines", "Details"], rows.collect()))?;
+    }
+    Ok(())
+}
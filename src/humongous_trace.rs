@@ -0,0 +1,123 @@
+use cli_table::format::Justify;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::numbers::grouped;
+use crate::report::Record;
+
+pub struct RegionCountRow {
+    pub regions: u64,
+    pub objects: u64,
+}
+
+impl Record for RegionCountRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Regions per Object", Justify::Right),
+        ("Humongous Objects", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![grouped(&self.regions), grouped(&self.objects)]
+    }
+}
+
+// Humongous objects seen in the region level trace (-XX:+G1TraceEagerReclaimHumongousObjects on JDK 8,
+// -Xlog:gc+humongous=debug since JDK 9), which lists every humongous object at every young collection
+#[derive(Default)]
+pub struct HumongousTrace {
+    // Start region -> object size of the objects that are still alive
+    live: HashMap<u32, u64>,
+    // Start region -> object size and regions of the first object listed at each region, to recognize
+    // objects that were already alive before these lines when merging
+    first_seen: HashMap<u32, (u64, u64)>,
+    // Footprint of the collection currently being traced, the lines of a collection are consecutive
+    current_regions: Option<u64>,
+    pub objects: u64,
+    pub regions: u64,
+    pub peak_regions: u64,
+    pub region_counts: BTreeMap<u64, u64>,
+}
+
+// JDK 8 / 9+: "Live humongous region 5 object size 8388624 start 0x00000005c0000000 with remset 0 code roots 0 is marked 0 reclaim candidate 1 type array 1"
+//             "Dead humongous region 5 object size 8388624 start 0x00000005c0000000 with remset 0 ..."
+// JDK 17+:    "Humongous region 5 (object size 8388624 @ 0x00000005c0000000) remset 0 code roots 0 marked 0 pinned count 0 reclaim candidate 1 type array 1"
+//             "Reclaimed humongous region 5 (object size 8388624 @ 0x00000005c0000000)"
+fn parse_trace(line: &str) -> Option<(u32, u64, bool)> {
+    let (before, rest) = line.split_once("umongous region ")?;
+    let dead = before.ends_with("Dead h") || before.ends_with("Reclaimed h");
+    let (region, rest) = rest.split_once(' ')?;
+    let size = rest.trim_start_matches('(').strip_prefix("object size ")?.split(' ').next()?;
+    Some((region.parse::<u32>().ok()?, size.parse::<u64>().ok()?, dead))
+}
+
+impl HumongousTrace {
+    pub fn observe(&mut self, line: &str, region_size: u64) {
+        let Some((region, size, dead)) = parse_trace(line) else {
+            if let Some(regions) = self.current_regions.take() {
+                self.peak_regions = self.peak_regions.max(regions);
+            }
+            return;
+        };
+        let regions = size.div_ceil(region_size);
+        *self.current_regions.get_or_insert(0) += regions;
+        // Objects are listed again at every collection for as long as they are alive
+        if self.live.get(&region) != Some(&size) {
+            self.objects += 1;
+            self.regions += regions;
+            *self.region_counts.entry(regions).or_insert(0) += 1;
+            self.live.insert(region, size);
+            self.first_seen.entry(region).or_insert((size, regions));
+        }
+        if dead {
+            self.live.remove(&region);
+        }
+    }
+
+    // An object whose regions are known up front, e.g. from the region type changes of a flight recording
+    pub fn record(&mut self, regions: u64) {
+        self.objects += 1;
+        self.regions += regions;
+        *self.region_counts.entry(regions).or_insert(0) += 1;
+    }
+
+    pub fn reset(&mut self) {
+        self.live.clear();
+        self.first_seen.clear();
+        if let Some(regions) = self.current_regions.take() {
+            self.peak_regions = self.peak_regions.max(regions);
+        }
+    }
+
+    // The later lines count the objects that were alive at their start again, unless they were listed with another size
+    pub fn merge(&mut self, other: HumongousTrace) {
+        self.objects += other.objects;
+        self.regions += other.regions;
+        for (regions, objects) in other.region_counts {
+            *self.region_counts.entry(regions).or_insert(0) += objects;
+        }
+        for (region, (size, regions)) in other.first_seen {
+            if self.live.remove(&region) == Some(size) {
+                self.objects -= 1;
+                self.regions -= regions;
+                *self.region_counts.get_mut(&regions).unwrap() -= 1;
+            }
+            self.first_seen.entry(region).or_insert((size, regions));
+        }
+        self.region_counts.retain(|_, objects| *objects > 0);
+        self.live.extend(other.live);
+        self.peak_regions = self.peak_regions.max(other.peak_regions).max(self.current_regions.unwrap_or(0));
+        self.current_regions = other.current_regions;
+    }
+
+    pub fn rows(&self) -> Vec<RegionCountRow> {
+        self.region_counts.iter().map(|(regions, objects)| RegionCountRow { regions: *regions, objects: *objects }).collect()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} humongous objects occupying {} regions in total, at most {} regions in a single collection",
+            grouped(&self.objects),
+            grouped(&self.regions),
+            grouped(&self.peak_regions)
+        )
+    }
+}
@@ -0,0 +1,151 @@
+use crate::buckets::MB;
+use crate::numbers::{grouped, ExactSum};
+
+// Occupancy at which humongous allocations arrive, relative to the marking threshold beyond it is far too late
+const LATE_MARKING_PERCENT: f64 = 150.0;
+
+struct IhopSample {
+    occupancy: u64,
+    request: u64,
+    threshold: u64,
+    // Of the heap, G1 reports it alongside the threshold
+    threshold_percent: Option<f64>,
+    humongous: bool,
+}
+
+// Leading digits of a "6408896512 bytes" or "6408896512B" value
+fn parse_bytes(text: &str) -> Option<u64> {
+    let end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    text[..end].parse::<u64>().ok()
+}
+
+fn value_after<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    line.split_once(label).map(|x| x.1)
+}
+
+// JDK 8:  "[G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: 6408896512 bytes,
+//          allocation request: 44325405 bytes, threshold: 3865470555 bytes (45.00 %), source: concurrent humongous allocation]"
+// JDK 9+: "[debug][gc,ergo,ihop] Request concurrent cycle initiation (occupancy higher than threshold) occupancy: 6408896512B
+//          allocation request: 44325405B threshold: 3865470555B (45.00) source: concurrent humongous allocation"
+fn parse_ihop_sample(line: &str) -> Option<IhopSample> {
+    if !line.contains("equest concurrent cycle initiation") {
+        return None;
+    }
+    let threshold = value_after(line, "threshold: ")?;
+    let threshold_percent = threshold.split_once('(').and_then(|x| x.1.split([' ', ')']).next()?.parse::<f64>().ok());
+    Some(IhopSample {
+        occupancy: parse_bytes(value_after(line, "occupancy: ")?)?,
+        request: parse_bytes(value_after(line, "allocation request: ")?)?,
+        threshold: parse_bytes(threshold)?,
+        threshold_percent,
+        humongous: line.contains("source: concurrent humongous allocation"),
+    })
+}
+
+// JDK 9+ with G1UseAdaptiveIHOP: "[debug][gc,ihop] GC(5) Adaptive IHOP information (value update), threshold: 3865470555B (45.00), ..."
+fn parse_adaptive_threshold(line: &str) -> Option<u64> {
+    let (_, rest) = line.split_once("Adaptive IHOP information (value update), threshold: ")?;
+    parse_bytes(rest)
+}
+
+// How close humongous allocations push the heap to the marking threshold (InitiatingHeapOccupancyPercent)
+#[derive(Default)]
+pub struct IhopAnalysis {
+    pub requests: u64,
+    pub humongous_requests: u64,
+    // Humongous allocations arriving when occupancy was already at or above the threshold
+    pub humongous_above_threshold: u64,
+    // Humongous allocations that took occupancy from below the threshold to at or above it
+    pub humongous_crossings: u64,
+    // Occupancy after the allocation as a percentage of the threshold
+    humongous_occupancy_sum: ExactSum,
+    pub max_humongous_occupancy: f64,
+    pub threshold: Option<u64>,
+    pub threshold_percent: Option<f64>,
+    pub adaptive_updates: u64,
+    pub adaptive_range: Option<(u64, u64)>,
+}
+
+impl IhopAnalysis {
+    pub fn observe(&mut self, line: &str) {
+        if let Some(threshold) = parse_adaptive_threshold(line) {
+            self.adaptive_updates += 1;
+            self.adaptive_range = Some(self.adaptive_range.map_or((threshold, threshold), |(min, max)| (min.min(threshold), max.max(threshold))));
+            return;
+        }
+        let Some(sample) = parse_ihop_sample(line) else { return };
+        self.requests += 1;
+        self.threshold = Some(sample.threshold);
+        self.threshold_percent = sample.threshold_percent.or(self.threshold_percent);
+        if !sample.humongous || sample.threshold == 0 {
+            return;
+        }
+        self.humongous_requests += 1;
+        if sample.occupancy >= sample.threshold {
+            self.humongous_above_threshold += 1;
+        } else if sample.occupancy + sample.request >= sample.threshold {
+            self.humongous_crossings += 1;
+        }
+        let occupancy = (sample.occupancy + sample.request) as f64 * 100.0 / sample.threshold as f64;
+        self.humongous_occupancy_sum += occupancy;
+        self.max_humongous_occupancy = self.max_humongous_occupancy.max(occupancy);
+    }
+
+    // Folds in the analysis of the lines that follow
+    pub fn merge(&mut self, other: IhopAnalysis) {
+        self.requests += other.requests;
+        self.humongous_requests += other.humongous_requests;
+        self.humongous_above_threshold += other.humongous_above_threshold;
+        self.humongous_crossings += other.humongous_crossings;
+        self.humongous_occupancy_sum += other.humongous_occupancy_sum;
+        self.max_humongous_occupancy = self.max_humongous_occupancy.max(other.max_humongous_occupancy);
+        self.threshold = other.threshold.or(self.threshold);
+        self.threshold_percent = other.threshold_percent.or(self.threshold_percent);
+        self.adaptive_updates += other.adaptive_updates;
+        self.adaptive_range = match (self.adaptive_range, other.adaptive_range) {
+            (Some((min, max)), Some((other_min, other_max))) => Some((min.min(other_min), max.max(other_max))),
+            (range, other_range) => range.or(other_range),
+        };
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let mut stats = vec![("concurrent cycle requests", grouped(&self.requests))];
+        if let Some(threshold) = self.threshold {
+            let percent = self.threshold_percent.map_or(String::new(), |x| format!(" ({:.2}% of the heap)", x));
+            stats.push(("marking threshold", format!("{}MB{}", grouped(&(threshold / MB)), percent)));
+        }
+        if let Some((min, max)) = self.adaptive_range {
+            stats.push(("adaptive IHOP updates", grouped(&self.adaptive_updates)));
+            stats.push(("adaptive threshold range", format!("{}MB - {}MB", grouped(&(min / MB)), grouped(&(max / MB)))));
+        }
+        if self.humongous_requests > 0 {
+            stats.push(("humongous allocation requests", grouped(&self.humongous_requests)));
+            stats.push(("already above the threshold", grouped(&self.humongous_above_threshold)));
+            stats.push(("pushed over the threshold", grouped(&self.humongous_crossings)));
+            stats.push(("avg occupancy after allocation (% of threshold)", format!("{:.1}%", self.humongous_occupancy_sum.value() / self.humongous_requests as f64)));
+            stats.push(("max occupancy after allocation (% of threshold)", format!("{:.1}%", self.max_humongous_occupancy)));
+        }
+        stats
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        if self.humongous_requests == 0 {
+            return None;
+        }
+        if self.max_humongous_occupancy >= LATE_MARKING_PERCENT {
+            Some(format!(
+                "Humongous allocations push occupancy to {:.0}% of the marking threshold, marking starts too late to keep up: lower InitiatingHeapOccupancyPercent, or use a larger region size so fewer allocations are humongous",
+                self.max_humongous_occupancy
+            ))
+        } else if self.humongous_crossings > 0 {
+            Some(format!(
+                "{} humongous allocations pushed occupancy over the marking threshold by themselves, starting concurrent cycles early: a larger region size keeps them out of the humongous regions",
+                self.humongous_crossings
+            ))
+        } else if self.humongous_above_threshold * 2 >= self.humongous_requests {
+            Some("Most humongous allocations arrive above the marking threshold: if the old generation has headroom, raising InitiatingHeapOccupancyPercent avoids back-to-back concurrent cycles".to_string())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::report::Report;
+use crate::timestamp;
+
+const NANOS: i64 = 1_000_000_000;
+
+// Commas, equals signs and spaces delimit tags, measurement names only need the commas and spaces escaped
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
+
+// $HOSTNAME isn't exported by every shell, /etc/hostname covers the rest
+fn host() -> Option<String> {
+    env::var("HOSTNAME").ok().or_else(|| fs::read_to_string("/etc/hostname").ok()).map(|x| x.trim().to_string()).filter(|x| !x.is_empty())
+}
+
+fn tags(host: Option<&str>, pairs: &[(&str, Option<&str>)]) -> String {
+    let host = host.map(|x| ("host", Some(x)));
+    host.iter().chain(pairs).filter_map(|(key, value)| value.map(|x| format!(",{}={}", key, escape(x)))).collect()
+}
+
+// The allocation count and bytes of each file per --interval window, placed at the start of the window. Only
+// windows with allocations get a point, an absent point reads as zero in Influx's aggregations.
+fn interval_lines(out: &mut Vec<String>, report: &Report, host: Option<&str>, interval: i64) {
+    for file in &report.retained {
+        let mut windows: BTreeMap<i64, (u64, u64)> = BTreeMap::new();
+        for allocation in &file.allocations {
+            if let Some(seconds) = allocation.wall_clock {
+                let window = windows.entry(timestamp::window_start(seconds, interval)).or_default();
+                window.0 += 1;
+                window.1 += allocation.bytes;
+            }
+        }
+        let tags = tags(host, &[("file", Some(&file.name)), ("label", file.label.as_deref()), ("report", report.title.as_deref())]);
+        for (start, (allocations, bytes)) in windows {
+            out.push(format!("humongous_allocations{} allocations={}i,bytes={}i {}", tags, allocations, bytes, start * NANOS));
+        }
+    }
+}
+
+// A point per file of its totals and a point per report of the size statistics, at the time of the analysis
+fn summary_lines(out: &mut Vec<String>, report: &Report, host: Option<&str>, percentiles: &[f64], now: i64) {
+    for summary in report.file_summaries.iter().filter(|x| x.error.is_none()) {
+        let tags = tags(host, &[("file", Some(&summary.file)), ("label", summary.label.as_deref()), ("report", report.title.as_deref())]);
+        let mut fields = vec![format!("allocations={}i", summary.allocations), format!("bytes={}i", summary.allocated_bytes), format!("evacuation_failures={}i", summary.evacuation_failures)];
+        if let Some(region_size) = &summary.region_size {
+            fields.push(format!("region_size_mb={}i", region_size.mb));
+        }
+        out.push(format!("humongous_file{} {} {}", tags, fields.join(","), now));
+    }
+    let stats = &report.size_stats;
+    let mut fields = vec![format!("count={}i", stats.count), format!("total_bytes={}i", stats.total_bytes)];
+    fields.extend(stats.mean().map(|x| format!("mean_bytes={}", x)));
+    fields.extend(stats.stddev().map(|x| format!("stddev_bytes={}", x)));
+    fields.extend(report.percentiles(percentiles).into_iter().map(|(label, bytes)| format!("{}={}i", escape(&label), bytes)));
+    out.push(format!("humongous_size{} {} {}", tags(host, &[("report", report.title.as_deref())]), fields.join(","), now));
+}
+
+fn lines(reports: &[Report], percentiles: &[f64], interval: u64) -> Vec<String> {
+    let host = host();
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |x| x.as_nanos() as i64);
+    let mut out = Vec::new();
+    for report in reports {
+        interval_lines(&mut out, report, host.as_deref(), interval as i64);
+        summary_lines(&mut out, report, host.as_deref(), percentiles, now);
+    }
+    out
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn post(url: &str, body: &str) -> Result<(), String> {
+    let mut request = ureq::post(url).set("Content-Type", "text/plain; charset=utf-8");
+    if let Ok(token) = env::var("INFLUX_TOKEN") {
+        request = request.set("Authorization", &format!("Token {}", token));
+    }
+    request.send_string(body).map(|_| log::debug!("Exported line protocol to {}", url)).map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn post(_: &str, _: &str) -> Result<(), String> {
+    Err("Influx is not available in the WebAssembly build".to_string())
+}
+
+// Line protocol with nanosecond timestamps to stdout for "-", POSTed to an http(s) write endpoint, e.g.
+// http://localhost:8086/api/v2/write?org=ORG&bucket=BUCKET, or written to a file otherwise. Interval points
+// are at the log's datestamps taken as UTC.
+pub fn export(target: &Path, reports: &[Report], percentiles: &[f64], interval: u64) -> Result<(), String> {
+    let body: String = lines(reports, percentiles, interval).into_iter().map(|x| x + "\n").collect();
+    let target = target.to_string_lossy();
+    if target == "-" {
+        io::stdout().lock().write_all(body.as_bytes()).map_err(|e| e.to_string())
+    } else if target.starts_with("http://") || target.starts_with("https://") {
+        post(&target, &body)
+    } else {
+        File::create(&*target).and_then(|mut x| x.write_all(body.as_bytes())).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,62 @@
+use crate::numbers::grouped;
+use crate::HumongousAllocation;
+
+// Time between consecutive humongous allocations of a file. A steady trickle every few hundred milliseconds
+// calls for a larger region size, the same number arriving at once points at a batch job to fix instead.
+#[derive(Default)]
+pub struct InterArrival {
+    // Seconds, only of allocations that both have an uptime or both have a datestamp
+    intervals: Vec<f64>,
+    // Uptime and datestamp of the previous allocation of the file
+    previous: Option<(Option<f64>, Option<i64>)>,
+}
+
+impl InterArrival {
+    // Before the first allocation of a file, the gap between two files isn't an interval
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    // The allocations of a file in log order
+    pub fn observe(&mut self, allocation: &HumongousAllocation) {
+        let previous = self.previous.replace((allocation.uptime, allocation.wall_clock));
+        let interval = match (previous, allocation.uptime, allocation.wall_clock) {
+            (Some((Some(previous), _)), Some(next), _) => next - previous,
+            // Whole seconds, still enough to tell a burst from a steady rate
+            (Some((_, Some(previous))), _, Some(next)) => (next - previous) as f64,
+            _ => return,
+        };
+        // The jvm restarted in the middle of the file
+        if interval >= 0.0 {
+            self.intervals.push(interval);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    // min, the requested percentiles and max in seconds, nearest rank as every interval is kept
+    pub fn percentiles(&self, percentiles: &[f64]) -> Vec<(String, f64)> {
+        let mut sorted = self.intervals.clone();
+        sorted.sort_by(f64::total_cmp);
+        let (Some(min), Some(max)) = (sorted.first(), sorted.last()) else { return Vec::new() };
+        let mut values = vec![("min".to_string(), *min)];
+        for percentile in percentiles {
+            let rank = ((sorted.len() as f64 * percentile / 100.0).ceil() as usize).clamp(1, sorted.len());
+            values.push((format!("p{}", percentile), sorted[rank - 1]));
+        }
+        values.push(("max".to_string(), *max));
+        values
+    }
+
+    pub fn stats(&self, percentiles: &[f64]) -> Vec<(String, String)> {
+        let mut stats = vec![("intervals".to_string(), grouped(&self.count()))];
+        stats.extend(self.percentiles(percentiles).into_iter().map(|(label, seconds)| (label, format!("{:.3}s", seconds))));
+        stats
+    }
+}
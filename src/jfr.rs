@@ -0,0 +1,614 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Seek, SeekFrom};
+
+// JDK Flight Recorder recordings (JDK 11+, format 2.x) are a sequence of self-contained chunks. Each chunk
+// describes its own event types in a metadata event, and keeps the values shared between events (class
+// names, strings, gc causes and so on) in constant pools. Only the events reported on are decoded.
+
+const MAGIC: &[u8; 4] = b"FLR\0";
+const HEADER_SIZE: usize = 68;
+const METADATA_EVENT: u64 = 0;
+const CHECKPOINT_EVENT: u64 = 1;
+
+// The events read from a recording, the others are skipped by their size
+const EVENTS: [&str; 11] = [
+    "jdk.ObjectAllocationOutsideTLAB",
+    "jdk.GarbageCollection",
+    "jdk.EvacuationFailed",
+    "jdk.G1HeapRegionTypeChange",
+    "jdk.JVMInformation",
+    "jdk.BooleanFlag",
+    "jdk.IntFlag",
+    "jdk.UnsignedIntFlag",
+    "jdk.LongFlag",
+    "jdk.UnsignedLongFlag",
+    "jdk.DoubleFlag",
+];
+
+pub struct Collection {
+    // Seconds since the epoch
+    pub time: f64,
+    // G1New, G1Old or G1Full
+    pub name: String,
+    pub cause: String,
+    pub duration: f64,
+}
+
+// What a recording holds about humongous allocations, every list in time order
+#[derive(Default)]
+pub struct Recording {
+    // Seconds since the epoch and size of every allocation outside of a TLAB, which includes all humongous ones
+    pub allocations: Vec<(f64, u64)>,
+    pub collections: Vec<Collection>,
+    pub evacuation_failures: Vec<f64>,
+    // Regions of each humongous object, from the region type changes (jdk.G1HeapRegionTypeChange)
+    pub humongous_objects: Vec<u64>,
+    // Most regions that were humongous at the same time
+    pub peak_humongous_regions: u64,
+    // Seconds since the epoch at which the JVM started, uptimes are relative to it
+    pub jvm_start: Option<f64>,
+    // "-XX:Name=value" and "-XX:+Name" for every flag, as if it was a "CommandLine flags:" line
+    pub flags_line: String,
+}
+
+// Leaves the reader where it was
+pub fn is_recording(reader: &mut (impl Read + Seek)) -> io::Result<bool> {
+    let mut magic = [0; 4];
+    let read = reader.read(&mut magic)?;
+    reader.seek(SeekFrom::Current(-(read as i64)))?;
+    Ok(read == magic.len() && &magic == MAGIC)
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    // Class and key of a constant pool entry
+    Reference(u64, i64),
+    Object(u64, Vec<Value>),
+}
+
+struct Field {
+    name: String,
+    class: u64,
+    constant_pool: bool,
+    array: bool,
+}
+
+struct Class {
+    name: String,
+    fields: Vec<Field>,
+}
+
+// An element of the metadata tree, e.g. <class name="jdk.GarbageCollection" id="...">
+struct Element {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<Element>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    // Integers are LEB128 encoded unless the chunk says otherwise
+    compressed: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], position: usize, compressed: bool) -> Reader<'a> {
+        Reader { data, position, compressed }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let bytes = self.data.get(self.position..self.position + count).ok_or("unexpected end of chunk")?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    // 7 bits per byte, least significant first, the 9th byte contributes all of its 8 bits
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0;
+        for index in 0..9 {
+            let byte = self.byte()? as u64;
+            if index == 8 {
+                return Ok(value | byte << 56);
+            }
+            value |= (byte & 0x7f) << (7 * index);
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    fn int(&mut self) -> Result<i64, String> {
+        if self.compressed {
+            return Ok(self.varint()? as i32 as i64);
+        }
+        Ok(i32::from_be_bytes(self.bytes(4)?.try_into().unwrap()) as i64)
+    }
+
+    fn long(&mut self) -> Result<i64, String> {
+        if self.compressed {
+            return Ok(self.varint()? as i64);
+        }
+        Ok(i64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn count(&mut self) -> Result<usize, String> {
+        usize::try_from(self.int()?).map_err(|_| "negative count".to_string())
+    }
+
+    fn string(&mut self, string_class: u64) -> Result<Value, String> {
+        match self.byte()? {
+            0 => Ok(Value::Null),
+            1 => Ok(Value::Text(String::new())),
+            2 => Ok(Value::Reference(string_class, self.long()?)),
+            3 => {
+                let length = self.count()?;
+                Ok(Value::Text(String::from_utf8_lossy(self.bytes(length)?).into_owned()))
+            }
+            4 => {
+                let length = self.count()?;
+                let chars: Result<String, String> = (0..length).map(|_| Ok(char::from_u32(self.int()? as u32).unwrap_or(char::REPLACEMENT_CHARACTER))).collect();
+                Ok(Value::Text(chars?))
+            }
+            5 => {
+                let length = self.count()?;
+                Ok(Value::Text(self.bytes(length)?.iter().map(|x| *x as char).collect()))
+            }
+            encoding => Err(format!("unknown string encoding {}", encoding)),
+        }
+    }
+
+    // Metadata strings are always inline
+    fn metadata_string(&mut self) -> Result<String, String> {
+        match self.string(0)? {
+            Value::Text(text) => Ok(text),
+            _ => Ok(String::new()),
+        }
+    }
+
+    fn element(&mut self, strings: &[String]) -> Result<Element, String> {
+        let string = |reader: &mut Reader| strings.get(reader.count()?).cloned().ok_or_else(|| "metadata string out of range".to_string());
+        let name = string(self)?;
+        let mut attributes = HashMap::new();
+        for _ in 0..self.count()? {
+            let key = string(self)?;
+            attributes.insert(key, string(self)?);
+        }
+        let children = (0..self.count()?).map(|_| self.element(strings)).collect::<Result<_, _>>()?;
+        Ok(Element { name, attributes, children })
+    }
+}
+
+struct Chunk<'a> {
+    data: &'a [u8],
+    compressed: bool,
+    start_nanos: i64,
+    start_ticks: i64,
+    ticks_per_second: f64,
+    classes: HashMap<u64, Class>,
+    string_class: u64,
+    pools: HashMap<(u64, i64), Value>,
+}
+
+impl<'a> Chunk<'a> {
+    // Reads the header, the metadata and the constant pools of the chunk at the start of data
+    fn parse(data: &'a [u8]) -> Result<Chunk<'a>, String> {
+        if data.len() < HEADER_SIZE || &data[..4] != MAGIC {
+            return Err("missing chunk header".to_string());
+        }
+        let field = |offset: usize| i64::from_be_bytes(data[offset..offset + 8].try_into().unwrap());
+        let (major, minor) = (u16::from_be_bytes([data[4], data[5]]), u16::from_be_bytes([data[6], data[7]]));
+        if major != 2 {
+            return Err(format!("unsupported format version {}.{}, only recordings of JDK 11 and later (2.x) are supported", major, minor));
+        }
+        let size = usize::try_from(field(8)).ok().filter(|x| (HEADER_SIZE..=data.len()).contains(x)).ok_or("chunk is incomplete, was the recording still running?")?;
+        let mut chunk = Chunk {
+            data: &data[..size],
+            compressed: i32::from_be_bytes(data[64..68].try_into().unwrap()) & 1 != 0,
+            start_nanos: field(32),
+            start_ticks: field(48),
+            ticks_per_second: field(56) as f64,
+            classes: HashMap::new(),
+            string_class: 0,
+            pools: HashMap::new(),
+        };
+        chunk.read_metadata(usize::try_from(field(24)).map_err(|_| "invalid metadata offset")?)?;
+        for (offset, event_type) in chunk.events()? {
+            if event_type == CHECKPOINT_EVENT {
+                chunk.read_checkpoint(offset)?;
+            }
+        }
+        Ok(chunk)
+    }
+
+    // Offset of the payload and type of every event
+    fn events(&self) -> Result<Vec<(usize, u64)>, String> {
+        let mut events = Vec::new();
+        let mut offset = HEADER_SIZE;
+        while offset < self.data.len() {
+            let mut reader = Reader::new(self.data, offset, self.compressed);
+            let size = usize::try_from(reader.int()?).ok().filter(|x| *x > 0).ok_or("invalid event size")?;
+            let event_type = reader.long()? as u64;
+            events.push((reader.position, event_type));
+            offset += size;
+        }
+        Ok(events)
+    }
+
+    fn read_metadata(&mut self, offset: usize) -> Result<(), String> {
+        let mut reader = Reader::new(self.data, offset, self.compressed);
+        reader.int()?;
+        if reader.long()? as u64 != METADATA_EVENT {
+            return Err("metadata offset does not point at the metadata".to_string());
+        }
+        // Start time, duration and metadata id
+        for _ in 0..3 {
+            reader.long()?;
+        }
+        let strings = (0..reader.count()?).map(|_| reader.metadata_string()).collect::<Result<Vec<_>, _>>()?;
+        let root = reader.element(&strings)?;
+        let attribute = |element: &Element, name: &str| element.attributes.get(name).cloned().unwrap_or_default();
+        let id = |element: &Element, name: &str| attribute(element, name).parse::<u64>().ok();
+        for class in root.children.iter().filter(|x| x.name == "metadata").flat_map(|x| &x.children).filter(|x| x.name == "class") {
+            let fields = class
+                .children
+                .iter()
+                .filter(|x| x.name == "field")
+                .map(|x| Field {
+                    name: attribute(x, "name"),
+                    class: id(x, "class").unwrap_or_default(),
+                    constant_pool: attribute(x, "constantPool") == "true",
+                    array: attribute(x, "dimension") == "1",
+                })
+                .collect();
+            let Some(class_id) = id(class, "id") else { continue };
+            if attribute(class, "name") == "java.lang.String" {
+                self.string_class = class_id;
+            }
+            self.classes.insert(class_id, Class { name: attribute(class, "name"), fields });
+        }
+        Ok(())
+    }
+
+    fn read_checkpoint(&mut self, offset: usize) -> Result<(), String> {
+        let mut reader = Reader::new(self.data, offset, self.compressed);
+        // Start time, duration, delta to the previous checkpoint and the type of checkpoint
+        for _ in 0..3 {
+            reader.long()?;
+        }
+        reader.byte()?;
+        for _ in 0..reader.count()? {
+            let class = reader.long()? as u64;
+            for _ in 0..reader.count()? {
+                let key = reader.long()?;
+                let value = self.value(&mut reader, class)?;
+                self.pools.insert((class, key), value);
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&self, reader: &mut Reader, class: u64) -> Result<Value, String> {
+        let description = self.classes.get(&class).ok_or_else(|| format!("unknown type {}", class))?;
+        Ok(match description.name.as_str() {
+            "boolean" | "byte" => Value::Integer(reader.byte()? as i8 as i64),
+            "char" | "short" | "int" => Value::Integer(reader.int()?),
+            "long" => Value::Integer(reader.long()?),
+            "float" => Value::Float(f32::from_be_bytes(reader.bytes(4)?.try_into().unwrap()) as f64),
+            "double" => Value::Float(f64::from_be_bytes(reader.bytes(8)?.try_into().unwrap())),
+            "java.lang.String" => reader.string(self.string_class)?,
+            _ => {
+                let mut values = Vec::with_capacity(description.fields.len());
+                for field in &description.fields {
+                    // None of the fields read are arrays (stack frames and the like), they are only skipped
+                    if field.array {
+                        for _ in 0..reader.count()? {
+                            self.field_value(reader, field)?;
+                        }
+                        values.push(Value::Null);
+                    } else {
+                        values.push(self.field_value(reader, field)?);
+                    }
+                }
+                Value::Object(class, values)
+            }
+        })
+    }
+
+    fn field_value(&self, reader: &mut Reader, field: &Field) -> Result<Value, String> {
+        if field.constant_pool && field.class != self.string_class {
+            Ok(Value::Reference(field.class, reader.long()?))
+        } else {
+            self.value(reader, field.class)
+        }
+    }
+
+    fn resolve<'v>(&'v self, value: &'v Value) -> &'v Value {
+        match value {
+            Value::Reference(class, key) => self.pools.get(&(*class, *key)).map_or(&Value::Null, |x| self.resolve(x)),
+            _ => value,
+        }
+    }
+
+    fn field<'v>(&'v self, value: &'v Value, name: &str) -> &'v Value {
+        match self.resolve(value) {
+            Value::Object(class, values) => {
+                let index = self.classes.get(class).and_then(|x| x.fields.iter().position(|x| x.name == name));
+                index.and_then(|x| values.get(x)).map_or(&Value::Null, |x| self.resolve(x))
+            }
+            _ => &Value::Null,
+        }
+    }
+
+    fn integer(&self, value: &Value, name: &str) -> Option<i64> {
+        match self.field(value, name) {
+            Value::Integer(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    // Strings are either a field of the event or wrapped in a constant pool type such as jdk.types.GCCause
+    fn text(&self, value: &Value, name: &str, inner: Option<&str>) -> String {
+        let value = self.field(value, name);
+        match inner.map_or(value, |x| self.field(value, x)) {
+            Value::Text(text) => text.clone(),
+            _ => String::new(),
+        }
+    }
+
+    // Seconds since the epoch of a tick count
+    fn time(&self, ticks: i64) -> f64 {
+        self.start_nanos as f64 / 1e9 + (ticks - self.start_ticks) as f64 / self.ticks_per_second
+    }
+}
+
+struct RegionTypeChange {
+    time: f64,
+    index: i64,
+    from: String,
+    to: String,
+}
+
+// Events are buffered per thread, so the type changes are sorted first. A humongous object is the region it
+// starts in and the continuing regions right after it, which change type at the same time.
+fn humongous_objects(mut changes: Vec<RegionTypeChange>, recording: &mut Recording) {
+    changes.sort_by(|a, b| a.time.total_cmp(&b.time).then(a.index.cmp(&b.index)));
+    let humongous = |x: &str| x.ends_with("Humongous");
+    // Last region and region count of the object whose regions are being listed
+    let mut current: Option<(i64, u64)> = None;
+    let mut live: u64 = 0;
+    for change in &changes {
+        match change.to.as_str() {
+            "Starts Humongous" => recording.humongous_objects.extend(current.replace((change.index, 1)).map(|x| x.1)),
+            "Continues Humongous" => match &mut current {
+                Some((last, regions)) if *last + 1 == change.index => (*last, *regions) = (change.index, *regions + 1),
+                _ => log::debug!("humongous region {} does not continue an object", change.index),
+            },
+            _ => {}
+        }
+        match (humongous(&change.from), humongous(&change.to)) {
+            (false, true) => live += 1,
+            (true, false) => live = live.saturating_sub(1),
+            _ => {}
+        }
+        recording.peak_humongous_regions = recording.peak_humongous_regions.max(live);
+    }
+    recording.humongous_objects.extend(current.map(|x| x.1));
+}
+
+pub fn parse(data: &[u8]) -> Result<Recording, String> {
+    let mut recording = Recording::default();
+    let mut flags = Vec::new();
+    let mut region_changes = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let chunk = Chunk::parse(&data[offset..])?;
+        let wanted: HashMap<u64, &str> = chunk.classes.iter().filter_map(|(id, class)| Some((*id, *EVENTS.iter().find(|x| **x == class.name)?))).collect();
+        for (position, event_type) in chunk.events()? {
+            let Some(name) = wanted.get(&event_type) else { continue };
+            let event = chunk.value(&mut Reader::new(chunk.data, position, chunk.compressed), event_type)?;
+            let time = chunk.time(chunk.integer(&event, "startTime").unwrap_or(chunk.start_ticks));
+            match *name {
+                "jdk.ObjectAllocationOutsideTLAB" => {
+                    if let Some(bytes) = chunk.integer(&event, "allocationSize") {
+                        recording.allocations.push((time, bytes as u64));
+                    }
+                }
+                "jdk.GarbageCollection" => recording.collections.push(Collection {
+                    time,
+                    name: chunk.text(&event, "name", Some("name")),
+                    cause: chunk.text(&event, "cause", Some("cause")),
+                    duration: chunk.integer(&event, "duration").unwrap_or(0) as f64 / chunk.ticks_per_second,
+                }),
+                "jdk.EvacuationFailed" => recording.evacuation_failures.push(time),
+                "jdk.G1HeapRegionTypeChange" => region_changes.push(RegionTypeChange {
+                    time,
+                    index: chunk.integer(&event, "index").unwrap_or(-1),
+                    from: chunk.text(&event, "from", Some("type")),
+                    to: chunk.text(&event, "to", Some("type")),
+                }),
+                "jdk.JVMInformation" => recording.jvm_start = chunk.integer(&event, "jvmStartTime").map(|x| x as f64 / 1000.0),
+                "jdk.BooleanFlag" => {
+                    let sign = if chunk.integer(&event, "value") == Some(0) { '-' } else { '+' };
+                    flags.push(format!("-XX:{}{}", sign, chunk.text(&event, "name", None)));
+                }
+                _ => match chunk.field(&event, "value") {
+                    Value::Integer(value) if *name == "jdk.UnsignedLongFlag" => flags.push(format!("-XX:{}={}", chunk.text(&event, "name", None), *value as u64)),
+                    Value::Integer(value) => flags.push(format!("-XX:{}={}", chunk.text(&event, "name", None), value)),
+                    Value::Float(value) => flags.push(format!("-XX:{}={}", chunk.text(&event, "name", None), value)),
+                    _ => {}
+                },
+            }
+        }
+        offset += chunk.data.len();
+    }
+    humongous_objects(region_changes, &mut recording);
+    // Flags are recorded at the start and end of every chunk
+    flags.sort();
+    flags.dedup();
+    recording.flags_line = flags.join(" ");
+    recording.allocations.sort_by(|a, b| a.0.total_cmp(&b.0));
+    recording.collections.sort_by(|a, b| a.time.total_cmp(&b.time));
+    recording.evacuation_failures.sort_by(f64::total_cmp);
+    Ok(recording)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(out: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                return out.push(byte);
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn text(out: &mut Vec<u8>, text: &str) {
+        out.push(3);
+        varint(out, text.len() as u64);
+        out.extend(text.as_bytes());
+    }
+
+    // Its size as a varint padded to 4 bytes, as the JVM writes it
+    fn event(out: &mut Vec<u8>, event_type: u64, payload: &[u8]) {
+        let mut body = Vec::new();
+        varint(&mut body, event_type);
+        body.extend(payload);
+        let size = 4 + body.len() as u64;
+        out.extend([(size & 0x7f) as u8 | 0x80, (size >> 7 & 0x7f) as u8 | 0x80, (size >> 14 & 0x7f) as u8 | 0x80, (size >> 21) as u8]);
+        out.extend(body);
+    }
+
+    struct Node(&'static str, Vec<(&'static str, String)>, Vec<Node>);
+
+    // The index of the string in the metadata strings, added when it's new
+    fn index(strings: &mut Vec<String>, text: &str) -> u64 {
+        match strings.iter().position(|x| x == text) {
+            Some(index) => index as u64,
+            None => {
+                strings.push(text.to_string());
+                strings.len() as u64 - 1
+            }
+        }
+    }
+
+    fn element(out: &mut Vec<u8>, strings: &mut Vec<String>, node: &Node) {
+        varint(out, index(strings, node.0));
+        varint(out, node.1.len() as u64);
+        for (key, value) in &node.1 {
+            varint(out, index(strings, key));
+            varint(out, index(strings, value));
+        }
+        varint(out, node.2.len() as u64);
+        node.2.iter().for_each(|x| element(out, strings, x));
+    }
+
+    fn class(id: u64, name: &'static str, fields: &[(&'static str, u64, bool)]) -> Node {
+        let fields = fields.iter().map(|(name, class, pool)| Node("field", vec![("name", name.to_string()), ("class", class.to_string()), ("constantPool", pool.to_string())], Vec::new()));
+        Node("class", vec![("id", id.to_string()), ("name", name.to_string())], fields.collect())
+    }
+
+    // A single chunk of compressed integers with a collection, an allocation, a humongous object of three regions
+    // and a flag, one second after the chunk started
+    fn recording() -> Vec<u8> {
+        let (long, string, gc_name, gc_cause, region_type) = (10, 11, 20, 21, 22);
+        let metadata = Node(
+            "root",
+            Vec::new(),
+            vec![Node(
+                "metadata",
+                Vec::new(),
+                vec![
+                    class(long, "long", &[]),
+                    class(string, "java.lang.String", &[]),
+                    class(gc_name, "jdk.types.GCName", &[("name", string, false)]),
+                    class(gc_cause, "jdk.types.GCCause", &[("cause", string, false)]),
+                    class(region_type, "jdk.types.G1HeapRegionType", &[("type", string, false)]),
+                    class(100, "jdk.ObjectAllocationOutsideTLAB", &[("startTime", long, false), ("allocationSize", long, false)]),
+                    class(101, "jdk.GarbageCollection", &[("startTime", long, false), ("duration", long, false), ("name", gc_name, true), ("cause", gc_cause, true)]),
+                    class(102, "jdk.G1HeapRegionTypeChange", &[("startTime", long, false), ("index", long, false), ("from", region_type, true), ("to", region_type, true)]),
+                    class(103, "jdk.LongFlag", &[("startTime", long, false), ("name", string, false), ("value", long, false)]),
+                ],
+            )],
+        );
+        let (mut strings, mut tree) = (Vec::new(), Vec::new());
+        element(&mut tree, &mut strings, &metadata);
+        let mut payload = vec![0, 0, 0];
+        varint(&mut payload, strings.len() as u64);
+        strings.iter().for_each(|x| text(&mut payload, x));
+        payload.extend(tree);
+        let mut events = Vec::new();
+        event(&mut events, METADATA_EVENT, &payload);
+
+        let pools: [(u64, &[&str]); 3] = [(gc_name, &["G1New"]), (gc_cause, &["G1 Humongous Allocation"]), (region_type, &["Free", "Starts Humongous", "Continues Humongous"])];
+        let mut payload = vec![0, 0, 0, 1];
+        varint(&mut payload, pools.len() as u64);
+        for (class, values) in pools {
+            varint(&mut payload, class);
+            varint(&mut payload, values.len() as u64);
+            for (key, value) in values.iter().enumerate() {
+                varint(&mut payload, key as u64);
+                text(&mut payload, value);
+            }
+        }
+        event(&mut events, CHECKPOINT_EVENT, &payload);
+
+        let second = 1_000_000_000;
+        let mut payload = Vec::new();
+        [second, 16777232].iter().for_each(|x| varint(&mut payload, *x));
+        event(&mut events, 100, &payload);
+        let mut payload = Vec::new();
+        [second, 5_000_000, 0, 0].iter().for_each(|x| varint(&mut payload, *x));
+        event(&mut events, 101, &payload);
+        for (index, to) in [(7, 2), (5, 1), (6, 2)] {
+            let mut payload = Vec::new();
+            [second, index, 0, to].iter().for_each(|x| varint(&mut payload, *x));
+            event(&mut events, 102, &payload);
+        }
+        let mut payload = Vec::new();
+        varint(&mut payload, second);
+        text(&mut payload, "G1HeapRegionSize");
+        varint(&mut payload, 4194304);
+        event(&mut events, 103, &payload);
+
+        let size = (HEADER_SIZE + events.len()) as i64;
+        let mut data = MAGIC.to_vec();
+        data.extend(2u16.to_be_bytes());
+        data.extend(1u16.to_be_bytes());
+        // Size, constant pool and metadata offsets, start time, duration, start ticks and ticks per second
+        for field in [size, 0, HEADER_SIZE as i64, 1_700_000_000 * second as i64, 0, 0, second as i64] {
+            data.extend(field.to_be_bytes());
+        }
+        data.extend(1i32.to_be_bytes());
+        data.extend(events);
+        data
+    }
+
+    #[test]
+    fn reads_the_events_of_a_chunk() {
+        let data = recording();
+        assert!(is_recording(&mut io::Cursor::new(&data)).unwrap());
+        let recording = parse(&data).unwrap();
+        assert_eq!(recording.allocations, [(1_700_000_001.0, 16777232)]);
+        let collection = &recording.collections[0];
+        assert_eq!((collection.time, collection.name.as_str(), collection.cause.as_str(), collection.duration), (1_700_000_001.0, "G1New", "G1 Humongous Allocation", 0.005));
+        // The region type changes arrive out of order
+        assert_eq!((recording.humongous_objects.as_slice(), recording.peak_humongous_regions), (&[3][..], 3));
+        assert_eq!(recording.flags_line, "-XX:G1HeapRegionSize=4194304");
+        assert!(parse(&data[..data.len() - 1]).is_err());
+    }
+}
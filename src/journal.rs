@@ -0,0 +1,30 @@
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::archive;
+
+// The messages of a unit logging its gc log to stdout, as journalctl -o json lines that container::strip unwraps.
+// Held in memory under "journal:UNIT", or "journal:stdin" for journalctl output piped in with -.
+pub fn load(unit: &str) -> Result<PathBuf, String> {
+    let (path, contents) = match unit {
+        "-" => {
+            let mut contents = Vec::new();
+            io::stdin().read_to_end(&mut contents).map_err(|e| format!("Unable to read the journal from stdin: {}", e))?;
+            (PathBuf::from("journal:stdin"), contents)
+        }
+        unit => {
+            let output = Command::new("journalctl").args(["--unit", unit, "--output", "json", "--no-pager"]).output().map_err(|e| format!("Unable to run journalctl: {}", e))?;
+            if !output.status.success() {
+                return Err(format!("journalctl failed for {}: {}", unit, String::from_utf8_lossy(&output.stderr).trim()));
+            }
+            (PathBuf::from(format!("journal:{}", unit)), output.stdout)
+        }
+    };
+    if contents.is_empty() {
+        return Err(format!("The journal of {} is empty", path.display()));
+    }
+    archive::register(&path, Arc::from(contents));
+    Ok(path)
+}
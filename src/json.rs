@@ -0,0 +1,252 @@
+use serde_json::{json, Map, Value};
+use std::io::{self, Write};
+
+use crate::heap_share::HeapShare;
+use crate::heap_sizing;
+use crate::report::Report;
+use crate::safepoint;
+use crate::validate::Capabilities;
+use crate::{Cli, Collector};
+
+fn file_summaries(report: &Report) -> Value {
+    let files = report.file_summaries.iter().map(|summary| {
+        let row = summary.row();
+        json!({
+            "file": summary.file,
+            "label": summary.label,
+            "status": row.status,
+            "region_size_mb": summary.region_size.as_ref().map(|x| x.mb),
+            "region_size": summary.region_size.as_ref().map(|x| x.describe()),
+            "max_heap_bytes": summary.max_heap,
+            "jdk_version": summary.jdk_version,
+            "allocations": summary.allocations,
+            "allocated_bytes": summary.allocated_bytes,
+            "evacuation_failures": summary.evacuation_failures,
+            "skipped_lines": row.skipped_lines,
+            "lines_read": summary.lines_read,
+            "undecodable_lines": summary.undecodable_lines,
+            "binary_lines": summary.binary_lines,
+            "unparseable_lines": summary.unparseable_lines,
+            "unexpected_allocations": summary.unexpected_allocations,
+            "filtered_allocations": summary.filtered_allocations,
+            "dated_allocations": summary.dated_allocations,
+            "uptime_allocations": summary.uptime_allocations,
+            "read_range": summary.read_range.map(|(start, end)| json!({ "start": start, "end": end })),
+            "details": row.details.lines().collect::<Vec<_>>(),
+        })
+    });
+    Value::Array(files.collect())
+}
+
+fn recommendation_value(report: &Report) -> Option<Value> {
+    report.recommendation().map(|x| {
+        json!({
+            "region_size_bytes": x.region_size,
+            "coverage_percentage": x.coverage,
+            "meets_target": x.meets_target,
+            "requires_jdk18": x.requires_jdk18(),
+            "summary": x.summary(),
+        })
+    })
+}
+
+fn report_value(report: &Report, args: &Cli) -> Value {
+    let buckets = report.region_size_array.iter().map(|x| {
+        json!({
+            "region_size": x.region_size,
+            "max_size": x.max_size,
+            "allocations": x.num_allocations,
+        })
+    });
+    let percentiles: Map<String, Value> = report.percentiles(&args.percentiles).into_iter().map(|(label, bytes)| (label, json!(bytes))).collect();
+    let (spikes, during_bursts) = safepoint::spikes(&report.safepoint_rows);
+    let recommendation = recommendation_value(report);
+    let inter_arrival: Map<String, Value> = report.inter_arrival.percentiles(&args.percentiles).into_iter().map(|(label, seconds)| (label, json!((seconds * 1000.0).round() / 1000.0))).collect();
+    let size_filter = (args.min_size.is_some() || args.max_size.is_some()).then(|| {
+        json!({
+            "min_bytes": args.min_size,
+            "max_bytes": args.max_size,
+            "excluded_allocations": report.file_summaries.iter().map(|x| x.filtered_allocations).sum::<u64>(),
+        })
+    });
+    let cumulative = report.cumulative.as_ref().map(|x| {
+        let percentiles: Map<String, Value> = x.percentiles(&args.percentiles).into_iter().map(|(label, bytes)| (label, json!(bytes))).collect();
+        json!({
+            "runs": x.runs,
+            "allocations": x.allocations(),
+            "allocated_bytes": x.allocated_bytes(),
+            "percentiles": percentiles,
+        })
+    });
+    // Allocation counts of each hour of the day, one per bucket
+    let heatmap = (args.heatmap && !report.heatmap.is_empty()).then(|| {
+        let hours: Vec<Value> = report.heatmap.hours(&report.region_size_array).into_iter().map(|(hour, counts)| json!({ "hour": hour, "allocations": counts })).collect();
+        json!({
+            "region_sizes": report.region_size_array.iter().map(|x| &x.region_size).collect::<Vec<_>>(),
+            "hours": hours,
+            "undated": report.heatmap.undated,
+        })
+    });
+    let size_frequency = args.size_frequency.map(|top| {
+        let (sizes, (other_sizes, other_allocations)) = report.size_clusters.frequencies(top);
+        json!({
+            "sizes": sizes.into_iter().map(|(bytes, allocations)| json!({ "bytes": bytes, "allocations": allocations })).collect::<Vec<_>>(),
+            "other_sizes": other_sizes,
+            "other_allocations": other_allocations,
+        })
+    });
+    let sampling = report.sampling.as_ref().map(|x| {
+        json!({
+            "bytes_read": x.bytes_read,
+            "bytes_total": x.bytes_total,
+            "estimated_allocations": x.allocations.value.round(),
+            "allocations_margin": x.allocations.margin().map(f64::round),
+            "estimated_allocated_bytes": x.allocated_bytes.value.round(),
+            "allocated_bytes_margin": x.allocated_bytes.margin().map(f64::round),
+        })
+    });
+    let quality = report.parse_quality(args);
+    json!({
+        "title": report.title,
+        "files": file_summaries(report),
+        "size_filter": size_filter,
+        "sampling": sampling,
+        "allocations": report.file_summaries.iter().map(|x| x.allocations).sum::<u64>(),
+        "allocated_bytes": report.file_summaries.iter().map(|x| x.allocated_bytes).sum::<u64>(),
+        "buckets": buckets.collect::<Vec<_>>(),
+        "size_statistics": {
+            "count": report.size_stats.count,
+            "total_bytes": report.size_stats.total_bytes,
+            "mean_bytes": report.size_stats.mean(),
+            "stddev_bytes": report.size_stats.stddev(),
+        },
+        "percentiles": percentiles,
+        // Allocation size -> allocations of that size, so the merge subcommand can work the percentiles out again
+        "histogram": report.size_clusters.sizes(),
+        "recommendation": recommendation,
+        "heap_sizing": heap_sizing::suggestions(report),
+        "waste": {
+            "objects": report.waste.objects,
+            "allocated_bytes": report.waste.allocated_bytes,
+            "wasted_bytes": report.waste.wasted_bytes,
+        },
+        "heap_share": HeapShare::new(report).map(|x| json!({
+            "max_heap_bytes": x.heap,
+            "largest_percent": x.largest.map(|bytes| x.percent(bytes)),
+            "peak_bytes": x.peak,
+            "peak_percent": x.peak.map(|bytes| x.percent(bytes)),
+            "cumulative_percent": x.percent(x.cumulative),
+            "regions_consumed": x.regions.map(|regions| regions.0),
+            "heap_regions": x.regions.map(|regions| regions.1),
+        })),
+        "parse_quality": {
+            "files": quality.files,
+            "lines_read": quality.lines_read,
+            "matched_lines": quality.matched_lines,
+            "filtered_allocations": quality.filtered_allocations,
+            "unparseable_lines": quality.unparseable_lines,
+            "undecodable_lines": quality.undecodable_lines,
+            "binary_lines": quality.binary_lines,
+            "unexpected_allocations": quality.unexpected_allocations,
+            "files_without_region_size": quality.files_without_region_size,
+            "inferred_region_sizes": quality.inferred_region_sizes,
+            "dated_allocations": quality.dated_allocations,
+            "uptime_allocations": quality.uptime_allocations,
+        },
+        "full_gcs": report.full_gc_rows.len(),
+        "evacuation_failures": report.evacuation_failures,
+        "gc_causes": report.gc_causes.causes,
+        "young_pauses": {
+            "pauses": report.gc_causes.young_pauses,
+            "humongous_allocation": report.gc_causes.humongous_young_pauses,
+            "pause_seconds": report.gc_causes.young_pause_time.value(),
+            "humongous_allocation_pause_seconds": report.gc_causes.humongous_young_pause_time.value(),
+        },
+        "inter_arrival": {
+            "intervals": report.inter_arrival.count(),
+            "percentiles_seconds": inter_arrival,
+        },
+        "allocations_per_cycle": report.allocations_per_cycle.counts,
+        "pause_impact": {
+            "pauses": report.pause_impact.rows().iter().map(|x| x.pauses).sum::<u64>(),
+            "avg_pause_ms_after_none": report.pause_impact.avg_pause(false),
+            "avg_pause_ms_after_allocations": report.pause_impact.avg_pause(true),
+            "correlation": report.pause_impact.correlation(),
+        },
+        "concurrent_cycle_triggers": report.concurrent_cycles.triggers,
+        "concurrent_cycle_durations": {
+            "cycles": report.cycle_durations.cycles(),
+            "humongous_cycles": report.cycle_durations.humongous_cycles,
+            "percentiles_seconds": report.cycle_durations.percentiles(),
+            "during_bursts": report.cycle_durations.during_bursts,
+            "humongous_allocations_during_marking": report.cycle_durations.allocations_during_marking,
+            "back_to_back": report.cycle_durations.back_to_back,
+        },
+        "mixed_collections": {
+            "collections": report.mixed_collections.collections,
+            "phases": report.mixed_collections.phases,
+            "pause_seconds": report.mixed_collections.pause_time.value(),
+            "reclaimed_bytes": report.mixed_collections.reclaimed_bytes,
+        },
+        "safepoints": {
+            "safepoints": report.safepoint_rows.iter().map(|x| x.safepoints).sum::<u64>(),
+            "time_to_safepoint_spikes": spikes,
+            "spikes_during_bursts": during_bursts,
+        },
+        "cumulative": cumulative,
+        "heatmap": heatmap,
+        "size_frequency": size_frequency,
+    })
+}
+
+// A single document with one entry per report, for scripts and other tools to consume
+pub fn write_json(reports: &[Report], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    let document = json!({ "reports": reports.iter().map(|x| report_value(x, args)).collect::<Vec<_>>() });
+    serde_json::to_writer_pretty(&mut *out, &document)?;
+    writeln!(out)?;
+    out.flush()
+}
+
+// The recommend subcommand, the recommendation and candidate region sizes of each report
+pub fn write_recommendations(reports: &[Report], out: &mut dyn Write) -> io::Result<()> {
+    let reports = reports.iter().map(|report| {
+        let candidates = report.candidates.what_if(report.max_heap()).into_iter().map(|x| {
+            json!({
+                "region_size": x.region_size,
+                "still_humongous": x.humongous,
+                "percentage": x.percentage,
+                "regions": x.regions,
+                "notes": x.notes,
+            })
+        });
+        json!({
+            "title": report.title,
+            "files": file_summaries(report),
+            "recommendation": recommendation_value(report),
+            "heap_sizing": heap_sizing::suggestions(report),
+            "jvm_flags": heap_sizing::flags(report),
+            "candidates": candidates.collect::<Vec<_>>(),
+        })
+    });
+    serde_json::to_writer_pretty(&mut *out, &json!({ "reports": reports.collect::<Vec<_>>() }))?;
+    writeln!(out)?;
+    out.flush()
+}
+
+pub fn write_capabilities(capabilities: &[Capabilities], out: &mut dyn Write, collector: Collector) -> io::Result<()> {
+    let files = capabilities.iter().map(|x| {
+        json!({
+            "file": x.file,
+            "format": x.row().format,
+            "region_size": x.region_size.as_ref().ok().cloned().flatten(),
+            "uptime": x.uptime,
+            "datestamp": x.datestamp,
+            "humongous_allocations": x.allocations,
+            "status": x.status(),
+            "suggestions": x.suggestions(collector),
+        })
+    });
+    serde_json::to_writer_pretty(&mut *out, &json!({ "files": files.collect::<Vec<_>>() }))?;
+    writeln!(out)?;
+    out.flush()
+}
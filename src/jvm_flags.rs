@@ -0,0 +1,237 @@
+const MB: u64 = 1024 * 1024;
+// G1 aims for roughly this many regions when it picks the region size ergonomically
+const TARGET_REGION_NUMBER: u64 = 2048;
+const MIN_REGION_SIZE: u64 = MB;
+const MAX_REGION_SIZE: u64 = 32 * MB;
+
+// Value of a "-XX:Name=value" flag from a "CommandLine flags:" line
+pub fn flag_value<'a>(flags_line: &'a str, name: &str) -> Option<&'a str> {
+    flags_line
+        .split_whitespace()
+        .filter_map(|x| x.strip_prefix("-XX:"))
+        .filter_map(|x| x.split_once('='))
+        .find(|x| x.0 == name)
+        .map(|x| x.1)
+}
+
+// "-XX:+Name" is true and "-XX:-Name" false
+fn boolean_flag(flags_line: &str, name: &str) -> Option<bool> {
+    flags_line.split_whitespace().filter_map(|x| x.strip_prefix("-XX:")).find_map(|x| match x.split_at_checked(1)? {
+        ("+", flag) if flag == name => Some(true),
+        ("-", flag) if flag == name => Some(false),
+        _ => None,
+    })
+}
+
+// Value of a "-Xmx8g" style option
+fn option_value<'a>(flags_line: &'a str, prefix: &str) -> Option<&'a str> {
+    flags_line.split_whitespace().find_map(|x| x.strip_prefix(prefix))
+}
+
+// Parses a JVM size such as "8589934592", "8g", "512M" or "1024k" into bytes
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1024),
+        'm' | 'M' => (&value[..value.len() - 1], MB),
+        'g' | 'G' => (&value[..value.len() - 1], 1024 * MB),
+        't' | 'T' => (&value[..value.len() - 1], 1024 * 1024 * MB),
+        _ => (value, 1),
+    };
+    // A size too large for 64 bits is as invalid as one that isn't a number
+    digits.parse::<u64>().ok().and_then(|x| x.checked_mul(multiplier))
+}
+
+// A size as the JVM flags take it, in the largest unit that divides it, e.g. "8g", "512m" or "1536k"
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        _ if bytes >= 1024 * MB && bytes.is_multiple_of(1024 * MB) => format!("{}g", bytes / (1024 * MB)),
+        _ if bytes >= MB && bytes.is_multiple_of(MB) => format!("{}m", bytes / MB),
+        _ if bytes.is_multiple_of(1024) => format!("{}k", bytes / 1024),
+        _ => bytes.to_string(),
+    }
+}
+
+// Initial and maximum heap size in bytes, preferring the ergonomically resolved -XX flags over -Xms/-Xmx
+pub fn heap_sizes(flags_line: &str) -> (Option<u64>, Option<u64>) {
+    let initial = flag_value(flags_line, "InitialHeapSize").or_else(|| option_value(flags_line, "-Xms")).and_then(parse_size);
+    let max = flag_value(flags_line, "MaxHeapSize").or_else(|| option_value(flags_line, "-Xmx")).and_then(parse_size);
+    (initial, max)
+}
+
+// Mirrors HeapRegion::setup_heap_region_size: the heap divided by the target region count, rounded down to a
+// power of two and clamped to 1MB..32MB. Up to JDK 14 the heap is the average of the initial and maximum heap,
+// JDK 15 (JDK-8241670) only uses the maximum.
+pub fn ergonomic_region_size(initial_heap: Option<u64>, max_heap: Option<u64>, jdk_version: u32) -> Option<u64> {
+    let heap_size = match (initial_heap, max_heap) {
+        (Some(_), Some(max)) if jdk_version >= 15 => max,
+        (Some(initial), Some(max)) => initial.midpoint(max),
+        (Some(size), None) | (None, Some(size)) => size,
+        (None, None) => return None,
+    };
+    let region_size = (heap_size / TARGET_REGION_NUMBER).max(MIN_REGION_SIZE);
+    let region_size = 1u64 << (63 - region_size.leading_zeros());
+    Some(region_size.clamp(MIN_REGION_SIZE, MAX_REGION_SIZE))
+}
+
+// The feature release of a "1.8.0_181", "8.0_181" or "17.0.2+8" version
+fn feature_release(version: &str) -> Option<u32> {
+    let mut numbers = version.split(|x: char| !x.is_ascii_digit());
+    match numbers.next()?.parse().ok()? {
+        1 => numbers.next()?.parse().ok(),
+        release => Some(release),
+    }
+}
+
+// The JDK release a line names: the "JRE (1.8.0_181-b13)" of a JDK 8 log header, the "Version: 17.0.2+8" of a
+// unified log's gc,init lines, the "JDK 17.0.2" of jcmd <pid> VM.version, the "JVM version is 25.181-b13" of
+// jinfo and the 'version "17.0.2"' of java -version. Before JDK 9 jinfo gives the HotSpot version, 24 and 25 with
+// a -b build number being JDK 7 and 8, later ones the JDK version with a + build number.
+pub fn jdk_version(line: &str) -> Option<u32> {
+    let line = line.trim();
+    if let Some((_, version)) = line.split_once("JVM version is ") {
+        let hotspot = version.contains("-b") && !version.contains('+');
+        return match feature_release(version)? {
+            release @ (24 | 25) if hotspot => Some(release - 17),
+            release => Some(release),
+        };
+    }
+    let version = line
+        .split_once("JRE (")
+        .or_else(|| line.split_once("] Version: "))
+        .or_else(|| line.split_once(" version \""))
+        .map(|x| x.1)
+        .or_else(|| line.strip_prefix("JDK "))?;
+    feature_release(version)
+}
+
+// A --flags-file, its flags as one "CommandLine flags:" style line along with the JDK release it names, if any
+#[derive(Clone, Debug)]
+pub struct FlagsFile {
+    pub flags_line: String,
+    pub jdk_version: Option<u32>,
+}
+
+// The flags saved next to a gc log as one "CommandLine flags:" style line. `jcmd <pid> VM.flags` and `jinfo -flags <pid>`
+// print "-XX:Name=value" options already, `jcmd <pid> VM.flags -all` and -XX:+PrintFlagsFinal a table:
+//     size_t G1HeapRegionSize                         = 4194304                                   {product} {ergonomic}
+//       bool UseG1GC                                  = true                                      {product} {ergonomic}
+// The JDK release comes from a version line saved along with them, e.g. by jinfo <pid> or jcmd <pid> VM.version.
+pub fn sidecar_flags(contents: &str) -> FlagsFile {
+    let jdk_version = contents.lines().find_map(jdk_version);
+    let mut flags = Vec::new();
+    for line in contents.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [_, name, "=" | ":=", value, ..] => flags.push(match *value {
+                "true" => format!("-XX:+{}", name),
+                "false" => format!("-XX:-{}", name),
+                value => format!("-XX:{}={}", name, value),
+            }),
+            words => flags.extend(words.iter().filter(|x| x.starts_with("-X")).map(|x| x.to_string())),
+        }
+    }
+    FlagsFile { flags_line: flags.join(" "), jdk_version }
+}
+
+// "[0.009s][info][gc,heap] Heap region size: 4M" (JDK 9 to 14) or "[0.010s][info][gc,init] Heap Region Size: 4M"
+// (JDK 15+), the region size G1 ended up with whether it was set or not
+pub fn logged_region_size(line: &str) -> Option<u64> {
+    let (_, size) = line.split_once("Heap region size: ").or_else(|| line.split_once("Heap Region Size: "))?;
+    parse_size(size)
+}
+
+// The G1 flags worth knowing when reading a humongous allocation report, with their labels
+const G1_VALUE_FLAGS: [(&str, &str); 8] = [
+    ("InitiatingHeapOccupancyPercent", "initiating heap occupancy percent"),
+    ("G1ReservePercent", "reserve percent"),
+    ("G1HeapWastePercent", "heap waste percent"),
+    ("G1MixedGCLiveThresholdPercent", "mixed gc live threshold percent"),
+    ("MaxGCPauseMillis", "max gc pause millis"),
+    ("ParallelGCThreads", "parallel gc threads"),
+    ("ConcGCThreads", "concurrent gc threads"),
+    ("MaxTenuringThreshold", "max tenuring threshold"),
+];
+const G1_BOOLEAN_FLAGS: [(&str, &str); 4] = [
+    ("UseG1GC", "G1"),
+    ("G1UseAdaptiveIHOP", "adaptive IHOP"),
+    ("ParallelRefProcEnabled", "parallel reference processing"),
+    ("PrintAdaptiveSizePolicy", "adaptive size policy logging"),
+];
+
+// Label and value of each G1 relevant setting present in a "CommandLine flags:" line, sizes in MB or KB
+pub fn g1_configuration(flags_line: &str) -> Vec<(&'static str, String)> {
+    let (initial_heap, max_heap) = heap_sizes(flags_line);
+    let region_size = flag_value(flags_line, "G1HeapRegionSize").and_then(parse_size);
+    let mut configuration: Vec<(&'static str, String)> = [("initial heap size", initial_heap), ("max heap size", max_heap), ("region size", region_size)]
+        .into_iter()
+        .filter_map(|(label, bytes)| Some((label, crate::buckets::format_region_size(bytes?))))
+        .collect();
+    for (name, label) in G1_VALUE_FLAGS {
+        if let Some(value) = flag_value(flags_line, name) {
+            configuration.push((label, value.to_string()));
+        }
+    }
+    for (name, label) in G1_BOOLEAN_FLAGS {
+        if let Some(enabled) = boolean_flag(flags_line, name) {
+            configuration.push((label, if enabled { "enabled" } else { "disabled" }.to_string()));
+        }
+    }
+    configuration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("8589934592"), Some(8 * 1024 * MB));
+        assert_eq!(parse_size("8g"), Some(8 * 1024 * MB));
+        assert_eq!(parse_size("512M"), Some(512 * MB));
+        assert_eq!(parse_size("1024k"), Some(MB));
+        assert_eq!(parse_size("8x"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn rejects_sizes_that_overflow() {
+        assert_eq!(parse_size("20000000t"), None);
+        assert_eq!(parse_size("18446744073709551615k"), None);
+        assert_eq!(parse_size("18446744073709551615"), Some(u64::MAX));
+    }
+
+    #[test]
+    fn picks_the_ergonomic_region_size_of_the_jdk_release() {
+        let (initial, max) = (Some(1024 * MB), Some(16 * 1024 * MB));
+        // 8.5GB on average before JDK 15, 16GB from then on
+        assert_eq!(ergonomic_region_size(initial, max, 8), Some(4 * MB));
+        assert_eq!(ergonomic_region_size(initial, max, 11), Some(4 * MB));
+        assert_eq!(ergonomic_region_size(initial, max, 15), Some(8 * MB));
+        assert_eq!(ergonomic_region_size(initial, max, 21), Some(8 * MB));
+        assert_eq!(ergonomic_region_size(None, Some(256 * MB), 17), Some(MB));
+        assert_eq!(ergonomic_region_size(None, Some(1024 * 1024 * MB), 17), Some(32 * MB));
+        assert_eq!(ergonomic_region_size(None, None, 17), None);
+    }
+
+    #[test]
+    fn reads_the_jdk_version() {
+        assert_eq!(jdk_version("Java HotSpot(TM) 64-Bit Server VM (25.181-b13) for linux-amd64 JRE (1.8.0_181-b13), built on Jul  7 2018"), Some(8));
+        assert_eq!(jdk_version("[0.004s][info][gc,init] Version: 17.0.2+8-86 (release)"), Some(17));
+        assert_eq!(jdk_version("JDK 17.0.2"), Some(17));
+        assert_eq!(jdk_version("JDK 8.0_181"), Some(8));
+        assert_eq!(jdk_version("JVM version is 25.181-b13"), Some(8));
+        assert_eq!(jdk_version("JVM version is 25+36-LTS"), Some(25));
+        assert_eq!(jdk_version("JVM version is 11.0.2+9"), Some(11));
+        assert_eq!(jdk_version("openjdk version \"21.0.1\" 2023-10-17"), Some(21));
+        assert_eq!(jdk_version("java version \"1.8.0_181\""), Some(8));
+        assert_eq!(jdk_version("[0.010s][info][gc,init] Heap Region Size: 4M"), None);
+    }
+
+    #[test]
+    fn reads_the_jdk_version_of_a_flags_file() {
+        let flags = sidecar_flags("JVM version is 25.181-b13\n-XX:MaxHeapSize=17179869184 -XX:+UseG1GC\n");
+        assert_eq!(flags.jdk_version, Some(8));
+        assert_eq!(flags.flags_line, "-XX:MaxHeapSize=17179869184 -XX:+UseG1GC");
+    }
+}
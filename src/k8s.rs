@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::archive;
+
+// A --k8s-pod, NAMESPACE/POD or NAMESPACE/POD/CONTAINER
+#[derive(Clone, Debug)]
+pub struct Pod {
+    namespace: String,
+    name: String,
+    container: Option<String>,
+}
+
+pub fn parse_pod(text: &str) -> Result<Pod, String> {
+    let parts: Vec<&str> = text.split('/').collect();
+    match parts[..] {
+        [namespace, name] | [namespace, name, _] if !namespace.is_empty() && !name.is_empty() => {
+            Ok(Pod { namespace: namespace.to_string(), name: name.to_string(), container: parts.get(2).filter(|x| !x.is_empty()).map(|x| x.to_string()) })
+        }
+        _ => Err(format!("{} is not a valid pod, expected NAMESPACE/POD or NAMESPACE/POD/CONTAINER", text)),
+    }
+}
+
+impl Pod {
+    // "k8s:cassandra/cassandra-0/cassandra"
+    fn path(&self) -> PathBuf {
+        let container = self.container.as_deref().map(|x| format!("/{}", x)).unwrap_or_default();
+        PathBuf::from(format!("k8s:{}/{}{}", self.namespace, self.name, container))
+    }
+}
+
+// The log of the pod's container from kubectl logs, with the current context and credentials, held in memory.
+// --timestamps gives every line a datestamp container::strip takes off again, so the lines are those of a file.
+pub fn load(pod: &Pod) -> Result<PathBuf, String> {
+    let mut kubectl = Command::new("kubectl");
+    kubectl.args(["logs", "--namespace", &pod.namespace, &pod.name, "--timestamps"]);
+    if let Some(container) = &pod.container {
+        kubectl.args(["--container", container]);
+    }
+    let path = pod.path();
+    log::info!("Fetching the log of {}", path.display());
+    let output = kubectl.output().map_err(|e| format!("Unable to run kubectl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("kubectl logs failed for {}: {}", path.display(), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    if output.stdout.is_empty() {
+        return Err(format!("The log of {} is empty", path.display()));
+    }
+    archive::register(&path, Arc::from(output.stdout));
+    Ok(path)
+}
@@ -0,0 +1,2255 @@
+mod alert;
+mod allocation_rate;
+mod anonymize;
+mod archive;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod bench;
+mod bins;
+mod buckets;
+mod bursts;
+mod calendar;
+#[cfg(feature = "chart")]
+mod chart;
+mod checkpoint;
+mod chunks;
+mod clusters;
+mod cms;
+mod compare;
+#[cfg(any(feature = "parquet", feature = "arrow"))]
+mod columnar;
+mod concurrent_cycle;
+mod config;
+mod container;
+mod dump;
+mod duplicates;
+mod eager_reclaim;
+mod encoding;
+mod error;
+mod events;
+mod export;
+mod evacuation_failure;
+mod file_summary;
+mod follow;
+mod full_gc;
+mod gc_cause;
+mod gc_cycles;
+mod generate;
+mod gnuplot;
+mod groups;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod html;
+mod heap_occupancy;
+mod heap_resize;
+mod heap_share;
+mod heap_sizing;
+mod heatmap;
+mod humongous_trace;
+mod ihop;
+mod influx;
+mod interarrival;
+mod jfr;
+mod json;
+mod journal;
+mod jvm_flags;
+mod k8s;
+mod line_parsing;
+mod markdown;
+mod merge;
+mod metrics;
+mod numbers;
+mod mixed_gc;
+mod object_shape;
+mod openj9;
+mod otlp;
+mod pager;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod parse_quality;
+mod pattern;
+mod pause_impact;
+mod print_metric;
+mod progress;
+mod recommendation;
+mod remote;
+mod report;
+mod rotation;
+mod safepoint;
+mod sampling;
+mod sections;
+mod serve;
+mod shenandoah;
+mod size_histogram;
+mod size_stats;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod state;
+mod statsd;
+mod throttle;
+mod timeline;
+mod timeseries;
+mod timestamp;
+#[cfg(feature = "tui")]
+mod tui;
+mod upload;
+mod validate;
+mod waste;
+mod watch;
+mod window;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+mod zgc;
+mod zing;
+
+use clap::{ArgAction, CommandFactory, ErrorKind, Parser};
+use glob::Pattern;
+use indicatif::ProgressBar;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, Write};
+use std::num::ParseIntError;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicU64;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use error::GcLogError;
+use file_summary::FileSummary;
+use follow::FollowEventKind;
+use report::{FileAllocations, GroupBy, OutputFormat, Report};
+use rotation::{OverlapFilter, RotationTracker};
+
+
+lazy_static! {
+    static ref RE_ALLOC: Regex = Regex::new(r"allocation request:\s(?P<alloc>\d{6,})(?: bytes|B\s).*source:\sconcurrent\shumongous\sallocation([\],\s]|$)").unwrap();
+}
+
+#[derive(Parser, Clone, Debug)]
+#[clap(author, version, about, long_about = None, subcommand_negates_reqs = true)]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Option<Subcommand>,
+    #[clap(required_unless_present_any = &["watch", "files-from", "from-events", "journal", "k8s-pod", "input"], validator = is_file)]
+    files: Vec<PathBuf>,
+    /// Also read the gc logs from this file, one path or URL per line, or from stdin with -
+    #[clap(long, value_name = "PATH", global = true)]
+    files_from: Option<PathBuf>,
+    /// Read the humongous allocations from a file written by --events ndjson, or a CSV with a bytes column, instead of a gc log
+    #[clap(long, value_name = "PATH", validator = is_file, multiple_occurrences = true, global = true)]
+    from_events: Vec<PathBuf>,
+    /// Also read the gc log a systemd unit writes to stdout from its journal (journalctl -u UNIT -o json), or journalctl -o json output piped to stdin with -
+    #[clap(long, value_name = "UNIT", multiple_occurrences = true, global = true)]
+    journal: Vec<String>,
+    /// Also read the gc log a pod's container writes to stdout, fetched with kubectl logs using the current context
+    #[clap(long, value_name = "NAMESPACE/POD[/CONTAINER]", parse(try_from_str = k8s::parse_pod), multiple_occurrences = true, global = true)]
+    k8s_pod: Vec<k8s::Pod>,
+    /// Also read a gc log under a label, e.g. node1=us-east/gc.log, kept with its file in the per-file sections, the json report, the exports and the metrics for grouping by node or region
+    #[clap(long, value_name = "LABEL=PATH", parse(try_from_str = parse_input), multiple_occurrences = true, global = true)]
+    input: Vec<(String, PathBuf)>,
+    /// Split files larger than 32MB into chunks parsed on this many threads, 0 uses every core
+    #[clap(long, value_name = "N", default_value_t = 1, global = true)]
+    jobs: usize,
+    /// Count each allocation as it's parsed without keeping it, so memory stays flat however large the logs. The sections built from the allocation times (timeline, bursts, Full GCs, pauses...) are left out
+    #[clap(long, conflicts_with_all = &["checkpoint", "dump", "export"], global = true)]
+    bounded_memory: bool,
+    /// Read the gc logs from disk at no more than this many MB per second, so an analysis on a production host doesn't starve the JVM of disk bandwidth
+    #[clap(long, value_name = "MB/S", validator = is_positive_rate, global = true)]
+    throttle: Option<f64>,
+    /// Run at the lowest CPU and I/O priority (renice 19 and ionice's idle class), e.g. along with --throttle on a production host
+    #[clap(long, global = true)]
+    low_priority: bool,
+    /// Seconds before a Full GC in which humongous allocations are attributed to it
+    #[clap(long, default_value_t = 60.0, global = true)]
+    full_gc_window: f64,
+    /// Width in seconds of each window in the humongous allocation / to-space exhaustion timeline
+    #[clap(long, default_value_t = 60.0, global = true)]
+    timeline_interval: f64,
+    /// Also break the allocations down by calendar aligned windows of the datestamps, e.g. 15m, 1h or 1d
+    #[clap(long, value_name = "DURATION", parse(try_from_str = parse_duration_arg), global = true)]
+    interval: Option<u64>,
+    /// The time zone times are shown in, UTC or a fixed offset such as +05:30. Datestamps are compared in UTC whatever offset they were logged with
+    #[clap(long, value_name = "TZ", default_value = "UTC", allow_hyphen_values = true, parse(try_from_str = timestamp::parse_offset), global = true)]
+    display_tz: i64,
+    /// Also show a heatmap of the allocations per hour of the day of their datestamps and region size bucket
+    #[clap(long, global = true)]
+    heatmap: bool,
+    /// Report runs of --timeline-interval windows with more than this many times the average allocation count as bursts
+    #[clap(long, value_name = "FACTOR", default_value_t = 3.0, global = true)]
+    burst_factor: f64,
+    /// Region size in MB to use instead of detecting it from the gc log header
+    #[clap(long, value_name = "MB", global = true)]
+    region_size: Option<u32>,
+    /// JVM flags saved next to gc logs that lack the CommandLine flags, i.e. the output of jcmd <pid> VM.flags or jinfo -flags <pid>, for the region and heap size
+    #[clap(long = "flags-file", value_name = "PATH", parse(try_from_str = read_flags_file), global = true)]
+    sidecar_flags: Option<jvm_flags::FlagsFile>,
+    /// Analyze every file given, even those with the same contents as another one
+    #[clap(long, global = true)]
+    keep_duplicates: bool,
+    /// Remote gc logs downloaded concurrently ahead of the one being parsed, each held in memory until it's parsed
+    #[clap(long, value_name = "N", default_value_t = 2, validator = is_positive, global = true)]
+    prefetch: usize,
+    /// Leading lines of each gc log to search for the CommandLine flags and the logged region size, 0 searches the whole file (e.g. when they only follow a log roll mid-file)
+    #[clap(long, value_name = "LINES", default_value_t = 100, global = true)]
+    header_lines: usize,
+    /// Comma separated region sizes to bucket allocations by, e.g. 1m,2m,4m,8m,16m,32m,64m [default: 2m..512m]
+    #[clap(long, value_delimiter = ',', parse(try_from_str = parse_size_arg), global = true)]
+    buckets: Option<Vec<u64>>,
+    /// Also print a histogram of the allocation sizes in bins of this width, e.g. 512k
+    #[clap(long, value_name = "WIDTH", parse(try_from_str = parse_size_arg), global = true)]
+    bins: Option<u64>,
+    /// Allocations at or above this size are counted in the last --bins bin
+    #[clap(long, value_name = "SIZE", default_value = "64m", parse(try_from_str = parse_size_arg), requires = "bins", global = true)]
+    bins_max: u64,
+    /// Only analyze allocations of at least this size, e.g. 8m for the ones no reasonable region size keeps out of the humongous regions
+    #[clap(long, value_name = "SIZE", parse(try_from_str = parse_size_arg), global = true)]
+    min_size: Option<u64>,
+    /// Only analyze allocations of at most this size, e.g. 4m
+    #[clap(long, value_name = "SIZE", parse(try_from_str = parse_size_arg), global = true)]
+    max_size: Option<u64>,
+    /// Number of most common allocation sizes to report, 0 to leave the section out
+    #[clap(long, value_name = "N", default_value_t = 10, global = true)]
+    top_sizes: usize,
+    /// Group allocation sizes within this many percent of each other when finding the most common sizes
+    #[clap(long, value_name = "PERCENT", default_value_t = 0.0, global = true)]
+    size_tolerance: f64,
+    /// Also list the N exact allocation sizes seen most often with their counts, the other sizes rolled up into one row
+    #[clap(long, value_name = "N", global = true)]
+    size_frequency: Option<usize>,
+    /// Also count lines matching this as humongous allocations, a regex with a (?P<bytes>...) capture or text around a {bytes} placeholder (repeatable)
+    #[clap(long, value_name = "PATTERN", parse(try_from_str = pattern::parse_line_pattern), multiple_occurrences = true, global = true)]
+    pattern: Vec<pattern::LinePattern>,
+    /// Record the allocation sizes in an HdrHistogram with this many significant digits (1 to 5), e.g. 3 for percentiles within 0.1% of the exact value at any size
+    #[clap(long, value_name = "DIGITS", validator = is_precision, global = true)]
+    precision: Option<u8>,
+    /// Comma separated percentiles of the allocation sizes to report
+    #[clap(long, value_delimiter = ',', default_value = "50,75,90,99", validator = is_percentile, global = true)]
+    percentiles: Vec<f64>,
+    /// Print sizes in the text, markdown and html reports as byte counts instead of KB, MB and GB
+    #[clap(long, global = true)]
+    raw_bytes: bool,
+    /// Print counts and byte totals without the thousands separators of the locale (12345678 rather than 12,345,678), for scripts reading the text report
+    #[clap(long, global = true)]
+    plain_numbers: bool,
+    /// Print the report straight to the terminal instead of through $PAGER when it's taller than the screen
+    #[clap(long, global = true)]
+    no_pager: bool,
+    /// Print only this value of the report, e.g. recommendation for the region size to use (16m), count, total-bytes, mean, stddev, min, max, waste or a percentile such as p99
+    #[clap(long, value_name = "METRIC", parse(try_from_str = print_metric::parse_print_metric), global = true)]
+    print: Option<print_metric::PrintMetric>,
+    /// Exit with 1 once the report is out when any file had an error or a warning, e.g. unparseable or undecodable lines, allocations of 512KB or less or an unknown region size
+    #[clap(long, conflicts_with_all = &["follow", "watch"], global = true)]
+    strict: bool,
+    /// Keep following the files as they grow (like tail -F) instead of producing a report
+    #[clap(long, global = true)]
+    follow: bool,
+    /// Serve Prometheus metrics at http://<ADDR>/metrics while following, e.g. 0.0.0.0:9404
+    #[clap(long, value_name = "ADDR", requires = "follow", global = true)]
+    metrics_addr: Option<String>,
+    /// Push metrics to this OpenTelemetry collector using OTLP/HTTP, e.g. http://localhost:4318
+    #[clap(long, value_name = "URL", global = true)]
+    otlp_endpoint: Option<String>,
+    /// Seconds between OTLP pushes while following
+    #[clap(long, value_name = "SECONDS", default_value_t = 10, requires = "follow", global = true)]
+    otlp_interval: u64,
+    /// Send StatsD counters and a size histogram for every allocation while following, e.g. localhost:8125
+    #[clap(long, value_name = "HOST:PORT", requires = "follow", global = true)]
+    statsd_addr: Option<String>,
+    /// Prefix of the StatsD metric names, e.g. cassandra gives cassandra.humongous.count
+    #[clap(long, value_name = "PREFIX", requires = "statsd-addr", global = true)]
+    statsd_prefix: Option<String>,
+    /// Tag the StatsD metrics with the file in the DogStatsD format
+    #[clap(long, requires = "statsd-addr", global = true)]
+    statsd_tags: bool,
+    /// POST a JSON alert to this URL when --alert-rate or --alert-size is breached while following or watching
+    #[clap(long, value_name = "URL", global = true)]
+    alert_webhook: Option<String>,
+    /// Alert when a file has more humongous allocations than this within a minute
+    #[clap(long, value_name = "COUNT", requires = "alert-webhook", global = true)]
+    alert_rate: Option<u64>,
+    /// Alert on a humongous allocation of at least this size, e.g. 64m
+    #[clap(long, value_name = "SIZE", parse(try_from_str = parse_size_arg), requires = "alert-webhook", global = true)]
+    alert_size: Option<u64>,
+    /// Send the alerts as a Slack message, for Slack's incoming webhooks and the chat tools compatible with them
+    #[clap(long, requires = "alert-webhook", global = true)]
+    alert_slack: bool,
+    /// Analyze every new file that appears in this directory, appending each report to the output
+    #[clap(long, value_name = "DIR", validator = is_dir, conflicts_with = "follow", global = true)]
+    watch: Option<PathBuf>,
+    /// Remember how far each gc log was read in this file, so the next run only analyzes what was appended since, along with the totals of every run
+    #[clap(long, value_name = "PATH", conflicts_with_all = &["follow", "watch", "compare"], global = true)]
+    state: Option<PathBuf>,
+    /// Save the progress of each gc log to this file every 30 seconds, so an interrupted analysis can be continued with --resume
+    #[clap(long, value_name = "PATH", conflicts_with_all = &["follow", "watch", "state", "sample"], global = true)]
+    checkpoint: Option<PathBuf>,
+    /// Continue where the analysis saved to --checkpoint left off
+    #[clap(long, requires = "checkpoint", global = true)]
+    resume: bool,
+    /// Replace file paths with stable hashed labels and leave out flag values that aren't numbers, for sharing reports publicly
+    #[clap(long, global = true)]
+    anonymize: bool,
+    /// Compare two gc logs, the first being the baseline and the second the candidate, e.g. before and after a region size change
+    #[clap(long, conflicts_with_all = &["follow", "watch"], global = true)]
+    compare: bool,
+    /// Compare two time ranges of the gc logs side by side, e.g. before and after a deployment: uptimes (600, 10m) or datestamps (2022-01-26T18:00:00), either side of the .. left out for an open range
+    #[clap(long, value_name = "FROM..TO", number_of_values = 2, parse(try_from_str = window::parse_window), conflicts_with_all = &["follow", "watch", "compare", "state", "checkpoint"], global = true)]
+    compare_windows: Vec<window::Window>,
+    // The time range analyzed, set for each side of --compare-windows
+    #[clap(skip)]
+    window: Option<window::Window>,
+    // The parts of the report subcommand's document
+    #[clap(skip)]
+    sections: Option<sections::Sections>,
+    /// List every humongous allocation with its file, line, uptime, timestamp and size instead of writing a report
+    #[clap(long, conflicts_with_all = &["follow", "watch", "compare", "state"], global = true)]
+    dump: bool,
+    /// Order of the --dump, as logged (log) or largest first (size)
+    #[clap(long, arg_enum, value_name = "ORDER", default_value = "log", requires = "dump", global = true)]
+    dump_order: dump::DumpOrder,
+    /// Only read this ratio of each gc log, e.g. 0.1, in evenly spread 1MB blocks, estimating the totals with a 95% error margin for a quick look at a huge log
+    #[clap(long, value_name = "RATIO", validator = is_ratio, conflicts_with_all = &["follow", "state"], global = true)]
+    sample: Option<f64>,
+    /// Also export every allocation along with summary tables, e.g. sqlite:humongous.db, parquet:DIR, arrow:allocations.arrow or xlsx:humongous.xlsx (repeatable)
+    #[clap(long, value_name = "FORMAT:PATH", parse(try_from_str = export::parse_export_target), multiple_occurrences = true, global = true)]
+    export: Vec<export::ExportTarget>,
+    /// Stream every humongous allocation to stdout as it's parsed (file, line, uptime, timestamp and bytes), the report is then only written to --output
+    #[clap(long, arg_enum, value_name = "FORMAT", conflicts_with = "follow", global = true)]
+    events: Option<events::EventsFormat>,
+    /// Also draw the allocation size distribution and the allocations over time, an SVG or a PNG by the file extension
+    #[cfg(feature = "chart")]
+    #[clap(long, value_name = "PATH", conflicts_with_all = &["follow", "compare", "dump"], global = true)]
+    chart: Option<PathBuf>,
+    /// Browse the allocations in an interactive terminal interface instead of writing a report
+    #[cfg(feature = "tui")]
+    #[clap(long, conflicts_with_all = &["follow", "watch", "output", "compare", "events", "dump"], global = true)]
+    tui: bool,
+    /// Increase logging verbosity (-v info, -vv debug, -vvv trace)
+    #[clap(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Only log errors
+    #[clap(short, long, conflicts_with = "verbose", global = true)]
+    quiet: bool,
+    /// Garbage collector the gc logs were written by
+    #[clap(long, arg_enum, default_value = "g1", global = true)]
+    collector: Collector,
+    /// Combine all files into one report (none), report each file separately (file), or group files by region size (region-size)
+    #[clap(long, arg_enum, default_value = "none", global = true)]
+    group_by: GroupBy,
+    /// Only analyze the zip archive entries whose path matches this glob
+    #[clap(long, value_name = "GLOB", default_value = "*gc*.log*", global = true)]
+    archive_pattern: Pattern,
+    /// Skip the files and archive entries whose path or name matches this glob, e.g. '*.hprof'. Can be repeated
+    #[clap(long, value_name = "GLOB", multiple_occurrences = true, global = true)]
+    exclude: Vec<Pattern>,
+    /// Format of the report
+    #[clap(long, arg_enum, default_value = "text", global = true)]
+    output_format: OutputFormat,
+    /// Write the report to this file instead of stdout, creating missing parent directories
+    #[clap(short, long, value_name = "PATH", global = true)]
+    output: Option<PathBuf>,
+    /// Print the report without colors, as when NO_COLOR is set
+    #[clap(long, global = true)]
+    no_color: bool,
+    /// Highlight the region size buckets with more than this many allocations in red
+    #[clap(long, value_name = "COUNT", global = true)]
+    highlight_count: Option<u32>,
+    /// Overwrite the --output file if it already exists
+    #[clap(long, requires = "output", global = true)]
+    force: bool,
+    /// Read default settings from this TOML file instead of ~/.config/gc-ho-parser/config.toml, command line flags take precedence
+    #[clap(long, value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+}
+
+impl Cli {
+    fn jobs(&self) -> usize {
+        match self.jobs {
+            0 => thread::available_parallelism().map_or(1, |x| x.get()),
+            jobs => jobs,
+        }
+    }
+
+    fn reads_events(&self, file: &Path) -> bool {
+        self.from_events.iter().any(|x| x == file)
+    }
+
+    // The --input label of the file, or of the archive or wildcard it came from
+    fn label(&self, file: &Path) -> Option<&str> {
+        let from = |path: &PathBuf| file.starts_with(path) || path.to_str().and_then(|x| glob::Pattern::new(x).ok()).is_some_and(|x| x.matches_path(file));
+        self.input.iter().find(|(_, path)| from(path)).map(|(label, _)| label.as_str())
+    }
+
+    fn in_size_range(&self, bytes: u64) -> bool {
+        self.min_size.is_none_or(|x| bytes >= x) && self.max_size.is_none_or(|x| bytes <= x)
+    }
+
+    // "8MB to 64MB", "at least 8MB" or "at most 64MB", None without --min-size and --max-size
+    fn size_filter(&self) -> Option<String> {
+        match (self.min_size, self.max_size) {
+            (Some(min), Some(max)) => Some(format!("{} to {}", bins::format_size(min), bins::format_size(max))),
+            (Some(min), None) => Some(format!("at least {}", bins::format_size(min))),
+            (None, Some(max)) => Some(format!("at most {}", bins::format_size(max))),
+            (None, None) => None,
+        }
+    }
+
+    // Whether the individual allocations have to outlive the parsing of their file
+    fn retains_allocations(&self) -> bool {
+        #[cfg(feature = "tui")]
+        if self.tui {
+            return true;
+        }
+        #[cfg(feature = "chart")]
+        if self.chart.is_some() {
+            return true;
+        }
+        self.state.is_some() || self.dump || !self.export.is_empty() || self.alert_webhook.is_some()
+    }
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+enum Subcommand {
+    /// Analyze gc logs and write the report, the same as giving the files without a subcommand
+    Analyze {
+        #[clap(required_unless_present_any = &["files-from", "from-events", "journal", "k8s-pod", "input"], validator = is_file)]
+        files: Vec<PathBuf>,
+    },
+    /// Write every analysis (buckets, percentiles, time series, bursts, waste, pauses, recommendation) as one markdown document, or html or json with --output-format
+    Report {
+        #[clap(required_unless_present_any = &["files-from", "from-events", "journal", "k8s-pod", "input"], validator = is_file)]
+        files: Vec<PathBuf>,
+        /// Comma separated parts to include (summary, buckets, percentiles, recommendation, waste, time-series, bursts, pauses), or to leave out when prefixed with -, e.g. -pauses,-bursts
+        #[clap(long, value_name = "SECTIONS", value_delimiter = ',', allow_hyphen_values = true)]
+        sections: Vec<String>,
+    },
+    /// Only print the region size recommendation and the candidate region sizes, followed by the JVM flags to use
+    Recommend {
+        #[clap(required_unless_present_any = &["files-from", "from-events", "journal", "k8s-pod", "input"], validator = is_file)]
+        files: Vec<PathBuf>,
+        /// Also write the JVM flags with their explanatory comments to this file, e.g. for a deployment config review
+        #[clap(long, value_name = "PATH")]
+        write_flags: Option<PathBuf>,
+    },
+    /// Compare two gc logs side by side, e.g. before and after a region size change
+    Compare {
+        #[clap(validator = is_file)]
+        baseline: PathBuf,
+        #[clap(validator = is_file)]
+        candidate: PathBuf,
+    },
+    /// Compare two json reports of earlier runs, e.g. of the last release and this one, showing the changes in the bucket counts, totals, waste and percentiles
+    Diff {
+        #[clap(validator = is_file)]
+        baseline: PathBuf,
+        #[clap(validator = is_file)]
+        candidate: PathBuf,
+        /// Exit with 1 when the allocations, bytes, wasted bytes or a percentile grew by more than this percentage, for regression gating in CI
+        #[clap(long, value_name = "PERCENT", validator = is_percentage)]
+        fail_above: Option<f64>,
+    },
+    /// Combine the json reports of earlier runs, e.g. one per host, into one report: the files, bucket counts and GC causes add up, and the statistics, percentiles and recommendation are worked out again from the histogram of allocation sizes
+    Merge {
+        #[clap(required = true, validator = is_file)]
+        reports: Vec<PathBuf>,
+    },
+    /// Export every allocation along with summary tables instead of writing a report, e.g. `export sqlite:humongous.db gc.log`
+    Export {
+        #[clap(value_name = "FORMAT:PATH", parse(try_from_str = export::parse_export_target))]
+        target: export::ExportTarget,
+        #[clap(required_unless_present_any = &["files-from", "from-events", "journal", "k8s-pod", "input"], validator = is_file)]
+        files: Vec<PathBuf>,
+    },
+    /// Analyze every new file that appears in the directory, appending each report to the output
+    Watch {
+        #[clap(validator = is_dir)]
+        dir: PathBuf,
+    },
+    /// Check what each gc log holds (format, region size, timestamps, humongous allocations) without analyzing it, suggesting the JVM flags for what's missing, and exit with 1 when one can't be analyzed
+    Validate {
+        #[clap(required_unless_present_any = &["files-from", "from-events", "journal", "k8s-pod", "input"], validator = is_file)]
+        files: Vec<PathBuf>,
+    },
+    /// Print a shell completion script, e.g. `completions bash > /etc/bash_completion.d/rs-gc-ho-allocation-parser`
+    Completions {
+        #[clap(arg_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page in roff format, e.g. `manpage | man -l -`
+    Manpage,
+    /// Analyze gc logs uploaded over HTTP (POST /analyze?format=json|html|markdown, or the form at /) with the other options given
+    Serve {
+        /// Address to listen on
+        #[clap(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        addr: String,
+        /// Largest upload accepted, and largest log a gzipped upload may decompress to, e.g. 512m
+        #[clap(long, value_name = "SIZE", default_value = "4g", parse(try_from_str = parse_size_arg))]
+        max_upload: u64,
+        /// Uploads analyzed at the same time
+        #[clap(long, value_name = "N", default_value_t = 4, validator = is_positive)]
+        workers: usize,
+    },
+    /// Time the humongous allocation line parsers (string parsing, regex find, regex captures and any --pattern) over a gc log
+    Bench {
+        /// Gc log to parse, read into memory first so only the parsing is timed
+        #[clap(validator = is_file)]
+        file: PathBuf,
+        /// Runs of each parser, the best and the mean are reported
+        #[clap(long, value_name = "N", default_value_t = 10, validator = is_positive)]
+        iterations: u32,
+    },
+    /// Write a synthetic gc log with humongous allocations at a given rate and size distribution, e.g. to test dashboards or reproduce a customer scenario. The region size is --region-size, or what G1 picks for the heap.
+    Generate {
+        /// jdk8 (-XX:+PrintGCDetails -XX:+PrintAdaptiveSizePolicy) or unified (JDK 17 -Xlog:gc*,gc+ergo+ihop=debug)
+        #[clap(long, arg_enum, default_value = "jdk8")]
+        log_format: generate::LogFormat,
+        /// Humongous allocations per minute on average
+        #[clap(long, value_name = "PER_MINUTE", default_value_t = 60.0, validator = is_positive_rate)]
+        rate: f64,
+        /// Comma separated allocation sizes, exact like 8m or uniform ranges like 1m-32m, optionally weighted, e.g. 8m:3,1m-32m:1
+        #[clap(long, value_name = "SIZES", value_delimiter = ',', default_value = "1m-32m", parse(try_from_str = generate::parse_size_component))]
+        sizes: Vec<generate::SizeComponent>,
+        /// Uptime covered by the log, e.g. 90s, 30m, 1h or 1d
+        #[clap(long, value_name = "DURATION", default_value = "1h", parse(try_from_str = parse_duration_arg))]
+        duration: u64,
+        /// Heap size, e.g. 8g
+        #[clap(long, value_name = "SIZE", default_value = "8g", parse(try_from_str = parse_size_arg))]
+        heap: u64,
+        /// Seed of the random numbers, the same seed and options give the same log
+        #[clap(long, value_name = "N", default_value_t = 1)]
+        seed: u64,
+    },
+    /// Serve the AnalyzeLog gRPC service of proto/analysis.proto with the other options given
+    #[cfg(feature = "grpc")]
+    Grpc {
+        /// Address to listen on
+        #[clap(long, value_name = "ADDR", default_value = "127.0.0.1:50051")]
+        addr: String,
+    },
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+pub enum Collector {
+    G1,
+    // Promotion failures instead of humongous allocations, there are no regions
+    Cms,
+    // Large page counts instead of humongous allocations
+    Zgc,
+    // Failed humongous allocations, against the region size logged at startup
+    Shenandoah,
+    // Tenure allocation failures of the -Xverbosegclog XML, large objects go to the LOA rather than regions
+    #[clap(name = "openj9")]
+    OpenJ9,
+    // Large object allocations of Azul Zing / Prime, C4 has no regions
+    #[clap(alias = "prime")]
+    Zing,
+}
+
+pub struct HumongousAllocation {
+    // Line number within its file
+    pub line: usize,
+    pub uptime: Option<f64>,
+    // Seconds since the epoch (UTC) of the line's datestamp, see timestamp::parse_wall_clock
+    pub wall_clock: Option<i64>,
+    pub bytes: u64,
+}
+
+// What the sections built from the allocation times keep of an allocation with an uptime
+#[derive(Clone, Copy)]
+pub struct AllocationTime {
+    pub uptime: f64,
+    pub bytes: u64,
+}
+
+pub struct RegionSize {
+    pub mb: u32,
+    pub source: RegionSizeSource,
+}
+
+impl RegionSize {
+    pub fn describe(&self) -> String {
+        match self.source {
+            RegionSizeSource::Flag | RegionSizeSource::Logged => format!("{}MB", self.mb),
+            RegionSizeSource::Inferred => format!("{}MB (inferred from heap size)", self.mb),
+            RegionSizeSource::Override => format!("{}MB (from --region-size)", self.mb),
+            RegionSizeSource::FlagsFile => format!("{}MB (from --flags-file)", self.mb),
+        }
+    }
+}
+
+pub enum RegionSizeSource {
+    // -XX:G1HeapRegionSize in the gc log header
+    Flag,
+    // Printed by the JVM at startup
+    Logged,
+    // Derived from the heap size flags because G1HeapRegionSize was absent
+    Inferred,
+    // Supplied with --region-size
+    Override,
+    // -XX:G1HeapRegionSize in the --flags-file of a log without CommandLine flags
+    FlagsFile,
+}
+
+
+
+fn is_file(path: &str) -> Result<(), String> {
+    if remote::is_remote(Path::new(path)) {
+        if archive::is_archive(Path::new(path)) {
+            return Err(format!("{} is a remote archive, only plain gc logs can be read from a URL", path));
+        }
+        if path.starts_with("s3://") && !cfg!(feature = "s3") {
+            return Err(format!("{} needs a build with --features s3", path));
+        }
+        return Ok(());
+    }
+    if Path::new(path).is_file() || archive::is_in_memory(Path::new(path)) { return Ok(()); }
+    match expand_glob(Path::new(path)) {
+        Some(files) if files.is_empty() => Err(format!("{} matches no files", path)),
+        Some(_) => Ok(()),
+        None => Err(format!("{} is not a valid file", path)),
+    }
+}
+
+// The files matching a path with wildcards, which cmd and PowerShell pass on unexpanded, or None when it has none.
+// Backslashes are separators on Windows, so C:\logs\gc*.log and \\server\share\gc*.log expand there as well.
+fn expand_glob(path: &Path) -> Option<Vec<PathBuf>> {
+    let pattern = path.to_str().filter(|x| x.contains(['*', '?', '[']) && !remote::is_remote(path))?;
+    let files = glob::glob(pattern).ok()?;
+    Some(files.filter_map(Result::ok).filter(|x| x.is_file()).collect())
+}
+
+// A --input LABEL=PATH, the path checked like a file argument
+fn parse_input(input: &str) -> Result<(String, PathBuf), String> {
+    match input.split_once('=') {
+        Some((label, path)) if !label.trim().is_empty() => is_file(path).map(|_| (label.trim().to_string(), PathBuf::from(path))),
+        _ => Err(format!("{} is not LABEL=PATH", input)),
+    }
+}
+
+// The non-empty lines of a --files-from list, other than # comments, each checked like a file argument
+fn read_files_from(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let contents = match path == Path::new("-") {
+        true => io::read_to_string(io::stdin()),
+        false => fs::read_to_string(path),
+    };
+    let contents = contents.map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|x| !x.1.is_empty() && !x.1.starts_with('#'))
+        .map(|(number, line)| is_file(line).map(|_| PathBuf::from(line)).map_err(|e| format!("{}:{}: {}", path.display(), number, e)))
+        .collect()
+}
+
+// Matched against the whole path as well as the file name, so '*.hprof' and 'gc.log.0' both work
+fn is_excluded(args: &Cli, file: &Path) -> bool {
+    let name = file.file_name().map(Path::new);
+    let excluded = args.exclude.iter().any(|x| x.matches_path(file) || name.is_some_and(|name| x.matches_path(name)));
+    if excluded {
+        log::debug!("Skipping {}, it matches --exclude", file.display());
+    }
+    excluded
+}
+
+// The files given as arguments followed by those listed in --files-from, the --input ones, the --from-events, the
+// --journal units and the --k8s-pod logs, wildcards expanded, without the --exclude ones
+fn input_files(args: &Cli, files: &[PathBuf]) -> Vec<PathBuf> {
+    let files: Vec<PathBuf> = match &args.files_from {
+        None => files.to_vec(),
+        Some(path) => match read_files_from(path) {
+            Ok(listed) if files.is_empty() && listed.is_empty() => {
+                Cli::command().error(ErrorKind::MissingRequiredArgument, format!("{} lists no gc logs", path.display())).exit()
+            }
+            Ok(listed) => files.iter().cloned().chain(listed).collect(),
+            Err(e) => Cli::command().error(ErrorKind::InvalidValue, format!("Invalid --files-from: {}", e)).exit(),
+        },
+    };
+    let journals = args.journal.iter().map(|unit| journal::load(unit).unwrap_or_else(|e| Cli::command().error(ErrorKind::Io, e).exit()));
+    let pods = args.k8s_pod.iter().map(|pod| k8s::load(pod).unwrap_or_else(|e| Cli::command().error(ErrorKind::Io, e).exit()));
+    let inputs = args.input.iter().map(|(_, path)| path.clone());
+    let files = files.into_iter().chain(inputs).chain(args.from_events.iter().cloned()).chain(journals).chain(pods);
+    let files: Vec<PathBuf> = files.flat_map(|x| if x.exists() { None } else { expand_glob(&x) }.unwrap_or_else(|| vec![x])).collect();
+    let given = files.len();
+    let files: Vec<PathBuf> = files.into_iter().filter(|x| !is_excluded(args, x)).collect();
+    if given > 0 && files.is_empty() {
+        Cli::command().error(ErrorKind::MissingRequiredArgument, "every gc log given matches --exclude").exit();
+    }
+    files
+}
+
+// Archives replaced by their entries, the excluded ones left out
+fn expand_archives(args: &Cli, files: &[PathBuf]) -> Vec<PathBuf> {
+    archive::expand(files, &args.archive_pattern).into_iter().filter(|x| !is_excluded(args, x)).collect()
+}
+
+fn is_dir(path: &str) -> Result<(), String> {
+    if std::path::Path::new(path).is_dir() { return Ok(()); }
+    Err(format!("{} is not a valid directory", path))
+}
+
+fn parse_size_arg(size: &str) -> Result<u64, String> {
+    jvm_flags::parse_size(size).ok_or_else(|| format!("{} is not a valid size", size))
+}
+
+// The flags of a --flags-file as one CommandLine flags line
+fn read_flags_file(path: &str) -> Result<jvm_flags::FlagsFile, String> {
+    let contents = std::fs::read(path).map_err(|e| format!("unable to read {}: {}", path, e))?;
+    let flags = jvm_flags::sidecar_flags(&String::from_utf8_lossy(&encoding::transcode(contents)));
+    match flags.flags_line.is_empty() {
+        true => Err(format!("{} has no JVM flags, expected the output of jcmd <pid> VM.flags or jinfo -flags <pid>", path)),
+        false => Ok(flags),
+    }
+}
+
+// "90s", "15m", "1h" or "1d" in seconds, a bare number is in seconds
+fn parse_duration_arg(duration: &str) -> Result<u64, String> {
+    let (number, unit) = match duration.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => duration.split_at(index),
+        None => (duration, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("{} is not a valid duration, expected e.g. 30m, 1h or 1d", duration)),
+    };
+    match number.parse::<u64>() {
+        Ok(x) if x > 0 => Ok(x * multiplier),
+        _ => Err(format!("{} is not a valid duration, expected e.g. 30m, 1h or 1d", duration)),
+    }
+}
+
+fn is_positive(count: &str) -> Result<(), String> {
+    match count.parse::<u32>() {
+        Ok(x) if x > 0 => Ok(()),
+        _ => Err(format!("{} is not a valid count, expected a whole number larger than 0", count)),
+    }
+}
+
+fn is_positive_rate(rate: &str) -> Result<(), String> {
+    match rate.parse::<f64>() {
+        Ok(x) if x > 0.0 && x.is_finite() => Ok(()),
+        _ => Err(format!("{} is not a valid rate, expected a number larger than 0", rate)),
+    }
+}
+
+fn is_ratio(ratio: &str) -> Result<(), String> {
+    match ratio.parse::<f64>() {
+        Ok(x) if x > 0.0 && x <= 1.0 => Ok(()),
+        _ => Err(format!("{} is not a valid ratio, expected a number in (0, 1]", ratio)),
+    }
+}
+
+fn is_precision(digits: &str) -> Result<(), String> {
+    match digits.parse::<u8>() {
+        Ok(1..=5) => Ok(()),
+        _ => Err(format!("{} is not a valid precision, expected 1 to 5 significant digits", digits)),
+    }
+}
+
+fn is_percentage(percentage: &str) -> Result<(), String> {
+    match percentage.parse::<f64>() {
+        Ok(x) if x >= 0.0 && x.is_finite() => Ok(()),
+        _ => Err(format!("{} is not a valid percentage, expected a number of 0 or more", percentage)),
+    }
+}
+
+fn is_percentile(percentile: &str) -> Result<(), String> {
+    match percentile.parse::<f64>() {
+        Ok(x) if x > 0.0 && x <= 100.0 => Ok(()),
+        _ => Err(format!("{} is not a valid percentile, expected a number in (0, 100]", percentile)),
+    }
+}
+
+// Manual string parsing implementation
+// Faster than Python's regex
+// Returns None for lines that are not humongous allocations, and an error for ones whose size can't be parsed
+// JDK 8 updates differ in what follows the source: the closing bracket, more fields after a comma or a space
+// ("..., source: concurrent humongous allocation, occupancy: 3932160000 bytes]"), or nothing at all when
+// another thread wrote to the log before the line was finished
+// Unified logging (gc+ergo+ihop=debug) writes the size as "allocation request: 11265758B threshold: ..."
+fn parse_humongous_object_allocation(line: &str) -> Option<Result<u64, ParseIntError>> {
+    let request = line.split_once("allocation request: ")?.1;
+    let after_source = request.split_once("source: concurrent humongous allocation")?.1;
+    if !(after_source.is_empty() || after_source.starts_with([']', ',', ' ', '\t'])) {
+        return None;
+    }
+    let (size, rest) = request.split_once(' ')?;
+    match rest.starts_with("bytes") {
+        true => Some(size.parse::<u64>()),
+        false => size.strip_suffix('B').map(|x| x.parse::<u64>()),
+    }
+}
+
+// The built in format first, custom --pattern matchers only see the lines it does not recognize
+fn parse_allocation(line: &str, patterns: &[pattern::LinePattern]) -> Option<Result<u64, ParseIntError>> {
+    parse_humongous_object_allocation(line).or_else(|| patterns.iter().find_map(|x| x.parse(line)))
+}
+
+// Original implementation that ended up being much too slow on large files (Python outperforms it)
+// Captures use a 3 pass system (find the match, find boundaries, determine captures)
+// No additional string parsing needed
+fn parse_humongous_object_allocation_with_regex_captures(line: &str) -> Option<Result<u64, ParseIntError>> {
+    RE_ALLOC.captures(line).and_then(|caps| caps.name("alloc")).map(|alloc| alloc.as_str().parse::<u64>())
+}
+
+// Second implementation to speed up regex parsing
+// Still too slow (Python outperforms)
+// Find uses a 2 pass system (find the match, find the boundaries)
+// Implementation must apply additional string parsing on top
+fn parse_humongous_object_allocation_with_regex_find(line: &str) -> Option<Result<u64, ParseIntError>> {
+    RE_ALLOC
+        .find(line)
+        .and_then(|alloc| alloc.as_str()["allocation request: ".len()..].split_once([' ', 'B']))
+        .map(|alloc| alloc.0.parse::<u64>())
+}
+
+// Warnings are shown by default, RUST_LOG takes precedence over the -v/-q flags
+fn init_logging(args: &Cli) {
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => log::LevelFilter::Error,
+        (false, 0) => log::LevelFilter::Warn,
+        (false, 1) => log::LevelFilter::Info,
+        (false, 2) => log::LevelFilter::Debug,
+        (false, _) => log::LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .format_target(false)
+        .parse_default_env()
+        .init();
+}
+
+fn open_gc_log(file: &Path) -> Result<(Box<dyn archive::ReadSeek>, u64), GcLogError> {
+    archive::open(file).map_err(|source| GcLogError::Open { path: file.to_path_buf(), source })
+}
+
+// What the startup lines of a gc log say about the JVM that wrote it
+#[derive(Default)]
+struct Header {
+    flags_line: Option<String>,
+    logged_region_size: Option<u64>,
+    jdk_version: Option<u32>,
+    // Lines of -Xlog's unified logging, i.e. JDK 9 or later
+    unified: bool,
+}
+
+// The "CommandLine flags:" line JDK 8 prints in the header of every log file, and the region size unified logs
+// print at startup. The flags line is normally the 4th, but banner lines can push it down and a log that starts
+// mid-run only has it after the next roll. The search stops at the flags line, or at the first collection once
+// the region size was found as the startup lines are over by then.
+fn read_header(file: &Path, header_lines: usize) -> Result<Header, GcLogError> {
+    let lines = line_parsing::lossy_lines(BufReader::new(open_gc_log(file)?.0));
+    let limit = if header_lines == 0 { usize::MAX } else { header_lines };
+    let mut scanned = 0;
+    let mut header = Header::default();
+    let mut logged_region_size = None;
+    for line in lines.take(limit) {
+        let (line, _) = line.map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?;
+        scanned += 1;
+        let line = container::strip(&line);
+        header.jdk_version = header.jdk_version.or_else(|| jvm_flags::jdk_version(&line));
+        header.unified |= line.starts_with('[') && line.contains("][gc");
+        if line.contains("CommandLine flags:") {
+            log::debug!("{}: CommandLine flags on line {}", file.display(), scanned);
+            return Ok(Header { flags_line: Some(line.into_owned()), logged_region_size, ..header });
+        }
+        if let Some(bytes) = jvm_flags::logged_region_size(&line) {
+            log::debug!("{}: region size logged on line {}", file.display(), scanned);
+            logged_region_size = Some(bytes);
+        } else if logged_region_size.is_some() && line.contains("GC(") {
+            break;
+        }
+    }
+    if scanned < 4 && logged_region_size.is_none() {
+        return Err(GcLogError::TooShort { path: file.to_path_buf() });
+    }
+    Ok(Header { logged_region_size, ..header })
+}
+
+fn extract_region_size(file: &Path, args: &Cli, summary: &mut FileSummary) -> Result<RegionSize, GcLogError> {
+    let header = read_header(file, args.header_lines)?;
+    let logged = header.logged_region_size.map(|bytes| RegionSize { mb: (bytes / 1024 / 1024) as u32, source: RegionSizeSource::Logged });
+    // The flags line of the log itself takes precedence over the --flags-file
+    let sidecar = header.flags_line.is_none();
+    let sidecar_version = args.sidecar_flags.as_ref().and_then(|x| x.jdk_version).filter(|_| sidecar);
+    summary.jdk_version = header.jdk_version.or(sidecar_version);
+    let Some(flags_line) = header.flags_line.or_else(|| args.sidecar_flags.as_ref().map(|x| x.flags_line.clone())) else {
+        return logged.ok_or_else(|| GcLogError::MissingFlags { path: file.to_path_buf() });
+    };
+    summary.jvm_configuration = jvm_flags::g1_configuration(&flags_line);
+    let (initial_heap, max_heap) = jvm_flags::heap_sizes(&flags_line);
+    (summary.initial_heap, summary.max_heap) = (initial_heap, max_heap);
+    // Unified logging, which has no -XX:+PrintAdaptiveSizePolicy, is what normally leaves the flags line out
+    if !sidecar && !flags_line.contains("PrintAdaptiveSizePolicy") {
+        summary.warn("Humongous allocation sizes are not being printed. Please add -XX:+PrintAdaptiveSizePolicy in order to print out humongous allocation sizes".to_string());
+    }
+    // A region size the log printed is what the JVM that wrote it used, the --flags-file may be of another run
+    if let Some(region_size) = jvm_flags::flag_value(&flags_line, "G1HeapRegionSize").filter(|_| !sidecar || logged.is_none()) {
+        let bytes = jvm_flags::parse_size(region_size)
+            .ok_or_else(|| GcLogError::InvalidRegionSize { path: file.to_path_buf(), value: region_size.to_string() })?;
+        let source = if sidecar { RegionSizeSource::FlagsFile } else { RegionSizeSource::Flag };
+        return Ok(RegionSize { mb: (bytes / 1024 / 1024) as u32, source });
+    }
+    if let Some(logged) = logged {
+        return Ok(logged);
+    }
+    // G1HeapRegionSize is only printed when set explicitly, otherwise G1 derived it from the heap size
+    // The flags line of a log is JDK 8's. A unified log that doesn't say which JDK wrote it is taken to be of
+    // JDK 15 or later, the releases in use today.
+    let jdk_version = summary.jdk_version.unwrap_or(if header.unified { 15 } else { 8 });
+    match jvm_flags::ergonomic_region_size(initial_heap, max_heap, jdk_version) {
+        Some(region_size) => Ok(RegionSize { mb: (region_size / 1024 / 1024) as u32, source: RegionSizeSource::Inferred }),
+        None => Err(GcLogError::UnknownRegionSize { path: file.to_path_buf() }),
+    }
+}
+
+fn is_flight_recording(file: &Path) -> Result<bool, GcLogError> {
+    jfr::is_recording(&mut open_gc_log(file)?.0).map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })
+}
+
+fn read_recording(file: &Path, mut reader: impl Read) -> Result<jfr::Recording, GcLogError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?;
+    jfr::parse(&data).map_err(|message| GcLogError::InvalidRecording { path: file.to_path_buf(), message })
+}
+
+// Flight recordings hold the value of every flag, G1HeapRegionSize included even when it was set ergonomically
+fn extract_recording_region_size(file: &Path, summary: &mut FileSummary) -> Result<RegionSize, GcLogError> {
+    let recording = read_recording(file, open_gc_log(file)?.0)?;
+    summary.jvm_configuration = jvm_flags::g1_configuration(&recording.flags_line);
+    (summary.initial_heap, summary.max_heap) = jvm_flags::heap_sizes(&recording.flags_line);
+    let Some(region_size) = jvm_flags::flag_value(&recording.flags_line, "G1HeapRegionSize") else {
+        return Err(GcLogError::UnknownRegionSize { path: file.to_path_buf() });
+    };
+    let bytes = jvm_flags::parse_size(region_size).ok_or_else(|| GcLogError::InvalidRegionSize { path: file.to_path_buf(), value: region_size.to_string() })?;
+    Ok(RegionSize { mb: (bytes / 1024 / 1024) as u32, source: RegionSizeSource::Flag })
+}
+
+fn file_region_size(file: &Path, args: &Cli, summary: &mut FileSummary) -> Result<Option<RegionSize>, GcLogError> {
+    // Exported allocations carry no flags, only --region-size places them in a region size
+    if args.reads_events(file) {
+        return Ok(args.region_size.map(|mb| RegionSize { mb, source: RegionSizeSource::Override }));
+    }
+    match (args.collector, args.region_size) {
+        (Collector::Cms | Collector::Zgc | Collector::OpenJ9 | Collector::Zing, _) => Ok(None),
+        (_, Some(mb)) => Ok(Some(RegionSize { mb, source: RegionSizeSource::Override })),
+        (Collector::G1, None) if is_flight_recording(file)? => extract_recording_region_size(file, summary).map(Some),
+        (Collector::G1, None) => extract_region_size(file, args, summary).map(Some),
+        // Read from the startup lines while parsing
+        (Collector::Shenandoah, None) => Ok(None),
+    }
+}
+
+// What the lines of a gc log add up to, before the sections of the report are built from it
+struct FileScan<'a> {
+    file: &'a Path,
+    args: &'a Cli,
+    region_size: u64,
+    // Only kept for the outputs that write every allocation (--dump, --state, --export, ...) and by the chunks
+    // of a file parsed in parallel
+    allocations: Vec<HumongousAllocation>,
+    // Allocations counted so far, the pauses are placed among them by it
+    counted: u64,
+    times: Vec<AllocationTime>,
+    // Datestamp of the first allocation of each --timeline-interval window of uptime that has one
+    window_dates: BTreeMap<u64, i64>,
+    full_gcs: Vec<full_gc::FullGc>,
+    evacuation_failures: Vec<f64>,
+    heap_samples: Vec<heap_occupancy::HeapSample>,
+    eden_samples: Vec<allocation_rate::EdenSample>,
+    heap_resizes: Vec<heap_resize::HeapResize>,
+    safepoints: Vec<safepoint::Safepoint>,
+    pauses: Vec<gc_cycles::Pause>,
+    marking_cycles: Vec<concurrent_cycle::MarkingCycle>,
+    cycle_timer: concurrent_cycle::CycleTimer,
+    // Promotion failure (CMS) or large page (ZGC) sizes with their count, only added to the histogram
+    // once the whole file is read as merging histograms loses precision
+    sizes: Vec<(u64, u64)>,
+    last_uptime: Option<f64>,
+    clock: timestamp::Clock,
+    // Name and --input label of the file in the --events, None without them
+    events: Option<(String, Option<String>)>,
+    checkpointer: Option<checkpoint::Checkpointer>,
+    // A chunk parsed on a thread of its own keeps its allocations as they are, they're counted once merged back in order
+    chunk: bool,
+}
+
+impl<'a> FileScan<'a> {
+    fn new(file: &'a Path, args: &'a Cli, region_size: u64, clock: timestamp::Clock) -> FileScan<'a> {
+        let events = args.events.map(|_| match args.anonymize {
+            true => (anonymize::label(&file.display().to_string()), args.label(file).map(anonymize::label)),
+            false => (file.display().to_string(), args.label(file).map(str::to_string)),
+        });
+        FileScan { file, args, region_size, allocations: Vec::new(), counted: 0, times: Vec::new(), window_dates: BTreeMap::new(), full_gcs: Vec::new(), evacuation_failures: Vec::new(), heap_samples: Vec::new(), eden_samples: Vec::new(), heap_resizes: Vec::new(), safepoints: Vec::new(), pauses: Vec::new(), marking_cycles: Vec::new(), cycle_timer: Default::default(), sizes: Vec::new(), last_uptime: None, clock, events, checkpointer: None, chunk: false }
+    }
+
+    fn record(&mut self, allocation: HumongousAllocation, report: &mut Report, summary: &mut FileSummary) {
+        log::debug!("{}:{}: humongous allocation of {} bytes", self.file.display(), allocation.line, allocation.bytes);
+        if let Some((file, label)) = self.events.as_ref().filter(|_| self.args.in_size_range(allocation.bytes)) {
+            events::emit(file, label.as_deref(), &allocation);
+        }
+        self.fold(allocation, report, summary);
+    }
+
+    // Counts the allocation in the report as it's parsed. Only its uptime and size are kept for the sections
+    // built from the allocation times, not even those with --bounded-memory, and the whole allocation only for
+    // the per allocation outputs.
+    fn fold(&mut self, allocation: HumongousAllocation, report: &mut Report, summary: &mut FileSummary) {
+        let args = self.args;
+        if self.chunk {
+            self.allocations.push(allocation);
+            return;
+        }
+        // Size filters apply before anything is counted, so every section only sees the allocations in range
+        if !args.in_size_range(allocation.bytes) || args.window.as_ref().is_some_and(|window| !window.contains(&allocation)) {
+            summary.filtered_allocations += 1;
+            return;
+        }
+        // Shenandoah and OpenJ9 log their region size at startup, before any allocation
+        if let (None, Some(bytes)) = (&summary.region_size, report.shenandoah.region_size().or(report.openj9.region_size())) {
+            if bytes >= buckets::MB {
+                summary.region_size = Some(RegionSize { mb: (bytes / buckets::MB) as u32, source: RegionSizeSource::Logged });
+            }
+        }
+        let item = allocation.bytes;
+        summary.allocations += 1;
+        summary.allocated_bytes += item;
+        summary.dated_allocations += allocation.wall_clock.is_some() as u64;
+        summary.uptime_allocations += allocation.uptime.is_some() as u64;
+        report.size_clusters.record(item);
+        if let Some(width) = args.bins {
+            report.size_bins.record(item, width, args.bins_max);
+        }
+        if let Some(interval) = args.interval {
+            report.calendar.record(allocation.wall_clock, interval, item);
+        }
+        report.size_stats.record(item, 1);
+        if report.allocs_histogram.increment(item).is_err() {
+            summary.warn_at(allocation.line, format!("allocation of {} bytes is outside of the histogram range", item));
+        }
+        // Only with a known region size, objects in a log whose region size is unknown can't be placed
+        if let (Collector::G1 | Collector::Shenandoah, Some(region_size)) = (args.collector, &summary.region_size) {
+            report.waste.observe(item, region_size.mb.max(1) as u64 * buckets::MB);
+        }
+        // The region size buckets follow G1's 50% humongous threshold
+        if let Collector::G1 = args.collector {
+            match buckets::bucket_index(&report.region_size_array, item) {
+                Some(index) => {
+                    report.region_size_array[index].num_allocations += 1;
+                    if args.heatmap {
+                        report.heatmap.record(allocation.wall_clock, &report.region_size_array[index]);
+                    }
+                    report.candidates.observe(item);
+                }
+                // Catch any 0 byte allocations or anything for a 1MB region because that should never happen
+                None => {
+                    log::warn!("{}: Unexpected byte allocation of {} <= 524289 occurred in the log", self.file.display(), item);
+                    summary.unexpected_allocations += 1;
+                }
+            }
+        }
+        self.counted += 1;
+        if let Some(uptime) = allocation.uptime.filter(|_| self.keeps_events()) {
+            self.times.push(AllocationTime { uptime, bytes: item });
+            if let Some(wall_clock) = allocation.wall_clock {
+                self.window_dates.entry((uptime / args.timeline_interval) as u64).or_insert(wall_clock);
+            }
+        }
+        if self.keeps_events() {
+            report.inter_arrival.observe(&allocation);
+        }
+        if self.keeps_allocations() {
+            self.allocations.push(allocation);
+        }
+    }
+
+    // The line's uptime and wall clock time, the time the container runtime logged it at standing in for the
+    // wall clock when -Xlog left the time decoration out
+    fn observe_clock(&mut self, line: &str, wrapped_wall_clock: Option<i64>) -> (Option<f64>, Option<i64>) {
+        match self.clock.observe(line) {
+            (uptime, None) if wrapped_wall_clock.is_some() => self.clock.anchor(uptime, wrapped_wall_clock),
+            observed => observed,
+        }
+    }
+
+    // Whether the events only the sections built from the allocation times use are kept
+    fn keeps_events(&self) -> bool {
+        !self.args.bounded_memory
+    }
+
+    // Whether every allocation is kept as it is, to be written out again or saved at the checkpoints
+    fn keeps_allocations(&self) -> bool {
+        self.args.retains_allocations() || self.checkpointer.is_some()
+    }
+
+    // Lines are numbered from first_line, the overlap filter only applies to the start of the file
+    // Returns the number of lines read
+    fn scan(&mut self, lines: impl Iterator<Item = io::Result<(String, bool)>>, first_line: usize, report: &mut Report, summary: &mut FileSummary, mut overlap: Option<&mut OverlapFilter>) -> Result<usize, GcLogError> {
+        let mut lines_read = 0;
+        for (index, line) in lines.enumerate() {
+            let line_number = first_line + index;
+            lines_read = index + 1;
+            summary.lines_read += 1;
+            if let Some(checkpointer) = &mut self.checkpointer {
+                checkpointer.tick(line_number - 1, &self.allocations);
+            }
+            let (line, lossy) = line.map_err(|source| GcLogError::Read { path: self.file.to_path_buf(), source })?;
+            if line_parsing::is_binary(&line, lossy) {
+                log::debug!("{}:{}: skipping binary line", self.file.display(), line_number);
+                summary.binary_lines += 1;
+                continue;
+            }
+            if lossy {
+                log::debug!("{}:{}: replaced invalid UTF-8", self.file.display(), line_number);
+                summary.undecodable_lines += 1;
+            }
+            let (line, wrapped_wall_clock) = container::unwrap(&line);
+            if overlap.as_deref_mut().is_some_and(|x| x.is_duplicate(&line)) {
+                continue;
+            }
+            let (uptime, wall_clock) = match self.args.collector {
+                Collector::OpenJ9 => self.clock.anchor(None, openj9::parse_wall_clock(&line)),
+                _ => self.observe_clock(&line, wrapped_wall_clock),
+            };
+            if let Some(uptime) = uptime {
+                summary.observe_uptime(uptime);
+                self.last_uptime = Some(uptime);
+            }
+            if let Collector::Cms = self.args.collector {
+                if let Some(failure) = cms::parse_cms_failure(&line) {
+                    log::debug!("{}:{}: promotion failure ({} sized objects)", self.file.display(), line_number, failure.sizes.len());
+                    report.cms_failures.record(&failure);
+                    self.sizes.extend(failure.sizes.iter().map(|x| (*x, 1)));
+                }
+            }
+            if let Collector::Zgc = self.args.collector {
+                if let Some(count) = zgc::parse_page_count(&line) {
+                    report.zgc_pages.record(&count);
+                    // Only the total size of each page type is logged, so every large page counts at the average size
+                    if count.page_type == zgc::LARGE && count.pages > 0 {
+                        self.sizes.push((count.bytes / count.pages, count.pages));
+                    }
+                    continue;
+                }
+            }
+            if let Collector::Shenandoah = self.args.collector {
+                if let Some(bytes) = report.shenandoah.observe(&line) {
+                    self.record(HumongousAllocation { line: line_number, uptime, wall_clock, bytes }, report, summary);
+                    continue;
+                }
+            }
+            if let Collector::Zing = self.args.collector {
+                if let Some(bytes) = report.zing.observe(&line) {
+                    self.record(HumongousAllocation { line: line_number, uptime, wall_clock, bytes }, report, summary);
+                    continue;
+                }
+            }
+            // None of the HotSpot lines below appear in the XML
+            if let Collector::OpenJ9 = self.args.collector {
+                if let Some((bytes, logged)) = report.openj9.observe(&line) {
+                    let (uptime, wall_clock) = self.clock.anchor(None, logged);
+                    self.record(HumongousAllocation { line: line_number, uptime, wall_clock, bytes }, report, summary);
+                }
+                continue;
+            }
+            report.ihop.observe(&line);
+            if report.gc_causes.observe(&line) && self.keeps_events() {
+                self.pauses.push(gc_cycles::Pause { allocations: self.counted, ..gc_cycles::Pause::parse(&line, line_number) });
+            }
+            if let Some(sample) = heap_occupancy::parse_heap_occupancy(&line, self.last_uptime).filter(|_| self.keeps_events()) {
+                self.heap_samples.push(sample);
+            }
+            if let Some(resize) = heap_resize::parse_heap_resize(&line, self.last_uptime) {
+                report.heap_resizes.record(&resize);
+                if self.keeps_events() {
+                    self.heap_resizes.push(resize);
+                }
+            }
+            if let Some(sample) = allocation_rate::parse_eden(&line, self.last_uptime, self.region_size).filter(|_| self.keeps_events()) {
+                self.eden_samples.push(sample);
+            }
+            if let Some(safepoint) = safepoint::parse_safepoint(&line, uptime) {
+                if self.keeps_events() {
+                    self.safepoints.push(safepoint);
+                }
+                continue;
+            }
+            match parse_allocation(&line, &self.args.pattern) {
+                Some(Ok(bytes)) => {
+                    self.record(HumongousAllocation { line: line_number, uptime, wall_clock, bytes }, report, summary);
+                    report.concurrent_cycles.record_humongous_request(&line);
+                    continue;
+                }
+                Some(Err(e)) => {
+                    log::warn!("{}:{}: unable to parse humongous allocation size: {}", self.file.display(), line_number, e);
+                    summary.unparseable_lines += 1;
+                    continue;
+                }
+                None => {}
+            }
+            if let Some(full_gc) = full_gc::parse_full_gc(&line, uptime) {
+                log::debug!("{}:{}: Full GC ({})", self.file.display(), line_number, full_gc.cause);
+                if self.keeps_events() {
+                    self.full_gcs.push(full_gc);
+                }
+                continue;
+            }
+            if evacuation_failure::is_evacuation_failure(&line) {
+                report.evacuation_failures += 1;
+                summary.evacuation_failures += 1;
+                if let Some(uptime) = uptime.filter(|_| self.keeps_events()) {
+                    self.evacuation_failures.push(uptime);
+                }
+            }
+            report.concurrent_cycles.observe(&line);
+            if let Some(cycle) = self.cycle_timer.observe(&line, uptime).filter(|_| self.keeps_events()) {
+                self.marking_cycles.push(cycle);
+            }
+            report.eager_reclaim.observe(&line, summary.region_size.as_ref().map(|x| x.mb as u64 * buckets::MB));
+            report.mixed_collections.observe(&line, uptime);
+            report.humongous_trace.observe(&line, self.region_size);
+        }
+        Ok(lines_read)
+    }
+
+    // --sample, the blocks are read one after the other with the lines numbered as if the skipped blocks
+    // weren't there. Returns the number and bytes of the allocations within --min-size / --max-size of
+    // each block.
+    fn scan_sample(&mut self, blocks: &[Range<u64>], report: &mut Report, summary: &mut FileSummary, overlap: &mut OverlapFilter, progress: &ProgressBar) -> Result<Vec<(u64, u64)>, GcLogError> {
+        let file = self.file;
+        let read_error = |source| GcLogError::Read { path: file.to_path_buf(), source };
+        let (mut overlap, mut first_line, mut samples) = (Some(overlap), 1, Vec::new());
+        for block in blocks {
+            let (allocations, allocated_bytes, sizes) = (summary.allocations, summary.allocated_bytes, self.sizes.len());
+            progress.set_position(block.start);
+            let lines = line_parsing::lossy_lines(BufReader::new(progress.wrap_read(chunks::open(file, block).map_err(read_error)?)));
+            first_line += self.scan(lines, first_line, report, summary, overlap.take())?;
+            // The allocations are only counted within --min-size / --max-size
+            let objects = self.sizes[sizes..].iter().filter(|x| self.args.in_size_range(x.0));
+            samples.push(objects.fold((summary.allocations - allocations, summary.allocated_bytes - allocated_bytes), |(count, bytes), x| (count + x.1, bytes + x.0 * x.1)));
+        }
+        Ok(samples)
+    }
+
+    // Parses the chunks in parallel, each into a report of its own that is merged back in order. Lines are
+    // counted up front so line numbers stay those of the file. An event spread over a chunk boundary, like a
+    // concurrent cycle request at the end of one chunk and the cycle it starts at the beginning of the next,
+    // isn't put together again.
+    fn scan_chunks(&mut self, ranges: &[Range<u64>], report: &mut Report, summary: &mut FileSummary, overlap: &mut OverlapFilter, progress: &ProgressBar) -> Result<(), GcLogError> {
+        let file = self.file;
+        let read_error = |source| GcLogError::Read { path: file.to_path_buf(), source };
+        let counts = thread::scope(|scope| {
+            let counting: Vec<_> = ranges.iter().map(|range| scope.spawn(move || chunks::count_lines(file, range))).collect();
+            counting.into_iter().map(|x| x.join().unwrap()).collect::<io::Result<Vec<usize>>>()
+        })
+        .map_err(read_error)?;
+        // Chunks that start with uptime-only lines need the JVM start time from the lines before them
+        for (line, _) in line_parsing::lossy_lines(BufReader::new(chunks::open(file, &ranges[0]).map_err(read_error)?)).map_while(Result::ok) {
+            let (line, wrapped_wall_clock) = container::unwrap(&line);
+            if self.observe_clock(&line, wrapped_wall_clock) != (None, None) {
+                break;
+            }
+        }
+
+        let mut overlap = Some(overlap);
+        let mut first_line = 1;
+        let scanned = thread::scope(|scope| {
+            let mut scanning = Vec::new();
+            for (range, count) in ranges.iter().zip(counts) {
+                let (overlap, mut scan) = (overlap.take(), FileScan::new(file, self.args, self.region_size, self.clock.clone()));
+                // Emitted and counted in order once every chunk is parsed
+                scan.events = None;
+                scan.chunk = true;
+                scanning.push(scope.spawn(move || {
+                    let (mut report, mut summary) = (Report::new(&[], None), FileSummary::new(file));
+                    let lines = line_parsing::lossy_lines(BufReader::new(progress.wrap_read(chunks::open(file, range).map_err(read_error)?)));
+                    scan.scan(lines, first_line, &mut report, &mut summary, overlap)?;
+                    Ok((scan, report, summary))
+                }));
+                first_line += count;
+            }
+            scanning.into_iter().map(|x| x.join().unwrap()).collect::<Result<Vec<_>, GcLogError>>()
+        })?;
+        for (scan, chunk_report, chunk_summary) in scanned {
+            // The pauses of the chunk are placed among the allocations as they're counted
+            let mut pauses = scan.pauses.into_iter().peekable();
+            for allocation in scan.allocations {
+                while let Some(pause) = pauses.next_if(|x| x.line < allocation.line) {
+                    self.pauses.push(gc_cycles::Pause { allocations: self.counted, ..pause });
+                }
+                self.record(allocation, report, summary);
+            }
+            self.pauses.extend(pauses.map(|x| gc_cycles::Pause { allocations: self.counted, ..x }));
+            self.full_gcs.extend(scan.full_gcs);
+            self.evacuation_failures.extend(scan.evacuation_failures);
+            self.heap_samples.extend(scan.heap_samples);
+            self.eden_samples.extend(scan.eden_samples);
+            self.heap_resizes.extend(scan.heap_resizes);
+            self.safepoints.extend(scan.safepoints);
+            self.marking_cycles.extend(scan.marking_cycles);
+            self.sizes.extend(scan.sizes);
+            report.merge_chunk(chunk_report);
+            summary.merge_chunk(chunk_summary);
+        }
+        Ok(())
+    }
+
+    // The events of a flight recording stand in for the lines of a log. Every allocation outside of a TLAB is
+    // recorded, the humongous ones are those of at least half a region. Lines are the number of the allocation
+    // event, and uptimes are relative to the JVM start time the recording holds.
+    fn scan_recording(&mut self, recording: jfr::Recording, report: &mut Report, summary: &mut FileSummary) {
+        let uptime = |time: f64| recording.jvm_start.map(|start| time - start);
+        // The allocations counted before each allocation event, and after the last one
+        let mut counted = Vec::with_capacity(recording.allocations.len() + 1);
+        for (index, (time, bytes)) in recording.allocations.iter().enumerate() {
+            counted.push(self.counted);
+            if let Some(uptime) = uptime(*time) {
+                summary.observe_uptime(uptime);
+            }
+            if bytes * 2 >= self.region_size {
+                self.record(HumongousAllocation { line: index + 1, uptime: uptime(*time), wall_clock: Some(*time as i64), bytes: *bytes }, report, summary);
+            }
+        }
+        counted.push(self.counted);
+        for collection in &recording.collections {
+            // Concurrent cycles (G1Old) carry the cause of the young collection that started them
+            if collection.name == "G1Old" {
+                continue;
+            }
+            report.gc_causes.record(&collection.cause);
+            // Allocations are numbered by their event, so the pause comes after those that happened before it
+            let line = recording.allocations.partition_point(|x| x.0 <= collection.time);
+            self.pauses.push(gc_cycles::Pause { line, allocations: counted[line], gc_id: None, duration: Some(collection.duration) });
+            if collection.name == "G1Full" {
+                self.full_gcs.push(full_gc::FullGc { uptime: uptime(collection.time), cause: collection.cause.clone(), duration: Some(collection.duration) });
+            }
+        }
+        report.evacuation_failures += recording.evacuation_failures.len();
+        summary.evacuation_failures += recording.evacuation_failures.len() as u64;
+        self.evacuation_failures.extend(recording.evacuation_failures.iter().filter_map(|x| uptime(*x)));
+        for regions in &recording.humongous_objects {
+            report.humongous_trace.record(*regions);
+        }
+        report.humongous_trace.peak_regions = report.humongous_trace.peak_regions.max(recording.peak_humongous_regions);
+    }
+}
+
+// Reads from the start byte on, which is only past the start of the file when resuming with --state
+fn gather_humongous_object_allocations(file: &Path, args: &Cli, report: &mut Report, summary: &mut FileSummary, overlap: &mut OverlapFilter, start: u64) -> Result<(), GcLogError> {
+    log::info!("Parsing {}", file.display());
+    let region_size = summary.region_size.as_ref().map_or(buckets::MB, |x| x.mb.max(1) as u64 * buckets::MB);
+    let (mut gc_log, file_size) = match remote::take_prefetched(file) {
+        Some(stream) => archive::open_stream(file, stream).map_err(|source| GcLogError::Open { path: file.to_path_buf(), source })?,
+        None => open_gc_log(file)?,
+    };
+    let from_events = args.reads_events(file);
+    let recording = !from_events && jfr::is_recording(&mut gc_log).map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?;
+    if recording && !matches!(args.collector, Collector::G1) {
+        return Err(GcLogError::UnsupportedRecording { path: file.to_path_buf() });
+    }
+    let progress = progress::file_progress_bar(&file.display().to_string(), file_size);
+    // A gc log read as is, rather than transcoded into memory, that can be split and seeked into
+    let plain = file.is_file() && !archive::is_in_memory(file) && !from_events;
+    // With --checkpoint, plain files read in a single pass from their start, where --resume picks up from the
+    // last checkpoint. Shenandoah and OpenJ9 need the startup lines, so are always read in full.
+    let consumed = Arc::new(AtomicU64::new(0));
+    let mut resumed = None;
+    let mut checkpointer = None;
+    if let Some(path) = args.checkpoint.as_deref().filter(|_| !recording && plain && !matches!(args.collector, Collector::Shenandoah | Collector::OpenJ9)) {
+        let mut checkpoints = checkpoint::Checkpoints::load(path).unwrap_or_else(|e| {
+            log::warn!("{}, starting a new one", e);
+            checkpoint::Checkpoints::default()
+        });
+        let first_line = state::first_line(file);
+        resumed = args.resume.then(|| checkpoints.resume(file, &first_line, file_size)).flatten();
+        let start = resumed.as_ref().map_or(0, |x| x.offset);
+        if let Some(resumed) = &resumed {
+            match resumed.complete {
+                true => log::info!("{} was complete at the checkpoint", file.display()),
+                false => log::info!("Resuming {} at line {} from the checkpoint", file.display(), resumed.line + 1),
+            }
+            gc_log.seek(io::SeekFrom::Start(start)).map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?;
+            progress.set_position(start);
+        }
+        checkpointer = Some(checkpoint::Checkpointer::new(path, checkpoints, file, first_line, start, consumed.clone()));
+    }
+    // With --state only complete lines are read, the rest of one still being written is left for the next run.
+    // Downloads can't be seeked to their end, and objects aren't appended to anyway.
+    let gc_log: Box<dyn Read> = if args.state.is_some() && !recording && !from_events && !remote::is_remote(file) {
+        let read_error = |source| GcLogError::Read { path: file.to_path_buf(), source };
+        let end = state::complete_lines_end(&mut gc_log, start, file_size).map_err(read_error)?;
+        gc_log.seek(io::SeekFrom::Start(start)).map_err(read_error)?;
+        progress.set_position(start);
+        summary.read_range = Some((start, end));
+        Box::new(gc_log.take(end - start))
+    } else {
+        Box::new(gc_log)
+    };
+
+    report.shenandoah.reset(summary.region_size.as_ref().map(|_| region_size));
+    report.inter_arrival.reset();
+    report.openj9.reset();
+    let mut scan = FileScan::new(file, args, region_size, timestamp::Clock::default());
+    scan.checkpointer = checkpointer;
+    // The allocations before the checkpoint, the sections built from the other lines only cover the rest
+    let first_line = match resumed {
+        Some(resumed) => {
+            resumed.allocations.into_iter().for_each(|x| scan.fold(x, report, summary));
+            resumed.line + 1
+        }
+        None => 1,
+    };
+    // Blocks read with --sample, out of how many, only of plain files read from the start
+    let sample = match args.sample {
+        Some(ratio) if !recording && args.state.is_none() && plain => {
+            let blocks = chunks::blocks(file, file_size, sampling::BLOCK_SIZE).map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?;
+            let count = blocks.len();
+            Some((sampling::select(blocks, ratio), count))
+        }
+        _ => None,
+    };
+    // Shenandoah reads its thresholds from the startup lines, OpenJ9 pairs the <af> and <minimum> elements
+    // of older releases, and the lines a rotated file shares with the previous one are skipped from its
+    // start, so all need the file in a single pass
+    let ranges = match args.jobs() {
+        jobs if jobs > 1 && !recording && args.state.is_none() && args.checkpoint.is_none() && plain && !matches!(args.collector, Collector::Shenandoah | Collector::OpenJ9) && !overlap.follows_previous() => {
+            chunks::split(file, file_size, jobs).map_err(|source| GcLogError::Read { path: file.to_path_buf(), source })?
+        }
+        _ => Vec::new(),
+    };
+    let mut samples = Vec::new();
+    let result = if from_events {
+        events::read(file, BufReader::new(progress.wrap_read(gc_log)), summary).map(|allocations| {
+            for allocation in allocations {
+                if let Some(uptime) = allocation.uptime {
+                    summary.observe_uptime(uptime);
+                }
+                scan.record(allocation, report, summary);
+            }
+        })
+    } else if recording {
+        read_recording(file, progress.wrap_read(gc_log)).map(|x| scan.scan_recording(x, report, summary))
+    } else if let Some((blocks, _)) = &sample {
+        log::debug!("{}: reading {} blocks of {}", file.display(), blocks.len(), sample.as_ref().map_or(0, |x| x.1));
+        scan.scan_sample(blocks, report, summary, overlap, &progress).map(|x| samples = x)
+    } else if ranges.len() > 1 {
+        log::debug!("{}: parsing {} chunks in parallel", file.display(), ranges.len());
+        scan.scan_chunks(&ranges, report, summary, overlap, &progress)
+    } else {
+        let lines = line_parsing::lossy_lines(checkpoint::Counted::new(BufReader::new(progress.wrap_read(gc_log)), consumed));
+        scan.scan(lines, first_line, report, summary, (first_line == 1).then_some(overlap)).map(|lines_read| {
+            if let Some(checkpointer) = &mut scan.checkpointer {
+                checkpointer.finish((first_line + lines_read).saturating_sub(1), &scan.allocations);
+            }
+        })
+    };
+    if result.is_err() {
+        progress.abandon();
+    }
+    result?;
+    progress.finish_and_clear();
+    let FileScan { allocations, times, window_dates, full_gcs, evacuation_failures, heap_samples, eden_samples, heap_resizes, safepoints, pauses, marking_cycles, mut sizes, .. } = scan;
+    let found = sizes.iter().map(|x| x.1).sum::<u64>();
+    sizes.retain(|x| args.in_size_range(x.0));
+    summary.filtered_allocations += found - sizes.iter().map(|x| x.1).sum::<u64>();
+    if args.sample.is_some() {
+        let sampling = report.sampling.get_or_insert_with(Default::default);
+        match sample {
+            Some((blocks, count)) => sampling.record(&samples, count, blocks.iter().map(|x| x.end - x.start).sum(), file_size),
+            // Read in full
+            None => {
+                let objects = sizes.iter().fold((summary.allocations, summary.allocated_bytes), |(count, bytes), x| (count + x.1, bytes + x.0 * x.1));
+                sampling.record(&[objects], 1, file_size, file_size);
+            }
+        }
+    }
+    let size_kind = if let Collector::Cms = args.collector { "promotion failure" } else { "large page" };
+    for (bytes, count) in sizes {
+        report.size_stats.record(bytes, count);
+        if report.allocs_histogram.increment_by(bytes, count).is_err() {
+            summary.warn(format!("{} of {} bytes is outside of the histogram range", size_kind, bytes));
+        }
+    }
+    if overlap.skipped_lines > 0 {
+        log::info!("{}: skipped {} lines already read from the previous file of the rotation", file.display(), overlap.skipped_lines);
+    }
+    report.concurrent_cycles.reset();
+    report.eager_reclaim.reset();
+    report.mixed_collections.reset();
+    report.humongous_trace.reset();
+    let file_name = file.display().to_string();
+    report.allocations_per_cycle.record(&pauses);
+    report.pause_impact.record(&pauses);
+    report.cycle_durations.record(&marking_cycles, &times, args.timeline_interval, args.burst_factor);
+    report.allocation_rate.record(&eden_samples, &times);
+    report.full_gc_rows.extend(full_gc::attribute_full_gcs(&file_name, &full_gcs, &times, args.full_gc_window));
+    report.heap_occupancy_rows.extend(heap_occupancy::build_occupancy_timeline(&file_name, &heap_samples, &times, args.timeline_interval));
+    report.burst_rows.extend(bursts::detect_bursts(&file_name, &times, &window_dates, args.timeline_interval, args.burst_factor));
+    report.safepoint_rows.extend(safepoint::build_safepoint_timeline(&file_name, &safepoints, &times, args.timeline_interval, args.burst_factor));
+    report.timeline_rows.extend(timeline::build_timeline(&file_name, &times, &evacuation_failures, &heap_resizes, args.timeline_interval));
+    if args.retains_allocations() {
+        report.retained.push(FileAllocations { name: file_name, label: args.label(file).map(str::to_string), allocations });
+    }
+    Ok(())
+}
+
+// Streams events from every followed file, printing them as they arrive and feeding the metrics endpoint
+fn follow_files(args: &Cli) {
+    let metrics = Arc::new(Mutex::new(metrics::Metrics::default()));
+    if let Some(address) = &args.metrics_addr {
+        if let Err(e) = metrics::serve(address, metrics.clone()) {
+            Cli::command().error(ErrorKind::Io, format!("Unable to serve metrics on {}: {}", address, e)).exit();
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    for file in &args.files {
+        let label = args.label(file).map(str::to_string);
+        let file = file.clone();
+        let sender = sender.clone();
+        let patterns = args.pattern.clone();
+        thread::spawn(move || {
+            if let Err(e) = follow::follow_file(file.clone(), label, &patterns, sender) {
+                log::error!("Stopped following {}: {}", file.display(), e);
+            }
+        });
+    }
+    drop(sender);
+
+    if let Some(endpoint) = &args.otlp_endpoint {
+        otlp::OtlpExporter::new(endpoint).spawn(metrics.clone(), Duration::from_secs(args.otlp_interval));
+    }
+
+    let mut alerter = args.alert_webhook.as_ref().map(|url| alert::Alerter::new(url, args.alert_slack, args.alert_rate, args.alert_size));
+    let statsd = args.statsd_addr.as_ref().map(|address| match statsd::StatsdClient::new(address, args.statsd_prefix.as_deref(), args.statsd_tags) {
+        Ok(client) => client,
+        Err(e) => Cli::command().error(ErrorKind::Io, format!("Unable to send StatsD metrics to {}: {}", address, e)).exit(),
+    });
+
+    for event in receiver {
+        match event.kind {
+            FollowEventKind::HumongousAllocation(bytes) => println!("{}: humongous allocation of {} bytes", event.file, bytes),
+            FollowEventKind::FullGc => println!("{}: Full GC", event.file),
+            FollowEventKind::EvacuationFailure => println!("{}: to-space exhausted", event.file),
+        }
+        if let Some(statsd) = &statsd {
+            statsd.record(&event);
+        }
+        if let Some(alerter) = &mut alerter {
+            alerter.observe(&event);
+        }
+        metrics.lock().unwrap().record(&event);
+    }
+}
+
+// The command line on top of the settings from the config file, which is optional unless --config was given
+fn parse_args() -> Cli {
+    let argv: Vec<OsString> = env::args_os().collect();
+    let command = Cli::command();
+    let Ok(matches) = command.clone().ignore_errors(true).try_get_matches_from(&argv) else { return Cli::parse_from(argv) };
+    let path = match matches.value_of("config") {
+        Some(path) => PathBuf::from(path),
+        None => match config::default_path().filter(|x| x.is_file()) {
+            Some(path) => path,
+            None => return Cli::parse_from(argv),
+        },
+    };
+    let settings = config::load(&path).unwrap_or_else(|e| command.clone().error(ErrorKind::Io, e).exit());
+    let (options, files) = config::config_args(&command, &settings, &matches)
+        .unwrap_or_else(|e| command.clone().error(ErrorKind::InvalidValue, format!("Invalid config file {}: {}", path.display(), e)).exit());
+    let Some((program, rest)) = argv.split_first() else { return Cli::parse_from(argv) };
+    // The options are global so they apply to the subcommands too, which take their own files
+    let files = if matches.subcommand().is_some() { Vec::new() } else { files };
+    Cli::parse_from(std::iter::once(program.clone()).chain(options).chain(rest.iter().cloned()).chain(files))
+}
+
+fn run_subcommand(subcommand: &Subcommand, args: &Cli) {
+    let mut command = Cli::command();
+    match subcommand {
+        Subcommand::Completions { shell } => {
+            let name = command.get_name().to_string();
+            clap_complete::generate(*shell, &mut command, name, &mut io::stdout());
+        }
+        Subcommand::Manpage => {
+            if let Err(e) = clap_mangen::Man::new(command).render(&mut io::stdout()) {
+                Cli::command().error(ErrorKind::Io, format!("Unable to write the man page: {}", e)).exit();
+            }
+        }
+        Subcommand::Serve { addr, max_upload, workers } => {
+            if let Err(e) = serve::serve(addr, args.clone(), *max_upload, *workers) {
+                Cli::command().error(ErrorKind::Io, format!("Unable to serve on {}: {}", addr, e)).exit();
+            }
+        }
+        Subcommand::Bench { file, iterations } => {
+            if let Err(e) = bench::run(file, *iterations, args, &mut io::stdout()) {
+                Cli::command().error(ErrorKind::Io, format!("Unable to benchmark {}: {}", file.display(), e)).exit();
+            }
+        }
+        Subcommand::Generate { log_format, rate, sizes, duration, heap, seed } => {
+            // With the initial heap the same as the maximum every JDK release picks the same region size
+            let region_size = args.region_size.map(|x| x as u64 * buckets::MB).or_else(|| jvm_flags::ergonomic_region_size(Some(*heap), Some(*heap), 8)).unwrap();
+            let sizes = generate::humongous_sizes(sizes, region_size).unwrap_or_else(|e| Cli::command().error(ErrorKind::InvalidValue, e).exit());
+            let generator = generate::Generator { format: *log_format, rate: *rate, sizes: &sizes, duration: *duration, heap: *heap, region_size, seed: *seed };
+            let mut output = open_output(args);
+            exit_on_write_error(generator.write(&mut output), "gc log");
+        }
+        Subcommand::Recommend { files, write_flags } => recommend_files(args, &input_files(args, files), write_flags.as_deref()),
+        Subcommand::Diff { baseline, candidate, fail_above } => diff_reports(args, baseline, candidate, *fail_above),
+        Subcommand::Merge { reports } => merge_reports(args, reports),
+        Subcommand::Export { target, files } => export_files(args, target, &input_files(args, files)),
+        Subcommand::Validate { files } => validate_files(args, &input_files(args, files)),
+        Subcommand::Analyze { .. } | Subcommand::Report { .. } | Subcommand::Compare { .. } | Subcommand::Watch { .. } => unreachable!("handled by run"),
+        #[cfg(feature = "grpc")]
+        Subcommand::Grpc { addr } => {
+            if let Err(e) = grpc::serve(addr, args.clone()) {
+                Cli::command().error(ErrorKind::Io, format!("Unable to serve gRPC on {}: {}", addr, e)).exit();
+            }
+        }
+    }
+}
+
+// Settings clap is unable to check by itself
+fn validate(args: &Cli) -> Result<(), String> {
+    if let Some(Err(e)) = args.buckets.as_deref().map(buckets::validate_region_sizes) {
+        return Err(format!("Invalid --buckets: {}", e));
+    }
+    if args.bins == Some(0) {
+        return Err("Invalid --bins: the bin width must be larger than 0".to_string());
+    }
+    if let (Some(min), Some(max)) = (args.min_size, args.max_size) {
+        if min > max {
+            return Err(format!("Invalid --min-size: {} is larger than --max-size {}", bins::format_size(min), bins::format_size(max)));
+        }
+    }
+    if args.bounded_memory && args.retains_allocations() {
+        return Err("--bounded-memory doesn't keep the allocations that --state, --tui, --chart and --alert-webhook need".to_string());
+    }
+    Ok(())
+}
+
+// Analyzes a gc log handed over as text and returns the json report, without touching the filesystem. The
+// options are command line flags, e.g. ["--buckets", "1m,2m,4m"]. This is the API of the WebAssembly build.
+pub fn analyze_text(name: &str, text: &str, options: &[String]) -> Result<String, String> {
+    let path = PathBuf::from(name);
+    archive::register(&path, Arc::from(text.as_bytes()));
+    let argv = std::iter::once(Cli::command().get_name().to_string()).chain(options.iter().cloned()).chain(["--".to_string(), name.to_string()]);
+    let result = Cli::try_parse_from(argv).map_err(|e| e.to_string()).and_then(|args| {
+        validate(&args)?;
+        let reports = analyze(&args, &args.files);
+        let mut json = Vec::new();
+        json::write_json(&reports, &mut json, &args).map_err(|e| format!("Unable to write the report: {}", e))?;
+        String::from_utf8(json).map_err(|e| e.to_string())
+    });
+    archive::unregister(&path);
+    result
+}
+
+// The report subcommand turns on the analyses that are otherwise opt-in, with hourly calendar windows unless
+// --interval says otherwise, and writes markdown rather than text
+fn report_args(args: Cli, files: Vec<PathBuf>, choices: &[String]) -> Cli {
+    let mut sections = sections::Sections::default();
+    for choice in choices {
+        if let Err(e) = sections.add(choice) {
+            Cli::command().error(ErrorKind::InvalidValue, format!("Invalid --sections: {}", e)).exit();
+        }
+    }
+    let output_format = match args.output_format {
+        OutputFormat::Text => OutputFormat::Markdown,
+        format => format,
+    };
+    Cli { files, sections: Some(sections), heatmap: true, interval: args.interval.or(Some(3600)), output_format, subcommand: None, ..args }
+}
+
+// Entry point of the command line tool
+pub fn run() {
+    let args = parse_args();
+    init_logging(&args);
+    if let Err(e) = validate(&args) {
+        Cli::command().error(ErrorKind::ValueValidation, e).exit();
+    }
+    timestamp::set_display_offset(args.display_tz);
+    numbers::set_plain(args.plain_numbers);
+    if let Some(limit) = args.throttle {
+        throttle::set_limit(limit);
+    }
+    if args.low_priority {
+        throttle::lower_priority();
+    }
+    // analyze, report, compare and watch are the flat command with their inputs filled in
+    let args = match args.subcommand.clone() {
+        Some(Subcommand::Analyze { files }) => Cli { files, subcommand: None, ..args },
+        Some(Subcommand::Report { files, sections }) => report_args(args, files, &sections),
+        Some(Subcommand::Compare { baseline, candidate }) => Cli { files: vec![baseline, candidate], compare: true, subcommand: None, ..args },
+        Some(Subcommand::Watch { dir }) => Cli { watch: Some(dir), subcommand: None, ..args },
+        Some(subcommand) => return run_subcommand(&subcommand, &args),
+        None => args,
+    };
+    let args = Cli { files: input_files(&args, &args.files), ..args };
+    if args.compare && (args.follow || args.watch.is_some() || args.state.is_some()) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "compare can't be combined with --follow, --watch or --state").exit();
+    }
+    if args.alert_webhook.is_some() && !(args.follow || args.watch.is_some()) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "--alert-webhook only applies to --follow and --watch").exit();
+    }
+    if args.alert_webhook.is_some() && args.alert_rate.is_none() && args.alert_size.is_none() {
+        Cli::command().error(ErrorKind::MissingRequiredArgument, "--alert-webhook needs --alert-rate or --alert-size to alert on").exit();
+    }
+    if let Some(path) = args.checkpoint.as_deref().filter(|_| args.resume) {
+        match path.exists() {
+            true => {
+                if let Err(e) = checkpoint::Checkpoints::load(path) {
+                    Cli::command().error(ErrorKind::ValueValidation, e).exit();
+                }
+            }
+            false => log::warn!("No checkpoint at {}, analyzing the files from the start", path.display()),
+        }
+    }
+    if args.watch.is_some() && (!args.files.is_empty() || args.follow || args.state.is_some()) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "watch can't be combined with files, --follow or --state").exit();
+    }
+
+    if args.follow {
+        if let Some(file) = args.files.iter().find(|x| archive::is_archive(x)) {
+            Cli::command().error(ErrorKind::ArgumentConflict, format!("{} is an archive, only plain gc logs can be followed", file.display())).exit();
+        }
+        if let Some(file) = args.files.iter().find(|x| remote::is_remote(x)) {
+            Cli::command().error(ErrorKind::ArgumentConflict, format!("{} is a URL, only local gc logs can be followed", file.display())).exit();
+        }
+        follow_files(&args);
+        return;
+    }
+
+    #[cfg(feature = "tui")]
+    if args.tui {
+        let files = analyze(&args, &args.files).into_iter().flat_map(|x| x.retained).collect();
+        if let Err(e) = tui::run(files, args.timeline_interval) {
+            Cli::command().error(ErrorKind::Io, format!("Unable to run the terminal interface: {}", e)).exit();
+        }
+        return;
+    }
+
+    if args.dump {
+        if !matches!(args.output_format, OutputFormat::Text) {
+            Cli::command().error(ErrorKind::ArgumentConflict, "--dump only supports the text format, --events ndjson or --export give every allocation to other tools").exit();
+        }
+        let mut output = open_output(&args);
+        let reports = analyze(&args, &args.files);
+        exit_on_write_error(report::write_dump(&reports, &mut output, &args), "dump");
+        exit_on_issues(&args, &reports, output);
+        return;
+    }
+
+    let mut output = open_output(&args);
+
+    if args.compare {
+        compare_files(&args, &mut output);
+        return;
+    }
+
+    if !args.compare_windows.is_empty() {
+        compare_windows(&args, &mut output);
+        return;
+    }
+
+    if let Some(dir) = &args.watch {
+        if let OutputFormat::Html = args.output_format {
+            Cli::command().error(ErrorKind::ArgumentConflict, "--watch appends to the report, which the html format does not support").exit();
+        }
+        let alerter = args.alert_webhook.as_ref().map(|url| alert::Alerter::new(url, args.alert_slack, args.alert_rate, args.alert_size));
+        let result = watch::watch_directory(dir, |file| {
+            if is_excluded(&args, file) {
+                return;
+            }
+            log::info!("Analyzing new file {}", file.display());
+            let mut reports = analyze(&args, &[file.to_path_buf()]);
+            for report in reports.iter_mut().filter(|x| x.title.is_none()) {
+                report.title = Some(match args.anonymize {
+                    true => anonymize::label(&file.display().to_string()),
+                    false => file.display().to_string(),
+                });
+            }
+            if let Some(alerter) = &alerter {
+                alerter.check_reports(&reports);
+            }
+            if let Err(e) = publish(&args, &reports, &mut output) {
+                log::error!("{}", e);
+            }
+        });
+        if let Err(e) = result {
+            Cli::command().error(ErrorKind::Io, format!("Unable to watch {}: {}", dir.display(), e)).exit();
+        }
+        return;
+    }
+
+    if let Some(path) = &args.state {
+        let (reports, state) = analyze_incrementally(&args, path);
+        if let Err(e) = publish(&args, &reports, &mut output) {
+            log::error!("{}", e);
+            drop(output);
+            std::process::exit(1);
+        }
+        // Only once the report is out, so a failed run is analyzed again by the next one
+        if let Err(e) = state.save(path) {
+            Cli::command().error(ErrorKind::Io, format!("Unable to write --state {}: {}", path.display(), e)).exit();
+        }
+        exit_on_issues(&args, &reports, output);
+        return;
+    }
+
+    let reports = analyze(&args, &args.files);
+    if let Err(e) = publish(&args, &reports, &mut output) {
+        log::error!("{}", e);
+        // Shows what was written before exiting
+        drop(output);
+        std::process::exit(1);
+    }
+    exit_on_issues(&args, &reports, output);
+}
+
+// With --strict, a file that had an error or a warning fails the run once the report is out
+fn exit_on_issues(args: &Cli, reports: &[Report], output: Box<dyn Write>) {
+    if !args.strict {
+        return;
+    }
+    let issues: Vec<String> = reports.iter().flat_map(|x| &x.file_summaries).flat_map(|x| x.details().into_iter().map(move |issue| format!("{}: {}", x.file, issue))).collect();
+    if issues.is_empty() {
+        return;
+    }
+    drop(output);
+    log::error!("--strict: {} issues in the gc logs\n{}", issues.len(), issues.join("\n"));
+    std::process::exit(1);
+}
+
+fn open_output(args: &Cli) -> Box<dyn Write> {
+    match report::open_output(args) {
+        Ok(output) => output,
+        Err(e) => Cli::command().error(ErrorKind::Io, format!("Unable to open --output: {}", e)).exit(),
+    }
+}
+
+// The reader going away (e.g. piped into head) is not an error
+fn exit_on_write_error(result: io::Result<()>, what: &str) {
+    match result {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Cli::command().error(ErrorKind::Io, format!("Unable to write the {}: {}", what, e)).exit(),
+        _ => {}
+    }
+}
+
+fn recommend_files(args: &Cli, files: &[PathBuf], write_flags: Option<&Path>) {
+    if !matches!(args.output_format, OutputFormat::Text | OutputFormat::Json) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "recommend only supports the text and json formats").exit();
+    }
+    let mut output = open_output(args);
+    let reports = analyze(args, files);
+    exit_on_write_error(report::write_recommendations(&reports, &mut output, args), "recommendation");
+    if let Some(path) = write_flags {
+        exit_on_write_error(std::fs::File::create(path).and_then(|mut file| report::write_flags_snippets(&reports, &mut file)), "JVM flags");
+    }
+    exit_on_issues(args, &reports, output);
+}
+
+// The json reports of earlier runs written as one report, in any --output-format
+fn merge_reports(args: &Cli, paths: &[PathBuf]) {
+    let report = match merge::merge(paths, args) {
+        Ok(report) => report,
+        Err(e) => Cli::command().error(ErrorKind::InvalidValue, e).exit(),
+    };
+    let mut output = open_output(args);
+    let reports = [report];
+    if let Err(e) = publish(args, &reports, &mut output) {
+        log::error!("{}", e);
+        drop(output);
+        std::process::exit(1);
+    }
+    exit_on_issues(args, &reports, output);
+}
+
+// Two json reports of earlier runs compared as --compare does gc logs, exiting with 1 on a regression past --fail-above
+fn diff_reports(args: &Cli, baseline: &Path, candidate: &Path, fail_above: Option<f64>) {
+    if !matches!(args.output_format, OutputFormat::Text) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "diff only supports the text format").exit();
+    }
+    let load = |path: &Path| match merge::merge(&[path.to_path_buf()], args) {
+        Ok(report) => report,
+        Err(e) => Cli::command().error(ErrorKind::InvalidValue, e).exit(),
+    };
+    let (baseline_report, candidate_report) = (load(baseline), load(candidate));
+    let mut output = open_output(args);
+    write_comparison(&baseline_report, &candidate_report, (&baseline.display().to_string(), &candidate.display().to_string()), &mut output, args);
+    let Some(threshold) = fail_above else { return };
+    let regressions = compare::regressions(&baseline_report, &candidate_report, args, threshold);
+    if !regressions.is_empty() {
+        drop(output);
+        log::error!("{} statistics grew by more than {}%\n{}", regressions.len(), threshold, regressions.join("\n"));
+        std::process::exit(1);
+    }
+}
+
+// Only the export targets are written, the one given to the subcommand and any --export
+fn export_files(args: &Cli, target: &export::ExportTarget, files: &[PathBuf]) {
+    // The allocations are only kept for the targets in --export
+    let args = Cli { export: std::iter::once(target).chain(&args.export).cloned().collect(), ..args.clone() };
+    if args.bounded_memory {
+        Cli::command().error(ErrorKind::ArgumentConflict, "export needs every allocation, which --bounded-memory doesn't keep").exit();
+    }
+    let reports = analyze(&args, files);
+    for target in &args.export {
+        if let Err(e) = export::export(target, &reports, &args) {
+            Cli::command().error(ErrorKind::Io, e).exit();
+        }
+    }
+    exit_on_issues(&args, &reports, Box::new(io::sink()));
+}
+
+// Checks the files without analyzing them, exiting with 1 when one can't be analyzed
+fn validate_files(args: &Cli, files: &[PathBuf]) {
+    if !matches!(args.output_format, OutputFormat::Text | OutputFormat::Json) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "validate only supports the text and json formats").exit();
+    }
+    let mut output = open_output(args);
+    let mut capabilities: Vec<_> = expand_archives(args, files).iter().map(|x| validate::check(x, args)).collect();
+    if args.anonymize {
+        capabilities.iter_mut().for_each(validate::anonymize);
+    }
+    exit_on_write_error(report::write_capabilities(&capabilities, &mut output, args), "capabilities");
+    if capabilities.iter().any(|x| !x.is_usable()) {
+        std::process::exit(1);
+    }
+}
+
+// Analyzes the baseline and candidate separately and prints them side by side
+fn compare_files(args: &Cli, output: &mut dyn Write) {
+    if args.files.len() != 2 {
+        Cli::command().error(ErrorKind::WrongNumberOfValues, "--compare takes exactly two files, the baseline and the candidate").exit();
+    }
+    if !matches!(args.group_by, GroupBy::None) || !matches!(args.output_format, OutputFormat::Text) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "--compare only supports the text format without --group-by").exit();
+    }
+    let mut reports = args.files.iter().map(|file| {
+        let mut reports = analyze(args, std::slice::from_ref(file));
+        reports.pop().unwrap_or_else(|| Report::new(args.buckets.as_deref().unwrap_or(&buckets::DEFAULT_REGION_SIZES), args.precision))
+    });
+    let (baseline, candidate) = (reports.next().unwrap(), reports.next().unwrap());
+    let names = (args.files[0].display().to_string(), args.files[1].display().to_string());
+    write_comparison(&baseline, &candidate, (&names.0, &names.1), output, args);
+}
+
+// The same gc logs analyzed once per window, the first window being the baseline
+fn compare_windows(args: &Cli, output: &mut dyn Write) {
+    if !matches!(args.group_by, GroupBy::None) || !matches!(args.output_format, OutputFormat::Text) {
+        Cli::command().error(ErrorKind::ArgumentConflict, "--compare-windows only supports the text format without --group-by").exit();
+    }
+    let mut reports = args.compare_windows.iter().map(|window| {
+        let args = Cli { window: Some(window.clone()), ..args.clone() };
+        let mut reports = analyze(&args, &args.files);
+        reports.pop().unwrap_or_else(|| Report::new(args.buckets.as_deref().unwrap_or(&buckets::DEFAULT_REGION_SIZES), args.precision))
+    });
+    let (baseline, candidate) = (reports.next().unwrap(), reports.next().unwrap());
+    write_comparison(&baseline, &candidate, (&args.compare_windows[0].text, &args.compare_windows[1].text), output, args);
+}
+
+fn write_comparison(baseline: &Report, candidate: &Report, names: (&str, &str), output: &mut dyn Write, args: &Cli) {
+    match compare::write_comparison(baseline, candidate, names, output, args) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            log::error!("Unable to write the comparison: {}", e);
+            std::process::exit(1);
+        }
+        _ => {}
+    }
+}
+
+// One report per group, in the order the groups were first seen
+fn analyze(args: &Cli, files: &[PathBuf]) -> Vec<Report> {
+    analyze_files(args, files, None)
+}
+
+// Resumes every file where the previous run left off, and records how far they were read this time
+fn analyze_incrementally(args: &Cli, path: &Path) -> (Vec<Report>, state::State) {
+    let mut state = match state::State::load(path) {
+        Ok(state) => state,
+        Err(e) => Cli::command().error(ErrorKind::Io, e).exit(),
+    };
+    let reports = analyze_files(args, &args.files, Some(&mut state));
+    (reports, state)
+}
+
+fn analyze_files(args: &Cli, files: &[PathBuf], mut state: Option<&mut state::State>) -> Vec<Report> {
+    let mut reports: Vec<Report> = Vec::new();
+    // Of each report, in MB
+    let mut region_sizes: Vec<Option<u32>> = Vec::new();
+    let mut rotations = RotationTracker::default();
+    // A new analysis doesn't pick up the checkpoints of an earlier one
+    if let Some(path) = args.checkpoint.as_deref().filter(|_| !args.resume) {
+        if let Err(e) = fs::remove_file(path).or_else(|e| if e.kind() == io::ErrorKind::NotFound { Ok(()) } else { Err(e) }) {
+            log::warn!("Unable to remove the checkpoint {}: {}", path.display(), e);
+        }
+    }
+    let mut files = expand_archives(args, files);
+    if !args.keep_duplicates {
+        files = duplicates::skip_duplicates(files);
+    }
+    let files = rotation::chronological(&files);
+    let downloads = remote::Prefetcher::start(&files, args.prefetch);
+    for file in &files {
+        if remote::is_remote(file) {
+            if let Err(e) = downloads.register_next(file) {
+                log::debug!("Unable to prefetch {}, requesting it again: {}", file.display(), e);
+            }
+        }
+        let mut summary = FileSummary::new(file);
+        summary.label = args.label(file).map(str::to_string);
+        let region_size = file_region_size(file, args, &mut summary);
+        let known = region_size.as_ref().ok().and_then(Option::as_ref);
+        let title = report::group_title(args.group_by, file, known);
+        let index = match args.group_by {
+            // Still one report per region size, as a bucket table over several region sizes means nothing. Files
+            // without a region size go with the first report.
+            GroupBy::None => match known {
+                Some(known) => region_sizes.iter().position(|x| *x == Some(known.mb)).or_else(|| region_sizes.iter().position(Option::is_none)),
+                None => (!reports.is_empty()).then_some(0),
+            },
+            _ => reports.iter().position(|x| x.title == title),
+        };
+        let index = match index {
+            Some(index) => index,
+            None => {
+                let mut report = Report::new(args.buckets.as_deref().unwrap_or(&buckets::DEFAULT_REGION_SIZES), args.precision);
+                report.title = title;
+                reports.push(report);
+                region_sizes.push(None);
+                reports.len() - 1
+            }
+        };
+        region_sizes[index] = region_sizes[index].or(known.map(|x| x.mb));
+        let report = &mut reports[index];
+        let mut overlap = rotations.filter(file);
+        let first_line = state.as_ref().map(|_| state::first_line(file)).unwrap_or_default();
+        let start = state.as_deref().map_or(0, |x| x.start_offset(file, &first_line, file.metadata().map_or(0, |x| x.len())));
+        let result = region_size.and_then(|region_size| {
+            summary.region_size = region_size;
+            gather_humongous_object_allocations(file, args, report, &mut summary, &mut overlap, start)
+        });
+        rotations.finish(file, overlap);
+        match result {
+            Err(e) => {
+                log::error!("Skipping {}: {}", file.display(), e);
+                summary.error = Some(e);
+            }
+            Ok(()) => {
+                if let (Some(state), Some((_, end))) = (state.as_deref_mut(), summary.read_range) {
+                    let name = file.display().to_string();
+                    let sizes = report.retained.iter().filter(|x| x.name == name).flat_map(|x| &x.allocations).map(|x| x.bytes);
+                    state.update(file, first_line, end, sizes);
+                }
+            }
+        }
+        // Remote and transcoded logs are only held in memory while they're parsed, and the download of a remote
+        // log that wasn't parsed is stopped
+        if remote::is_remote(file) || (file.is_file() && archive::is_in_memory(file)) {
+            archive::unregister(file);
+            drop(remote::take_prefetched(file));
+        }
+        report.file_summaries.push(summary);
+    }
+    // Nothing left to resume once every file was parsed
+    if let Some(path) = &args.checkpoint {
+        if reports.iter().flat_map(|x| &x.file_summaries).all(|x| x.error.is_none()) && path.exists() {
+            if let Err(e) = fs::remove_file(path) {
+                log::warn!("Unable to remove the checkpoint {}: {}", path.display(), e);
+            }
+        }
+    }
+    if matches!(args.group_by, GroupBy::None) && reports.len() > 1 {
+        let sizes: Vec<String> = region_sizes.iter().flatten().map(|x| format!("{}MB", x)).collect();
+        log::warn!("The files have different region sizes ({}), the report is split per region size as with --group-by region-size", sizes.join(", "));
+        for (report, region_size) in reports.iter_mut().zip(&region_sizes) {
+            report.title = Some(region_size.map_or("Unknown region size".to_string(), |x| format!("{}MB region size", x)));
+        }
+    }
+    if let Some(state) = state {
+        for report in &mut reports {
+            report.cumulative = Some(state.cumulative(report.file_summaries.iter().map(|x| x.file.as_str())));
+        }
+    }
+    for report in &mut reports {
+        if args.buckets.is_none() {
+            buckets::trim_large_buckets(&mut report.region_size_array);
+        }
+        buckets::compute_percentages(&mut report.region_size_array);
+    }
+    if args.anonymize {
+        anonymize::scrub(&mut reports);
+    }
+    reports
+}
+
+// The document of the report subcommand, without the parts left out by --sections
+fn write_sections(reports: &[Report], sections: &sections::Sections, output: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    let mut document = Vec::new();
+    report::write_reports(reports, &mut document, args)?;
+    let document = String::from_utf8_lossy(&document);
+    match args.output_format {
+        OutputFormat::Json => {
+            let mut value: serde_json::Value = serde_json::from_str(&document)?;
+            sections.filter_json(&mut value);
+            serde_json::to_writer_pretty(&mut *output, &value)?;
+            writeln!(output)?;
+        }
+        OutputFormat::Html => write!(output, "{}", sections.filter_html(&document))?,
+        _ => write!(output, "{}", sections.filter_markdown(&document))?,
+    }
+    output.flush()
+}
+
+// Writes the reports, then pushes them to the OTLP collector and the --export targets when configured
+fn publish(args: &Cli, reports: &[Report], output: &mut dyn Write) -> Result<(), String> {
+    let written = match &args.sections {
+        Some(sections) => write_sections(reports, sections, output, args),
+        None => report::write_reports(reports, output, args),
+    };
+    match written {
+        // The reader went away (e.g. piped into head), nothing left to report to
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => return Err(format!("Unable to write the report: {}", e)),
+        Ok(()) => {}
+    }
+    if let Some(endpoint) = &args.otlp_endpoint {
+        otlp::OtlpExporter::new(endpoint).export_reports(reports)?;
+    }
+    for target in &args.export {
+        export::export(target, reports, args)?;
+    }
+    #[cfg(feature = "chart")]
+    if let Some(path) = &args.chart {
+        chart::write_chart(path, reports, args.timeline_interval)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_jdk8_humongous_allocation_lines() {
+        let prefix = "2024-03-01T10:15:00.123+0000: 1.234: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: 3932160000 bytes, allocation request: 16777232 bytes, threshold: 3865470540 bytes (45.00 %), source: concurrent humongous allocation";
+        let lines = [
+            format!("{}]", prefix),
+            format!("{}, occupancy: 3932160000 bytes]", prefix),
+            format!("{} occupancy: 3932160000 bytes]", prefix),
+            format!("{}\t]", prefix),
+            // Another thread wrote to the log before the line was finished
+            prefix.to_string(),
+        ];
+        for line in lines {
+            assert_eq!(parse_humongous_object_allocation(&line), Some(Ok(16777232)), "{}", line);
+            assert_eq!(parse_humongous_object_allocation_with_regex_find(&line), Some(Ok(16777232)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn parses_the_unified_humongous_allocation_lines() {
+        let line = "[2024-03-01T10:15:00.123+0000][2.672s][debug][gc,ergo,ihop] Request concurrent cycle initiation (occupancy higher than threshold) occupancy: 2038681733B allocation request: 11265758B threshold: 1932735283B (45.00) source: concurrent humongous allocation";
+        assert_eq!(parse_humongous_object_allocation(line), Some(Ok(11265758)));
+        assert_eq!(parse_humongous_object_allocation_with_regex_find(line), Some(Ok(11265758)));
+        assert_eq!(parse_humongous_object_allocation_with_regex_captures(line), Some(Ok(11265758)));
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        let lines = [
+            "",
+            "2024-03-01T10:15:00.123+0000: 1.234: [GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0123 secs]",
+            // Young collections request a cycle too, with a different source
+            "1.234: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, reason: occupancy higher than threshold, occupancy: 3932160000 bytes, allocation request: 0 bytes, threshold: 3865470540 bytes (45.00 %), source: end of GC]",
+            "1.234: [G1Ergonomics (Concurrent Cycles) do not request concurrent cycle initiation, reason: still doing mixed collections, occupancy: 3932160000 bytes, allocation request: 16777232 bytes, threshold: 3865470540 bytes (45.00 %), source: concurrent humongous allocations]",
+            "[2.672s][debug][gc,ergo,ihop] Request concurrent cycle initiation (occupancy higher than threshold) occupancy: 2038681733B allocation request: 0B threshold: 1932735283B (45.00) source: end of GC",
+        ];
+        for line in lines {
+            assert_eq!(parse_humongous_object_allocation(line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn rejects_unparseable_sizes() {
+        let lines = [
+            "1.234: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, allocation request: 16M bytes, source: concurrent humongous allocation]",
+            "1.234: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, allocation request: 99999999999999999999 bytes, source: concurrent humongous allocation]",
+            "1.234: [G1Ergonomics (Concurrent Cycles) request concurrent cycle initiation, allocation request:  bytes, source: concurrent humongous allocation]",
+            "[2.672s][debug][gc,ergo,ihop] Request concurrent cycle initiation (occupancy higher than threshold) occupancy: 2038681733B allocation request: 11MB threshold: 1932735283B (45.00) source: concurrent humongous allocation",
+        ];
+        for line in lines {
+            assert!(matches!(parse_humongous_object_allocation(line), Some(Err(_))), "{}", line);
+        }
+    }
+
+    // A generated log of an hour, written to the temp directory under the test's name
+    fn generated_log(name: &str, format: generate::LogFormat) -> PathBuf {
+        let sizes = generate::humongous_sizes(&[generate::parse_size_component("1m-32m").unwrap()], 2 * buckets::MB).unwrap();
+        let generator = generate::Generator { format, rate: 60.0, sizes: &sizes, duration: 3600, heap: 4 * 1024 * buckets::MB, region_size: 2 * buckets::MB, seed: 7 };
+        let mut log = Vec::new();
+        generator.write(&mut log).unwrap();
+        let path = env::temp_dir().join(format!("{}-{}.log", name, std::process::id()));
+        fs::write(&path, log).unwrap();
+        path
+    }
+
+    // Parses the file in a single pass without ranges, or in parallel over them
+    fn parse<'a>(path: &'a Path, args: &'a Cli, ranges: &[Range<u64>]) -> (FileScan<'a>, Report, FileSummary) {
+        let (mut report, mut summary) = (Report::new(&[], None), FileSummary::new(path));
+        let mut overlap = RotationTracker::default().filter(path);
+        let mut scan = FileScan::new(path, args, 2 * buckets::MB, timestamp::Clock::default());
+        if ranges.len() > 1 {
+            scan.scan_chunks(ranges, &mut report, &mut summary, &mut overlap, &ProgressBar::hidden()).unwrap();
+        } else {
+            let lines = line_parsing::lossy_lines(BufReader::new(fs::File::open(path).unwrap()));
+            scan.scan(lines, 1, &mut report, &mut summary, Some(&mut overlap)).unwrap();
+        }
+        (scan, report, summary)
+    }
+
+    #[test]
+    fn chunks_add_up_to_a_single_pass() {
+        for (name, format) in [("chunks-unified", generate::LogFormat::Unified), ("chunks-jdk8", generate::LogFormat::Jdk8)] {
+            let path = generated_log(name, format);
+            let args = Cli::parse_from(["rs-gc-ho-allocation-parser", path.to_str().unwrap()]);
+            let len = fs::metadata(&path).unwrap().len();
+            let ranges = chunks::blocks(&path, len, len / 7).unwrap();
+            assert!(ranges.len() > 1);
+            let (single, single_report, single_summary) = parse(&path, &args, &[]);
+            let (chunked, chunked_report, chunked_summary) = parse(&path, &args, &ranges);
+            fs::remove_file(&path).unwrap();
+            assert!(single_summary.allocations > 0 && single.pauses.iter().any(|x| x.allocations > 0));
+            if let generate::LogFormat::Unified = format {
+                assert!(single_report.gc_causes.young_pause_time.value() > 0.0);
+            }
+
+            assert_eq!(single_summary.allocations, chunked_summary.allocations);
+            assert_eq!(single_summary.allocated_bytes, chunked_summary.allocated_bytes);
+            assert_eq!(single_summary.lines_read, chunked_summary.lines_read);
+            assert_eq!(single.sizes, chunked.sizes);
+            let pauses = |scan: &FileScan| scan.pauses.iter().map(|x| (x.line, x.allocations)).collect::<Vec<_>>();
+            assert_eq!(pauses(&single), pauses(&chunked));
+            let times = |scan: &FileScan| scan.times.iter().map(|x| (x.uptime.to_bits(), x.bytes)).collect::<Vec<_>>();
+            assert_eq!(times(&single), times(&chunked));
+            assert_eq!(single.window_dates, chunked.window_dates);
+            assert_eq!(single_report.inter_arrival.count(), chunked_report.inter_arrival.count());
+
+            let (single_causes, chunked_causes) = (&single_report.gc_causes, &chunked_report.gc_causes);
+            assert_eq!(single_causes.causes, chunked_causes.causes);
+            assert_eq!(single_causes.young_pause_time, chunked_causes.young_pause_time);
+            assert_eq!(single_causes.humongous_young_pause_time, chunked_causes.humongous_young_pause_time);
+            assert_eq!(single_report.mixed_collections.pause_time, chunked_report.mixed_collections.pause_time);
+            assert_eq!(single_report.ihop.stats(), chunked_report.ihop.stats());
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::{self, BufRead};
+
+// Returns the text inside a parenthesized group, where `text` starts just after the opening '('
+// Nested parentheses are balanced so causes such as "(System.gc())" are kept intact
+pub fn parenthesized(text: &str) -> Option<&str> {
+    let mut depth = 1;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Lines of a gc log with any invalid UTF-8 (application output, garbage left behind by a crash) replaced
+// rather than failing the read, along with whether the line needed replacing
+pub fn lossy_lines(mut reader: impl BufRead) -> impl Iterator<Item = io::Result<(String, bool)>> {
+    // The UTF-8 byte order mark some Windows editors write at the start of the file. An error reading it comes
+    // up again with the first line.
+    if reader.fill_buf().is_ok_and(|x| x.starts_with(&[0xEF, 0xBB, 0xBF])) {
+        reader.consume(3);
+    }
+    reader.split(b'\n').map(|line| {
+        let mut bytes = line?;
+        if bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+        Ok(match String::from_utf8(bytes) {
+            Ok(text) => (text, false),
+            Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), true),
+        })
+    })
+}
+
+// NUL bytes, or a line that is mostly bytes that aren't UTF-8, is binary garbage rather than text
+pub fn is_binary(line: &str, lossy: bool) -> bool {
+    line.contains('\0') || (lossy && line.chars().filter(|x| *x == char::REPLACEMENT_CHARACTER).count() * 2 > line.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_the_byte_order_mark_of_the_first_line() {
+        let text = "\u{feff}first\r\n\u{feff}second\n";
+        let lines: Vec<String> = lossy_lines(text.as_bytes()).map(|x| x.unwrap().0).collect();
+        assert_eq!(lines, ["first", "\u{feff}second"]);
+    }
+}
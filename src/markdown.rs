@@ -0,0 +1,390 @@
+use std::io::{self, Write};
+
+use crate::bins::display_size;
+use crate::concurrent_cycle;
+use crate::groups;
+use crate::heap_share::HeapShare;
+use crate::heap_sizing;
+use crate::numbers::grouped;
+use crate::report::{describe_interval, Report};
+use crate::safepoint;
+use crate::{Cli, Collector};
+
+// Pipes would end the cell early and newlines the row, <br> is understood by GitHub, Jira and Confluence
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn is_numeric(cell: &str) -> bool {
+    cell == "-" || cell.trim_end_matches('%').parse::<f64>().is_ok()
+}
+
+// GitHub flavored markdown table, columns holding only numbers are right aligned
+fn table(out: &mut dyn Write, headers: &[&str], rows: Vec<Vec<String>>) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "| {} |", headers.iter().map(|x| escape(x)).collect::<Vec<_>>().join(" | "))?;
+    let alignment: Vec<&str> = (0..headers.len())
+        .map(|column| if !rows.is_empty() && rows.iter().all(|x| is_numeric(&x[column])) { "---:" } else { "---" })
+        .collect();
+    writeln!(out, "| {} |", alignment.join(" | "))?;
+    for row in rows {
+        writeln!(out, "| {} |", row.iter().map(|x| escape(x)).collect::<Vec<_>>().join(" | "))?;
+    }
+    writeln!(out)
+}
+
+fn size_stats(out: &mut dyn Write, report: &Report, kind: &str, args: &Cli) -> io::Result<()> {
+    writeln!(out, "### {} Statistics", kind)?;
+    let rows = report.size_stats.stats(args.raw_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+    table(out, &["Statistic", "Value"], rows.collect())
+}
+
+pub fn write_groups(reports: &[Report], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    writeln!(out, "## Groups")?;
+    let rows = groups::rows(reports, args.raw_bytes).into_iter().map(|x| {
+        vec![x.group, grouped(&x.files), grouped(&x.allocations), x.allocated_bytes, x.p99, x.recommendation]
+    });
+    table(out, &["Group", "Files", "Humongous Allocations", "Allocated Bytes", "p99 Size", "Recommended Region Size"], rows.collect())
+}
+
+pub fn write_markdown(report: &Report, out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    match &report.title {
+        Some(title) => writeln!(out, "## Humongous Allocation Report: {}", escape(title))?,
+        None => writeln!(out, "## Humongous Allocation Report")?,
+    }
+    let files = report.file_summaries.iter().map(|x| {
+        vec![x.file.clone(), x.region_size.as_ref().map_or("-".to_string(), |r| r.describe()), grouped(&x.allocations)]
+    });
+    table(out, &["File", "Region Size", "Humongous Allocations"], files.collect())?;
+    if let Some(size_filter) = report.size_filter(args) {
+        writeln!(out, "_{}_\n", size_filter)?;
+    }
+    if let Some(sampling) = &report.sampling {
+        writeln!(out, "### Sampling")?;
+        writeln!(out, "_Counts are of the lines read, percentages and percentiles estimate the whole._")?;
+        let rows = sampling.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+    }
+
+    for summary in report.file_summaries.iter().filter(|x| !x.jvm_configuration.is_empty()) {
+        writeln!(out, "### JVM Configuration: {}", escape(&summary.name()))?;
+        let rows = summary.jvm_configuration.iter().map(|(label, value)| vec![label.to_string(), value.clone()]);
+        table(out, &["Setting", "Value"], rows.collect())?;
+    }
+
+    if let Collector::Cms = args.collector {
+        writeln!(out, "### CMS Promotion Failures")?;
+        let rows = report.cms_failures.rows().into_iter().map(|x| vec![x.event.to_string(), grouped(&x.count)]);
+        table(out, &["Event", "Count"], rows.collect())?;
+        if report.cms_failures.sized_objects > 0 {
+            size_stats(out, report, "Promotion Failure Object Size", args)?;
+            writeln!(out, "### Promotion Failure Object Size Percentiles")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            table(out, &["Percentile", "Bytes"], rows.collect())?;
+        }
+    } else if let Collector::Zgc = args.collector {
+        writeln!(out, "### ZGC Pages per Collection ({} collections)", grouped(&report.zgc_pages.collections()))?;
+        let rows = report.zgc_pages.rows().into_iter().map(|x| {
+            vec![x.page_type.to_string(), x.avg_pages, grouped(&x.max_pages), x.avg_size, grouped(&x.max_size)]
+        });
+        table(out, &["Page Type", "Avg Pages", "Max Pages", "Avg Size (MB)", "Max Size (MB)"], rows.collect())?;
+        if report.zgc_pages.large_pages() > 0 {
+            size_stats(out, report, "Large Page Size", args)?;
+            writeln!(out, "### Large Page Size Percentiles")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            table(out, &["Percentile", "Bytes"], rows.collect())?;
+        }
+    } else if let Collector::Shenandoah = args.collector {
+        writeln!(out, "### Shenandoah Allocation Failures")?;
+        let rows = report.shenandoah.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if !report.shenandoah.humongous_sizes.is_empty() {
+            let rows = report.shenandoah.rows().into_iter().map(|x| vec![x.region_size, grouped(&x.allocations), x.cumulative_percentage]);
+            table(out, &["Region Size", "Failed Humongous Allocations", "No Longer Humongous (cumulative %)"], rows.collect())?;
+            size_stats(out, report, "Failed Humongous Allocation Size", args)?;
+            writeln!(out, "### Failed Humongous Allocation Size Percentiles")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            table(out, &["Percentile", "Bytes"], rows.collect())?;
+        }
+    } else if let Collector::OpenJ9 = args.collector {
+        writeln!(out, "### OpenJ9 Allocation Failures")?;
+        let rows = report.openj9.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if report.openj9.tenure_failures > 0 {
+            size_stats(out, report, "Tenure Allocation Failure Size", args)?;
+            writeln!(out, "### Tenure Allocation Failure Size Percentiles")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            table(out, &["Percentile", "Bytes"], rows.collect())?;
+        }
+    } else if let Collector::Zing = args.collector {
+        writeln!(out, "### Zing Large Object Allocations")?;
+        let rows = report.zing.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if report.zing.large_allocations > 0 {
+            size_stats(out, report, "Large Object Size", args)?;
+            writeln!(out, "### Large Object Size Percentiles")?;
+            let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+            table(out, &["Percentile", "Bytes"], rows.collect())?;
+        }
+    } else if report.candidates.total > 0 {
+        writeln!(out, "### Allocations by Region Size")?;
+        let rows = report.region_size_array.iter().map(|x| {
+            vec![x.region_size.clone(), display_size(x.max_size, args.raw_bytes), grouped(&x.num_allocations), x.percentage.clone(), x.cumulative_percentage.clone()]
+        });
+        table(out, &["Region Size", "Max Allocation Size (50%)", "Number of Allocations", "% of Allocations", "Cumulative %"], rows.collect())?;
+
+        size_stats(out, report, "Allocation Size", args)?;
+        writeln!(out, "### Allocation Size Percentiles")?;
+        let rows = report.percentiles(&args.percentiles).into_iter().map(|(label, value)| vec![label, display_size(value, args.raw_bytes)]);
+        table(out, &["Percentile", "Bytes"], rows.collect())?;
+
+        if let Some(recommendation) = report.recommendation() {
+            writeln!(out, "### Recommendation\n\n{}", escape(&recommendation.summary()))?;
+            if recommendation.requires_jdk18() {
+                writeln!(out, "\nRegion sizes above 32MB require JDK 18 or newer.")?;
+            }
+            writeln!(out)?;
+        }
+        let suggestions = heap_sizing::suggestions(report);
+        if !suggestions.is_empty() {
+            writeln!(out, "### Heap Sizing\n")?;
+            for suggestion in suggestions {
+                writeln!(out, "- {}", escape(&suggestion))?;
+            }
+            writeln!(out)?;
+        }
+        writeln!(out, "### Candidate Region Sizes")?;
+        let rows = report.candidates.what_if(report.max_heap()).into_iter().map(|x| {
+            vec![x.region_size, grouped(&x.humongous), x.percentage, x.regions, x.notes.to_string()]
+        });
+        table(out, &["Region Size", "Still Humongous", "% Still Humongous", "Regions in Heap", "Notes"], rows.collect())?;
+    } else {
+        writeln!(out, "No humongous allocations were identified in the provided data set.\n")?;
+    }
+
+    if args.top_sizes > 0 && !report.size_clusters.is_empty() {
+        writeln!(out, "### Most Common Allocation Sizes")?;
+        let rows = report.size_clusters.rows(args.size_tolerance, args.top_sizes, args.raw_bytes).into_iter().map(|x| {
+            vec![x.size, x.range, grouped(&x.allocations), x.percentage, x.bytes, x.likely_object]
+        });
+        table(out, &["Most Common Size", "Size Range", "Number of Allocations", "% of Allocations", "Humongous Bytes", "Likely Object"], rows.collect())?;
+    }
+
+    if let (Some(top), false) = (args.size_frequency, report.size_clusters.is_empty()) {
+        writeln!(out, "### Allocation Size Frequency")?;
+        let rows = report.size_clusters.frequency_rows(top).into_iter().map(|x| vec![x.size, grouped(&x.allocations), x.percentage]);
+        table(out, &["Allocation Size (bytes)", "Number of Allocations", "% of Allocations"], rows.collect())?;
+    }
+
+    if let (Some(width), false) = (args.bins, report.size_bins.is_empty()) {
+        writeln!(out, "### Allocation Sizes ({} bins)", crate::bins::format_size(width))?;
+        let rows = report.size_bins.rows(width, args.bins_max).into_iter().map(|x| vec![x.range, grouped(&x.allocations), x.percentage]);
+        table(out, &["Allocation Size", "Number of Allocations", "% of Allocations"], rows.collect())?;
+    }
+
+    if report.waste.objects > 0 {
+        writeln!(out, "### Humongous Region Waste")?;
+        let rows = report.waste.stats(args.raw_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+    }
+
+    if let Some(heap_share) = HeapShare::new(report) {
+        writeln!(out, "### Humongous Share of Heap")?;
+        let rows = heap_share.stats(args.raw_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+    }
+
+    if !report.full_gc_rows.is_empty() {
+        let attributed = report.full_gc_rows.iter().filter(|x| x.preceding_allocations > 0).count();
+        writeln!(out, "### Full GC Events: {} ({} preceded by humongous allocations within {}s)", grouped(&report.full_gc_rows.len()), grouped(&attributed), args.full_gc_window)?;
+        let rows = report.full_gc_rows.iter().map(|x| {
+            vec![x.file.clone(), x.uptime.clone(), x.cause.clone(), x.duration.clone(), grouped(&x.preceding_allocations)]
+        });
+        table(out, &["File", "Uptime (s)", "Cause", "Duration (s)", "Preceding Humongous Allocations"], rows.collect())?;
+    }
+
+    if report.evacuation_failures > 0 || !report.heap_resizes.is_empty() {
+        match report.evacuation_failures {
+            0 => writeln!(out, "### Heap Resizes ({}s windows)", args.timeline_interval)?,
+            failures => writeln!(out, "### To-space Exhausted Events: {} ({}s windows)", grouped(&failures), args.timeline_interval)?,
+        }
+        if !report.heap_resizes.is_empty() {
+            writeln!(out, "Heap resizes: {}", report.heap_resizes.summary())?;
+            if let Some(hint) = report.heap_resizes.hint() {
+                writeln!(out, "\n{}", escape(&hint))?;
+            }
+        }
+        // None with --bounded-memory
+        if !report.timeline_rows.is_empty() {
+            let rows = report.timeline_rows.iter().map(|x| {
+                vec![x.file.clone(), x.window_start.clone(), grouped(&x.allocations), grouped(&x.bytes), grouped(&x.evacuation_failures), grouped(&x.expanded), grouped(&x.shrunk)]
+            });
+            table(out, &["File", "Window Start (s)", "Humongous Allocations", "Humongous Bytes", "To-space Exhausted", "Heap Expanded (MB)", "Heap Shrunk (MB)"], rows.collect())?;
+        }
+    }
+
+    if let (Some(interval), false) = (args.interval, report.calendar.is_empty()) {
+        writeln!(out, "### Humongous Allocations per {}", describe_interval(interval))?;
+        let rows = report.calendar.rows().into_iter().map(|x| vec![x.window_start, grouped(&x.allocations), grouped(&x.bytes), grouped(&x.p99)]);
+        table(out, &["Window Start", "Humongous Allocations", "Humongous Bytes", "p99 Size"], rows.collect())?;
+    }
+
+    if args.heatmap && !report.heatmap.is_empty() {
+        writeln!(out, "### Humongous Allocations per Hour of the Day and Region Size")?;
+        let headers: Vec<&str> = std::iter::once("Hour").chain(report.region_size_array.iter().map(|x| x.region_size.as_str())).collect();
+        let rows = report.heatmap.hours(&report.region_size_array).into_iter().map(|(hour, counts)| std::iter::once(hour).chain(counts.iter().map(grouped)).collect());
+        table(out, &headers, rows.collect())?;
+    }
+
+    if !report.burst_rows.is_empty() {
+        writeln!(out, "### Allocation Bursts (over {}x the average per {}s window)", args.burst_factor, args.timeline_interval)?;
+        let rows = report.burst_rows.iter().map(|x| {
+            vec![x.file.clone(), x.start.clone(), x.start_time.clone(), x.duration.clone(), grouped(&x.allocations), grouped(&x.bytes), x.factor.clone()]
+        });
+        table(out, &["File", "Start (s)", "Start Time", "Duration (s)", "Humongous Allocations", "Humongous Bytes", "x Average"], rows.collect())?;
+    }
+
+    if !report.inter_arrival.is_empty() {
+        writeln!(out, "### Time Between Humongous Allocations")?;
+        let rows = report.inter_arrival.stats(&args.percentiles).into_iter().map(|(label, value)| vec![label, value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+    }
+
+    if !report.heap_occupancy_rows.is_empty() {
+        writeln!(out, "### Heap Occupancy ({}s windows)", args.timeline_interval)?;
+        let rows = report.heap_occupancy_rows.iter().map(|x| {
+            vec![
+                x.file.clone(),
+                x.window_start.clone(),
+                grouped(&x.collections),
+                grouped(&x.max_before),
+                grouped(&x.avg_after),
+                grouped(&x.capacity),
+                grouped(&x.humongous),
+                x.humongous_percentage.clone(),
+            ]
+        });
+        table(out, &["File", "Window Start (s)", "GCs", "Max Heap Before GC (MB)", "Avg Heap After GC (MB)", "Heap Capacity (MB)", "Humongous Allocated (MB)", "Humongous % of Live"], rows.collect())?;
+    }
+
+    if !report.safepoint_rows.is_empty() {
+        let (spikes, during_bursts) = safepoint::spikes(&report.safepoint_rows);
+        writeln!(out, "### Safepoints ({}s windows): {} time to safepoint spikes over {}x the average, {} during allocation bursts", args.timeline_interval, grouped(&spikes), args.burst_factor, grouped(&during_bursts))?;
+        let rows = report.safepoint_rows.iter().map(|x| {
+            vec![
+                x.file.clone(),
+                x.window_start.clone(),
+                grouped(&x.safepoints),
+                x.max_reaching.clone(),
+                x.stopped.clone(),
+                grouped(&x.spikes),
+                grouped(&x.allocations),
+                x.burst.to_string(),
+            ]
+        });
+        table(out, &["File", "Window Start (s)", "Safepoints", "Max Time to Safepoint (ms)", "Stopped (ms)", "Spikes", "Humongous Allocations", "Allocation Burst"], rows.collect())?;
+    }
+
+    if !report.gc_causes.causes.is_empty() {
+        writeln!(out, "### GC Causes: {} collections ({} forced by humongous allocation)", grouped(&report.gc_causes.total()), grouped(&report.gc_causes.humongous()))?;
+        if let Some(share) = report.gc_causes.humongous_young_share() {
+            writeln!(out, "Young pauses triggered by humongous allocation: {}\n", share)?;
+        }
+        if let Some(share) = report.gc_causes.humongous_young_time_share() {
+            writeln!(out, "Young pause time of those triggered by humongous allocation: {}\n", share)?;
+        }
+        let rows = report.gc_causes.rows().into_iter().map(|x| vec![x.cause, grouped(&x.collections), x.percentage]);
+        table(out, &["Cause", "Collections", "% of Collections"], rows.collect())?;
+    }
+
+    if let Some(cumulative) = &report.cumulative {
+        writeln!(out, "### Cumulative Totals ({} runs)", grouped(&cumulative.runs))?;
+        let rows = report.cumulative_stats(cumulative, args).into_iter().map(|(label, value)| vec![label, value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+    }
+
+    if !report.allocations_per_cycle.is_empty() {
+        writeln!(out, "### Humongous Allocations per GC Cycle")?;
+        let rows = report.allocations_per_cycle.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        let rows = report.allocations_per_cycle.rows().into_iter().map(|x| vec![x.allocations, grouped(&x.cycles), x.percentage]);
+        table(out, &["Humongous Allocations per Cycle", "Cycles", "% of Cycles"], rows.collect())?;
+    }
+
+    if !report.pause_impact.is_empty() {
+        writeln!(out, "### Pause Impact of Humongous Allocations")?;
+        let rows = report.pause_impact.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        let rows = report.pause_impact.rows().into_iter().map(|x| vec![x.allocations, grouped(&x.pauses), x.avg_pause, x.max_pause]);
+        table(out, &["Humongous Allocations Since Previous Pause", "Pauses", "Avg Pause (ms)", "Max Pause (ms)"], rows.collect())?;
+    }
+
+    if !report.concurrent_cycles.triggers.is_empty() {
+        let total: u64 = report.concurrent_cycles.triggers.values().sum();
+        let humongous = report.concurrent_cycles.triggers.get(concurrent_cycle::HUMONGOUS_TRIGGER).copied().unwrap_or(0);
+        writeln!(out, "### Concurrent Cycles: {} ({} initiated by humongous allocation)", grouped(&total), grouped(&humongous))?;
+        let rows = report.concurrent_cycles.rows().into_iter().map(|x| vec![x.trigger, grouped(&x.cycles)]);
+        table(out, &["Trigger", "Concurrent Cycles"], rows.collect())?;
+    }
+
+    if report.cycle_durations.cycles() > 0 {
+        writeln!(out, "### Concurrent Cycle Durations ({} cycles)", grouped(&report.cycle_durations.cycles()))?;
+        let rows = report.cycle_durations.stats().into_iter().map(|(label, value)| vec![label, value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if let Some(hint) = report.cycle_durations.hint() {
+            writeln!(out, "{}\n", escape(&hint))?;
+        }
+    }
+
+    if report.ihop.requests > 0 || report.ihop.adaptive_updates > 0 {
+        writeln!(out, "### Marking Threshold (IHOP)")?;
+        let rows = report.ihop.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if let Some(hint) = report.ihop.hint() {
+            writeln!(out, "{}\n", escape(&hint))?;
+        }
+    }
+
+    if report.allocation_rate.collections > 0 {
+        writeln!(out, "### Allocation Rate")?;
+        let rows = report.allocation_rate.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if let Some(hint) = report.allocation_rate.hint() {
+            writeln!(out, "{}\n", escape(&hint))?;
+        }
+    }
+
+    if report.eager_reclaim.collections > 0 {
+        writeln!(out, "### Humongous Eager Reclaim ({} collections)", grouped(&report.eager_reclaim.collections))?;
+        let rows = report.eager_reclaim.stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+    }
+
+    if report.mixed_collections.collections > 0 {
+        writeln!(out, "### Mixed Collections")?;
+        let rows = report.mixed_collections.stats(report.size_stats.total_bytes).into_iter().map(|(label, value)| vec![label.to_string(), value]);
+        table(out, &["Statistic", "Value"], rows.collect())?;
+        if let Some(hint) = report.mixed_collections.hint(report.size_stats.total_bytes) {
+            writeln!(out, "{}\n", escape(&hint))?;
+        }
+    }
+
+    if report.humongous_trace.objects > 0 {
+        writeln!(out, "### Humongous Region Trace\n\n{}", report.humongous_trace.summary())?;
+        let rows = report.humongous_trace.rows().into_iter().map(|x| vec![grouped(&x.regions), grouped(&x.objects)]);
+        table(out, &["Regions per Object", "Humongous Objects"], rows.collect())?;
+    }
+
+    writeln!(out, "### Parse Quality")?;
+    let rows = report.parse_quality(args).stats().into_iter().map(|(label, value)| vec![label.to_string(), value]);
+    table(out, &["Statistic", "Value"], rows.collect())?;
+
+    if report.file_summaries.iter().any(|x| x.has_issues()) {
+        writeln!(out, "### File Summary")?;
+        let rows = report.file_summaries.iter().map(|x| x.row()).map(|x| {
+            vec![x.file, x.status.to_string(), grouped(&x.allocations), grouped(&x.skipped_lines), x.details]
+        });
+        table(out, &["File", "Status", "Humongous Allocations", "Skipped Lines", "Details"], rows.collect())?;
+    }
+    out.flush()
+}
@@ -0,0 +1,123 @@
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::buckets::{self, MB};
+use crate::file_summary::FileSummary;
+use crate::report::Report;
+use crate::{Cli, Collector, RegionSize, RegionSizeSource};
+
+// The source behind a file's region size, from its description in the json report
+fn region_size_source(description: &str) -> RegionSizeSource {
+    if description.contains("inferred") {
+        RegionSizeSource::Inferred
+    } else if description.contains("--region-size") {
+        RegionSizeSource::Override
+    } else if description.contains("--flags-file") {
+        RegionSizeSource::FlagsFile
+    } else {
+        RegionSizeSource::Logged
+    }
+}
+
+// A file of an earlier report, its error (if any) kept as one of its details
+fn file_summary(file: &Value) -> FileSummary {
+    let mut summary = FileSummary::new(Path::new(file["file"].as_str().unwrap_or("-")));
+    summary.label = file["label"].as_str().map(str::to_string);
+    summary.region_size = file["region_size_mb"].as_u64().map(|mb| RegionSize { mb: mb as u32, source: region_size_source(file["region_size"].as_str().unwrap_or_default()) });
+    summary.max_heap = file["max_heap_bytes"].as_u64();
+    summary.jdk_version = file["jdk_version"].as_u64().map(|x| x as u32);
+    summary.allocations = file["allocations"].as_u64().unwrap_or(0);
+    summary.allocated_bytes = file["allocated_bytes"].as_u64().unwrap_or(0);
+    summary.evacuation_failures = file["evacuation_failures"].as_u64().unwrap_or(0);
+    summary.lines_read = file["lines_read"].as_u64().unwrap_or(0);
+    summary.undecodable_lines = file["undecodable_lines"].as_u64().unwrap_or(0);
+    summary.binary_lines = file["binary_lines"].as_u64().unwrap_or(0);
+    summary.unparseable_lines = file["unparseable_lines"].as_u64().unwrap_or(0);
+    summary.unexpected_allocations = file["unexpected_allocations"].as_u64().unwrap_or(0);
+    summary.filtered_allocations = file["filtered_allocations"].as_u64().unwrap_or(0);
+    summary.dated_allocations = file["dated_allocations"].as_u64().unwrap_or(0);
+    summary.uptime_allocations = file["uptime_allocations"].as_u64().unwrap_or(0);
+    summary.warnings = file["details"].as_array().into_iter().flatten().filter_map(Value::as_str).map(str::to_string).collect();
+    summary
+}
+
+// Every allocation of a size counted again, as the report would have while parsing the gc log
+fn record_size(report: &mut Report, args: &Cli, bytes: u64, count: u64, region_size: Option<u64>) {
+    report.size_stats.record(bytes, count);
+    if report.allocs_histogram.increment_by(bytes, count).is_err() {
+        log::warn!("An allocation of {} bytes is outside of the histogram range", bytes);
+    }
+    let index = buckets::bucket_index(&report.region_size_array, bytes).filter(|_| matches!(args.collector, Collector::G1));
+    if let Some(index) = index {
+        report.region_size_array[index].num_allocations += count as u32;
+    }
+    for _ in 0..count {
+        report.size_clusters.record(bytes);
+        if let Some(width) = args.bins {
+            report.size_bins.record(bytes, width, args.bins_max);
+        }
+        if let Some(region_size) = region_size {
+            report.waste.observe(bytes, region_size);
+        }
+        if index.is_some() {
+            report.candidates.observe(bytes);
+        }
+    }
+}
+
+// Adds one report of a json document. Without the histogram of allocation sizes (reports of older versions) only
+// the bucket counts, the waste and the files are merged, the statistics, percentiles and recommendation can't be.
+fn merge_report(report: &mut Report, args: &Cli, value: &Value, path: &Path) {
+    let files = value["files"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let region_size = files.iter().find_map(|x| x["region_size_mb"].as_u64()).map(|mb| mb.max(1) * MB);
+    report.file_summaries.extend(files.iter().map(file_summary));
+    report.evacuation_failures += value["evacuation_failures"].as_u64().unwrap_or(0) as usize;
+    for (cause, count) in value["gc_causes"].as_object().into_iter().flatten() {
+        *report.gc_causes.causes.entry(cause.clone()).or_insert(0) += count.as_u64().unwrap_or(0);
+    }
+    let young_pauses = &value["young_pauses"];
+    report.gc_causes.young_pauses += young_pauses["pauses"].as_u64().unwrap_or(0);
+    report.gc_causes.humongous_young_pauses += young_pauses["humongous_allocation"].as_u64().unwrap_or(0);
+    report.gc_causes.young_pause_time += young_pauses["pause_seconds"].as_f64().unwrap_or(0.0);
+    report.gc_causes.humongous_young_pause_time += young_pauses["humongous_allocation_pause_seconds"].as_f64().unwrap_or(0.0);
+    if let Some(histogram) = value["histogram"].as_object() {
+        for (bytes, count) in histogram {
+            match (bytes.parse::<u64>(), count.as_u64()) {
+                (Ok(bytes), Some(count)) => record_size(report, args, bytes, count, region_size),
+                _ => log::warn!("{}: skipping the histogram entry {}: {}", path.display(), bytes, count),
+            }
+        }
+        return;
+    }
+    log::warn!("{} has no histogram of the allocation sizes, only its buckets, waste and files are merged", path.display());
+    for bucket in value["buckets"].as_array().into_iter().flatten() {
+        let label = bucket["region_size"].as_str().unwrap_or_default();
+        match report.region_size_array.iter_mut().find(|x| x.region_size == label) {
+            Some(merged) => merged.num_allocations += bucket["allocations"].as_u64().unwrap_or(0) as u32,
+            None => log::warn!("{}: the {} bucket isn't one of the --buckets, its allocations are left out", path.display(), label),
+        }
+    }
+    let waste = &value["waste"];
+    report.waste.objects += waste["objects"].as_u64().unwrap_or(0);
+    report.waste.allocated_bytes += waste["allocated_bytes"].as_u64().unwrap_or(0);
+    report.waste.wasted_bytes += waste["wasted_bytes"].as_u64().unwrap_or(0);
+}
+
+// One report of every report of the json documents written by earlier runs, e.g. one per host
+pub fn merge(paths: &[PathBuf], args: &Cli) -> Result<Report, String> {
+    let mut report = Report::new(args.buckets.as_deref().unwrap_or(&buckets::DEFAULT_REGION_SIZES), args.precision);
+    for path in paths {
+        let text = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+        let document: Value = serde_json::from_str(&text).map_err(|e| format!("{} is not a json report: {}", path.display(), e))?;
+        let reports = document["reports"].as_array().ok_or_else(|| format!("{} is not a json report, written with --output-format json", path.display()))?;
+        for value in reports {
+            merge_report(&mut report, args, value, path);
+        }
+    }
+    if args.buckets.is_none() {
+        buckets::trim_large_buckets(&mut report.region_size_array);
+    }
+    buckets::compute_percentages(&mut report.region_size_array);
+    Ok(report)
+}
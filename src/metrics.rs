@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::follow::{FollowEvent, FollowEventKind};
+use crate::recommendation::G1_REGION_SIZES;
+
+#[derive(Default)]
+pub struct FileMetrics {
+    // The --input label of the file
+    pub label: Option<String>,
+    // Cumulative counts per histogram bucket, +Inf is size_count
+    pub size_buckets: Vec<u64>,
+    pub size_sum: u64,
+    pub size_count: u64,
+    pub full_gcs: u64,
+    pub evacuation_failures: u64,
+}
+
+// Prometheus metrics for every followed gc log, labelled by file and --input label
+#[derive(Default)]
+pub struct Metrics {
+    files: BTreeMap<String, FileMetrics>,
+}
+
+// Histogram buckets at 50% of every valid region size, i.e. the largest allocation each region size avoids treating as humongous
+fn bucket_bounds() -> impl Iterator<Item = u64> {
+    G1_REGION_SIZES.iter().map(|x| x / 2)
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// The file="gc.log" labels of a file's series, with label="node1" for an --input one
+fn series_labels(file: &str, metrics: &FileMetrics) -> String {
+    match &metrics.label {
+        Some(label) => format!("file=\"{}\",label=\"{}\"", escape_label(file), escape_label(label)),
+        None => format!("file=\"{}\"", escape_label(file)),
+    }
+}
+
+impl Metrics {
+    pub fn files(&self) -> &BTreeMap<String, FileMetrics> {
+        &self.files
+    }
+
+    pub fn record(&mut self, event: &FollowEvent) {
+        let metrics = self.files.entry(event.file.clone()).or_insert_with(|| FileMetrics {
+            label: event.label.clone(),
+            size_buckets: vec![0; G1_REGION_SIZES.len()],
+            ..FileMetrics::default()
+        });
+        match event.kind {
+            FollowEventKind::HumongousAllocation(bytes) => {
+                metrics.size_sum += bytes;
+                metrics.size_count += 1;
+                let first = bucket_bounds().take_while(|x| *x < bytes).count();
+                for bucket in &mut metrics.size_buckets[first..] {
+                    *bucket += 1;
+                }
+            }
+            FollowEventKind::FullGc => metrics.full_gcs += 1,
+            FollowEventKind::EvacuationFailure => metrics.evacuation_failures += 1,
+        }
+    }
+
+    // Prometheus text exposition format 0.0.4
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        text.push_str("# HELP gc_humongous_allocations_total Humongous allocations seen in the gc log.\n# TYPE gc_humongous_allocations_total counter\n");
+        for (file, metrics) in &self.files {
+            let _ = writeln!(text, "gc_humongous_allocations_total{{{}}} {}", series_labels(file, metrics), metrics.size_count);
+        }
+        text.push_str("# HELP gc_humongous_allocation_size_bytes Size of humongous allocations.\n# TYPE gc_humongous_allocation_size_bytes histogram\n");
+        for (file, metrics) in &self.files {
+            let labels = series_labels(file, metrics);
+            for (bound, count) in bucket_bounds().zip(&metrics.size_buckets) {
+                let _ = writeln!(text, "gc_humongous_allocation_size_bytes_bucket{{{},le=\"{}\"}} {}", labels, bound, count);
+            }
+            let _ = writeln!(text, "gc_humongous_allocation_size_bytes_bucket{{{},le=\"+Inf\"}} {}", labels, metrics.size_count);
+            let _ = writeln!(text, "gc_humongous_allocation_size_bytes_sum{{{}}} {}", labels, metrics.size_sum);
+            let _ = writeln!(text, "gc_humongous_allocation_size_bytes_count{{{}}} {}", labels, metrics.size_count);
+        }
+        text.push_str("# HELP gc_full_gc_total Full GC events seen in the gc log.\n# TYPE gc_full_gc_total counter\n");
+        for (file, metrics) in &self.files {
+            let _ = writeln!(text, "gc_full_gc_total{{{}}} {}", series_labels(file, metrics), metrics.full_gcs);
+        }
+        text.push_str("# HELP gc_to_space_exhausted_total To-space exhausted / evacuation failure events seen in the gc log.\n# TYPE gc_to_space_exhausted_total counter\n");
+        for (file, metrics) in &self.files {
+            let _ = writeln!(text, "gc_to_space_exhausted_total{{{}}} {}", series_labels(file, metrics), metrics.evacuation_failures);
+        }
+        text
+    }
+}
+
+fn handle_connection(stream: TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let mut stream = stream;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    if path == "/metrics" {
+        let body = metrics.lock().unwrap().render();
+        write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+    } else {
+        let body = "Not Found, metrics are served at /metrics\n";
+        write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+    }
+}
+
+// Serves GET /metrics on a background thread
+pub fn serve(address: &str, metrics: Arc<Mutex<Metrics>>) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    log::info!("Serving Prometheus metrics on http://{}/metrics", listener.local_addr()?);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = handle_connection(stream, &metrics) {
+                log::debug!("Metrics request failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
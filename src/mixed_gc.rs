@@ -0,0 +1,120 @@
+use crate::bins::human_size;
+use crate::gc_cause::parse_gc_cause;
+use crate::gc_cycles::Pause;
+use crate::heap_occupancy::parse_heap_occupancy;
+use crate::numbers::{grouped, ExactSum};
+
+// JDK 8:    "[GC pause (G1 Evacuation Pause) (mixed), 0.0123456 secs]", the heap transition follows on a
+//           "   [Eden: ... Heap: 5000.0M(8192.0M)->4000.0M(8192.0M)]" line
+// JDK 9-11: "[info][gc] GC(9) Pause Mixed (G1 Evacuation Pause) 5000M->4000M(8192M) 12.345ms"
+// JDK 12+:  "[info][gc] GC(9) Pause Young (Mixed) (G1 Evacuation Pause) 5000M->4000M(8192M) 12.345ms"
+fn is_mixed(line: &str) -> bool {
+    line.contains(") (mixed)") || line.contains(" Pause Mixed ") || line.contains(" Pause Young (Mixed) ")
+}
+
+// Mixed collections are the only ones that reclaim old regions without a full GC, if they can't keep up with
+// the old regions humongous objects churn through the heap fills up until a full GC
+#[derive(Default)]
+pub struct MixedCollections {
+    pub collections: u64,
+    // Runs of mixed collections between young only ones, G1 starts one after each concurrent cycle
+    pub phases: u64,
+    in_phase: bool,
+    // A JDK 8 mixed pause whose heap transition is still to come
+    pending: bool,
+    pub pause_time: ExactSum,
+    pub reclaimed_bytes: u64,
+    // Uptime of the previous phase's start in this file, and the gaps between the phases' starts
+    last_phase_start: Option<f64>,
+    phase_gaps: u64,
+    phase_gap_total: ExactSum,
+}
+
+impl MixedCollections {
+    pub fn observe(&mut self, line: &str, uptime: Option<f64>) {
+        if parse_gc_cause(line).is_some() {
+            // Unified logging with -Xlog:gc+start logs a pause at its start too, without the heap or duration
+            let unified = line.contains(" Pause ");
+            if unified && !line.trim_end().ends_with("ms") {
+                return;
+            }
+            self.pending = false;
+            if !is_mixed(line) {
+                self.in_phase = false;
+                return;
+            }
+            self.collections += 1;
+            self.pause_time += Pause::parse(line, 0).duration.unwrap_or(0.0);
+            if !self.in_phase {
+                self.in_phase = true;
+                self.phases += 1;
+                if let (Some(previous), Some(uptime)) = (self.last_phase_start, uptime) {
+                    self.phase_gaps += 1;
+                    self.phase_gap_total += uptime - previous;
+                }
+                self.last_phase_start = uptime.or(self.last_phase_start);
+            }
+            self.pending = !unified;
+            if unified {
+                self.record_transition(line);
+            }
+        } else if self.pending && line.contains(" Heap: ") {
+            self.pending = false;
+            self.record_transition(line);
+        }
+    }
+
+    fn record_transition(&mut self, line: &str) {
+        if let Some(sample) = parse_heap_occupancy(line, Some(0.0)) {
+            self.reclaimed_bytes += sample.before.saturating_sub(sample.after);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.in_phase = false;
+        self.pending = false;
+        self.last_phase_start = None;
+    }
+
+    pub fn merge(&mut self, other: MixedCollections) {
+        self.collections += other.collections;
+        self.phases += other.phases;
+        self.pause_time += other.pause_time;
+        self.reclaimed_bytes += other.reclaimed_bytes;
+        self.phase_gaps += other.phase_gaps;
+        self.phase_gap_total += other.phase_gap_total;
+        (self.in_phase, self.pending, self.last_phase_start) = (other.in_phase, other.pending, other.last_phase_start.or(self.last_phase_start));
+    }
+
+    pub fn stats(&self, humongous_bytes: u64) -> Vec<(&'static str, String)> {
+        let collections = self.collections.max(1) as f64;
+        let mut stats = vec![
+            ("mixed collections", format!("{} in {} mixed phases", grouped(&self.collections), grouped(&self.phases))),
+            ("avg mixed collections per phase", format!("{:.1}", self.collections as f64 / self.phases.max(1) as f64)),
+        ];
+        if self.phase_gaps > 0 {
+            stats.push(("avg time between mixed phases", format!("{:.1}s", self.phase_gap_total.value() / self.phase_gaps as f64)));
+        }
+        stats.push(("avg pause", format!("{:.1}ms", self.pause_time.value() * 1000.0 / collections)));
+        stats.push(("reclaimed", human_size(self.reclaimed_bytes)));
+        stats.push(("avg reclaimed per mixed collection", human_size((self.reclaimed_bytes as f64 / collections) as u64)));
+        if self.pause_time.value() > 0.0 {
+            stats.push(("reclaimed per second of pause", format!("{}/s", human_size((self.reclaimed_bytes as f64 / self.pause_time.value()) as u64))));
+        }
+        if humongous_bytes > 0 {
+            stats.push(("reclaimed per humongous byte allocated", format!("{:.2}", self.reclaimed_bytes as f64 / humongous_bytes as f64)));
+        }
+        stats
+    }
+
+    // Old regions reclaimed more slowly than humongous objects take them up
+    pub fn hint(&self, humongous_bytes: u64) -> Option<String> {
+        (self.reclaimed_bytes < humongous_bytes).then(|| {
+            format!(
+                "Mixed collections reclaimed {} while {} were allocated as humongous objects: unless eager reclaim frees them at young collections, the old generation fills up faster than it is cleaned",
+                human_size(self.reclaimed_bytes),
+                human_size(humongous_bytes)
+            )
+        })
+    }
+}
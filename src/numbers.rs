@@ -0,0 +1,105 @@
+use std::env;
+use std::fmt::Display;
+use std::ops::AddAssign;
+use std::sync::OnceLock;
+
+// The separator between the thousands of counts and byte totals, None with --plain-numbers
+static SEPARATOR: OnceLock<Option<char>> = OnceLock::new();
+
+// The thousands separator of the LC_ALL, LC_NUMERIC or LANG locale
+fn locale_separator() -> char {
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"].iter().filter_map(|x| env::var(x).ok()).find(|x| !x.is_empty()).unwrap_or_default();
+    separator_of(&locale)
+}
+
+// Decimals always print with a dot, so the locales that group with dots or apostrophes, e.g. "de_DE.UTF-8" or
+// "de_CH.UTF-8", group with spaces like "fr_FR.UTF-8" rather than print "1.234.5". Others, "C" and POSIX group
+// with commas.
+fn separator_of(locale: &str) -> char {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    let (language, _) = locale.split_once('_').unwrap_or((locale, ""));
+    match language {
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr" | "fr" | "ru" | "sv" | "pl" | "cs" | "sk" | "fi" | "nb" | "nn" | "no" | "uk" | "hu" | "bg" | "et" | "lv" | "lt" => ' ',
+        _ => ',',
+    }
+}
+
+pub fn set_plain(plain: bool) {
+    if SEPARATOR.set((!plain).then(locale_separator)).is_err() {
+        log::warn!("The number format was already set");
+    }
+}
+
+fn separator() -> Option<char> {
+    *SEPARATOR.get_or_init(|| Some(locale_separator()))
+}
+
+// 12345678 as "12,345,678", only the integer part of the number at the start of the value is grouped so
+// "1234567 (12.5%)" becomes "1,234,567 (12.5%)"
+pub fn grouped<T: Display + ?Sized>(value: &T) -> String {
+    let text = value.to_string();
+    let Some(separator) = separator() else { return text };
+    let (sign, rest) = text.strip_prefix(['-', '+']).map_or(("", text.as_str()), |x| (&text[..1], x));
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let (integer, rest) = rest.split_at(digits);
+    let mut formatted = String::from(sign);
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (digits - index) % 3 == 0 {
+            formatted.push(separator);
+        }
+        formatted.push(digit);
+    }
+    formatted.push_str(rest);
+    formatted
+}
+
+// A sum of seconds or percentages kept in billionths, so that it doesn't depend on the order of the additions
+// and the chunks of a --jobs run add up to what a single pass over the file does
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExactSum(i128);
+
+impl ExactSum {
+    pub fn value(&self) -> f64 {
+        self.0 as f64 / 1e9
+    }
+}
+
+impl AddAssign<f64> for ExactSum {
+    fn add_assign(&mut self, value: f64) {
+        self.0 += (value * 1e9).round() as i128;
+    }
+}
+
+impl AddAssign for ExactSum {
+    fn add_assign(&mut self, other: ExactSum) {
+        self.0 += other.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_with_commas_or_spaces() {
+        assert_eq!(separator_of("en_US.UTF-8"), ',');
+        assert_eq!(separator_of("C"), ',');
+        assert_eq!(separator_of("de_DE.UTF-8"), ' ');
+        assert_eq!(separator_of("de_CH.UTF-8"), ' ');
+        assert_eq!(separator_of("fr_FR@euro"), ' ');
+    }
+
+    #[test]
+    fn sums_in_any_order() {
+        let values = [0.1, 0.0234, 1.5e-3, 12.75, 0.2, 3.333];
+        let mut forward = ExactSum::default();
+        values.iter().for_each(|x| forward += *x);
+        let mut first = ExactSum::default();
+        let mut second = ExactSum::default();
+        values[3..].iter().rev().for_each(|x| first += *x);
+        values[..3].iter().for_each(|x| second += *x);
+        first += second;
+        assert_eq!(forward, first);
+        assert_eq!(forward.value(), 16.4079);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::bins::human_size;
+
+// Array headers of 64-bit HotSpot: mark word, compressed class pointer and length, or with a full class pointer
+// (-XX:-UseCompressedClassPointers) padded to 24 bytes
+const HEADERS: [(u64, &str); 2] = [(16, ""), (24, ", without compressed class pointers")];
+// Objects are 8 byte aligned
+const ALIGNMENT: u64 = 8;
+
+// A guess at the array behind an allocation size, to help find the allocation site. Humongous objects are nearly
+// always arrays; a payload that is a power of two or a round number of bytes points at a buffer sized by the code.
+pub fn likely_object(bytes: u64) -> Option<String> {
+    if !bytes.is_multiple_of(ALIGNMENT) {
+        return None;
+    }
+    for (header, note) in HEADERS {
+        let payload = bytes.checked_sub(header)?;
+        if payload.is_power_of_two() {
+            return Some(format!("{} buffer: byte[{}], int[{}] or long[{}]{}", human_size(payload), payload, payload / 4, payload / 8, note));
+        }
+    }
+    let payload = bytes - HEADERS[0].0;
+    // Element counts a person would write, padding fills up to the next 8 bytes
+    for (element, names) in [(1, "byte"), (2, "char"), (4, "int"), (8, "long")] {
+        let rounded = (payload / element).next_multiple_of(1000);
+        if (rounded * element).next_multiple_of(ALIGNMENT) == payload && rounded > 0 {
+            return Some(format!("{}[{}] (a round number of elements)", names, rounded));
+        }
+    }
+    Some(format!("array of ~{} bytes: byte[{}], char[{}], int[{}] or long[{}]", payload, payload, payload / 2, payload / 4, payload / 8))
+}
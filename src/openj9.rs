@@ -0,0 +1,133 @@
+use crate::numbers::grouped;
+use crate::timestamp;
+
+// OpenJ9's counterpart of humongous allocations in its verbose:gc XML: allocation failures in the tenure
+// space, where objects too large for the nursery go and, when the small object area (SOA) has no room
+// left for them, the large object area (LOA) is kept for
+#[derive(Default)]
+pub struct OpenJ9Allocations {
+    pub nursery_failures: u64,
+    pub tenure_failures: u64,
+    // Tenure allocation failures the collection made room for in the LOA
+    pub satisfied_from_loa: u64,
+    // Allocation failures that still couldn't be satisfied, an OutOfMemoryError
+    pub unsatisfied: u64,
+    // Smallest free share of the LOA seen before and after the collections
+    pub min_loa_free_percent: Option<u64>,
+    // Of the balanced policy, whose regions hold large arrays as arraylets
+    region_size: Option<u64>,
+    // The type and time of the <af> element of older releases, whose size is in the <minimum> element that follows
+    pending: Option<(String, Option<i64>)>,
+}
+
+// The value of name="..." in an element
+fn attribute<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let (_, rest) = line.split_once(&format!(" {}=\"", name))?;
+    Some(rest.split_once('"')?.0)
+}
+
+// "2021-06-14T10:00:05.456", which parse_wall_clock reads by position like a datestamp, or "Jun 14 10:00:05 2021"
+// before OpenJ9 0.9
+pub fn parse_wall_clock(line: &str) -> Option<i64> {
+    let value = attribute(line, "timestamp")?;
+    if value.starts_with(|x: char| x.is_ascii_digit()) {
+        return timestamp::parse_wall_clock(value);
+    }
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let mut fields = value.split_whitespace();
+    let month = fields.next().and_then(|x| MONTHS.iter().position(|y| *y == x))? + 1;
+    let (day, time, year) = (fields.next()?.parse::<u32>().ok()?, fields.next()?, fields.next()?);
+    timestamp::parse_wall_clock(&format!("{}-{:02}-{:02}T{}", year, month, day, time))
+}
+
+impl OpenJ9Allocations {
+    pub fn region_size(&self) -> Option<u64> {
+        self.region_size
+    }
+
+    // Returns the size of a tenure allocation failure and the time of the element it was logged with
+    // "<af-start id="3" threadId="0000000000F4B800" totalBytesRequested="16777232" timestamp="2021-06-14T10:00:05.456" intervalms="5333.133" type="tenure" />"
+    // or, before OpenJ9 0.9, "<af type="tenured" id="3" timestamp="..." intervalms="...">" then "<minimum requested_bytes="16777232" />"
+    pub fn observe(&mut self, line: &str) -> Option<(u64, Option<i64>)> {
+        let line = line.trim_start();
+        if line.starts_with("<af-start ") {
+            let bytes = self.record(attribute(line, "type")?, attribute(line, "totalBytesRequested")?.parse().ok()?)?;
+            return Some((bytes, parse_wall_clock(line)));
+        }
+        if line.starts_with("<af ") {
+            self.pending = attribute(line, "type").map(|x| (x.to_string(), parse_wall_clock(line)));
+        } else if line.starts_with("<minimum ") {
+            let (kind, wall_clock) = self.pending.take()?;
+            return Some((self.record(&kind, attribute(line, "requested_bytes")?.parse().ok()?)?, wall_clock));
+        } else if line.starts_with("<af-end ") {
+            if attribute(line, "success") == Some("false") {
+                self.unsatisfied += 1;
+            } else if attribute(line, "from").is_some_and(|x| x.ends_with("loa")) {
+                self.satisfied_from_loa += 1;
+            }
+        } else if line.starts_with("<mem type=\"loa\"") {
+            let percent = attribute(line, "percent")?.parse::<u64>().ok()?;
+            self.min_loa_free_percent = Some(self.min_loa_free_percent.map_or(percent, |x| x.min(percent)));
+        } else if line.starts_with("<attribute name=\"regionSize\"") {
+            self.region_size = attribute(line, "value")?.parse().ok();
+        }
+        None
+    }
+
+    fn record(&mut self, kind: &str, bytes: u64) -> Option<u64> {
+        if kind.starts_with("tenure") {
+            self.tenure_failures += 1;
+            Some(bytes)
+        } else {
+            self.nursery_failures += 1;
+            None
+        }
+    }
+
+    // A new file, the region size is logged again at startup
+    pub fn reset(&mut self) {
+        self.region_size = None;
+        self.pending = None;
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let mut stats = vec![
+            ("nursery allocation failures", grouped(&self.nursery_failures)),
+            ("tenure allocation failures", grouped(&self.tenure_failures)),
+            ("satisfied from the large object area", grouped(&self.satisfied_from_loa)),
+            ("unsatisfied", grouped(&self.unsatisfied)),
+        ];
+        if let Some(percent) = self.min_loa_free_percent {
+            stats.push(("smallest free share of the large object area", format!("{}%", percent)));
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_tenure_allocation_failures() {
+        let time = timestamp::parse_wall_clock("2021-06-14T10:00:05");
+        assert!(time.is_some());
+        let mut current = OpenJ9Allocations::default();
+        assert_eq!(current.observe(r#"<attribute name="regionSize" value="2097152" />"#), None);
+        assert_eq!(current.region_size(), Some(2097152));
+        let tenure = r#"<af-start id="3" threadId="0000000000F4B800" totalBytesRequested="16777232" timestamp="2021-06-14T10:00:05.456" intervalms="5333.133" type="tenure" />"#;
+        assert_eq!(current.observe(tenure), Some((16777232, time)));
+        assert_eq!(current.observe(r#"<af-start id="4" threadId="0000000000F4B800" totalBytesRequested="32" timestamp="2021-06-14T10:00:06.789" intervalms="1333.133" type="nursery" />"#), None);
+        current.observe(r#"  <mem type="loa" free="1048576" total="33554432" percent="3" />"#);
+        current.observe(r#"<af-end id="5" timestamp="2021-06-14T10:00:05.466" threadId="0000000000F4B800" success="true" from="tenure-loa"/>"#);
+        current.observe(r#"<af-end id="6" timestamp="2021-06-14T10:00:06.799" threadId="0000000000F4B800" success="false" />"#);
+        assert_eq!((current.tenure_failures, current.nursery_failures, current.satisfied_from_loa, current.unsatisfied), (1, 1, 1, 1));
+        assert_eq!(current.min_loa_free_percent, Some(3));
+
+        // Before OpenJ9 0.9 the size follows the <af> element
+        let mut older = OpenJ9Allocations::default();
+        assert_eq!(older.observe(r#"<af type="tenured" id="3" timestamp="Jun 14 10:00:05 2021" intervalms="5333.133">"#), None);
+        assert_eq!(older.observe(r#"  <minimum requested_bytes="16777232" />"#), Some((16777232, time)));
+        assert_eq!(older.observe(r#"  <minimum requested_bytes="16777232" />"#), None);
+    }
+}
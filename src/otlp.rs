@@ -0,0 +1,198 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::metrics::Metrics;
+use crate::recommendation::G1_REGION_SIZES;
+use crate::report::Report;
+
+const SCOPE: &str = env!("CARGO_PKG_NAME");
+// AGGREGATION_TEMPORALITY_CUMULATIVE
+const CUMULATIVE: u8 = 2;
+
+// Pushes metrics to an OpenTelemetry collector using OTLP/HTTP with the JSON encoding
+pub struct OtlpExporter {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    url: String,
+    start: u64,
+}
+
+fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |x| x.as_nanos() as u64)
+}
+
+type Attributes<'a> = &'a [(&'a str, &'a str)];
+
+fn attributes(pairs: Attributes) -> Value {
+    pairs.iter().map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } })).collect()
+}
+
+// 64 bit integers are strings in the protobuf JSON mapping
+fn sum(name: &str, description: &str, unit: &str, points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "unit": unit,
+        "sum": { "aggregationTemporality": CUMULATIVE, "isMonotonic": true, "dataPoints": points },
+    })
+}
+
+fn gauge(name: &str, description: &str, unit: &str, points: Vec<Value>) -> Value {
+    json!({ "name": name, "description": description, "unit": unit, "gauge": { "dataPoints": points } })
+}
+
+fn histogram(name: &str, description: &str, unit: &str, points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "unit": unit,
+        "histogram": { "aggregationTemporality": CUMULATIVE, "dataPoints": points },
+    })
+}
+
+impl OtlpExporter {
+    // `endpoint` is the collector's base address, e.g. http://localhost:4318
+    pub fn new(endpoint: &str) -> OtlpExporter {
+        OtlpExporter {
+            url: format!("{}/v1/metrics", endpoint.trim_end_matches('/')),
+            start: unix_nanos(SystemTime::now()),
+        }
+    }
+
+    fn int_point(&self, pairs: Attributes, value: u64, now: u64) -> Value {
+        json!({ "attributes": attributes(pairs), "startTimeUnixNano": self.start.to_string(), "timeUnixNano": now.to_string(), "asInt": value.to_string() })
+    }
+
+    fn double_point(&self, pairs: Attributes, value: f64, now: u64) -> Value {
+        json!({ "attributes": attributes(pairs), "timeUnixNano": now.to_string(), "asDouble": value })
+    }
+
+    // `counts` holds one entry per bound plus a final one for everything above the last bound
+    fn histogram_point(&self, pairs: Attributes, bounds: &[u64], counts: &[u64], sum: Option<u64>, now: u64) -> Value {
+        let mut point = json!({
+            "attributes": attributes(pairs),
+            "startTimeUnixNano": self.start.to_string(),
+            "timeUnixNano": now.to_string(),
+            "count": counts.iter().sum::<u64>().to_string(),
+            "bucketCounts": counts.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
+            "explicitBounds": bounds,
+        });
+        if let Some(sum) = sum {
+            point["sum"] = json!(sum);
+        }
+        point
+    }
+
+    fn send(&self, metrics: Vec<Value>) -> Result<(), String> {
+        let body = json!({
+            "resourceMetrics": [{
+                "resource": { "attributes": [{ "key": "service.name", "value": { "stringValue": SCOPE } }] },
+                "scopeMetrics": [{ "scope": { "name": SCOPE, "version": env!("CARGO_PKG_VERSION") }, "metrics": metrics }],
+            }],
+        });
+        self.post(&body)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn post(&self, body: &Value) -> Result<(), String> {
+        ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+            .map(|_| log::debug!("Exported metrics to {}", self.url))
+            .map_err(|e| format!("unable to export metrics: {}", e))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn post(&self, _: &Value) -> Result<(), String> {
+        Err("unable to export metrics: OTLP is not available in the WebAssembly build".to_string())
+    }
+
+    // One push of the final results of an analysis, the size histogram has one point per group
+    pub fn export_reports(&self, reports: &[Report]) -> Result<(), String> {
+        let now = unix_nanos(SystemTime::now());
+        let mut allocations = Vec::new();
+        let mut rates = Vec::new();
+        let mut sizes = Vec::new();
+        let mut full_gcs = Vec::new();
+        let mut evacuation_failures = Vec::new();
+        for report in reports {
+            for summary in report.file_summaries.iter().filter(|x| x.error.is_none()) {
+                let mut file = vec![("file", summary.file.as_str())];
+                file.extend(summary.label.as_deref().map(|x| ("label", x)));
+                let file = file.as_slice();
+                allocations.push(self.int_point(file, summary.allocations, now));
+                if let Some(span) = summary.uptime_span().filter(|x| *x > 0.0) {
+                    rates.push(self.double_point(file, summary.allocations as f64 * 60.0 / span, now));
+                }
+                let file_full_gcs = report.full_gc_rows.iter().filter(|x| x.file == summary.file).count();
+                full_gcs.push(self.int_point(file, file_full_gcs as u64, now));
+                evacuation_failures.push(self.int_point(file, summary.evacuation_failures, now));
+            }
+            let buckets = &report.region_size_array;
+            let bounds: Vec<u64> = buckets[..buckets.len() - 1].iter().map(|x| x.max_size).collect();
+            let counts: Vec<u64> = buckets.iter().map(|x| x.num_allocations as u64).collect();
+            let group = report.title.as_deref().map(|x| ("group", x));
+            sizes.push(self.histogram_point(group.as_slice(), &bounds, &counts, None, now));
+        }
+        self.send(vec![
+            sum("gc.humongous.allocations", "Humongous allocations in the gc log", "{allocation}", allocations),
+            gauge("gc.humongous.allocation.rate", "Humongous allocations per minute of jvm uptime covered by the gc log", "{allocation}/min", rates),
+            histogram("gc.humongous.allocation.size", "Size of humongous allocations across the gc logs of a group, bucketed at 50% of each region size", "By", sizes),
+            sum("gc.full_gc", "Full GC events in the gc log", "{event}", full_gcs),
+            sum("gc.to_space_exhausted", "To-space exhausted / evacuation failure events in the gc log", "{event}", evacuation_failures),
+        ])
+    }
+
+    // `previous` holds the allocation counts of the last push, used to derive the current rate
+    fn live_metrics(&self, metrics: &Metrics, previous: &mut BTreeMap<String, u64>, interval: Duration) -> Vec<Value> {
+        let now = unix_nanos(SystemTime::now());
+        let bounds: Vec<u64> = G1_REGION_SIZES.iter().map(|x| x / 2).collect();
+        let mut allocations = Vec::new();
+        let mut rates = Vec::new();
+        let mut sizes = Vec::new();
+        let mut full_gcs = Vec::new();
+        let mut evacuation_failures = Vec::new();
+        for (file_name, metrics) in metrics.files() {
+            let mut file = vec![("file", file_name.as_str())];
+            file.extend(metrics.label.as_deref().map(|x| ("label", x)));
+            let file = file.as_slice();
+            allocations.push(self.int_point(file, metrics.size_count, now));
+            let last = previous.insert(file_name.clone(), metrics.size_count).unwrap_or(0);
+            rates.push(self.double_point(file, (metrics.size_count - last) as f64 * 60.0 / interval.as_secs_f64(), now));
+            // The Prometheus buckets are cumulative, OTLP wants the count of each bucket on its own
+            let mut counts: Vec<u64> = metrics.size_buckets.iter().scan(0, |below, x| {
+                let count = x - *below;
+                *below = *x;
+                Some(count)
+            }).collect();
+            counts.push(metrics.size_count - metrics.size_buckets.last().copied().unwrap_or(0));
+            sizes.push(self.histogram_point(file, &bounds, &counts, Some(metrics.size_sum), now));
+            full_gcs.push(self.int_point(file, metrics.full_gcs, now));
+            evacuation_failures.push(self.int_point(file, metrics.evacuation_failures, now));
+        }
+        vec![
+            sum("gc.humongous.allocations", "Humongous allocations in the gc log", "{allocation}", allocations),
+            gauge("gc.humongous.allocation.rate", "Humongous allocations per minute since the previous export", "{allocation}/min", rates),
+            histogram("gc.humongous.allocation.size", "Size of humongous allocations, bucketed at 50% of each region size", "By", sizes),
+            sum("gc.full_gc", "Full GC events in the gc log", "{event}", full_gcs),
+            sum("gc.to_space_exhausted", "To-space exhausted / evacuation failure events in the gc log", "{event}", evacuation_failures),
+        ]
+    }
+
+    // Pushes the live metrics every `interval` on a background thread, failed pushes are logged and retried next time
+    pub fn spawn(self, metrics: Arc<Mutex<Metrics>>, interval: Duration) {
+        thread::spawn(move || {
+            let mut previous = BTreeMap::new();
+            loop {
+                thread::sleep(interval);
+                // Don't hold the lock while waiting on the collector
+                let payload = self.live_metrics(&metrics.lock().unwrap(), &mut previous, interval);
+                if let Err(e) = self.send(payload) {
+                    log::warn!("{}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,68 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::Cli;
+
+// Like git, a report printed to a terminal goes through the pager when it doesn't fit on the screen. Not for
+// --watch, which keeps writing to stdout, nor with --output or --no-pager.
+pub fn wanted(args: &Cli) -> bool {
+    !args.no_pager && args.output.is_none() && args.watch.is_none() && io::stdout().is_terminal() && command().is_some()
+}
+
+// $PAGER with its arguments, less when unset, none when set to an empty string or cat
+fn command() -> Option<Vec<String>> {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let words: Vec<String> = pager.split_whitespace().map(String::from).collect();
+    words.first().is_some_and(|x| x != "cat").then_some(words)
+}
+
+// Holds the whole report, which is shown once written as its height is only known then
+#[derive(Default)]
+pub struct Pager {
+    buffer: Vec<u8>,
+}
+
+impl Pager {
+    fn show(&self) -> io::Result<()> {
+        let height = console::Term::stdout().size_checked().map_or(usize::MAX, |x| x.0 as usize);
+        let lines = self.buffer.iter().filter(|x| **x == b'\n').count();
+        let Some(command) = command().filter(|_| lines >= height) else { return io::stdout().write_all(&self.buffer) };
+        let mut pager = Command::new(&command[0]);
+        pager.args(&command[1..]).stdin(Stdio::piped());
+        // As git sets it: the table colors pass through and the report stays on the screen after quitting
+        if env::var_os("LESS").is_none() {
+            pager.env("LESS", "FRX");
+        }
+        let mut child = match pager.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                log::debug!("Unable to run the pager {}: {}", command[0], e);
+                return io::stdout().write_all(&self.buffer);
+            }
+        };
+        // Quitting the pager before the end closes the pipe
+        let written = child.stdin.take().unwrap().write_all(&self.buffer);
+        child.wait()?;
+        written.or_else(|e| if e.kind() == io::ErrorKind::BrokenPipe { Ok(()) } else { Err(e) })
+    }
+}
+
+impl Write for Pager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        if let Err(e) = self.show() {
+            log::debug!("Unable to show the report: {}", e);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use arrow_array::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::error::Error;
+use std::fs::{self, File};
+use std::path::Path;
+
+use crate::columnar;
+use crate::report::Report;
+use crate::Cli;
+
+fn write(path: &Path, batch: &RecordBatch) -> Result<(), Box<dyn Error>> {
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), Some(properties))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// allocations.parquet, files.parquet and statistics.parquet in the directory, replacing earlier exports
+pub fn export(dir: &Path, reports: &[Report], args: &Cli) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    write(&dir.join("allocations.parquet"), &columnar::allocations(reports)?)?;
+    write(&dir.join("files.parquet"), &columnar::files(reports)?)?;
+    write(&dir.join("statistics.parquet"), &columnar::statistics(reports, args)?)?;
+    Ok(())
+}
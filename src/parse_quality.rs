@@ -0,0 +1,89 @@
+use crate::file_summary::FileSummary;
+use crate::numbers::grouped;
+use crate::RegionSizeSource;
+
+// How much of the gc logs of a report was read and understood, to judge how far to trust the rest of it
+#[derive(Default)]
+pub struct ParseQuality {
+    pub files: u64,
+    pub lines_read: u64,
+    // Allocation lines, whether counted, filtered out or with an unparseable size
+    pub matched_lines: u64,
+    pub filtered_allocations: u64,
+    pub unparseable_lines: u64,
+    pub undecodable_lines: u64,
+    pub binary_lines: u64,
+    pub unexpected_allocations: u64,
+    // Of the collectors with regions, the files whose region size wasn't found and the ones it was inferred for
+    pub files_without_region_size: Option<u64>,
+    pub inferred_region_sizes: u64,
+    pub allocations: u64,
+    pub dated_allocations: u64,
+    pub uptime_allocations: u64,
+}
+
+fn percent(count: u64, total: u64) -> String {
+    format!("{:.1}%", count as f64 * 100.0 / total.max(1) as f64)
+}
+
+impl ParseQuality {
+    pub fn new(summaries: &[FileSummary], has_regions: bool) -> ParseQuality {
+        let mut quality = ParseQuality { files_without_region_size: has_regions.then_some(0), ..ParseQuality::default() };
+        for summary in summaries {
+            quality.files += 1;
+            quality.lines_read += summary.lines_read;
+            quality.matched_lines += summary.allocations + summary.filtered_allocations + summary.unparseable_lines;
+            quality.filtered_allocations += summary.filtered_allocations;
+            quality.unparseable_lines += summary.unparseable_lines;
+            quality.undecodable_lines += summary.undecodable_lines;
+            quality.binary_lines += summary.binary_lines;
+            quality.unexpected_allocations += summary.unexpected_allocations;
+            if let Some(missing) = &mut quality.files_without_region_size {
+                match &summary.region_size {
+                    None => *missing += 1,
+                    Some(region_size) if matches!(region_size.source, RegionSizeSource::Inferred) => quality.inferred_region_sizes += 1,
+                    Some(_) => {}
+                }
+            }
+            quality.allocations += summary.allocations;
+            quality.dated_allocations += summary.dated_allocations;
+            quality.uptime_allocations += summary.uptime_allocations;
+        }
+        quality
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let mut matched = grouped(&self.matched_lines);
+        if self.filtered_allocations > 0 {
+            matched.push_str(&format!(", {} outside of the size filters or window", grouped(&self.filtered_allocations)));
+        }
+        if self.unparseable_lines > 0 {
+            matched.push_str(&format!(", {} with an unparseable size", grouped(&self.unparseable_lines)));
+        }
+        let mut stats = vec![
+            ("lines read", format!("{} in {} file{}", grouped(&self.lines_read), grouped(&self.files), if self.files == 1 { "" } else { "s" })),
+            ("matched allocation lines", matched),
+            ("undecodable lines", format!("{} with invalid UTF-8 read with it replaced, {} of binary data skipped", grouped(&self.undecodable_lines), grouped(&self.binary_lines))),
+            ("suspicious allocations", format!("{} of 512KB or less, which can never be humongous", grouped(&self.unexpected_allocations))),
+        ];
+        if let Some(missing) = self.files_without_region_size {
+            let mut files = format!("{} of {}", grouped(&missing), grouped(&self.files));
+            if self.inferred_region_sizes > 0 {
+                files.push_str(&format!(", inferred for {} more", grouped(&self.inferred_region_sizes)));
+            }
+            stats.push(("files without a region size", files));
+        }
+        stats.push((
+            "timestamp coverage",
+            format!(
+                "{} of {} allocations dated ({}), {} with an uptime ({})",
+                grouped(&self.dated_allocations),
+                grouped(&self.allocations),
+                percent(self.dated_allocations, self.allocations),
+                grouped(&self.uptime_allocations),
+                percent(self.uptime_allocations, self.allocations)
+            ),
+        ));
+        stats
+    }
+}
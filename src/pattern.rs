@@ -0,0 +1,30 @@
+use regex::Regex;
+use std::num::ParseIntError;
+
+const BYTES_PLACEHOLDER: &str = "{bytes}";
+
+// A user supplied matcher for humongous allocation lines the built in parser does not recognize
+#[derive(Clone, Debug)]
+pub struct LinePattern {
+    regex: Regex,
+}
+
+// Either a regex with a named `bytes` capture, e.g. "humongous alloc of (?P<bytes>\d+)B",
+// or literal text around a {bytes} placeholder, e.g. "Humongous object of {bytes} bytes allocated"
+pub fn parse_line_pattern(value: &str) -> Result<LinePattern, String> {
+    let source = match value.split_once(BYTES_PLACEHOLDER) {
+        Some((prefix, suffix)) => format!(r"{}(?P<bytes>\d+){}", regex::escape(prefix), regex::escape(suffix)),
+        None => value.to_string(),
+    };
+    let regex = Regex::new(&source).map_err(|e| e.to_string())?;
+    if !regex.capture_names().any(|x| x == Some("bytes")) {
+        return Err(format!("{} has no {} placeholder or (?P<bytes>...) capture", value, BYTES_PLACEHOLDER));
+    }
+    Ok(LinePattern { regex })
+}
+
+impl LinePattern {
+    pub fn parse(&self, line: &str) -> Option<Result<u64, ParseIntError>> {
+        self.regex.captures(line).and_then(|caps| caps.name("bytes")).map(|bytes| bytes.as_str().parse::<u64>())
+    }
+}
@@ -0,0 +1,109 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::gc_cycles::{allocations_before_pauses, describe_range, range_start, Pause};
+use crate::numbers::grouped;
+use crate::report::Record;
+
+pub struct PauseImpactRow {
+    pub allocations: String,
+    pub pauses: u64,
+    pub avg_pause: String,
+    pub max_pause: String,
+}
+
+impl Record for PauseImpactRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Humongous Allocations Since Previous Pause", Justify::Right),
+        ("Pauses", Justify::Right),
+        ("Avg Pause (ms)", Justify::Right),
+        ("Max Pause (ms)", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.allocations.clone(), grouped(&self.pauses), self.avg_pause.clone(), self.max_pause.clone()]
+    }
+}
+
+#[derive(Default)]
+struct PauseGroup {
+    pauses: u64,
+    total: f64,
+    max: f64,
+}
+
+// Pause durations against the humongous allocations since the previous pause, to put a latency cost on them.
+// Only pauses whose duration is on the pause line are included.
+#[derive(Default)]
+pub struct PauseImpact {
+    // Start of the allocation count range -> pauses after that many allocations
+    groups: BTreeMap<u64, PauseGroup>,
+    // Sums for the correlation coefficient, allocations as x and milliseconds as y
+    n: f64,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xx: f64,
+    sum_yy: f64,
+    sum_xy: f64,
+}
+
+impl PauseImpact {
+    pub fn record(&mut self, pauses: &[Pause]) {
+        for (count, duration) in allocations_before_pauses(pauses) {
+            let Some(duration) = duration else { continue };
+            let (x, y) = (count as f64, duration * 1000.0);
+            let group = self.groups.entry(range_start(count)).or_default();
+            group.pauses += 1;
+            group.total += y;
+            group.max = group.max.max(y);
+            self.n += 1.0;
+            self.sum_x += x;
+            self.sum_y += y;
+            self.sum_xx += x * x;
+            self.sum_yy += y * y;
+            self.sum_xy += x * y;
+        }
+    }
+
+    // Nothing to compare when no pause followed a humongous allocation
+    pub fn is_empty(&self) -> bool {
+        self.groups.keys().all(|x| *x == 0)
+    }
+
+    // Pearson's r, None when either side doesn't vary
+    pub fn correlation(&self) -> Option<f64> {
+        let covariance = self.n * self.sum_xy - self.sum_x * self.sum_y;
+        let variance_x = self.n * self.sum_xx - self.sum_x * self.sum_x;
+        let variance_y = self.n * self.sum_yy - self.sum_y * self.sum_y;
+        (variance_x > 0.0 && variance_y > 0.0).then(|| covariance / (variance_x.sqrt() * variance_y.sqrt()))
+    }
+
+    // Milliseconds, of the pauses after humongous allocations or of those after none
+    pub fn avg_pause(&self, after_allocations: bool) -> Option<f64> {
+        let groups = self.groups.iter().filter(|(start, _)| (**start > 0) == after_allocations);
+        let (pauses, total) = groups.fold((0, 0.0), |(pauses, total), (_, x)| (pauses + x.pauses, total + x.total));
+        (pauses > 0).then(|| total / pauses as f64)
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let format = |x: Option<f64>| x.map_or("-".to_string(), |x| format!("{:.1}ms", x));
+        vec![
+            ("pauses", grouped(&(self.n as u64))),
+            ("avg pause after no humongous allocations", format(self.avg_pause(false))),
+            ("avg pause after humongous allocations", format(self.avg_pause(true))),
+            ("correlation of allocations and pause time", self.correlation().map_or("-".to_string(), |x| format!("{:.2}", x))),
+        ]
+    }
+
+    pub fn rows(&self) -> Vec<PauseImpactRow> {
+        self.groups
+            .iter()
+            .map(|(start, group)| PauseImpactRow {
+                allocations: describe_range(*start),
+                pauses: group.pauses,
+                avg_pause: format!("{:.1}", group.total / group.pauses as f64),
+                max_pause: format!("{:.1}", group.max),
+            })
+            .collect()
+    }
+}
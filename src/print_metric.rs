@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use crate::buckets::MB;
+use crate::report::Report;
+
+// A single value of each report printed instead of the report by --print, for shell scripts
+#[derive(Clone, Copy, Debug)]
+pub enum PrintMetric {
+    // The recommended region size as -XX:G1HeapRegionSize takes it, e.g. 16m
+    Recommendation,
+    Count,
+    TotalBytes,
+    Mean,
+    Stddev,
+    Min,
+    Max,
+    Percentile(f64),
+    // Share of the humongous regions' space left unused
+    Waste,
+}
+
+pub fn parse_print_metric(value: &str) -> Result<PrintMetric, String> {
+    Ok(match value {
+        "recommendation" => PrintMetric::Recommendation,
+        "count" => PrintMetric::Count,
+        "total-bytes" => PrintMetric::TotalBytes,
+        "mean" => PrintMetric::Mean,
+        "stddev" => PrintMetric::Stddev,
+        "min" => PrintMetric::Min,
+        "max" => PrintMetric::Max,
+        "waste" => PrintMetric::Waste,
+        _ => match value.strip_prefix('p').and_then(|x| x.parse::<f64>().ok()) {
+            Some(percentile) if percentile > 0.0 && percentile <= 100.0 => PrintMetric::Percentile(percentile),
+            _ => return Err(format!("unknown metric {}, expected recommendation, count, total-bytes, mean, stddev, min, max, waste or a percentile such as p99", value)),
+        },
+    })
+}
+
+impl PrintMetric {
+    // None when the report has nothing to tell, e.g. no humongous allocations
+    fn value(self, report: &Report) -> Option<String> {
+        let stats = &report.size_stats;
+        match self {
+            PrintMetric::Recommendation => report.recommendation().map(|x| format!("{}m", x.region_size / MB)),
+            PrintMetric::Count => Some(stats.count.to_string()),
+            PrintMetric::TotalBytes => Some(stats.total_bytes.to_string()),
+            PrintMetric::Mean => stats.mean().map(|x| format!("{:.0}", x)),
+            PrintMetric::Stddev => stats.stddev().map(|x| format!("{:.0}", x)),
+            PrintMetric::Min => report.percentiles(&[]).first().map(|x| x.1.to_string()),
+            PrintMetric::Max => report.percentiles(&[]).last().map(|x| x.1.to_string()),
+            PrintMetric::Percentile(percentile) => report.percentiles(&[percentile]).get(1).map(|x| x.1.to_string()),
+            PrintMetric::Waste => (report.waste.objects > 0).then(|| format!("{:.2}", report.waste.percentage())),
+        }
+    }
+}
+
+// The value alone, or after the title and a tab for each --group-by report. Sizes are in bytes. A report
+// without the value gets an empty one.
+pub fn write_metric(reports: &[Report], out: &mut dyn Write, metric: PrintMetric) -> io::Result<()> {
+    for report in reports {
+        let value = metric.value(report).unwrap_or_default();
+        match &report.title {
+            Some(title) => writeln!(out, "{}\t{}", title, value)?,
+            None => writeln!(out, "{}", value)?,
+        }
+    }
+    Ok(())
+}
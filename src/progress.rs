@@ -0,0 +1,18 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+
+// Byte based progress for a single gc log, hidden when stdout isn't a terminal so piped or
+// redirected reports aren't interleaved with progress output
+pub fn file_progress_bar(file_name: &str, file_size: u64) -> ProgressBar {
+    if !std::io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+    let progress = ProgressBar::new(file_size);
+    progress.set_style(
+        ProgressStyle::with_template("{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    progress.set_message(file_name.to_string());
+    progress
+}
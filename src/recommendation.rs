@@ -0,0 +1,126 @@
+use cli_table::format::Justify;
+
+use crate::buckets::{format_region_size, MB};
+use crate::numbers::grouped;
+use crate::report::Record;
+
+// Valid G1HeapRegionSize values, sizes above 32MB require JDK 18+
+pub const G1_REGION_SIZES: [u64; 10] = [MB, 2 * MB, 4 * MB, 8 * MB, 16 * MB, 32 * MB, 64 * MB, 128 * MB, 256 * MB, 512 * MB];
+pub const MAX_PRE_JDK18_REGION_SIZE: u64 = 32 * MB;
+// Share of the observed humongous allocations that the recommended region size should turn into regular allocations
+pub const COVERAGE_TARGET: f64 = 95.0;
+
+pub struct WhatIfRow {
+    pub region_size: String,
+    pub humongous: u64,
+    pub percentage: String,
+    pub regions: String,
+    pub notes: &'static str,
+}
+
+impl Record for WhatIfRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Region Size", Justify::Right),
+        ("Still Humongous", Justify::Right),
+        ("% Still Humongous", Justify::Right),
+        ("Regions in Heap", Justify::Right),
+        ("Notes", Justify::Left),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.region_size.clone(), grouped(&self.humongous), self.percentage.clone(), self.regions.clone(), self.notes.to_string()]
+    }
+}
+
+// For every valid region size, how many of the observed allocations would no longer be humongous
+#[derive(Default)]
+pub struct RegionSizeCandidates {
+    fits: [u64; G1_REGION_SIZES.len()],
+    pub total: u64,
+}
+
+pub struct Recommendation {
+    pub region_size: u64,
+    // Percentage of the observed allocations that fit within 50% of the region
+    pub coverage: f64,
+    pub meets_target: bool,
+}
+
+impl RegionSizeCandidates {
+    pub fn observe(&mut self, bytes: u64) {
+        self.total += 1;
+        let first = G1_REGION_SIZES.partition_point(|x| x / 2 < bytes);
+        for fits in &mut self.fits[first..] {
+            *fits += 1;
+        }
+    }
+
+    fn coverage(&self, index: usize) -> f64 {
+        self.fits[index] as f64 * 100.0 / self.total as f64
+    }
+
+    // The smallest region size up to max_region_size that reaches the coverage target, or the largest one when
+    // none does
+    pub fn recommend(&self, max_region_size: u64) -> Option<Recommendation> {
+        if self.total == 0 {
+            return None;
+        }
+        let candidates = G1_REGION_SIZES.partition_point(|x| *x <= max_region_size).max(1);
+        let index = (0..candidates).find(|x| self.coverage(*x) >= COVERAGE_TARGET).unwrap_or(candidates - 1);
+        Some(Recommendation {
+            region_size: G1_REGION_SIZES[index],
+            coverage: self.coverage(index),
+            meets_target: self.coverage(index) >= COVERAGE_TARGET,
+        })
+    }
+}
+
+impl RegionSizeCandidates {
+    // The tradeoff of each region size: fewer humongous allocations against fewer, larger regions in the heap
+    pub fn what_if(&self, max_heap: Option<u64>) -> Vec<WhatIfRow> {
+        G1_REGION_SIZES
+            .iter()
+            .enumerate()
+            .map(|(index, region_size)| {
+                let humongous = self.total - self.fits[index];
+                WhatIfRow {
+                    region_size: format_region_size(*region_size),
+                    humongous,
+                    percentage: format!("{:.2}%", humongous as f64 * 100.0 / self.total.max(1) as f64),
+                    regions: max_heap.map_or("-".to_string(), |x| grouped(&(x / region_size))),
+                    notes: if *region_size > MAX_PRE_JDK18_REGION_SIZE { "JDK 18+" } else { "" },
+                }
+            })
+            .collect()
+    }
+}
+
+impl Recommendation {
+    pub fn summary(&self) -> String {
+        if self.meets_target {
+            format!("Use a {} region size (-XX:G1HeapRegionSize={}m), {:.1}% of the humongous allocations would no longer be humongous", format_region_size(self.region_size), self.region_size / MB, self.coverage)
+        } else {
+            format!("No region size keeps {}% of the allocations below 50% of a region, the largest ({}) only covers {:.1}%", COVERAGE_TARGET, format_region_size(self.region_size), self.coverage)
+        }
+    }
+
+    pub fn requires_jdk18(&self) -> bool {
+        self.region_size > MAX_PRE_JDK18_REGION_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommends_up_to_the_largest_region_size_of_the_jdk() {
+        let mut candidates = RegionSizeCandidates::default();
+        (0..100).for_each(|_| candidates.observe(40 * MB));
+        let recommendation = candidates.recommend(*G1_REGION_SIZES.last().unwrap()).unwrap();
+        assert_eq!((recommendation.region_size, recommendation.meets_target), (128 * MB, true));
+        assert!(recommendation.requires_jdk18());
+        let recommendation = candidates.recommend(MAX_PRE_JDK18_REGION_SIZE).unwrap();
+        assert_eq!((recommendation.region_size, recommendation.meets_target), (32 * MB, false));
+    }
+}
@@ -0,0 +1,332 @@
+use flate2::read::GzDecoder;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+use crate::encoding;
+
+// Gc logs given as http(s):// or s3:// URLs are streamed from the response body each time they're opened, see
+// archive::open, without touching the disk or holding the whole object in memory
+//
+// The downloads run concurrently on plain threads rather than on a tokio runtime: ureq is blocking, the parsers
+// read the logs through std::io::Read on their own thread, and tokio is only built with the grpc feature. A
+// thread per download and a bounded channel to the parser fetch the logs ahead just the same, without an async
+// HTTP client and runtime in every build.
+
+const SCHEMES: [&str; 3] = ["http://", "https://", "s3://"];
+
+pub fn is_remote(path: &Path) -> bool {
+    path.to_str().is_some_and(|x| SCHEMES.iter().any(|scheme| x.starts_with(scheme)))
+}
+
+// Reached through the configured endpoint (AWS_ENDPOINT_URL for MinIO and the like, path-style) or AWS
+// (virtual-hosted), presigned with the AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY credentials if set, unsigned
+// for public buckets otherwise
+#[cfg(feature = "s3")]
+fn presign(object: &str) -> io::Result<String> {
+    use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
+    use std::env;
+    use std::time::Duration;
+
+    let (bucket, key) = object.split_once('/').filter(|x| !x.1.is_empty()).ok_or_else(|| io::Error::other(format!("s3://{} is not an s3://BUCKET/KEY", object)))?;
+    let region = env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")).unwrap_or_else(|_| "us-east-1".to_string());
+    let (endpoint, style) = match env::var("AWS_ENDPOINT_URL") {
+        Ok(endpoint) => (endpoint, UrlStyle::Path),
+        Err(_) => (format!("https://s3.{}.amazonaws.com", region), UrlStyle::VirtualHost),
+    };
+    let endpoint: url::Url = endpoint.parse().map_err(|e| io::Error::other(format!("invalid endpoint {}: {}", endpoint, e)))?;
+    let bucket = Bucket::new(endpoint, style, bucket.to_string(), region).map_err(|e| io::Error::other(e.to_string()))?;
+    let credentials = Credentials::from_env();
+    Ok(bucket.get_object(credentials.as_ref(), key).sign(Duration::from_secs(300)).to_string())
+}
+
+#[cfg(not(feature = "s3"))]
+fn presign(_: &str) -> io::Result<String> {
+    Err(io::Error::other("s3:// inputs are not available, rebuild with --features s3"))
+}
+
+// The response body and its Content-Length
+#[cfg(not(target_arch = "wasm32"))]
+fn get(url: &str) -> io::Result<(Box<dyn Read + Send + Sync>, Option<u64>)> {
+    let response = ureq::get(url).call().map_err(|e| io::Error::other(e.to_string()))?;
+    let len = response.header("Content-Length").and_then(|x| x.parse().ok());
+    Ok((response.into_reader(), len))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn get(_: &str) -> io::Result<(Box<dyn Read + Send + Sync>, Option<u64>)> {
+    Err(io::Error::other("remote inputs are not available in the WebAssembly build"))
+}
+
+// A gc log read as it is downloaded. Only its head, enough to tell a flight recording or UTF-16, can be
+// read again, which is all the seeking done before a log is read from the start.
+pub struct Stream {
+    head: Vec<u8>,
+    body: Box<dyn Read + Send>,
+    // Of the head, then of the body after it
+    position: u64,
+    // Of the decoded log, unknown when it's gunzipped
+    pub len: Option<u64>,
+}
+
+impl Stream {
+    pub fn new(mut body: Box<dyn Read + Send>, len: Option<u64>) -> io::Result<Stream> {
+        let mut head = Vec::with_capacity(encoding::HEAD_LEN);
+        (&mut body).take(encoding::HEAD_LEN as u64).read_to_end(&mut head)?;
+        Ok(Stream { head, body, position: 0, len })
+    }
+
+    pub fn head(&self) -> &[u8] {
+        &self.head
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = match self.head.get(self.position as usize..) {
+            Some(mut head) if !head.is_empty() => head.read(buf)?,
+            _ => self.body.read(buf)?,
+        };
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for Stream {
+    fn seek(&mut self, to: SeekFrom) -> io::Result<u64> {
+        let target = match to {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+        let head = self.head.len() as u64;
+        match target {
+            Some(target) if target == self.position || (target <= head && self.position <= head) => {
+                self.position = target;
+                Ok(target)
+            }
+            _ => Err(io::Error::new(io::ErrorKind::Unsupported, "a download can only be seeked within its first bytes")),
+        }
+    }
+}
+
+// The object as a stream, gunzipped on the way when it's gzipped whatever its name
+pub fn open(path: &Path) -> io::Result<Stream> {
+    let location = path.to_string_lossy();
+    let url = match location.strip_prefix("s3://") {
+        Some(object) => presign(object)?,
+        None => location.to_string(),
+    };
+    let (body, len) = get(&url)?;
+    let mut body = BufReader::new(body);
+    log::info!("Streaming {}", location);
+    if body.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Stream::new(Box::new(GzDecoder::new(body)), None)
+    } else {
+        Stream::new(Box::new(body), len)
+    }
+}
+
+// A download started ahead is read this many bytes at a time, and holds at most CHUNKS of them that the parser
+// hasn't read yet
+const CHUNK_SIZE: u64 = 1024 * 1024;
+const CHUNKS: usize = 4;
+
+enum Chunk {
+    // The Content-Length of the log, once the response came
+    Started(Option<u64>),
+    Data(Vec<u8>),
+    End,
+    Failed(io::Error),
+}
+
+// The body of a download started ahead, read as its chunks arrive from the thread downloading it
+struct Prefetched {
+    chunks: Receiver<Chunk>,
+    chunk: io::Cursor<Vec<u8>>,
+    download: Option<JoinHandle<()>>,
+    done: bool,
+}
+
+fn download(path: &Path, chunks: &SyncSender<Chunk>) -> io::Result<()> {
+    let mut stream = open(path)?;
+    if chunks.send(Chunk::Started(stream.len)).is_err() {
+        return Ok(());
+    }
+    loop {
+        let mut chunk = Vec::new();
+        if (&mut stream).take(CHUNK_SIZE).read_to_end(&mut chunk)? == 0 {
+            let _ = chunks.send(Chunk::End);
+            return Ok(());
+        }
+        // Parsing went on without it
+        if chunks.send(Chunk::Data(chunk)).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+impl Prefetched {
+    fn start(path: PathBuf) -> Prefetched {
+        let (sender, chunks) = mpsc::sync_channel(CHUNKS);
+        let download = thread::spawn(move || {
+            if let Err(e) = download(&path, &sender) {
+                let _ = sender.send(Chunk::Failed(e));
+            }
+        });
+        Prefetched { chunks, chunk: io::Cursor::new(Vec::new()), download: Some(download), done: false }
+    }
+
+    // The chunks stopped without an end, the thread went away
+    fn stopped(&mut self) -> io::Error {
+        self.done = true;
+        match self.download.take().map(JoinHandle::join) {
+            Some(Err(_)) => io::Error::other("the download thread panicked"),
+            _ => io::Error::other("the download stopped before the end of the log"),
+        }
+    }
+
+    // The Content-Length, or the error the download failed with before the response came
+    fn started(&mut self) -> io::Result<Option<u64>> {
+        match self.chunks.recv() {
+            Ok(Chunk::Started(len)) => Ok(len),
+            Ok(Chunk::Failed(e)) => Err(e),
+            Ok(_) => Err(io::Error::other("the download sent its body before its response")),
+            Err(_) => Err(self.stopped()),
+        }
+    }
+}
+
+impl Read for Prefetched {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.chunk.read(buf)?;
+            if read > 0 || buf.is_empty() || self.done {
+                return Ok(read);
+            }
+            match self.chunks.recv() {
+                Ok(Chunk::Data(chunk)) => self.chunk = io::Cursor::new(chunk),
+                Ok(Chunk::End) => self.done = true,
+                Ok(Chunk::Failed(e)) => {
+                    self.done = true;
+                    return Err(e);
+                }
+                Ok(Chunk::Started(_)) => return Err(io::Error::other("the download sent its response twice")),
+                Err(_) => return Err(self.stopped()),
+            }
+        }
+    }
+}
+
+thread_local! {
+    // The prefetched downloads of the logs about to be parsed, see take_prefetched
+    static PREFETCHED: RefCell<HashMap<PathBuf, Stream>> = RefCell::new(HashMap::new());
+}
+
+// The download started ahead for the remote log, which only the parse of the log reads. The other opens, for
+// its header, request it again.
+pub fn take_prefetched(path: &Path) -> Option<Stream> {
+    PREFETCHED.with(|x| x.borrow_mut().remove(path))
+}
+
+// Starts the downloads of the remote logs among the files in the order they're analyzed, each on its own thread
+// while the logs before it are parsed. At most `ahead` downloads run besides the one being parsed, and each
+// holds no more than CHUNKS chunks the parser hasn't read, which bounds the memory to that many chunks per
+// download whatever the size of the logs.
+pub struct Prefetcher {
+    downloads: Option<Receiver<(PathBuf, Prefetched)>>,
+}
+
+impl Prefetcher {
+    pub fn start(files: &[PathBuf], ahead: usize) -> Prefetcher {
+        let remote: Vec<PathBuf> = files.iter().filter(|x| is_remote(x)).cloned().collect();
+        if remote.is_empty() {
+            return Prefetcher { downloads: None };
+        }
+        // The spawning thread holds on to one download while the channel is full
+        let (sender, receiver) = mpsc::sync_channel(ahead.saturating_sub(1));
+        thread::spawn(move || {
+            for path in remote {
+                let download = Prefetched::start(path.clone());
+                if sender.send((path, download)).is_err() {
+                    break;
+                }
+            }
+        });
+        Prefetcher { downloads: Some(receiver) }
+    }
+
+    // Waits for the response to the download of the next remote log, which has to be `path`, and keeps it for
+    // take_prefetched. Without one the parse requests the log again and reports what went wrong.
+    pub fn register_next(&self, path: &Path) -> io::Result<()> {
+        let Some((next, mut download)) = self.downloads.as_ref().and_then(|x| x.recv().ok()) else { return Ok(()) };
+        if next != path {
+            return Err(io::Error::other(format!("the next download is {} instead", next.display())));
+        }
+        let len = download.started()?;
+        let stream = Stream::new(Box::new(download), len)?;
+        PREFETCHED.with(|x| x.borrow_mut().insert(path.to_path_buf(), stream));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_with_seeks_within_the_head() {
+        let log = b"[0.010s][info][gc] Using G1\n[0.012s][info][gc,init] Heap Region Size: 4M\n";
+        let mut stream = Stream::new(Box::new(io::Cursor::new(log.to_vec())), Some(log.len() as u64)).unwrap();
+        assert_eq!(stream.head(), &log[..encoding::HEAD_LEN]);
+        let mut magic = [0; 4];
+        stream.read_exact(&mut magic).unwrap();
+        stream.seek(SeekFrom::Current(-4)).unwrap();
+        let mut contents = Vec::new();
+        stream.read_to_end(&mut contents).unwrap();
+        assert_eq!(contents, log);
+        assert!(stream.seek(SeekFrom::Start(0)).is_err());
+        assert!(stream.seek(SeekFrom::End(0)).is_err());
+    }
+
+    fn prefetched(download: impl FnOnce(SyncSender<Chunk>) + Send + 'static) -> Prefetched {
+        let (sender, chunks) = mpsc::sync_channel(CHUNKS);
+        let download = thread::spawn(move || download(sender));
+        Prefetched { chunks, chunk: io::Cursor::new(Vec::new()), download: Some(download), done: false }
+    }
+
+    #[test]
+    fn reads_the_chunks_of_a_download() {
+        let mut download = prefetched(|chunks| {
+            chunks.send(Chunk::Started(Some(13))).unwrap();
+            chunks.send(Chunk::Data(b"first\n".to_vec())).unwrap();
+            chunks.send(Chunk::Data(b"second\n".to_vec())).unwrap();
+            chunks.send(Chunk::End).unwrap();
+        });
+        assert_eq!(download.started().unwrap(), Some(13));
+        let mut contents = String::new();
+        download.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "first\nsecond\n");
+    }
+
+    #[test]
+    fn fails_a_download_whose_thread_panicked() {
+        let mut download = prefetched(|chunks| {
+            chunks.send(Chunk::Started(None)).unwrap();
+            chunks.send(Chunk::Data(b"first\n".to_vec())).unwrap();
+            panic!("connection reset");
+        });
+        assert_eq!(download.started().unwrap(), None);
+        let error = download.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.to_string(), "the download thread panicked");
+    }
+
+    #[test]
+    fn fails_a_download_without_a_response() {
+        let mut download = prefetched(|chunks| chunks.send(Chunk::Failed(io::Error::other("connection refused"))).unwrap());
+        assert_eq!(download.started().unwrap_err().to_string(), "connection refused");
+    }
+}
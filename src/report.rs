@@ -0,0 +1,808 @@
+use cli_table::{format::Justify, Cell, CellStruct, ColorChoice, Style, Table, TableStruct};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::Path;
+
+use crate::allocation_rate::AllocationRate;
+use crate::bins::{display_size, SizeBins};
+use crate::buckets::{self, G1RegionBucket};
+use crate::bursts::BurstRow;
+use crate::calendar::CalendarWindows;
+use crate::heatmap::Heatmap;
+use crate::clusters::SizeClusters;
+use crate::cms::CmsFailures;
+use crate::dump;
+use crate::concurrent_cycle::{self, CycleAttribution, CycleDurations};
+use crate::eager_reclaim::EagerReclaim;
+use crate::file_summary::FileSummary;
+use crate::gc_cause::GcCauses;
+use crate::gc_cycles::AllocationsPerCycle;
+use crate::heap_occupancy::HeapOccupancyRow;
+use crate::heap_resize::HeapResizes;
+use crate::heap_share::HeapShare;
+use crate::heap_sizing;
+use crate::humongous_trace::HumongousTrace;
+use crate::ihop::IhopAnalysis;
+use crate::full_gc::FullGcRow;
+use crate::interarrival::InterArrival;
+use crate::mixed_gc::MixedCollections;
+use crate::numbers::grouped;
+use crate::parse_quality::ParseQuality;
+use crate::pause_impact::PauseImpact;
+use crate::recommendation::{Recommendation, RegionSizeCandidates, G1_REGION_SIZES, MAX_PRE_JDK18_REGION_SIZE};
+use crate::sampling::Sampling;
+use crate::openj9::OpenJ9Allocations;
+use crate::pager::{self, Pager};
+use crate::shenandoah::ShenandoahAllocations;
+use crate::size_histogram::SizeHistogram;
+use crate::size_stats::SizeStats;
+use crate::state::Cumulative;
+use crate::safepoint::{self, SafepointRow};
+use crate::timeline::TimelineRow;
+use crate::validate::{Capabilities, CapabilityRow};
+use crate::waste::HumongousWaste;
+use crate::zgc::ZgcPages;
+use crate::zing::ZingAllocations;
+use crate::{Cli, Collector, HumongousAllocation, RegionSize};
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+pub enum OutputFormat {
+    Text,
+    // Self-contained document with inline SVG charts
+    Html,
+    // GitHub flavored markdown tables for pasting into tickets and wikis
+    Markdown,
+    // Totals, buckets, percentiles and the recommendation of each report for other tools
+    Json,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+pub enum GroupBy {
+    // Everything in a single report
+    None,
+    File,
+    RegionSize,
+}
+
+// Aggregated results across the gc logs of one group
+pub struct Report {
+    // None when everything is in a single report
+    pub title: Option<String>,
+    pub allocs_histogram: SizeHistogram,
+    pub size_stats: SizeStats,
+    pub inter_arrival: InterArrival,
+    pub region_size_array: Vec<G1RegionBucket>,
+    pub size_bins: SizeBins,
+    pub size_clusters: SizeClusters,
+    pub waste: HumongousWaste,
+    pub full_gc_rows: Vec<FullGcRow>,
+    pub evacuation_failures: usize,
+    pub timeline_rows: Vec<TimelineRow>,
+    pub heap_occupancy_rows: Vec<HeapOccupancyRow>,
+    pub safepoint_rows: Vec<SafepointRow>,
+    pub calendar: CalendarWindows,
+    pub heatmap: Heatmap,
+    pub burst_rows: Vec<BurstRow>,
+    pub gc_causes: GcCauses,
+    pub allocations_per_cycle: AllocationsPerCycle,
+    pub pause_impact: PauseImpact,
+    pub concurrent_cycles: CycleAttribution,
+    pub cycle_durations: CycleDurations,
+    pub ihop: IhopAnalysis,
+    pub allocation_rate: AllocationRate,
+    pub heap_resizes: HeapResizes,
+    pub eager_reclaim: EagerReclaim,
+    pub mixed_collections: MixedCollections,
+    pub humongous_trace: HumongousTrace,
+    pub cms_failures: CmsFailures,
+    pub zgc_pages: ZgcPages,
+    pub shenandoah: ShenandoahAllocations,
+    pub openj9: OpenJ9Allocations,
+    pub zing: ZingAllocations,
+    pub file_summaries: Vec<FileSummary>,
+    pub candidates: RegionSizeCandidates,
+    // Every allocation of each file, only kept for the outputs that need them, see Cli::retains_allocations
+    pub retained: Vec<FileAllocations>,
+    // Totals of every --state run so far
+    pub cumulative: Option<Cumulative>,
+    // With --sample
+    pub sampling: Option<Sampling>,
+}
+
+pub struct FileAllocations {
+    pub name: String,
+    // The --input label of the file
+    pub label: Option<String>,
+    pub allocations: Vec<HumongousAllocation>,
+}
+
+// Stdout (through the pager when it's a terminal) unless --output was given, in which case the file is created (along with any missing
+// parent directories) and an existing file is only replaced when --force is set
+pub fn open_output(args: &Cli) -> io::Result<Box<dyn Write>> {
+    match &args.output {
+        // stdout is left to the events
+        None if args.events.is_some() => Ok(Box::new(io::sink())),
+        None if pager::wanted(args) => Ok(Box::<Pager>::default()),
+        None => Ok(Box::new(io::stdout().lock())),
+        Some(path) => {
+            // Watch mode keeps adding to the same report
+            if args.watch.is_some() {
+                return Ok(Box::new(OpenOptions::new().create(true).append(true).open(path)?));
+            }
+            if path.exists() && !args.force {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists, use --force to overwrite it", path.display())));
+            }
+            if let Some(parent) = path.parent().filter(|x| !x.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            Ok(Box::new(BufWriter::new(File::create(path)?)))
+        }
+    }
+}
+
+// Files whose region size could not be determined are grouped together
+pub fn group_title(group_by: GroupBy, file: &Path, region_size: Option<&RegionSize>) -> Option<String> {
+    match group_by {
+        GroupBy::None => None,
+        GroupBy::File => Some(file.display().to_string()),
+        GroupBy::RegionSize => Some(region_size.map_or("Unknown region size".to_string(), |x| format!("{}MB region size", x.mb))),
+    }
+}
+
+// How tables are printed: in color when stdout is a terminal, and as key: value lines once wider than the
+// terminal. Reports written to a file or piped into another program are not colored
+#[derive(Clone, Copy)]
+pub struct TableStyle {
+    color: ColorChoice,
+    width: Option<usize>,
+}
+
+pub fn table_style(args: &Cli) -> TableStyle {
+    let no_color = args.no_color || env::var_os("NO_COLOR").is_some_and(|x| !x.is_empty());
+    match args.output {
+        Some(_) => TableStyle { color: ColorChoice::Never, width: None },
+        None if no_color || !io::stdout().is_terminal() => TableStyle { color: ColorChoice::Never, width: terminal_width() },
+        None => TableStyle { color: ColorChoice::Always, width: terminal_width() },
+    }
+}
+
+impl TableStyle {
+    // Text outside of tables in the given color, when tables are in color
+    pub fn paint(&self, text: String, color: console::Color) -> String {
+        match self.color {
+            ColorChoice::Never => text,
+            _ => console::Style::new().fg(color).force_styling(true).apply_to(text).to_string(),
+        }
+    }
+}
+
+// $COLUMNS when set, e.g. for CI logs that aren't terminals, otherwise the width of the terminal on stdout
+fn terminal_width() -> Option<usize> {
+    env::var("COLUMNS").ok().and_then(|x| x.parse().ok()).or_else(|| console::Term::stdout().size_checked().map(|x| x.1 as usize))
+}
+
+// The recommend subcommand, only the recommendation and candidate region sizes of each report
+pub fn write_recommendations(reports: &[Report], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    if let OutputFormat::Json = args.output_format {
+        return crate::json::write_recommendations(reports, out);
+    }
+    for report in reports {
+        if let Some(title) = &report.title {
+            writeln!(out, "\n=== {} ===", title)?;
+        }
+        report.write_region_sizes(out)?;
+        if report.candidates.total > 0 {
+            report.write_recommendation(out, table_style(args))?;
+            if let Some(snippet) = heap_sizing::flags_snippet(report) {
+                writeln!(out, "\nJVM Flags:\n{}", snippet)?;
+            }
+        } else {
+            writeln!(out, "\nNo humongous allocations were identified in the provided data set.")?;
+        }
+    }
+    Ok(())
+}
+
+// The recommend subcommand's --write-flags, the snippet of each report under its title
+pub fn write_flags_snippets(reports: &[Report], out: &mut dyn Write) -> io::Result<()> {
+    for report in reports {
+        let Some(snippet) = heap_sizing::flags_snippet(report) else { continue };
+        if let Some(title) = &report.title {
+            writeln!(out, "# === {} ===", title)?;
+        }
+        writeln!(out, "{}", snippet)?;
+    }
+    out.flush()
+}
+
+// --dump, every allocation of every report in a single table
+pub fn write_dump(reports: &[Report], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    let rows = dump::rows(reports.iter().flat_map(|x| &x.retained), args.dump_order);
+    write_rows(out, &rows, table_style(args))?;
+    out.flush()
+}
+
+// The validate subcommand, what each file holds and the flags that would fill in the rest
+pub fn write_capabilities(capabilities: &[Capabilities], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    if let OutputFormat::Json = args.output_format {
+        return crate::json::write_capabilities(capabilities, out, args.collector);
+    }
+    let rows: Vec<CapabilityRow> = capabilities.iter().map(|x| x.row()).collect();
+    write_rows(out, &rows, table_style(args))?;
+    let suggestions: Vec<(&str, String)> = capabilities.iter().flat_map(|x| x.suggestions(args.collector).into_iter().map(|suggestion| (x.file.as_str(), suggestion))).collect();
+    if !suggestions.is_empty() {
+        writeln!(out, "\nSuggestions:")?;
+        for (file, suggestion) in suggestions {
+            writeln!(out, "\t{}: {}", file, suggestion)?;
+        }
+    }
+    out.flush()
+}
+
+pub fn write_reports(reports: &[Report], out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+    if let Some(metric) = args.print {
+        return crate::print_metric::write_metric(reports, out, metric);
+    }
+    match args.output_format {
+        OutputFormat::Text => {
+            if crate::groups::is_fleet(reports, args) {
+                writeln!(out, "\n=== Groups ===")?;
+                write_rows(out, &crate::groups::rows(reports, args.raw_bytes), table_style(args))?;
+            }
+            for report in reports {
+                if let Some(title) = &report.title {
+                    writeln!(out, "\n=== {} ===", title)?;
+                }
+                report.write_text(out, args)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Html => crate::html::write_html(reports, out, args),
+        OutputFormat::Json => crate::json::write_json(reports, out, args),
+        OutputFormat::Markdown => {
+            if crate::groups::is_fleet(reports, args) {
+                crate::markdown::write_groups(reports, out, args)?;
+            }
+            for report in reports {
+                crate::markdown::write_markdown(report, out, args)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+// "1h" style description of a --interval
+pub fn describe_interval(seconds: u64) -> String {
+    match seconds {
+        x if x % 86400 == 0 => format!("{}d", x / 86400),
+        x if x % 3600 == 0 => format!("{}h", x / 3600),
+        x if x % 60 == 0 => format!("{}m", x / 60),
+        x => format!("{}s", x),
+    }
+}
+
+// A row of a text table: the title and justification of each column and the text of each cell
+pub trait Record {
+    const COLUMNS: &'static [(&'static str, Justify)];
+
+    fn cells(&self) -> Vec<String>;
+}
+
+// The bold title row of the columns
+pub fn title_row(columns: &[(&str, Justify)]) -> Vec<CellStruct> {
+    columns.iter().map(|x| x.0.cell().bold(true)).collect()
+}
+
+// The text of a table's cells, what it is written as once wider than the terminal
+pub struct Records {
+    pub titles: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Records {
+    pub fn of<T: Record>(rows: &[T]) -> Records {
+        Records { titles: T::COLUMNS.iter().map(|x| x.0.to_string()).collect(), rows: rows.iter().map(Record::cells).collect() }
+    }
+}
+
+pub fn write_rows<T: Record>(out: &mut dyn Write, rows: &[T], style: TableStyle) -> io::Result<()> {
+    let records = Records::of(rows);
+    let cells: Vec<Vec<CellStruct>> = records.rows.iter().map(|row| row.iter().zip(T::COLUMNS).map(|(cell, column)| cell.cell().justify(column.1)).collect()).collect();
+    write_table(out, cells.table().title(title_row(T::COLUMNS)), records, style)
+}
+
+pub fn write_table(out: &mut dyn Write, table: TableStruct, records: Records, style: TableStyle) -> io::Result<()> {
+    let table = table.color_choice(ColorChoice::Never);
+    let plain = table.display()?.to_string();
+    match style.width {
+        Some(width) if plain.lines().any(|x| x.chars().count() > width) => write_records(out, records),
+        _ => write!(out, "{}", table.color_choice(style.color).display()?),
+    }
+}
+
+// A table too wide for the terminal as a block of "title: cell" lines per row, the lines of a multi-line cell
+// joined with "; "
+fn write_records(out: &mut dyn Write, records: Records) -> io::Result<()> {
+    for (index, row) in records.rows.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        for (title, cell) in records.titles.iter().zip(row) {
+            writeln!(out, "\t{}: {}", title, cell.lines().collect::<Vec<_>>().join("; "))?;
+        }
+    }
+    Ok(())
+}
+
+impl Report {
+    pub fn new(region_sizes: &[u64], precision: Option<u8>) -> Report {
+        Report {
+            title: None,
+            allocs_histogram: SizeHistogram::new(precision),
+            size_stats: SizeStats::default(),
+            inter_arrival: InterArrival::default(),
+            region_size_array: crate::buckets::build_buckets(region_sizes),
+            size_bins: SizeBins::default(),
+            size_clusters: SizeClusters::default(),
+            waste: HumongousWaste::default(),
+            full_gc_rows: Vec::new(),
+            evacuation_failures: 0,
+            timeline_rows: Vec::new(),
+            heap_occupancy_rows: Vec::new(),
+            safepoint_rows: Vec::new(),
+            calendar: CalendarWindows::default(),
+            heatmap: Heatmap::default(),
+            burst_rows: Vec::new(),
+            gc_causes: GcCauses::default(),
+            allocations_per_cycle: AllocationsPerCycle::default(),
+            pause_impact: PauseImpact::default(),
+            concurrent_cycles: CycleAttribution::default(),
+            cycle_durations: CycleDurations::default(),
+            ihop: IhopAnalysis::default(),
+            allocation_rate: AllocationRate::default(),
+            heap_resizes: HeapResizes::default(),
+            eager_reclaim: EagerReclaim::default(),
+            mixed_collections: MixedCollections::default(),
+            humongous_trace: HumongousTrace::default(),
+            cms_failures: CmsFailures::default(),
+            zgc_pages: ZgcPages::default(),
+            shenandoah: ShenandoahAllocations::default(),
+            openj9: OpenJ9Allocations::default(),
+            zing: ZingAllocations::default(),
+            file_summaries: Vec::new(),
+            candidates: RegionSizeCandidates::default(),
+            retained: Vec::new(),
+            cumulative: None,
+            sampling: None,
+        }
+    }
+
+    // Adds what the per-line analyzers found in a later chunk of the same file, see FileScan::scan_chunks
+    pub fn merge_chunk(&mut self, chunk: Report) {
+        self.evacuation_failures += chunk.evacuation_failures;
+        self.gc_causes.merge(chunk.gc_causes);
+        self.concurrent_cycles.merge(chunk.concurrent_cycles);
+        self.ihop.merge(chunk.ihop);
+        self.eager_reclaim.merge(chunk.eager_reclaim);
+        self.mixed_collections.merge(chunk.mixed_collections);
+        self.humongous_trace.merge(chunk.humongous_trace);
+        self.cms_failures.merge(chunk.cms_failures);
+        self.zgc_pages.merge(chunk.zgc_pages);
+        self.zing.merge(chunk.zing);
+        self.heap_resizes.merge(chunk.heap_resizes);
+    }
+
+    // "Size filter: 8MB to 64MB (120 allocations excluded)" when --min-size or --max-size is set
+    pub fn size_filter(&self, args: &Cli) -> Option<String> {
+        let excluded: u64 = self.file_summaries.iter().map(|x| x.filtered_allocations).sum();
+        args.size_filter().map(|x| format!("Size filter: {} ({} allocations excluded)", x, excluded))
+    }
+
+    // The bytes read from each file this run, then the totals of every --state run so far
+    pub fn cumulative_stats(&self, cumulative: &Cumulative, args: &Cli) -> Vec<(String, String)> {
+        let mut stats: Vec<(String, String)> = self
+            .file_summaries
+            .iter()
+            .filter_map(|x| x.read_range.map(|(start, end)| (x.file.clone(), format!("read bytes {} to {} in this run", start, end))))
+            .collect();
+        let new: u64 = self.file_summaries.iter().map(|x| x.allocations).sum();
+        stats.push(("allocations".to_string(), format!("{} ({} in this run)", grouped(&cumulative.allocations()), grouped(&new))));
+        stats.push(("allocated bytes".to_string(), display_size(cumulative.allocated_bytes(), args.raw_bytes)));
+        stats.extend(cumulative.percentiles(&args.percentiles).into_iter().map(|(label, value)| (label, display_size(value, args.raw_bytes))));
+        stats
+    }
+
+    // Region sizes only matter to the collectors that have regions
+    pub fn parse_quality(&self, args: &Cli) -> ParseQuality {
+        ParseQuality::new(&self.file_summaries, matches!(args.collector, Collector::G1 | Collector::Shenandoah))
+    }
+
+    // The largest heap of the files, they normally share their configuration
+    pub fn max_heap(&self) -> Option<u64> {
+        self.file_summaries.iter().filter_map(|x| x.max_heap).max()
+    }
+
+    // Region sizes above 32MB are left out when a log is known to be of a release before JDK 18
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        let pre_jdk18 = self.file_summaries.iter().any(|x| x.jdk_version.is_some_and(|x| x < 18));
+        self.candidates.recommend(if pre_jdk18 { MAX_PRE_JDK18_REGION_SIZE } else { *G1_REGION_SIZES.last().unwrap() })
+    }
+
+    // Whether every log is known to be of JDK 18 or later, which take region sizes above 32MB
+    pub fn on_jdk18(&self) -> bool {
+        !self.file_summaries.is_empty() && self.file_summaries.iter().all(|x| x.jdk_version.is_some_and(|x| x >= 18))
+    }
+
+    // min, the requested percentiles and max of the allocation sizes, empty when nothing was recorded
+    pub fn percentiles(&self, percentiles: &[f64]) -> Vec<(String, u64)> {
+        self.allocs_histogram.percentiles(percentiles)
+    }
+
+    fn write_size_stats(&self, out: &mut dyn Write, kind: &str, raw_bytes: bool) -> io::Result<()> {
+        writeln!(out, "\n{} Statistics:", kind)?;
+        for (label, value) in self.size_stats.stats(raw_bytes) {
+            writeln!(out, "\t{}: {}", label, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_region_sizes(&self, out: &mut dyn Write) -> io::Result<()> {
+        for summary in &self.file_summaries {
+            if let Some(region_size) = &summary.region_size {
+                writeln!(out, "Region Size: {} - {:?}", region_size.describe(), summary.name())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_recommendation(&self, out: &mut dyn Write, style: TableStyle) -> io::Result<()> {
+        if let Some(recommendation) = self.recommendation() {
+            writeln!(out, "\nRecommendation:\n\t{}", recommendation.summary())?;
+            if recommendation.requires_jdk18() {
+                writeln!(out, "\tRegion sizes above 32MB require JDK 18 or newer")?;
+            }
+        }
+        let suggestions = heap_sizing::suggestions(self);
+        if !suggestions.is_empty() {
+            writeln!(out, "\nHeap Sizing:")?;
+            for suggestion in suggestions {
+                writeln!(out, "\t{}", suggestion)?;
+            }
+        }
+        writeln!(out, "\nCandidate Region Sizes:")?;
+        write_rows(out, &self.candidates.what_if(self.max_heap()), style)
+    }
+
+    pub fn write_text(&self, out: &mut dyn Write, args: &Cli) -> io::Result<()> {
+        let style = table_style(args);
+        self.write_region_sizes(out)?;
+
+        if let Some(size_filter) = self.size_filter(args) {
+            writeln!(out, "{}", size_filter)?;
+        }
+
+        if let Some(sampling) = &self.sampling {
+            writeln!(out, "\nSampling (counts are of the lines read, percentages and percentiles estimate the whole):")?;
+            for (label, value) in sampling.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        for summary in self.file_summaries.iter().filter(|x| !x.jvm_configuration.is_empty()) {
+            writeln!(out, "\nJVM Configuration ({}):", summary.name())?;
+            for (label, value) in &summary.jvm_configuration {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        if let Collector::Cms = args.collector {
+            writeln!(out, "\nCMS Promotion Failures:")?;
+            write_rows(out, &self.cms_failures.rows(), style)?;
+            if self.cms_failures.sized_objects > 0 {
+                self.write_size_stats(out, "Promotion Failure Object Size", args.raw_bytes)?;
+                writeln!(out, "\nPromotion Failure Object Size Percentiles:")?;
+                for (label, value) in self.percentiles(&args.percentiles) {
+                    writeln!(out, "\t{}: {}", label, display_size(value, args.raw_bytes))?;
+                }
+            }
+        } else if let Collector::Zgc = args.collector {
+            writeln!(out, "\nZGC Pages per Collection ({} collections):", grouped(&self.zgc_pages.collections()))?;
+            write_rows(out, &self.zgc_pages.rows(), style)?;
+            if self.zgc_pages.large_pages() > 0 {
+                self.write_size_stats(out, "Large Page Size", args.raw_bytes)?;
+                writeln!(out, "\nLarge Page Size Percentiles:")?;
+                for (label, value) in self.percentiles(&args.percentiles) {
+                    writeln!(out, "\t{}: {}", label, display_size(value, args.raw_bytes))?;
+                }
+            }
+        } else if let Collector::Shenandoah = args.collector {
+            writeln!(out, "\nShenandoah Allocation Failures:")?;
+            for (label, value) in self.shenandoah.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if !self.shenandoah.humongous_sizes.is_empty() {
+                write_rows(out, &self.shenandoah.rows(), style)?;
+                self.write_size_stats(out, "Failed Humongous Allocation Size", args.raw_bytes)?;
+                writeln!(out, "\nFailed Humongous Allocation Size Percentiles:")?;
+                for (label, value) in self.percentiles(&args.percentiles) {
+                    writeln!(out, "\t{}: {}", label, display_size(value, args.raw_bytes))?;
+                }
+            }
+        } else if let Collector::OpenJ9 = args.collector {
+            writeln!(out, "\nOpenJ9 Allocation Failures:")?;
+            for (label, value) in self.openj9.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if self.openj9.tenure_failures > 0 {
+                self.write_size_stats(out, "Tenure Allocation Failure Size", args.raw_bytes)?;
+                writeln!(out, "\nTenure Allocation Failure Size Percentiles:")?;
+                for (label, value) in self.percentiles(&args.percentiles) {
+                    writeln!(out, "\t{}: {}", label, display_size(value, args.raw_bytes))?;
+                }
+            }
+        } else if let Collector::Zing = args.collector {
+            writeln!(out, "\nZing Large Object Allocations:")?;
+            for (label, value) in self.zing.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if self.zing.large_allocations > 0 {
+                self.write_size_stats(out, "Large Object Size", args.raw_bytes)?;
+                writeln!(out, "\nLarge Object Size Percentiles:")?;
+                for (label, value) in self.percentiles(&args.percentiles) {
+                    writeln!(out, "\t{}: {}", label, display_size(value, args.raw_bytes))?;
+                }
+            }
+        } else if self.region_size_array.iter().map(|x| x.num_allocations).sum::<u32>() > 0 {
+            let recommended = self.recommendation().map(|x| x.region_size);
+            let (table, records) = buckets::table(&self.region_size_array, args.raw_bytes, args.highlight_count, recommended);
+            write_table(out, table, records, style)?;
+            self.write_size_stats(out, "Allocation Size", args.raw_bytes)?;
+            writeln!(out, "\nAllocation Size Percentiles:")?;
+            for (label, value) in self.percentiles(&args.percentiles) {
+                // Red when still humongous with the recommended region size, green when it fits
+                let value = match recommended {
+                    Some(region_size) if value > region_size / 2 => style.paint(display_size(value, args.raw_bytes), console::Color::Red),
+                    Some(_) => style.paint(display_size(value, args.raw_bytes), console::Color::Green),
+                    None => display_size(value, args.raw_bytes),
+                };
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            self.write_recommendation(out, style)?;
+        } else {
+            writeln!(out, "\nNo humongous allocations were identified in the provided data set.")?;
+        }
+
+        if args.top_sizes > 0 && !self.size_clusters.is_empty() {
+            writeln!(out, "\nMost Common Allocation Sizes:")?;
+            write_rows(out, &self.size_clusters.rows(args.size_tolerance, args.top_sizes, args.raw_bytes), style)?;
+        }
+
+        if let (Some(top), false) = (args.size_frequency, self.size_clusters.is_empty()) {
+            writeln!(out, "\nAllocation Size Frequency:")?;
+            write_rows(out, &self.size_clusters.frequency_rows(top), style)?;
+        }
+
+        if let (Some(width), false) = (args.bins, self.size_bins.is_empty()) {
+            writeln!(out, "\nAllocation Sizes ({} bins):", crate::bins::format_size(width))?;
+            write_rows(out, &self.size_bins.rows(width, args.bins_max), style)?;
+        }
+
+        if self.waste.objects > 0 {
+            writeln!(out, "\nHumongous Region Waste:")?;
+            for (label, value) in self.waste.stats(args.raw_bytes) {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        if let Some(heap_share) = HeapShare::new(self) {
+            writeln!(out, "\nHumongous Share of Heap:")?;
+            for (label, value) in heap_share.stats(args.raw_bytes) {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        if !self.full_gc_rows.is_empty() {
+            let attributed = self.full_gc_rows.iter().filter(|x| x.preceding_allocations > 0).count();
+            writeln!(out, "\nFull GC Events: {} ({} preceded by humongous allocations within {}s)", grouped(&self.full_gc_rows.len()), grouped(&attributed), args.full_gc_window)?;
+            write_rows(out, &self.full_gc_rows, style)?;
+        }
+
+        if self.evacuation_failures > 0 || !self.heap_resizes.is_empty() {
+            writeln!(out)?;
+            if self.evacuation_failures > 0 {
+                writeln!(out, "To-space Exhausted Events: {}", grouped(&self.evacuation_failures))?;
+            }
+            if !self.heap_resizes.is_empty() {
+                writeln!(out, "Heap Resizes: {}", self.heap_resizes.summary())?;
+                if let Some(hint) = self.heap_resizes.hint() {
+                    writeln!(out, "\t{}", hint)?;
+                }
+            }
+            // None with --bounded-memory
+            if !self.timeline_rows.is_empty() {
+                writeln!(out, "Timeline ({}s windows):", args.timeline_interval)?;
+                write_rows(out, &self.timeline_rows, style)?;
+            }
+        }
+
+        if let Some(interval) = args.interval {
+            writeln!(out, "\nHumongous Allocations per {}:", describe_interval(interval))?;
+            if self.calendar.is_empty() {
+                writeln!(out, "\tNo datestamps found, add -XX:+PrintGCDateStamps (JDK 8) or the time decoration (JDK 9+)")?;
+            } else {
+                write_rows(out, &self.calendar.rows(), style)?;
+                if self.calendar.undated > 0 {
+                    writeln!(out, "\n{} allocations without a datestamp are not included", grouped(&self.calendar.undated))?;
+                }
+            }
+        }
+
+        if args.heatmap {
+            writeln!(out, "\nHumongous Allocations per Hour of the Day and Region Size:")?;
+            if self.heatmap.is_empty() {
+                writeln!(out, "\tNo datestamps found, add -XX:+PrintGCDateStamps (JDK 8) or the time decoration (JDK 9+)")?;
+            } else {
+                let (table, records) = self.heatmap.table(&self.region_size_array);
+                write_table(out, table, records, style)?;
+                if self.heatmap.undated > 0 {
+                    writeln!(out, "\n{} allocations without a datestamp are not included", grouped(&self.heatmap.undated))?;
+                }
+            }
+        }
+
+        if !self.burst_rows.is_empty() {
+            writeln!(out, "\nAllocation Bursts (over {}x the average per {}s window):", args.burst_factor, args.timeline_interval)?;
+            write_rows(out, &self.burst_rows, style)?;
+        }
+
+        if !self.inter_arrival.is_empty() {
+            writeln!(out, "\nTime Between Humongous Allocations:")?;
+            for (label, value) in self.inter_arrival.stats(&args.percentiles) {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        if !self.heap_occupancy_rows.is_empty() {
+            writeln!(out, "\nHeap Occupancy ({}s windows):", args.timeline_interval)?;
+            write_rows(out, &self.heap_occupancy_rows, style)?;
+        }
+
+        if !self.safepoint_rows.is_empty() {
+            let (spikes, during_bursts) = safepoint::spikes(&self.safepoint_rows);
+            writeln!(out, "\nSafepoints ({}s windows): {} time to safepoint spikes over {}x the average, {} during allocation bursts", args.timeline_interval, grouped(&spikes), args.burst_factor, grouped(&during_bursts))?;
+            write_rows(out, &self.safepoint_rows, style)?;
+        }
+
+        if !self.gc_causes.causes.is_empty() {
+            writeln!(out, "\nGC Causes: {} collections ({} forced by humongous allocation)", grouped(&self.gc_causes.total()), grouped(&self.gc_causes.humongous()))?;
+            if let Some(share) = self.gc_causes.humongous_young_share() {
+                writeln!(out, "\tyoung pauses triggered by humongous allocation: {}", share)?;
+            }
+            if let Some(share) = self.gc_causes.humongous_young_time_share() {
+                writeln!(out, "\tyoung pause time of those triggered by humongous allocation: {}", share)?;
+            }
+            write_rows(out, &self.gc_causes.rows(), style)?;
+        }
+
+        if !self.allocations_per_cycle.is_empty() {
+            writeln!(out, "\nHumongous Allocations per GC Cycle:")?;
+            for (label, value) in self.allocations_per_cycle.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            write_rows(out, &self.allocations_per_cycle.rows(), style)?;
+        }
+
+        if !self.pause_impact.is_empty() {
+            writeln!(out, "\nPause Impact of Humongous Allocations:")?;
+            for (label, value) in self.pause_impact.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            write_rows(out, &self.pause_impact.rows(), style)?;
+        }
+
+        if !self.concurrent_cycles.triggers.is_empty() {
+            let total: u64 = self.concurrent_cycles.triggers.values().sum();
+            let humongous = self.concurrent_cycles.triggers.get(concurrent_cycle::HUMONGOUS_TRIGGER).copied().unwrap_or(0);
+            writeln!(out, "\nConcurrent Cycles: {} ({} initiated by humongous allocation)", grouped(&total), grouped(&humongous))?;
+            write_rows(out, &self.concurrent_cycles.rows(), style)?;
+        }
+
+        if self.cycle_durations.cycles() > 0 {
+            writeln!(out, "\nConcurrent Cycle Durations ({} cycles):", grouped(&self.cycle_durations.cycles()))?;
+            for (label, value) in self.cycle_durations.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if let Some(hint) = self.cycle_durations.hint() {
+                writeln!(out, "\t{}", hint)?;
+            }
+        }
+
+        if self.ihop.requests > 0 || self.ihop.adaptive_updates > 0 {
+            writeln!(out, "\nMarking Threshold (IHOP):")?;
+            for (label, value) in self.ihop.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if let Some(hint) = self.ihop.hint() {
+                writeln!(out, "\t{}", hint)?;
+            }
+        }
+
+        if self.allocation_rate.collections > 0 {
+            writeln!(out, "\nAllocation Rate:")?;
+            for (label, value) in self.allocation_rate.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if let Some(hint) = self.allocation_rate.hint() {
+                writeln!(out, "\t{}", hint)?;
+            }
+        }
+
+        if self.eager_reclaim.collections > 0 {
+            writeln!(out, "\nHumongous Eager Reclaim ({} collections):", grouped(&self.eager_reclaim.collections))?;
+            for (label, value) in self.eager_reclaim.stats() {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        if self.mixed_collections.collections > 0 {
+            writeln!(out, "\nMixed Collections:")?;
+            for (label, value) in self.mixed_collections.stats(self.size_stats.total_bytes) {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+            if let Some(hint) = self.mixed_collections.hint(self.size_stats.total_bytes) {
+                writeln!(out, "\t{}", hint)?;
+            }
+        }
+
+        if self.humongous_trace.objects > 0 {
+            writeln!(out, "\nHumongous Region Trace: {}", self.humongous_trace.summary())?;
+            write_rows(out, &self.humongous_trace.rows(), style)?;
+        }
+
+        if let Some(cumulative) = &self.cumulative {
+            writeln!(out, "\nCumulative Totals ({} runs):", grouped(&cumulative.runs))?;
+            for (label, value) in self.cumulative_stats(cumulative, args) {
+                writeln!(out, "\t{}: {}", label, value)?;
+            }
+        }
+
+        writeln!(out, "\nParse Quality:")?;
+        for (label, value) in self.parse_quality(args).stats() {
+            writeln!(out, "\t{}: {}", label, value)?;
+        }
+
+        if self.file_summaries.iter().any(|x| x.has_issues()) {
+            writeln!(out, "\nFile Summary:")?;
+            write_rows(out, &self.file_summaries.iter().map(|x| x.row()).collect::<Vec<_>>(), style)?;
+        }
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CauseRow {
+        cause: &'static str,
+        count: u64,
+    }
+
+    impl Record for CauseRow {
+        const COLUMNS: &'static [(&'static str, Justify)] = &[("Cause", Justify::Left), ("Count", Justify::Right)];
+
+        fn cells(&self) -> Vec<String> {
+            vec![self.cause.to_string(), grouped(&self.count)]
+        }
+    }
+
+    #[test]
+    fn writes_wide_tables_as_records_of_their_cells() {
+        let rows = [CauseRow { cause: "G1 Humongous Allocation | young", count: 1200 }, CauseRow { cause: "Metadata GC Threshold\nconcurrent", count: 3 }];
+        let mut out = Vec::new();
+        write_rows(&mut out, &rows, TableStyle { color: ColorChoice::Never, width: Some(20) }).unwrap();
+        let expected = "\tCause: G1 Humongous Allocation | young\n\tCount: 1,200\n\n\tCause: Metadata GC Threshold; concurrent\n\tCount: 3\n";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
@@ -0,0 +1,194 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::{archive, container, line_parsing, remote, timestamp};
+
+// Only the start of a file is searched for its first timestamp, past the header
+const FIRST_TIMESTAMP_SEARCH_LINES: usize = 1000;
+// and the end of it for its last one
+const LAST_TIMESTAMP_SEARCH_BYTES: u64 = 64 * 1024;
+
+// Datestamps are preferred since the uptime restarts along with the JVM. They are kept in milliseconds since the
+// epoch (UTC) so that files logged with different UTC offsets are ordered by when they were written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Timestamp {
+    Date(i64),
+    Uptime(f64),
+}
+
+impl Timestamp {
+    pub fn parse(line: &str) -> Option<Timestamp> {
+        match timestamp::parse_datestamp(line).and_then(timestamp::parse_wall_clock_millis) {
+            Some(millis) => Some(Timestamp::Date(millis)),
+            None => timestamp::parse_uptime(line).map(Timestamp::Uptime),
+        }
+    }
+}
+
+// Lines with and without datestamps can't be ordered against each other
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Timestamp) -> Option<Ordering> {
+        match (self, other) {
+            (Timestamp::Date(a), Timestamp::Date(b)) => a.partial_cmp(b),
+            (Timestamp::Uptime(a), Timestamp::Uptime(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+// The path without the rotation suffix, e.g. gc.log for gc.log.3 (unified logging) and gc.log.3.current (JDK 8)
+pub fn rotation_set(path: &Path) -> PathBuf {
+    let Some(name) = path.file_name().and_then(|x| x.to_str()) else { return path.to_path_buf() };
+    let name = name.strip_suffix(".current").unwrap_or(name);
+    let name = match name.rsplit_once('.') {
+        Some((base, index)) if !index.is_empty() && index.bytes().all(|x| x.is_ascii_digit()) => base,
+        _ => name,
+    };
+    path.with_file_name(name)
+}
+
+// Remote logs aren't downloaded just to be ordered, without timestamps they keep their command line place
+fn is_pending_download(path: &Path) -> bool {
+    remote::is_remote(path) && !archive::is_in_memory(path)
+}
+
+fn first_timestamp(path: &Path) -> Option<Timestamp> {
+    if is_pending_download(path) {
+        return None;
+    }
+    let reader = BufReader::new(archive::open(path).ok()?.0);
+    line_parsing::lossy_lines(reader).take(FIRST_TIMESTAMP_SEARCH_LINES).map_while(Result::ok).find_map(|x| Timestamp::parse(&container::strip(&x.0)))
+}
+
+fn last_timestamp(path: &Path) -> Option<Timestamp> {
+    if is_pending_download(path) {
+        return None;
+    }
+    let (mut file, length) = archive::open(path).ok()?;
+    file.seek(SeekFrom::Start(length.saturating_sub(LAST_TIMESTAMP_SEARCH_BYTES))).ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    String::from_utf8_lossy(&tail).lines().rev().find_map(|x| Timestamp::parse(&container::strip(x)))
+}
+
+// The datestamp, as uptimes of different JVMs can't be ordered against each other
+fn date(timestamp: &Option<Timestamp>) -> Option<i64> {
+    match timestamp {
+        Some(Timestamp::Date(millis)) => Some(*millis),
+        _ => None,
+    }
+}
+
+// The files of a rotation set are analyzed together, oldest first, and the sets by their earliest datestamp
+// so the time based sections see the files in order. Sets without datestamps keep their command line place.
+pub fn chronological(files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut sets = Vec::new();
+    let mut members: Vec<(usize, Option<Timestamp>, &PathBuf)> = files
+        .iter()
+        .map(|file| {
+            let set = rotation_set(file);
+            let index = sets.iter().position(|x| *x == set).unwrap_or_else(|| {
+                sets.push(set);
+                sets.len() - 1
+            });
+            (index, first_timestamp(file), file)
+        })
+        .collect();
+    let earliest: Vec<Option<i64>> = (0..sets.len()).map(|set| members.iter().filter(|x| x.0 == set).filter_map(|x| date(&x.1)).min()).collect();
+    let mut dated: Vec<usize> = (0..sets.len()).filter(|x| earliest[*x].is_some()).collect();
+    dated.sort_by_key(|x| earliest[*x]);
+    let mut dated = dated.into_iter();
+    let order: Vec<usize> = (0..sets.len()).map(|set| if earliest[set].is_some() { dated.next().unwrap_or(set) } else { set }).collect();
+    members.sort_by(|a, b| {
+        let position = |set| order.iter().position(|x| *x == set);
+        position(a.0).cmp(&position(b.0)).then_with(|| match (&a.1, &b.1) {
+            (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            _ => Ordering::Equal,
+        })
+    });
+    warn_overlaps(&members);
+    members.into_iter().map(|x| x.2.clone()).collect()
+}
+
+// Files of different rotation sets covering the same time, e.g. the same log passed twice under different
+// names or the logs of several JVMs, whose allocations are all counted
+fn warn_overlaps(members: &[(usize, Option<Timestamp>, &PathBuf)]) {
+    let mut previous: Option<(usize, &PathBuf, Timestamp)> = None;
+    for (set, first, file) in members {
+        let Some(first) = date(first) else { continue };
+        if let Some((_, previous_file, Timestamp::Date(last))) = previous.as_ref().filter(|x| x.0 != *set) {
+            if first < *last {
+                let (first, last) = (timestamp::format_wall_clock(first / 1000), timestamp::format_wall_clock(last / 1000));
+                log::warn!("{} starts at {}, before {} ends at {}, the allocations of both are counted", file.display(), first, previous_file.display(), last);
+            }
+        }
+        if let Some(last) = last_timestamp(file).filter(|x| matches!(x, Timestamp::Date(_))) {
+            previous = Some((*set, file, last));
+        }
+    }
+}
+
+// The time range covered by the files of a rotation set analyzed so far
+#[derive(Default)]
+pub struct RotationTracker {
+    covered: HashMap<PathBuf, (Timestamp, Timestamp)>,
+}
+
+// Skips the lines at the start of a rotated file that were already read at the end of the previous one
+pub struct OverlapFilter {
+    set: PathBuf,
+    previous: Option<(Timestamp, Timestamp)>,
+    skipping: bool,
+    done: bool,
+    first: Option<Timestamp>,
+    pub skipped_lines: u64,
+}
+
+impl RotationTracker {
+    pub fn filter(&self, file: &Path) -> OverlapFilter {
+        let set = rotation_set(file);
+        let previous = self.covered.get(&set).cloned();
+        OverlapFilter { set, previous, skipping: false, done: false, first: None, skipped_lines: 0 }
+    }
+
+    pub fn finish(&mut self, file: &Path, filter: OverlapFilter) {
+        if let (Some(first), Some(last)) = (filter.first, last_timestamp(file)) {
+            let first = match filter.previous {
+                Some((previous_first, _)) if first >= previous_first => previous_first,
+                _ => first,
+            };
+            self.covered.insert(filter.set, (first, last));
+        }
+    }
+}
+
+impl OverlapFilter {
+    // Whether the start of the file may repeat lines of an earlier file of its rotation
+    pub fn follows_previous(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    // Lines without a timestamp (headers, continuation lines) share the fate of the line before them
+    pub fn is_duplicate(&mut self, line: &str) -> bool {
+        if self.done {
+            return false;
+        }
+        if let Some(timestamp) = Timestamp::parse(line) {
+            if self.first.is_none() {
+                self.first = Some(timestamp.clone());
+            }
+            self.skipping = match &self.previous {
+                // A first timestamp before the start of the previous files means the JVM restarted, not an overlap
+                Some((first, last)) => self.first.as_ref().is_some_and(|x| x >= first) && timestamp <= *last,
+                None => false,
+            };
+            self.done = !self.skipping;
+        }
+        if self.skipping {
+            self.skipped_lines += 1;
+        }
+        self.skipping
+    }
+}
@@ -0,0 +1,152 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::{bursts, AllocationTime};
+
+// Times to safepoint below this go unnoticed by the application, however far above the average they are
+const MIN_SPIKE_MS: f64 = 1.0;
+
+pub struct SafepointRow {
+    pub file: String,
+    pub window_start: String,
+    pub safepoints: u64,
+    pub max_reaching: String,
+    pub stopped: String,
+    pub spikes: u64,
+    pub allocations: u64,
+    pub burst: &'static str,
+}
+
+impl Record for SafepointRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Window Start (s)", Justify::Right),
+        ("Safepoints", Justify::Right),
+        ("Max Time to Safepoint (ms)", Justify::Right),
+        ("Stopped (ms)", Justify::Right),
+        ("Spikes", Justify::Right),
+        ("Humongous Allocations", Justify::Right),
+        ("Allocation Burst", Justify::Left),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.file.clone(),
+            self.window_start.clone(),
+            grouped(&self.safepoints),
+            self.max_reaching.clone(),
+            self.stopped.clone(),
+            grouped(&self.spikes),
+            grouped(&self.allocations),
+            self.burst.to_string(),
+        ]
+    }
+}
+
+pub struct Safepoint {
+    pub uptime: f64,
+    // Time to safepoint, until every thread stopped
+    pub reaching_ms: f64,
+    // Time to safepoint included
+    pub stopped_ms: f64,
+}
+
+// "N seconds" or "N ns" following the label, in ms
+fn duration_after(line: &str, label: &str) -> Option<f64> {
+    let (_, rest) = line.split_once(label)?;
+    let mut words = rest.split_whitespace();
+    let value = words.next()?.parse::<f64>().ok()?;
+    match words.next()?.trim_end_matches(',') {
+        "seconds" => Some(value * 1000.0),
+        "ns" => Some(value / 1_000_000.0),
+        _ => None,
+    }
+}
+
+// -XX:+PrintSafepointStatistics, the times in ms of the op at the uptime it starts with
+// "9.963: G1IncCollectionPause             [     116          0              0    ]      [     0     0     0     0    12    ]  0"
+fn parse_statistics(line: &str) -> Option<(f64, f64)> {
+    let (op, rest) = line.split_once('[')?;
+    let (threads, rest) = rest.split_once(']')?;
+    let (times, _) = rest.trim_start().strip_prefix('[')?.split_once(']')?;
+    if !op.contains(": ") || threads.split_whitespace().any(|x| x.parse::<u64>().is_err()) {
+        return None;
+    }
+    let times = times.split_whitespace().map(|x| x.parse::<f64>().ok()).collect::<Option<Vec<_>>>()?;
+    let [_spin, _block, sync, cleanup, vmop] = times[..] else { return None };
+    Some((sync, sync + cleanup + vmop))
+}
+
+// JDK 8 -XX:+PrintGCApplicationStoppedTime and -Xlog:safepoint up to JDK 16:
+//   "Total time for which application threads were stopped: 0.0123456 seconds, Stopping threads took: 0.0001234 seconds"
+// -Xlog:safepoint from JDK 17:
+//   "Safepoint "G1CollectForAllocation", Time since last: 1015197405 ns, Reaching safepoint: 146745 ns, Cleanup: 4563 ns, At safepoint: 5183473 ns, Total: 5334781 ns"
+// or a row of -XX:+PrintSafepointStatistics
+pub fn parse_safepoint(line: &str, uptime: Option<f64>) -> Option<Safepoint> {
+    let (reaching_ms, stopped_ms) = if line.contains("Total time for which application threads were stopped: ") {
+        (duration_after(line, "Stopping threads took: ")?, duration_after(line, "were stopped: ")?)
+    } else if line.contains("Reaching safepoint: ") {
+        (duration_after(line, "Reaching safepoint: ")?, duration_after(line, " Total: ")?)
+    } else if !line.trim_start().starts_with('[') && line.contains(']') {
+        parse_statistics(line)?
+    } else {
+        return None;
+    };
+    Some(Safepoint { uptime: uptime?, reaching_ms, stopped_ms })
+}
+
+#[derive(Default)]
+struct Window {
+    safepoints: u64,
+    max_reaching: f64,
+    stopped: f64,
+    spikes: u64,
+    allocations: u64,
+}
+
+// The safepoints of a single file in fixed windows of `interval` seconds of uptime, next to the humongous
+// allocations of the same window and whether it's part of an allocation burst. A spike is a time to
+// safepoint over `factor` times the average of the file, the same factor bursts are found by. Windows
+// without a safepoint are omitted.
+pub fn build_safepoint_timeline(file: &str, safepoints: &[Safepoint], allocations: &[AllocationTime], interval: f64, factor: f64) -> Vec<SafepointRow> {
+    if safepoints.is_empty() {
+        return Vec::new();
+    }
+    let average = safepoints.iter().map(|x| x.reaching_ms).sum::<f64>() / safepoints.len() as f64;
+    let threshold = (average * factor).max(MIN_SPIKE_MS);
+    let mut windows: BTreeMap<u64, Window> = BTreeMap::new();
+    for safepoint in safepoints {
+        let window = windows.entry((safepoint.uptime / interval) as u64).or_default();
+        window.safepoints += 1;
+        window.max_reaching = window.max_reaching.max(safepoint.reaching_ms);
+        window.stopped += safepoint.stopped_ms;
+        window.spikes += u64::from(safepoint.reaching_ms > threshold);
+    }
+    for allocation in allocations {
+        if let Some(window) = windows.get_mut(&((allocation.uptime / interval) as u64)) {
+            window.allocations += 1;
+        }
+    }
+    let bursts = bursts::burst_windows(allocations, interval, factor);
+    windows
+        .into_iter()
+        .map(|(index, window)| SafepointRow {
+            file: file.to_string(),
+            window_start: format!("{:.0}", index as f64 * interval),
+            safepoints: window.safepoints,
+            max_reaching: format!("{:.3}", window.max_reaching),
+            stopped: format!("{:.3}", window.stopped),
+            spikes: window.spikes,
+            allocations: window.allocations,
+            burst: if bursts.iter().any(|x| x.contains(&index)) { "yes" } else { "" },
+        })
+        .collect()
+}
+
+// Spikes in time to safepoint, and how many of them fell within an allocation burst
+pub fn spikes(rows: &[SafepointRow]) -> (u64, u64) {
+    let during_bursts = rows.iter().filter(|x| !x.burst.is_empty()).map(|x| x.spikes).sum();
+    (rows.iter().map(|x| x.spikes).sum(), during_bursts)
+}
@@ -0,0 +1,86 @@
+use std::ops::Range;
+
+use crate::buckets::MB;
+use crate::numbers::grouped;
+
+// Blocks of a gc log read or skipped as a whole with --sample, small enough that even a sample of a few
+// hundred MB has plenty of them to estimate from
+pub const BLOCK_SIZE: u64 = 1024 * 1024;
+
+// Every 1/ratio-th block, spread evenly over the file so that a busy hour isn't missed or taken in full. The
+// first block is always read as it holds the header.
+pub fn select(blocks: Vec<Range<u64>>, ratio: f64) -> Vec<Range<u64>> {
+    blocks.into_iter().enumerate().filter(|(index, _)| ((*index + 1) as f64 * ratio).ceil() > (*index as f64 * ratio).ceil()).map(|x| x.1).collect()
+}
+
+// Total over the blocks of the files estimated from the blocks read, along with the variance of the estimate
+#[derive(Default)]
+pub struct Estimate {
+    pub value: f64,
+    variance: f64,
+    // A file with more than one block but only a single one read has no spread to go by
+    unbounded: bool,
+}
+
+impl Estimate {
+    // The mean of the blocks read times the number of blocks, with the finite population correction as the
+    // blocks are read without replacement
+    fn add(&mut self, samples: &[f64], blocks: usize) {
+        let (n, total) = (samples.len() as f64, blocks as f64);
+        let Some(mean) = (n > 0.0).then(|| samples.iter().sum::<f64>() / n) else { return };
+        self.value += mean * total;
+        if samples.len() == blocks {
+            return;
+        }
+        if samples.len() < 2 {
+            self.unbounded = true;
+            return;
+        }
+        let sample_variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        self.variance += total * total * sample_variance / n * (1.0 - n / total);
+    }
+
+    // Half width of the 95% confidence interval
+    pub fn margin(&self) -> Option<f64> {
+        (!self.unbounded).then(|| 1.96 * self.variance.sqrt())
+    }
+
+    fn describe(&self, format: impl Fn(f64) -> String) -> String {
+        match self.margin() {
+            Some(margin) => format!("{} ± {} (95%)", format(self.value), format(margin)),
+            None => format!("{} (too few blocks read for an error margin)", format(self.value)),
+        }
+    }
+}
+
+// What --sample read of the files of a report, and the totals estimated from it
+#[derive(Default)]
+pub struct Sampling {
+    pub bytes_read: u64,
+    pub bytes_total: u64,
+    pub allocations: Estimate,
+    pub allocated_bytes: Estimate,
+}
+
+impl Sampling {
+    // The allocation count and bytes of each block read out of the blocks of the file, a file read in full
+    // is a single block
+    pub fn record(&mut self, samples: &[(u64, u64)], blocks: usize, bytes_read: u64, bytes_total: u64) {
+        self.bytes_read += bytes_read;
+        self.bytes_total += bytes_total;
+        self.allocations.add(&samples.iter().map(|x| x.0 as f64).collect::<Vec<_>>(), blocks);
+        self.allocated_bytes.add(&samples.iter().map(|x| x.1 as f64).collect::<Vec<_>>(), blocks);
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let read = match self.bytes_total {
+            0 => 100.0,
+            total => self.bytes_read as f64 * 100.0 / total as f64,
+        };
+        vec![
+            ("read", format!("{:.1}% ({:.1}MB of {:.1}MB)", read, self.bytes_read as f64 / MB as f64, self.bytes_total as f64 / MB as f64)),
+            ("estimated allocations", self.allocations.describe(|x| grouped(&format!("{:.0}", x)))),
+            ("estimated allocated bytes", self.allocated_bytes.describe(|x| grouped(&format!("{:.0}", x)))),
+        ]
+    }
+}
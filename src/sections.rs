@@ -0,0 +1,142 @@
+use clap::ArgEnum;
+use serde_json::Value;
+
+// Parts of the report subcommand's document, each made of the sections of the report with these headings
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Section {
+    // Sampling, JVM configuration, cumulative totals, parse quality and the per group and per file summaries
+    Summary,
+    Buckets,
+    Percentiles,
+    Recommendation,
+    Waste,
+    // Timeline, calendar windows, heatmap, heap occupancy and allocation rate
+    TimeSeries,
+    Bursts,
+    // Full GCs, safepoints, GC causes, pause impact, concurrent cycles, marking and eager reclaim
+    Pauses,
+}
+
+// The part a markdown or html section heading belongs to, the more specific headings first
+fn section_of(heading: &str) -> Option<Section> {
+    const PAUSES: [&str; 10] = [
+        "Full GC Events",
+        "Safepoints",
+        "GC Causes",
+        "Humongous Allocations per GC Cycle",
+        "Pause Impact",
+        "Concurrent Cycle",
+        "Marking Threshold",
+        "Humongous Eager Reclaim",
+        "Humongous Region Trace",
+        "Mixed Collections",
+    ];
+    // The timeline is headed by its to-space exhausted events or heap resizes in markdown
+    const TIME_SERIES: [&str; 6] = ["Humongous Allocations over Time", "To-space Exhausted", "Heap Resizes", "Humongous Allocations per ", "Heap Occupancy", "Allocation Rate"];
+    const BUCKETS: [&str; 8] = ["Allocations by Region Size", "CMS Promotion Failures", "ZGC Pages", "Shenandoah Allocation Failures", "OpenJ9 Allocation Failures", "Most Common Allocation Sizes", "Allocation Size Frequency", "Allocation Sizes ("];
+    const SUMMARY: [&str; 6] = ["Sampling", "JVM Configuration", "Cumulative Totals", "Groups", "Parse Quality", "File Summary"];
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|x| heading.starts_with(x));
+    if starts(&PAUSES) {
+        Some(Section::Pauses)
+    } else if starts(&TIME_SERIES) {
+        Some(Section::TimeSeries)
+    } else if starts(&["Allocation Bursts", "Time Between Humongous Allocations"]) {
+        Some(Section::Bursts)
+    } else if starts(&["Recommendation", "Heap Sizing", "Candidate Region Sizes"]) {
+        Some(Section::Recommendation)
+    } else if starts(&["Humongous Region Waste", "Humongous Share of Heap"]) {
+        Some(Section::Waste)
+    } else if heading.contains("Statistics") || heading.contains("Percentiles") {
+        Some(Section::Percentiles)
+    } else if starts(&BUCKETS) {
+        Some(Section::Buckets)
+    } else if starts(&SUMMARY) {
+        Some(Section::Summary)
+    } else {
+        None
+    }
+}
+
+// The json keys of each report that belong to a part, the others are always kept
+fn section_of_key(key: &str) -> Option<Section> {
+    match key {
+        "files" | "sampling" | "cumulative" | "parse_quality" => Some(Section::Summary),
+        "buckets" | "size_frequency" => Some(Section::Buckets),
+        "size_statistics" | "percentiles" => Some(Section::Percentiles),
+        "recommendation" | "heap_sizing" => Some(Section::Recommendation),
+        "waste" | "heap_share" => Some(Section::Waste),
+        "heatmap" => Some(Section::TimeSeries),
+        "inter_arrival" => Some(Section::Bursts),
+        "full_gcs" | "evacuation_failures" | "gc_causes" | "young_pauses" | "allocations_per_cycle" | "pause_impact" | "concurrent_cycle_triggers" | "concurrent_cycle_durations" | "mixed_collections" | "safepoints" => Some(Section::Pauses),
+        _ => None,
+    }
+}
+
+// The parts given to --sections, "buckets,percentiles" only includes those and "-bursts" leaves bursts out
+#[derive(Clone, Debug, Default)]
+pub struct Sections {
+    included: Vec<Section>,
+    excluded: Vec<Section>,
+}
+
+impl Sections {
+    pub fn add(&mut self, choice: &str) -> Result<(), String> {
+        let (excluded, name) = match choice.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, choice),
+        };
+        let names = || Section::value_variants().iter().filter_map(|x| x.to_possible_value()).map(|x| x.get_name()).collect::<Vec<_>>().join(", ");
+        let section = Section::from_str(name, true).map_err(|_| format!("{} is not a section, expected one of {} (prefixed with - to leave it out)", name, names()))?;
+        match excluded {
+            true => self.excluded.push(section),
+            false => self.included.push(section),
+        }
+        Ok(())
+    }
+
+    fn shows(&self, section: Option<Section>) -> bool {
+        section.is_none_or(|x| (self.included.is_empty() || self.included.contains(&x)) && !self.excluded.contains(&x))
+    }
+
+    // Drops the "### " sections of the parts left out, up to the next heading of the same or a higher level
+    pub fn filter_markdown(&self, document: &str) -> String {
+        let mut shown = true;
+        let mut filtered = String::new();
+        for line in document.split_inclusive('\n') {
+            if let Some(heading) = line.strip_prefix("### ") {
+                shown = self.shows(section_of(heading));
+            } else if line.starts_with("## ") {
+                shown = true;
+            }
+            if shown {
+                filtered.push_str(line);
+            }
+        }
+        filtered
+    }
+
+    // Drops the <h2> sections of the parts left out, up to the next heading or the end of the body
+    pub fn filter_html(&self, document: &str) -> String {
+        let mut shown = true;
+        let mut filtered = String::new();
+        for line in document.split_inclusive('\n') {
+            if let Some(heading) = line.strip_prefix("<h2>") {
+                shown = self.shows(section_of(heading));
+            } else if line.starts_with("<h1>") || line.starts_with("</body>") {
+                shown = true;
+            }
+            if shown {
+                filtered.push_str(line);
+            }
+        }
+        filtered
+    }
+
+    pub fn filter_json(&self, document: &mut Value) {
+        for report in document.get_mut("reports").and_then(Value::as_array_mut).into_iter().flatten() {
+            if let Some(report) = report.as_object_mut() {
+                report.retain(|key, _| self.shows(section_of_key(key)));
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::report::{self, OutputFormat};
+use crate::upload::Upload;
+use crate::Cli;
+
+// Lets support engineers upload a log from the browser, the report replaces the page
+const UPLOAD_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Humongous Allocation Report</title></head>
+<body style="font-family:sans-serif;margin:2em auto;max-width:640px">
+<h1>Humongous Allocation Report</h1>
+<p>Upload a gc log (plain, gzipped, or a zip / tar.gz support bundle) to analyze it.</p>
+<p><input type="file" id="log"> <select id="format"><option value="html">HTML</option><option value="json">JSON</option><option value="markdown">Markdown</option></select>
+<button onclick="analyze()">Analyze</button></p>
+<p id="status"></p>
+<script>
+async function analyze() {
+  const file = document.getElementById('log').files[0];
+  if (!file) return;
+  const format = document.getElementById('format').value;
+  document.getElementById('status').textContent = 'Analyzing ' + file.name + '...';
+  const response = await fetch('/analyze?format=' + format + '&name=' + encodeURIComponent(file.name), { method: 'POST', body: file });
+  const text = await response.text();
+  if (format === 'html' && response.ok) { document.open(); document.write(text); document.close(); }
+  else { document.body.innerHTML = ''; const pre = document.createElement('pre'); pre.textContent = text; document.body.appendChild(pre); }
+}
+</script>
+</body>
+</html>
+"#;
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    content_length: Option<u64>,
+}
+
+fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request { method, path: path.to_string(), query: query.to_string(), content_length: None };
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            return Ok(request);
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                request.content_length = value.trim().parse::<u64>().ok();
+            }
+        }
+    }
+}
+
+fn respond(out: &mut impl Write, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(out, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, content_type, body.len())?;
+    out.write_all(body)
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query.split('&').filter_map(|x| x.split_once('=')).find(|x| x.0 == name).map(|x| x.1)
+}
+
+// "%2F" style escapes of the query string, anything malformed is kept as is
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], value.get(i + 1..i + 3).and_then(|x| u8::from_str_radix(x, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn output_format(name: Option<&str>) -> Option<(OutputFormat, &'static str)> {
+    match name.unwrap_or("json") {
+        "json" => Some((OutputFormat::Json, "application/json")),
+        "html" => Some((OutputFormat::Html, "text/html; charset=utf-8")),
+        "markdown" => Some((OutputFormat::Markdown, "text/markdown; charset=utf-8")),
+        _ => None,
+    }
+}
+
+// A client that stops sending or reading frees its worker after this long
+const TIMEOUT: Duration = Duration::from_secs(60);
+const TOO_LARGE: &str = "413 Content Too Large";
+
+fn analyze_upload(reader: impl BufRead, request: &Request, args: &Cli, max_upload: u64) -> Result<(&'static str, Vec<u8>), (&'static str, String)> {
+    let length = request.content_length.ok_or(("411 Length Required", "The upload needs a Content-Length".to_string()))?;
+    if length > max_upload {
+        return Err((TOO_LARGE, format!("The upload of {} bytes is larger than the {} bytes allowed", length, max_upload)));
+    }
+    let format = query_param(&request.query, "format");
+    let (output_format, content_type) = output_format(format).ok_or(("400 Bad Request", format!("Unknown format {}, expected json, html or markdown", format.unwrap_or_default())))?;
+    let name = query_param(&request.query, "name").map_or("gc.log".to_string(), percent_decode);
+    let upload = Upload::save(reader.take(length), &name, max_upload).map_err(|e| match e.kind() {
+        io::ErrorKind::FileTooLarge => (TOO_LARGE, format!("The decompressed upload is larger than the {} bytes allowed", max_upload)),
+        _ => ("400 Bad Request", format!("Unable to read the upload: {}", e)),
+    })?;
+
+    let mut args = args.clone();
+    args.output_format = output_format;
+    let reports = upload.analyze(&args);
+    let mut body = Vec::new();
+    report::write_reports(&reports, &mut body, &args).map_err(|e| ("500 Internal Server Error", format!("Unable to write the report: {}", e)))?;
+    Ok((content_type, upload.relabel(&String::from_utf8_lossy(&body)).into_bytes()))
+}
+
+fn handle_connection(stream: TcpStream, args: &Cli, max_upload: u64) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let request = read_request(&mut reader)?;
+    let mut out = &stream;
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") => respond(&mut out, "200 OK", "text/html; charset=utf-8", UPLOAD_PAGE.as_bytes()),
+        ("POST", "/analyze") => {
+            log::info!("Analyzing an upload of {} bytes", request.content_length.unwrap_or(0));
+            match analyze_upload(reader, &request, args, max_upload) {
+                Ok((content_type, body)) => respond(&mut out, "200 OK", content_type, &body),
+                Err((status, message)) => {
+                    log::warn!("Upload failed: {}", message);
+                    respond(&mut out, status, "text/plain", format!("{}\n", message).as_bytes())
+                }
+            }
+        }
+        _ => respond(&mut out, "404 Not Found", "text/plain", b"Not Found, POST gc logs to /analyze?format=json|html|markdown\n"),
+    }
+}
+
+// Analyzes uploaded gc logs with the settings of the command line until the process is stopped. Each of the
+// workers handles a connection at a time, further ones wait to be accepted.
+pub fn serve(address: &str, args: Cli, max_upload: u64, workers: usize) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    log::info!("Serving gc log analysis on http://{}/ with {} workers", listener.local_addr()?, workers);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                for stream in listener.incoming().flatten() {
+                    if let Err(e) = handle_connection(stream, &args, max_upload) {
+                        log::debug!("Request failed: {}", e);
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
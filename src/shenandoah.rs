@@ -0,0 +1,142 @@
+use cli_table::format::Justify;
+
+use crate::buckets::{format_region_size, MB};
+use crate::jvm_flags::parse_size;
+use crate::numbers::grouped;
+use crate::report::Record;
+
+// ShenandoahRegionSize is a power of two between ShenandoahMinRegionSize and ShenandoahMaxRegionSize
+pub const SHENANDOAH_REGION_SIZES: [u64; 8] = [256 * 1024, 512 * 1024, MB, 2 * MB, 4 * MB, 8 * MB, 16 * MB, 32 * MB];
+
+pub struct ShenandoahRegionRow {
+    pub region_size: String,
+    pub allocations: u64,
+    pub cumulative_percentage: String,
+}
+
+impl Record for ShenandoahRegionRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Region Size", Justify::Right),
+        ("Failed Humongous Allocations", Justify::Right),
+        ("No Longer Humongous (cumulative %)", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.region_size.clone(), grouped(&self.allocations), self.cumulative_percentage.clone()]
+    }
+}
+
+// Shenandoah only logs the allocations it failed to satisfy, the humongous ones among them are the
+// objects larger than the humongous threshold (a full region by default) that found no contiguous free regions
+#[derive(Default)]
+pub struct ShenandoahAllocations {
+    // Of the file being parsed, from the startup lines
+    region_size: Option<u64>,
+    threshold: Option<u64>,
+    pub failed_allocations: u64,
+    pub humongous_sizes: Vec<u64>,
+    // Smallest largest-contiguous-free-space seen, i.e. the worst fragmentation for humongous objects
+    pub min_humongous_free: Option<u64>,
+}
+
+// "[0.006s][info][gc,init] Regions: 2048 x 4096K" (JDK 11) or "Heap Region Size: 4M" (JDK 17)
+fn parse_region_size(line: &str) -> Option<u64> {
+    match line.split_once("] Regions: ") {
+        Some((_, rest)) => parse_size(rest.split_once(" x ")?.1),
+        None => parse_size(line.split_once("Heap Region Size: ")?.1),
+    }
+}
+
+impl ShenandoahAllocations {
+    pub fn region_size(&self) -> Option<u64> {
+        self.region_size
+    }
+
+    // Returns the size of a failed humongous allocation
+    pub fn observe(&mut self, line: &str) -> Option<u64> {
+        if let Some(region_size) = parse_region_size(line) {
+            self.region_size = Some(region_size);
+        } else if let Some((_, threshold)) = line.split_once("Humongous Object Threshold: ") {
+            self.threshold = parse_size(threshold);
+        } else if let Some((_, rest)) = line.split_once("Failed to allocate ") {
+            // "Failed to allocate Shared, 8192K", TLABs and GCLABs are never humongous
+            self.failed_allocations += 1;
+            let size = parse_size(rest.strip_prefix("Shared, ")?)?;
+            if size > self.threshold.or(self.region_size)? {
+                self.humongous_sizes.push(size);
+                return Some(size);
+            }
+        } else if let Some((free, _)) = line.split_once(" humongous, Frag: ") {
+            // "Free: 3065M, Max: 4096K regular, 12288K humongous, Frag: 1% external, 2% internal; Reserve: 205M, Max: 4096K"
+            let humongous_free = parse_size(free.rsplit_once(", ")?.1)?;
+            self.min_humongous_free = Some(self.min_humongous_free.map_or(humongous_free, |x| x.min(humongous_free)));
+        }
+        None
+    }
+
+    pub fn reset(&mut self, region_size: Option<u64>) {
+        self.region_size = region_size;
+        self.threshold = None;
+    }
+
+    // Allocations no larger than a region are not humongous
+    pub fn rows(&self) -> Vec<ShenandoahRegionRow> {
+        let total = self.humongous_sizes.len().max(1) as f64;
+        let mut cumulative = 0;
+        let mut previous = 0;
+        let mut rows: Vec<ShenandoahRegionRow> = SHENANDOAH_REGION_SIZES
+            .iter()
+            .map(|region_size| {
+                let allocations = self.humongous_sizes.iter().filter(|x| **x > previous && **x <= *region_size).count() as u64;
+                previous = *region_size;
+                cumulative += allocations;
+                ShenandoahRegionRow {
+                    region_size: format_region_size(*region_size),
+                    allocations,
+                    cumulative_percentage: format!("{:.2}%", cumulative as f64 * 100.0 / total),
+                }
+            })
+            .collect();
+        rows.push(ShenandoahRegionRow {
+            region_size: "Overflow".to_string(),
+            allocations: self.humongous_sizes.len() as u64 - cumulative,
+            cumulative_percentage: "-".to_string(),
+        });
+        rows
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let mut stats = vec![
+            ("failed allocations", grouped(&self.failed_allocations)),
+            ("failed humongous allocations", grouped(&self.humongous_sizes.len())),
+        ];
+        if let Some(free) = self.min_humongous_free {
+            stats.push(("smallest contiguous free space for humongous objects", format!("{}K", grouped(&(free / 1024)))));
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buckets::MB;
+
+    #[test]
+    fn reads_the_failed_humongous_allocations() {
+        let mut jdk11 = ShenandoahAllocations::default();
+        assert_eq!(jdk11.observe("[0.006s][info][gc,init] Regions: 2048 x 4096K"), None);
+        assert_eq!(jdk11.region_size(), Some(4 * MB));
+        assert_eq!(jdk11.observe("[0.006s][info][gc,init] Humongous Object Threshold: 2048K"), None);
+        assert_eq!(jdk11.observe("[12.345s][info][gc] Failed to allocate Shared, 3072K"), Some(3 * MB));
+        assert_eq!(jdk11.observe("[12.346s][info][gc] Failed to allocate TLAB, 4096K"), None);
+        assert_eq!(jdk11.observe("[12.347s][info][gc] Failed to allocate Shared, 1024K"), None);
+        assert_eq!(jdk11.observe("[12.348s][info][gc,ergo] Free: 3065M, Max: 4096K regular, 12288K humongous, Frag: 1% external, 2% internal; Reserve: 205M, Max: 4096K"), None);
+        assert_eq!((jdk11.failed_allocations, jdk11.min_humongous_free), (3, Some(12 * MB)));
+
+        let mut jdk17 = ShenandoahAllocations::default();
+        jdk17.observe("[2024-03-01T10:15:00.123+0000][0.006s][info][gc,init] Heap Region Size: 4M");
+        assert_eq!(jdk17.observe("[2024-03-01T10:15:12.345+0000][12.345s][info][gc] Failed to allocate Shared, 3072K"), None);
+        assert_eq!(jdk17.observe("[2024-03-01T10:15:12.346+0000][12.346s][info][gc] Failed to allocate Shared, 8192K"), Some(8 * MB));
+    }
+}
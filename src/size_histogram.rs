@@ -0,0 +1,46 @@
+use histogram::Histogram;
+
+// The recorder behind the size percentiles. The histogram crate's defaults keep reports comparable across
+// releases, --precision trades them for an HdrHistogram whose values are within the given number of
+// significant digits at any size.
+pub enum SizeHistogram {
+    Default(Histogram),
+    Hdr(hdrhistogram::Histogram<u64>),
+}
+
+impl SizeHistogram {
+    pub fn new(precision: Option<u8>) -> SizeHistogram {
+        // --precision is validated to be in 1..=5, which HdrHistogram always accepts
+        match precision.and_then(|x| hdrhistogram::Histogram::new(x).ok()) {
+            Some(hdr) => SizeHistogram::Hdr(hdr),
+            None => SizeHistogram::Default(Histogram::new()),
+        }
+    }
+
+    pub fn increment_by(&mut self, bytes: u64, count: u64) -> Result<(), String> {
+        match self {
+            SizeHistogram::Default(histogram) => histogram.increment_by(bytes, count).map_err(str::to_string),
+            SizeHistogram::Hdr(histogram) => histogram.record_n(bytes, count).map_err(|e| format!("{:?}", e)),
+        }
+    }
+
+    pub fn increment(&mut self, bytes: u64) -> Result<(), String> {
+        self.increment_by(bytes, 1)
+    }
+
+    // min, the requested percentiles and max, empty when nothing was recorded
+    pub fn percentiles(&self, percentiles: &[f64]) -> Vec<(String, u64)> {
+        let (min, values, max) = match self {
+            SizeHistogram::Default(histogram) => {
+                let Ok(min) = histogram.minimum() else { return Vec::new() };
+                (min, percentiles.iter().map(|x| histogram.percentile(*x).unwrap()).collect::<Vec<_>>(), histogram.maximum().unwrap())
+            }
+            SizeHistogram::Hdr(histogram) if histogram.is_empty() => return Vec::new(),
+            SizeHistogram::Hdr(histogram) => (histogram.min(), percentiles.iter().map(|x| histogram.value_at_percentile(*x)).collect(), histogram.max()),
+        };
+        let mut result = vec![("min".to_string(), min)];
+        result.extend(percentiles.iter().zip(values).map(|(percentile, value)| (format!("p{}", percentile), value)));
+        result.push(("max".to_string(), max));
+        result
+    }
+}
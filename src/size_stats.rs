@@ -0,0 +1,42 @@
+use crate::bins::display_size;
+use crate::numbers::grouped;
+
+// Count, total, mean and standard deviation of the sizes recorded in the histogram. The sums are kept exactly
+// as the histogram buckets are too coarse for them.
+#[derive(Default)]
+pub struct SizeStats {
+    pub count: u64,
+    pub total_bytes: u64,
+    sum_of_squares: u128,
+}
+
+impl SizeStats {
+    pub fn record(&mut self, bytes: u64, count: u64) {
+        self.count += count;
+        self.total_bytes += bytes * count;
+        self.sum_of_squares += bytes as u128 * bytes as u128 * count as u128;
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.total_bytes as f64 / self.count as f64)
+    }
+
+    // Of every size recorded rather than of a sample, n * sum(x²) - sum(x)² is computed exactly to avoid the
+    // cancellation of subtracting the squared mean
+    pub fn stddev(&self) -> Option<f64> {
+        let count = self.count as u128;
+        let total = self.total_bytes as u128;
+        let spread = (count * self.sum_of_squares).checked_sub(total * total)?;
+        (count > 0).then(|| (spread as f64).sqrt() / count as f64)
+    }
+
+    pub fn stats(&self, raw_bytes: bool) -> Vec<(&'static str, String)> {
+        let size = |x: f64| display_size(x.round() as u64, raw_bytes);
+        vec![
+            ("count", grouped(&self.count)),
+            ("total bytes", display_size(self.total_bytes, raw_bytes)),
+            ("mean", self.mean().map_or("-".to_string(), size)),
+            ("stddev", self.stddev().map_or("-".to_string(), size)),
+        ]
+    }
+}
@@ -0,0 +1,59 @@
+use rusqlite::{params, Connection};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::report::Report;
+use crate::timestamp::format_wall_clock;
+use crate::Cli;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS allocations (report TEXT, file TEXT NOT NULL, line INTEGER NOT NULL, uptime REAL, datestamp TEXT, bytes INTEGER NOT NULL, label TEXT);
+CREATE TABLE IF NOT EXISTS files (report TEXT, file TEXT NOT NULL, region_size_mb INTEGER, allocations INTEGER NOT NULL, allocated_bytes INTEGER NOT NULL, evacuation_failures INTEGER NOT NULL, status TEXT NOT NULL, label TEXT);
+CREATE TABLE IF NOT EXISTS region_buckets (report TEXT, region_size TEXT NOT NULL, max_allocation_size INTEGER NOT NULL, allocations INTEGER NOT NULL);
+CREATE TABLE IF NOT EXISTS percentiles (report TEXT, percentile TEXT NOT NULL, bytes INTEGER NOT NULL);
+CREATE TABLE IF NOT EXISTS size_statistics (report TEXT, count INTEGER NOT NULL, total_bytes INTEGER NOT NULL, mean_bytes REAL, stddev_bytes REAL);
+";
+
+// Replaces an existing database, except in watch mode where every new file's rows are added to it.
+// The report column holds the --group-by title, NULL when everything is in a single report, and the label column
+// the --input label of the file.
+pub fn export(path: &Path, reports: &[Report], args: &Cli) -> Result<(), Box<dyn Error>> {
+    if args.watch.is_none() && path.exists() {
+        fs::remove_file(path)?;
+    }
+    let mut connection = Connection::open(path)?;
+    connection.execute_batch(SCHEMA)?;
+    let transaction = connection.transaction()?;
+    for report in reports {
+        let title = report.title.as_deref();
+        let mut insert = transaction.prepare("INSERT INTO allocations VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
+        for file in &report.retained {
+            for allocation in &file.allocations {
+                let datestamp = allocation.wall_clock.map(format_wall_clock);
+                insert.execute(params![title, file.name, allocation.line as i64, allocation.uptime, datestamp, allocation.bytes as i64, file.label])?;
+            }
+        }
+        let mut insert = transaction.prepare("INSERT INTO files VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?;
+        for summary in &report.file_summaries {
+            let row = summary.row();
+            let region_size = summary.region_size.as_ref().map(|x| x.mb);
+            insert.execute(params![title, summary.file, region_size, summary.allocations as i64, summary.allocated_bytes as i64, summary.evacuation_failures as i64, row.status, summary.label])?;
+        }
+        let mut insert = transaction.prepare("INSERT INTO region_buckets VALUES (?1, ?2, ?3, ?4)")?;
+        for bucket in &report.region_size_array {
+            insert.execute(params![title, bucket.region_size, bucket.max_size as i64, bucket.num_allocations])?;
+        }
+        let mut insert = transaction.prepare("INSERT INTO percentiles VALUES (?1, ?2, ?3)")?;
+        for (label, bytes) in report.percentiles(&args.percentiles) {
+            insert.execute(params![title, label, bytes as i64])?;
+        }
+        let stats = &report.size_stats;
+        transaction.execute(
+            "INSERT INTO size_statistics VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![title, stats.count as i64, stats.total_bytes as i64, stats.mean(), stats.stddev()],
+        )?;
+    }
+    transaction.commit()?;
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::{archive, line_parsing};
+
+// Bytes read from the end of the file at a time while looking for the end of its last complete line
+const TAIL_BLOCK_SIZE: u64 = 64 * 1024;
+
+// What --state remembers of a gc log between runs
+#[derive(Default)]
+pub struct FileState {
+    // Where the next run picks up, always the end of a line
+    pub offset: u64,
+    // A different first line means the log was rotated or replaced, and is read from the start again
+    pub first_line: String,
+    pub runs: u64,
+    // Allocation size -> allocations of that size in every run so far
+    pub sizes: BTreeMap<u64, u64>,
+}
+
+// The --state file, a json document keyed by the path of each gc log
+#[derive(Default)]
+pub struct State {
+    pub files: BTreeMap<String, FileState>,
+}
+
+// The first line of the file, which doesn't change for as long as it is being appended to
+pub fn first_line(file: &Path) -> String {
+    let Ok((reader, _)) = archive::open(file) else { return String::new() };
+    line_parsing::lossy_lines(BufReader::new(reader)).next().and_then(Result::ok).map(|x| x.0).unwrap_or_default()
+}
+
+// The end of the last complete line at or after start, a line still being written is left for the next run
+pub fn complete_lines_end(reader: &mut (impl Read + Seek), start: u64, len: u64) -> io::Result<u64> {
+    let mut end = len;
+    while end > start {
+        let block_start = end.saturating_sub(TAIL_BLOCK_SIZE).max(start);
+        let mut block = vec![0; (end - block_start) as usize];
+        reader.seek(SeekFrom::Start(block_start))?;
+        reader.read_exact(&mut block)?;
+        if let Some(index) = block.iter().rposition(|x| *x == b'\n') {
+            return Ok(block_start + index as u64 + 1);
+        }
+        end = block_start;
+    }
+    Ok(start)
+}
+
+impl State {
+    // A state file that doesn't exist yet is an empty state
+    pub fn load(path: &Path) -> Result<State, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(State::default()),
+            Err(e) => return Err(format!("Unable to read {}: {}", path.display(), e)),
+        };
+        let invalid = || format!("Invalid state file {}", path.display());
+        let document: Value = serde_json::from_str(&text).map_err(|e| format!("{}: {}", invalid(), e))?;
+        let mut state = State::default();
+        for (name, file) in document.get("files").and_then(Value::as_object).ok_or_else(invalid)? {
+            let sizes = file.get("sizes").and_then(Value::as_object).ok_or_else(invalid)?;
+            let sizes = sizes.iter().map(|(size, count)| Some((size.parse::<u64>().ok()?, count.as_u64()?))).collect::<Option<_>>().ok_or_else(invalid)?;
+            state.files.insert(
+                name.clone(),
+                FileState {
+                    offset: file.get("offset").and_then(Value::as_u64).ok_or_else(invalid)?,
+                    first_line: file.get("first_line").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    runs: file.get("runs").and_then(Value::as_u64).unwrap_or(0),
+                    sizes,
+                },
+            );
+        }
+        Ok(state)
+    }
+
+    // Written next to the state file and renamed over it, so an interrupted run leaves the old state intact
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let files: Map<String, Value> = self
+            .files
+            .iter()
+            .map(|(name, file)| (name.clone(), json!({ "offset": file.offset, "first_line": file.first_line, "runs": file.runs, "sizes": file.sizes })))
+            .collect();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(&json!({ "files": files }))?)?;
+        fs::rename(&temporary, path)
+    }
+
+    // Where to resume reading the file, from the start when it was rotated, replaced or truncated since
+    pub fn start_offset(&self, file: &Path, first_line: &str, len: u64) -> u64 {
+        match self.files.get(&file.display().to_string()) {
+            Some(state) if state.first_line == first_line && state.offset <= len => state.offset,
+            Some(_) => {
+                log::info!("{} was rotated or truncated since the last run, reading it from the start", file.display());
+                0
+            }
+            None => 0,
+        }
+    }
+
+    pub fn update(&mut self, file: &Path, first_line: String, offset: u64, sizes: impl Iterator<Item = u64>) {
+        let state = self.files.entry(file.display().to_string()).or_default();
+        state.offset = offset;
+        state.first_line = first_line;
+        state.runs += 1;
+        for size in sizes {
+            *state.sizes.entry(size).or_insert(0) += 1;
+        }
+    }
+
+    pub fn cumulative<'a>(&self, files: impl Iterator<Item = &'a str>) -> Cumulative {
+        let mut cumulative = Cumulative::default();
+        for state in files.filter_map(|x| self.files.get(x)) {
+            cumulative.runs = cumulative.runs.max(state.runs);
+            for (size, count) in &state.sizes {
+                *cumulative.sizes.entry(*size).or_insert(0) += count;
+            }
+        }
+        cumulative
+    }
+}
+
+// The allocations of every --state run of the files of a report, this one included
+#[derive(Default)]
+pub struct Cumulative {
+    pub runs: u64,
+    pub sizes: BTreeMap<u64, u64>,
+}
+
+impl Cumulative {
+    pub fn allocations(&self) -> u64 {
+        self.sizes.values().sum()
+    }
+
+    pub fn allocated_bytes(&self) -> u64 {
+        self.sizes.iter().map(|(size, count)| size * count).sum()
+    }
+
+    // min, the requested percentiles and max, exact as every size is kept
+    pub fn percentiles(&self, percentiles: &[f64]) -> Vec<(String, u64)> {
+        let total = self.allocations();
+        let (Some(min), Some(max)) = (self.sizes.keys().next(), self.sizes.keys().next_back()) else { return Vec::new() };
+        let mut values = vec![("min".to_string(), *min)];
+        for percentile in percentiles {
+            let rank = ((total as f64 * percentile / 100.0).ceil() as u64).max(1);
+            let mut seen = 0;
+            let size = self.sizes.iter().find(|(_, count)| {
+                seen += **count;
+                seen >= rank
+            });
+            values.push((format!("p{}", percentile), size.map_or(*max, |x| *x.0)));
+        }
+        values.push(("max".to_string(), *max));
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_and_loads_the_state() {
+        let path = std::env::temp_dir().join(format!("state-round-trip-{}.json", std::process::id()));
+        let mut state = State::default();
+        state.update(Path::new("/var/log/gc.log"), "[0.010s][info][gc] Using G1".to_string(), 4096, [4194304, 4194304, 33554432].into_iter());
+        state.update(Path::new("/var/log/gc.log"), "[0.010s][info][gc] Using G1".to_string(), 8192, [4194304].into_iter());
+        state.save(&path).unwrap();
+        let loaded = State::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let file = &loaded.files["/var/log/gc.log"];
+        assert_eq!(file.offset, 8192);
+        assert_eq!(file.first_line, "[0.010s][info][gc] Using G1");
+        assert_eq!(file.runs, 2);
+        assert_eq!(file.sizes, BTreeMap::from([(4194304, 3), (33554432, 1)]));
+        assert_eq!(loaded.start_offset(Path::new("/var/log/gc.log"), "[0.010s][info][gc] Using G1", 10000), 8192);
+        assert_eq!(loaded.start_offset(Path::new("/var/log/gc.log"), "[0.011s][info][gc] Using G1", 10000), 0);
+        assert_eq!(loaded.start_offset(Path::new("/var/log/gc.log"), "[0.010s][info][gc] Using G1", 100), 0);
+    }
+
+    #[test]
+    fn loads_a_missing_state_as_empty() {
+        let state = State::load(&std::env::temp_dir().join("state-that-does-not-exist.json")).unwrap();
+        assert!(state.files.is_empty());
+    }
+
+    #[test]
+    fn finds_the_end_of_the_last_complete_line() {
+        let mut log = io::Cursor::new(b"first\nsecond\nthird still being writ".to_vec());
+        assert_eq!(complete_lines_end(&mut log, 0, 34).unwrap(), 13);
+        assert_eq!(complete_lines_end(&mut log, 13, 34).unwrap(), 13);
+    }
+}
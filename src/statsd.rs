@@ -0,0 +1,48 @@
+use std::io;
+use std::net::UdpSocket;
+
+use crate::follow::{FollowEvent, FollowEventKind};
+
+// Sends a datagram per followed event to a StatsD daemon. UDP can't tell whether anyone is listening, so a
+// failed send is only logged at debug level.
+pub struct StatsdClient {
+    socket: UdpSocket,
+    prefix: String,
+    // DogStatsD "|#file:gc.log,label:node1" tags, plain StatsD has no tags
+    tags: bool,
+}
+
+impl StatsdClient {
+    pub fn new(address: &str, prefix: Option<&str>, tags: bool) -> io::Result<StatsdClient> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.connect(address)?;
+        log::info!("Sending StatsD metrics to {}", socket.peer_addr()?);
+        Ok(StatsdClient { socket, prefix: prefix.map_or(String::new(), |x| format!("{}.", x.trim_end_matches('.'))), tags })
+    }
+
+    fn metric(&self, name: &str, value: u64, kind: &str, event: &FollowEvent) -> String {
+        if !self.tags {
+            return format!("{}{}:{}|{}", self.prefix, name, value, kind);
+        }
+        // Tag values end at a comma or pipe
+        let tag = |x: &str| x.replace([',', '|'], "_");
+        let label = event.label.as_deref().map_or(String::new(), |x| format!(",label:{}", tag(x)));
+        format!("{}{}:{}|{}|#file:{}{}", self.prefix, name, value, kind, tag(&event.file), label)
+    }
+
+    pub fn record(&self, event: &FollowEvent) {
+        let metrics = match event.kind {
+            FollowEventKind::HumongousAllocation(bytes) => vec![
+                self.metric("humongous.count", 1, "c", event),
+                self.metric("humongous.bytes", bytes, "c", event),
+                self.metric("humongous.size", bytes, "h", event),
+            ],
+            FollowEventKind::FullGc => vec![self.metric("full_gc", 1, "c", event)],
+            FollowEventKind::EvacuationFailure => vec![self.metric("to_space_exhausted", 1, "c", event)],
+        };
+        // One datagram for all of them, StatsD splits on newlines
+        if let Err(e) = self.socket.send(metrics.join("\n").as_bytes()) {
+            log::debug!("Unable to send StatsD metrics: {}", e);
+        }
+    }
+}
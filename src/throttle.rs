@@ -0,0 +1,76 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Reading can run ahead of the limit by this much after a pause, e.g. while the previous file's report is built
+const BURST: Duration = Duration::from_secs(1);
+
+// The --throttle limit, shared by every reader so parallel chunks don't multiply it
+static LIMITER: OnceLock<Mutex<Limiter>> = OnceLock::new();
+
+struct Limiter {
+    bytes_per_second: f64,
+    // When the bytes read so far would have been read at the limit
+    next: Instant,
+}
+
+impl Limiter {
+    // How long to wait after reading the bytes to stay within the limit
+    fn consume(&mut self, bytes: usize) -> Duration {
+        let now = Instant::now();
+        self.next = self.next.max(now.checked_sub(BURST).unwrap_or(now)) + Duration::from_secs_f64(bytes as f64 / self.bytes_per_second);
+        self.next.saturating_duration_since(now)
+    }
+}
+
+pub fn set_limit(mb_per_second: f64) {
+    let limiter = Limiter { bytes_per_second: mb_per_second * 1024.0 * 1024.0, next: Instant::now() };
+    if LIMITER.set(Mutex::new(limiter)).is_err() {
+        log::warn!("The read limit was already set");
+    }
+}
+
+// A file read no faster than --throttle, as is without it
+pub struct Throttled<R> {
+    inner: R,
+}
+
+impl<R> Throttled<R> {
+    pub fn new(inner: R) -> Throttled<R> {
+        Throttled { inner }
+    }
+}
+
+impl<R: Read> Read for Throttled<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if let Some(limiter) = LIMITER.get() {
+            let wait = limiter.lock().unwrap().consume(read);
+            if !wait.is_zero() {
+                thread::sleep(wait);
+            }
+        }
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for Throttled<R> {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(position)
+    }
+}
+
+// --low-priority, the lowest CPU (renice 19) and I/O (ionice idle class) priority, so the JVM on the same host
+// comes first. Both tools are Linux ones, elsewhere only the CPU priority may be lowered.
+pub fn lower_priority() {
+    let pid = std::process::id().to_string();
+    for (program, args) in [("renice", ["-n", "19", "-p"]), ("ionice", ["-c", "3", "-p"])] {
+        match Command::new(program).args(args).arg(&pid).output() {
+            Ok(output) if output.status.success() => log::debug!("Lowered the priority with {}", program),
+            Ok(output) => log::warn!("Unable to lower the priority with {}: {}", program, String::from_utf8_lossy(&output.stderr).trim()),
+            Err(e) => log::warn!("Unable to lower the priority with {}: {}", program, e),
+        }
+    }
+}
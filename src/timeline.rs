@@ -0,0 +1,77 @@
+use cli_table::format::Justify;
+use std::collections::BTreeMap;
+
+use crate::buckets::MB;
+use crate::heap_resize::{HeapResize, HeapSizing};
+use crate::numbers::grouped;
+use crate::report::Record;
+use crate::AllocationTime;
+
+pub struct TimelineRow {
+    pub file: String,
+    pub window_start: String,
+    pub allocations: u64,
+    pub bytes: u64,
+    pub evacuation_failures: u64,
+    pub expanded: u64,
+    pub shrunk: u64,
+}
+
+impl Record for TimelineRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Window Start (s)", Justify::Right),
+        ("Humongous Allocations", Justify::Right),
+        ("Humongous Bytes", Justify::Right),
+        ("To-space Exhausted", Justify::Right),
+        ("Heap Expanded (MB)", Justify::Right),
+        ("Heap Shrunk (MB)", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.file.clone(),
+            self.window_start.clone(),
+            grouped(&self.allocations),
+            grouped(&self.bytes),
+            grouped(&self.evacuation_failures),
+            grouped(&self.expanded),
+            grouped(&self.shrunk),
+        ]
+    }
+}
+
+fn window<'a>(windows: &'a mut BTreeMap<u64, TimelineRow>, file: &str, uptime: f64, interval: f64) -> &'a mut TimelineRow {
+    let index = (uptime / interval) as u64;
+    windows.entry(index).or_insert_with(|| TimelineRow {
+        file: file.to_string(),
+        window_start: format!("{:.0}", index as f64 * interval),
+        allocations: 0,
+        bytes: 0,
+        evacuation_failures: 0,
+        expanded: 0,
+        shrunk: 0,
+    })
+}
+
+// Groups timestamped events of a single file into fixed windows of `interval` seconds of uptime
+// Windows without any activity are omitted to keep long logs readable
+pub fn build_timeline(file: &str, allocations: &[AllocationTime], evacuation_failures: &[f64], resizes: &[HeapResize], interval: f64) -> Vec<TimelineRow> {
+    let mut windows: BTreeMap<u64, TimelineRow> = BTreeMap::new();
+    for allocation in allocations {
+        let row = window(&mut windows, file, allocation.uptime, interval);
+        row.allocations += 1;
+        row.bytes += allocation.bytes;
+    }
+    for uptime in evacuation_failures {
+        window(&mut windows, file, *uptime, interval).evacuation_failures += 1;
+    }
+    for resize in resizes {
+        match (resize.uptime, &resize.sizing) {
+            (Some(uptime), HeapSizing::Expand(bytes)) => window(&mut windows, file, uptime, interval).expanded += bytes / MB,
+            (Some(uptime), HeapSizing::Shrink(bytes)) => window(&mut windows, file, uptime, interval).shrunk += bytes / MB,
+            _ => {}
+        }
+    }
+    windows.into_values().collect()
+}
@@ -0,0 +1,48 @@
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::report::Report;
+use crate::timestamp::{self, format_wall_clock};
+
+// The allocation count and bytes of every --interval window from the first datestamp to the last, across
+// all reports, the empty windows included so a graph drops to zero rather than interpolating over them
+pub fn windows(reports: &[Report], interval: u64) -> Vec<(i64, u64, u64)> {
+    let interval = interval as i64;
+    let mut windows: BTreeMap<i64, (u64, u64)> = BTreeMap::new();
+    for allocation in reports.iter().flat_map(|x| &x.retained).flat_map(|x| &x.allocations) {
+        if let Some(seconds) = allocation.wall_clock {
+            let window = windows.entry(timestamp::window_start(seconds, interval)).or_default();
+            window.0 += 1;
+            window.1 += allocation.bytes;
+        }
+    }
+    let (Some(first), Some(last)) = (windows.keys().next().copied(), windows.keys().next_back().copied()) else { return Vec::new() };
+    (0..=(last - first) / interval)
+        .map(|index| {
+            let start = first + index * interval;
+            let (allocations, bytes) = windows.get(&start).copied().unwrap_or_default();
+            (start, allocations, bytes)
+        })
+        .collect()
+}
+
+// A CSV for Grafana's CSV data source, or a JSON object per line when the path ends in .json or .ndjson.
+// Timestamps are in the --display-tz, without an offset.
+pub fn export(path: &Path, reports: &[Report], interval: u64) -> io::Result<()> {
+    let ndjson = path.extension().and_then(|x| x.to_str()).is_some_and(|x| x == "json" || x == "ndjson");
+    let mut out = BufWriter::new(File::create(path)?);
+    if !ndjson {
+        writeln!(out, "timestamp,allocations,bytes")?;
+    }
+    for (start, allocations, bytes) in windows(reports, interval) {
+        let timestamp = format_wall_clock(start).replace(' ', "T");
+        match ndjson {
+            true => writeln!(out, "{}", json!({ "timestamp": timestamp, "allocations": allocations, "bytes": bytes }))?,
+            false => writeln!(out, "{},{},{}", timestamp, allocations, bytes)?,
+        }
+    }
+    out.flush()
+}
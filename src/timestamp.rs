@@ -0,0 +1,241 @@
+use std::sync::OnceLock;
+
+// Extracts the JVM uptime (in seconds) from the decorations at the start of a gc log line.
+// Handles both the JDK 8 -XX:+PrintGCTimeStamps form ("1131.424: [GC pause ...")
+// and the unified logging uptime decorations ("[1131.424s]" / "[1131424ms]").
+pub fn parse_uptime(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    if line.starts_with('[') {
+        parse_unified_uptime(line)
+    } else {
+        // The datestamp (if present) comes first and never contains ": " itself
+        line.split(": ")
+            .take(2)
+            .take_while(|x| !x.starts_with('['))
+            .find_map(|x| x.parse::<f64>().ok())
+    }
+}
+
+fn parse_unified_uptime(line: &str) -> Option<f64> {
+    let mut rest = line;
+    while let Some(decoration) = rest.strip_prefix('[') {
+        let (value, remainder) = decoration.split_once(']')?;
+        if let Some(millis) = value.strip_suffix("ms") {
+            if let Ok(millis) = millis.parse::<f64>() {
+                return Some(millis / 1000.0);
+            }
+        } else if let Some(secs) = value.strip_suffix('s') {
+            if let Ok(secs) = secs.parse::<f64>() {
+                return Some(secs);
+            }
+        }
+        rest = remainder;
+    }
+    None
+}
+
+// Extracts the -XX:+PrintGCDateStamps / unified `time` decoration, e.g. "2022-01-26T18:54:10.491+0000".
+// Datestamps keep increasing across JVM restarts, unlike the uptime.
+pub fn parse_datestamp(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let datestamp = match line.strip_prefix('[') {
+        Some(rest) => rest.split_once(']')?.0,
+        None => line.split_once(": ")?.0,
+    };
+    let bytes = datestamp.as_bytes();
+    let looks_like_date = bytes.len() >= 19 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[4] == b'-' && bytes[10] == b'T';
+    looks_like_date.then_some(datestamp)
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
+// Seconds east of UTC the times are shown in, see --display-tz
+static DISPLAY_OFFSET: OnceLock<i64> = OnceLock::new();
+
+pub fn set_display_offset(seconds: i64) {
+    if DISPLAY_OFFSET.set(seconds).is_err() {
+        log::warn!("The display time zone was already set");
+    }
+}
+
+fn display_offset() -> i64 {
+    DISPLAY_OFFSET.get().copied().unwrap_or(0)
+}
+
+// "UTC", "Z" or a fixed offset such as "+05:30", "-0800" or "+02", in seconds east of UTC
+pub fn parse_offset(text: &str) -> Result<i64, String> {
+    if text.eq_ignore_ascii_case("utc") || text == "Z" {
+        return Ok(0);
+    }
+    let invalid = || format!("{} is not a time zone, expected UTC or an offset such as +05:30 or -0800", text);
+    let sign = match text.as_bytes().first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => return Err(invalid()),
+    };
+    let digits = text[1..].replace(':', "");
+    if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|x| x.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let (hours, minutes) = (digits[..2].parse::<i64>().unwrap(), digits.get(2..).filter(|x| !x.is_empty()).map_or(0, |x| x.parse::<i64>().unwrap()));
+    if hours > 18 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(sign * (hours * 3600 + minutes * 60))
+}
+
+// The milliseconds and UTC offset after the seconds of a datestamp, e.g. ".491+0000", either may be left out
+fn parse_fraction_and_offset(datestamp: &str) -> Option<(i64, Option<i64>)> {
+    let rest = datestamp.get(19..)?;
+    let (millis, zone) = match rest.strip_prefix('.') {
+        Some(fraction) => {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            let millis = format!("{:0<3}", &fraction[..digits.min(3)]).parse::<i64>().ok()?;
+            (millis, &fraction[digits..])
+        }
+        None => (0, rest),
+    };
+    match zone {
+        "" => Some((millis, None)),
+        zone => parse_offset(zone).ok().map(|x| (millis, Some(x))),
+    }
+}
+
+// The seconds since the epoch of a datestamp's date and time as written, and its UTC offset if it has one
+pub fn parse_local(datestamp: &str) -> Option<(i64, Option<i64>)> {
+    let field = |range: std::ops::Range<usize>| datestamp.get(range)?.parse::<i64>().ok();
+    let days = days_from_civil(field(0..4)?, field(5..7)?, field(8..10)?);
+    let local = days * 86400 + field(11..13)? * 3600 + field(14..16)? * 60 + field(17..19)?;
+    Some((local, parse_fraction_and_offset(datestamp).and_then(|x| x.1)))
+}
+
+// Datestamps without a UTC offset, e.g. those of OpenJ9 or typed on the command line, are in the --display-tz
+pub fn to_utc(local: i64, offset: Option<i64>) -> i64 {
+    local - offset.unwrap_or_else(display_offset)
+}
+
+// Seconds since the epoch (UTC) of the time in a datestamp
+pub fn parse_wall_clock(datestamp: &str) -> Option<i64> {
+    parse_local(datestamp).map(|(local, offset)| to_utc(local, offset))
+}
+
+// Like parse_wall_clock in milliseconds, to order the lines of files logged in different time zones
+pub fn parse_wall_clock_millis(datestamp: &str) -> Option<i64> {
+    Some(parse_wall_clock(datestamp)? * 1000 + parse_fraction_and_offset(datestamp).map_or(0, |x| x.0))
+}
+
+// The start of the calendar aligned window (e.g. the hour of the day) of a time in the --display-tz
+pub fn window_start(seconds: i64, interval: i64) -> i64 {
+    seconds - (seconds + display_offset()).rem_euclid(interval)
+}
+
+// The hour of the day of a time in the --display-tz
+pub fn hour_of_day(seconds: i64) -> usize {
+    ((seconds + display_offset()).rem_euclid(86400) / 3600) as usize
+}
+
+// "2022-01-26 18:00:00" in the --display-tz
+pub fn format_wall_clock(seconds: i64) -> String {
+    format_utc(seconds + display_offset())
+}
+
+// "2022-01-26 18:00:00" in UTC
+pub fn format_utc(seconds: i64) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    let time = seconds.rem_euclid(86400);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, time / 3600, time % 3600 / 60, time % 60)
+}
+
+// Gives every line both an uptime and a wall clock time when it can be worked out, so the time based
+// sections work with -XX:+PrintGCTimeStamps, -XX:+PrintGCDateStamps or both (and the unified equivalents).
+// Lines with both anchor the JVM start time, which is kept up to date across restarts.
+#[derive(Default, Clone)]
+pub struct Clock {
+    // Wall clock seconds at uptime 0
+    start: Option<f64>,
+}
+
+impl Clock {
+    // Logs with datestamps only count their uptime from the first datestamp of the file
+    pub fn observe(&mut self, line: &str) -> (Option<f64>, Option<i64>) {
+        self.anchor(parse_uptime(line), parse_datestamp(line).and_then(parse_wall_clock))
+    }
+
+    // For logs whose timestamps aren't decorations, e.g. the attributes of OpenJ9's XML
+    pub fn anchor(&mut self, uptime: Option<f64>, wall_clock: Option<i64>) -> (Option<f64>, Option<i64>) {
+        match (uptime, wall_clock) {
+            (Some(uptime), Some(wall_clock)) => {
+                self.start = Some(wall_clock as f64 - uptime);
+                (Some(uptime), Some(wall_clock))
+            }
+            (Some(uptime), None) => (Some(uptime), self.start.map(|start| (start + uptime) as i64)),
+            (None, Some(wall_clock)) => {
+                let start = *self.start.get_or_insert(wall_clock as f64);
+                (Some(wall_clock as f64 - start), Some(wall_clock))
+            }
+            (None, None) => (None, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_uptime_and_datestamp_decorations() {
+        let jdk8 = "2022-01-26T18:54:10.491+0000: 1131.424: [GC pause (G1 Humongous Allocation) (young) (initial-mark), 0.0123456 secs]";
+        assert_eq!((parse_uptime(jdk8), parse_datestamp(jdk8)), (Some(1131.424), Some("2022-01-26T18:54:10.491+0000")));
+        let uptime_only = "1131.424: [GC pause (G1 Evacuation Pause) (young), 0.0123456 secs]";
+        assert_eq!((parse_uptime(uptime_only), parse_datestamp(uptime_only)), (Some(1131.424), None));
+        let unified = "[2022-01-26T18:54:10.491+0000][1131.424s][info][gc] GC(123) Pause Full (G1 Humongous Allocation) 7934M->3410M(8192M) 9876.543ms";
+        assert_eq!((parse_uptime(unified), parse_datestamp(unified)), (Some(1131.424), Some("2022-01-26T18:54:10.491+0000")));
+        assert_eq!(parse_uptime("[1131424ms][info][gc] Using G1"), Some(1131.424));
+        assert_eq!((parse_uptime("   [Eden: 16.0M(16.0M)->0.0B(16.0M)]"), parse_datestamp("   [Eden: 16.0M(16.0M)->0.0B(16.0M)]")), (None, None));
+    }
+
+    #[test]
+    fn reads_the_datestamps_in_utc() {
+        assert_eq!(parse_wall_clock("2022-01-26T18:54:10.491+0000"), Some(1643223250));
+        assert_eq!(parse_wall_clock("2022-01-26T19:54:10.491+0100"), Some(1643223250));
+        assert_eq!(parse_wall_clock_millis("2022-01-26T18:54:10.491Z"), Some(1643223250491));
+        assert_eq!(format_utc(1643223250), "2022-01-26 18:54:10");
+    }
+
+    #[test]
+    fn parses_the_display_offsets() {
+        assert_eq!(["UTC", "Z", "+05:30", "-0800", "+02"].map(|x| parse_offset(x).unwrap()), [0, 0, 19800, -28800, 7200]);
+        assert!(["+25", "0800", "+5", "+08:75"].iter().all(|x| parse_offset(x).is_err()));
+    }
+
+    #[test]
+    fn anchors_the_uptime_to_the_datestamps() {
+        let mut clock = Clock::default();
+        assert_eq!(clock.observe("2022-01-26T18:54:10.000+0000: 10.000: [GC pause (G1 Evacuation Pause) (young), 0.0123456 secs]"), (Some(10.0), Some(1643223250)));
+        assert_eq!(clock.observe("70.000: [GC pause (G1 Evacuation Pause) (young), 0.0123456 secs]"), (Some(70.0), Some(1643223310)));
+        // Logs with datestamps only count their uptime from the first one
+        let mut clock = Clock::default();
+        clock.observe("[2022-01-26T18:54:10.000+0000][info][gc] Using G1");
+        assert_eq!(clock.observe("[2022-01-26T18:55:10.000+0000][info][gc] GC(0) Pause Young (Normal) (G1 Evacuation Pause) 24M->4M(256M) 3.456ms"), (Some(60.0), Some(1643223310)));
+    }
+}
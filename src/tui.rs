@@ -0,0 +1,192 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{BarChart, Block, List, ListState, Paragraph, Sparkline};
+use ratatui::Frame;
+use std::io;
+
+use crate::buckets::{self, DEFAULT_REGION_SIZES};
+use crate::jvm_flags::parse_size;
+use crate::report::FileAllocations;
+use crate::HumongousAllocation;
+
+// "min=8m max=64m from=100 to=500", sizes in bytes (with an optional k/m/g suffix) and uptimes in seconds
+#[derive(Default)]
+struct Filter {
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    from: Option<f64>,
+    to: Option<f64>,
+}
+
+impl Filter {
+    fn parse(text: &str) -> Result<Filter, String> {
+        let mut filter = Filter::default();
+        for term in text.split_whitespace() {
+            let (key, value) = term.split_once('=').ok_or_else(|| format!("expected key=value, got {}", term))?;
+            let size = || parse_size(value).ok_or_else(|| format!("{} is not a valid size", value));
+            let uptime = || value.parse::<f64>().map_err(|_| format!("{} is not a valid uptime", value));
+            match key {
+                "min" => filter.min_size = Some(size()?),
+                "max" => filter.max_size = Some(size()?),
+                "from" => filter.from = Some(uptime()?),
+                "to" => filter.to = Some(uptime()?),
+                _ => return Err(format!("unknown filter {}, expected min, max, from or to", key)),
+            }
+        }
+        Ok(filter)
+    }
+
+    // Allocations without an uptime only pass when no time range is set
+    fn matches(&self, allocation: &HumongousAllocation) -> bool {
+        let in_time_range = match allocation.uptime {
+            Some(uptime) => self.from.is_none_or(|x| uptime >= x) && self.to.is_none_or(|x| uptime <= x),
+            None => self.from.is_none() && self.to.is_none(),
+        };
+        in_time_range && self.min_size.is_none_or(|x| allocation.bytes >= x) && self.max_size.is_none_or(|x| allocation.bytes <= x)
+    }
+}
+
+struct App {
+    files: Vec<FileAllocations>,
+    // Index 0 is all files together
+    selected: ListState,
+    filter: Filter,
+    filter_text: String,
+    editing: bool,
+    error: Option<String>,
+    interval: f64,
+}
+
+impl App {
+    fn allocations(&self) -> Vec<&HumongousAllocation> {
+        // Moving past the last file only gets clamped when the list is drawn
+        let files: Vec<&FileAllocations> = match self.selected.selected() {
+            Some(index) if index > 0 => self.files.get(index - 1).or(self.files.last()).into_iter().collect(),
+            _ => self.files.iter().collect(),
+        };
+        files.into_iter().flat_map(|x| &x.allocations).filter(|x| self.filter.matches(x)).collect()
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let allocations = self.allocations();
+        let [main, filter_area, status_area] = Layout::vertical([Constraint::Min(0), Constraint::Length(3), Constraint::Length(1)]).areas(frame.area());
+        let [files_area, charts] = Layout::horizontal([Constraint::Length(40), Constraint::Min(0)]).areas(main);
+        let [distribution_area, timeline_area] = Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(charts);
+
+        let mut region_buckets = buckets::build_buckets(&DEFAULT_REGION_SIZES);
+        for allocation in &allocations {
+            if let Some(index) = buckets::bucket_index(&region_buckets, allocation.bytes) {
+                region_buckets[index].num_allocations += 1;
+            }
+        }
+        let bars: Vec<(&str, u64)> = region_buckets.iter().map(|x| (x.region_size.as_str(), x.num_allocations as u64)).collect();
+        let distribution = BarChart::default()
+            .block(Block::bordered().title("Allocations by region size they stop being humongous at"))
+            .data(&bars)
+            .bar_width(8)
+            .bar_style(Style::new().fg(Color::Cyan));
+        frame.render_widget(distribution, distribution_area);
+
+        let uptimes: Vec<f64> = allocations.iter().filter_map(|x| x.uptime).collect();
+        let start = uptimes.iter().cloned().fold(f64::INFINITY, f64::min);
+        let end = uptimes.iter().cloned().fold(0.0, f64::max);
+        let mut windows = vec![0; if uptimes.is_empty() { 0 } else { ((end - start) / self.interval) as usize + 1 }];
+        for uptime in &uptimes {
+            windows[((uptime - start) / self.interval) as usize] += 1;
+        }
+        let title = if uptimes.is_empty() {
+            "Allocations over time".to_string()
+        } else {
+            format!("Allocations per {}s, {:.0}s to {:.0}s uptime", self.interval, start, end)
+        };
+        let timeline = Sparkline::default().block(Block::bordered().title(title)).data(&windows).style(Style::new().fg(Color::Yellow));
+        frame.render_widget(timeline, timeline_area);
+
+        let filter_title = match &self.error {
+            Some(error) => format!("Filter: {}", error),
+            None if self.editing => "Filter (Enter to apply, Esc to cancel)".to_string(),
+            None => "Filter (/ to edit), e.g. min=8m max=64m from=100 to=500".to_string(),
+        };
+        let filter_style = if self.editing { Style::new().fg(Color::Green) } else { Style::new() };
+        frame.render_widget(Paragraph::new(self.filter_text.as_str()).style(filter_style).block(Block::bordered().title(filter_title)), filter_area);
+
+        let mut sizes: Vec<u64> = allocations.iter().map(|x| x.bytes).collect();
+        sizes.sort_unstable();
+        let percentile = |p: f64| sizes.get(((sizes.len() as f64 * p / 100.0).ceil() as usize).saturating_sub(1)).copied().unwrap_or(0);
+        let status = format!(
+            " {} allocations, {} bytes | min {} p50 {} p99 {} max {} | q to quit, up/down to select a file",
+            sizes.len(),
+            sizes.iter().sum::<u64>(),
+            sizes.first().copied().unwrap_or(0),
+            percentile(50.0),
+            percentile(99.0),
+            sizes.last().copied().unwrap_or(0),
+        );
+        frame.render_widget(Paragraph::new(status), status_area);
+
+        let names = std::iter::once("All files".to_string()).chain(self.files.iter().map(|x| format!("{} ({})", x.name, x.allocations.len())));
+        let files = List::new(names)
+            .block(Block::bordered().title("Files"))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(files, files_area, &mut self.selected);
+    }
+
+    // Returns false once the user asked to quit
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.editing {
+            match code {
+                KeyCode::Enter => match Filter::parse(&self.filter_text) {
+                    Ok(filter) => {
+                        self.filter = filter;
+                        self.error = None;
+                        self.editing = false;
+                    }
+                    Err(e) => self.error = Some(e),
+                },
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.error = None;
+                }
+                KeyCode::Backspace => {
+                    self.filter_text.pop();
+                }
+                KeyCode::Char(c) => self.filter_text.push(c),
+                _ => {}
+            }
+            return true;
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Up => self.selected.select_previous(),
+            KeyCode::Down => self.selected.select_next(),
+            _ => {}
+        }
+        true
+    }
+}
+
+// Runs until the user quits, the terminal is restored even when drawing fails
+pub fn run(files: Vec<FileAllocations>, interval: f64) -> io::Result<()> {
+    let mut app = App {
+        files,
+        selected: ListState::default().with_selected(Some(0)),
+        filter: Filter::default(),
+        filter_text: String::new(),
+        editing: false,
+        error: None,
+        interval,
+    };
+    let mut terminal = ratatui::init();
+    let result = (|| loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
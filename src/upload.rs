@@ -0,0 +1,82 @@
+use flate2::read::GzDecoder;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::archive;
+use crate::report::Report;
+use crate::Cli;
+
+static UPLOADS: AtomicU64 = AtomicU64::new(0);
+
+// A path in the temporary directory that no other upload of this process uses
+pub fn temp_path() -> PathBuf {
+    env::temp_dir().join(format!("gc-ho-parser-{}-{}", process::id(), UPLOADS.fetch_add(1, Ordering::Relaxed)))
+}
+
+// A gc log received by one of the servers, saved to a temporary directory since a log is read more than once.
+// Gzipped logs are decompressed on the way, archives are kept as they are and expanded like on the command line.
+// The directory is removed again when the upload is dropped.
+pub struct Upload {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl Upload {
+    // Fails with ErrorKind::FileTooLarge when the upload, or what a gzipped one decompresses to, is larger than
+    // max_bytes
+    pub fn save(mut body: impl BufRead, name: &str, max_bytes: u64) -> io::Result<Upload> {
+        let dir = temp_path();
+        fs::create_dir_all(&dir)?;
+        // Only the file name is kept so the upload can't escape its directory
+        let name = Path::new(name).file_name().and_then(|x| x.to_str()).unwrap_or("gc.log");
+        let gzipped = body.fill_buf()?.starts_with(&[0x1f, 0x8b]) && !archive::is_archive(Path::new(name));
+        let upload = Upload { path: dir.join(if gzipped { name.strip_suffix(".gz").unwrap_or(name) } else { name }), dir };
+        let mut file = File::create(&upload.path)?;
+        let limit = max_bytes.saturating_add(1);
+        let written = if gzipped { io::copy(&mut GzDecoder::new(body).take(limit), &mut file)? } else { io::copy(&mut body.take(limit), &mut file)? };
+        if written > max_bytes {
+            return Err(io::Error::new(io::ErrorKind::FileTooLarge, format!("the log is larger than {} bytes", max_bytes)));
+        }
+        Ok(upload)
+    }
+
+    pub fn analyze(&self, args: &Cli) -> Vec<Report> {
+        crate::analyze(args, std::slice::from_ref(&self.path))
+    }
+
+    // Reports name the files by their temporary path
+    pub fn relabel(&self, text: &str) -> String {
+        text.replace(&format!("{}/", self.dir.display()), "")
+    }
+}
+
+impl Drop for Upload {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            log::warn!("Unable to remove {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn limits_the_decompressed_size() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[b'\n'; 4096]).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(Upload::save(&gzipped[..], "gc.log.gz", 1024).err().map(|x| x.kind()), Some(io::ErrorKind::FileTooLarge));
+        let upload = Upload::save(&gzipped[..], "gc.log.gz", 4096).unwrap();
+        assert_eq!(fs::metadata(&upload.path).unwrap().len(), 4096);
+        assert!(Upload::save(&[b'\n'; 2048][..], "gc.log", 1024).is_err());
+    }
+}
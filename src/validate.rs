@@ -0,0 +1,245 @@
+use cli_table::format::Justify;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::error::GcLogError;
+use crate::report::Record;
+use crate::shenandoah::ShenandoahAllocations;
+use crate::{anonymize, archive, cms, container, jfr, line_parsing, openj9, timestamp, zgc, zing, Cli, Collector};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    // -XX:+PrintGCDetails and friends
+    Jdk8,
+    // -Xlog, lines start with their decorations
+    Unified,
+    FlightRecording,
+    // OpenJ9's -Xverbosegclog
+    Xml,
+    // No timestamped line to tell by
+    Unknown,
+}
+
+impl LogFormat {
+    fn describe(&self) -> &'static str {
+        match self {
+            LogFormat::Jdk8 => "JDK 8",
+            LogFormat::Unified => "unified",
+            LogFormat::FlightRecording => "flight recording",
+            LogFormat::Xml => "OpenJ9 XML",
+            LogFormat::Unknown => "unknown",
+        }
+    }
+}
+
+// What a gc log holds of what the analysis needs, found without analyzing it
+pub struct Capabilities {
+    pub file: String,
+    pub format: LogFormat,
+    // The region size, None when the collector has no regions, or why it can't be found
+    pub region_size: Result<Option<String>, GcLogError>,
+    pub uptime: bool,
+    pub datestamp: bool,
+    pub allocations: bool,
+    // The file couldn't be read
+    pub error: Option<String>,
+}
+
+pub struct CapabilityRow {
+    pub file: String,
+    pub format: &'static str,
+    pub region_size: String,
+    pub timestamps: String,
+    pub allocations: &'static str,
+    pub status: &'static str,
+}
+
+impl Record for CapabilityRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("File", Justify::Left),
+        ("Format", Justify::Left),
+        ("Region Size", Justify::Left),
+        ("Timestamps", Justify::Left),
+        ("Humongous Allocations", Justify::Left),
+        ("Status", Justify::Left),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.file.clone(),
+            self.format.to_string(),
+            self.region_size.clone(),
+            self.timestamps.clone(),
+            self.allocations.to_string(),
+            self.status.to_string(),
+        ]
+    }
+}
+
+impl Capabilities {
+    // Whether the file can be analyzed at all, a log without allocations or timestamps still gives a report
+    pub fn is_usable(&self) -> bool {
+        self.error.is_none() && self.region_size.is_ok()
+    }
+
+    pub fn status(&self) -> &'static str {
+        match self.is_usable() {
+            false => "ERROR",
+            true if !self.allocations || !(self.uptime || self.datestamp) => "WARN",
+            true => "OK",
+        }
+    }
+
+    pub fn timestamps(&self) -> String {
+        match (self.uptime, self.datestamp) {
+            (true, true) => "uptime, datestamp".to_string(),
+            (true, false) => "uptime".to_string(),
+            (false, true) => "datestamp".to_string(),
+            (false, false) => "none".to_string(),
+        }
+    }
+
+    pub fn row(&self) -> CapabilityRow {
+        CapabilityRow {
+            file: self.file.clone(),
+            format: self.format.describe(),
+            region_size: match &self.region_size {
+                Ok(Some(region_size)) => region_size.clone(),
+                Ok(None) => "-".to_string(),
+                Err(_) => "unknown".to_string(),
+            },
+            timestamps: self.timestamps(),
+            allocations: if self.allocations { "found" } else { "none" },
+            status: self.status(),
+        }
+    }
+
+    // The JVM flags (or options of this tool) that would fill in what's missing
+    pub fn suggestions(&self, collector: Collector) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        if let Some(error) = &self.error {
+            suggestions.push(error.clone());
+            return suggestions;
+        }
+        let unified = self.format == LogFormat::Unified;
+        match &self.region_size {
+            Err(GcLogError::MissingFlags { .. } | GcLogError::UnknownRegionSize { .. }) if unified => {
+                suggestions.push("No region size, add -Xlog:gc+init=info (JDK 17+) or -Xlog:gc+heap=info (JDK 11) to log it at startup, or give --region-size".to_string());
+            }
+            Err(GcLogError::MissingFlags { .. } | GcLogError::UnknownRegionSize { .. }) => {
+                suggestions.push("No region size, keep the CommandLine flags line at the start of the log (a larger --header-lines when it follows a roll), add -XX:G1HeapRegionSize or give --region-size".to_string());
+            }
+            Err(e) => suggestions.push(e.to_string()),
+            Ok(_) => {}
+        }
+        if !self.allocations {
+            suggestions.push(match (collector, self.format) {
+                (_, LogFormat::FlightRecording) => "No allocations outside of a TLAB, record with the jdk.ObjectAllocationOutsideTLAB event enabled (e.g. settings=profile)",
+                (Collector::G1, LogFormat::Unified) => "No humongous allocation requests, add -Xlog:gc+ergo+ihop=debug",
+                (Collector::G1, _) => "No humongous allocation requests, add -XX:+PrintAdaptiveSizePolicy",
+                (Collector::Cms, _) => "No promotion failures with their sizes, add -XX:+PrintPromotionFailure",
+                (Collector::Zgc, _) => "No large page counts, add -Xlog:gc+reloc=info",
+                (Collector::Shenandoah, _) => "No failed allocations, add -Xlog:gc+ergo=info and -Xlog:gc+init=info",
+                (Collector::OpenJ9, LogFormat::Xml) => "No tenure allocation failures, none of the objects went to the tenure space directly",
+                (Collector::OpenJ9, _) => "No verbose:gc XML, log with -Xverbosegclog:gc.log",
+                (Collector::Zing, _) => "No large object allocations with their sizes, match the lines that log them with --pattern",
+            }.to_string());
+        }
+        // Every element of the XML has its timestamp
+        if !self.datestamp && !matches!(self.format, LogFormat::FlightRecording | LogFormat::Xml) {
+            suggestions.push(match (unified, self.uptime) {
+                (true, true) => "No datestamps for --interval and ordering files, add the time decoration, e.g. -Xlog:gc*:file=gc.log:time,uptime",
+                (true, false) => "No timestamps for the timeline, bursts and Full GC attribution, add the time and uptime decorations, e.g. -Xlog:gc*:file=gc.log:time,uptime",
+                (false, true) => "No datestamps for --interval and ordering files, add -XX:+PrintGCDateStamps",
+                (false, false) => "No timestamps for the timeline, bursts and Full GC attribution, add -XX:+PrintGCDateStamps -XX:+PrintGCTimeStamps",
+            }.to_string());
+        }
+        suggestions
+    }
+}
+
+// Reads the header for the region size, then the lines until an allocation and both kinds of timestamps
+// have been seen, which is the whole file only when something is missing
+pub fn check(file: &Path, args: &Cli) -> Capabilities {
+    let mut capabilities = Capabilities {
+        file: file.display().to_string(),
+        format: LogFormat::Unknown,
+        region_size: Ok(None),
+        uptime: false,
+        datestamp: false,
+        allocations: false,
+        error: None,
+    };
+    let mut summary = crate::FileSummary::new(file);
+    let region_size = crate::file_region_size(file, args, &mut summary);
+    let region_mb = region_size.as_ref().ok().and_then(|x| x.as_ref().map(|x| x.mb));
+    capabilities.region_size = region_size.map(|x| x.map(|x| x.describe()));
+    let (mut reader, _) = match archive::open(file) {
+        Ok(opened) => opened,
+        Err(e) => {
+            capabilities.error = Some(format!("Unable to read {}: {}", file.display(), e));
+            return capabilities;
+        }
+    };
+    if jfr::is_recording(&mut reader).unwrap_or(false) {
+        capabilities.format = LogFormat::FlightRecording;
+        if let Ok(recording) = crate::read_recording(file, reader) {
+            let region_size = region_mb.map_or(crate::buckets::MB, |x| x.max(1) as u64 * crate::buckets::MB);
+            capabilities.allocations = recording.allocations.iter().any(|x| x.1 * 2 >= region_size);
+            capabilities.uptime = recording.jvm_start.is_some();
+            capabilities.datestamp = true;
+        }
+        return capabilities;
+    }
+
+    let mut shenandoah = ShenandoahAllocations::default();
+    let mut openj9 = openj9::OpenJ9Allocations::default();
+    for line in line_parsing::lossy_lines(BufReader::new(reader)) {
+        let line = match line {
+            Ok((line, _)) => line,
+            Err(e) => {
+                capabilities.error = Some(format!("Unable to read {}: {}", file.display(), e));
+                break;
+            }
+        };
+        let line = container::strip(&line);
+        let uptime = timestamp::parse_uptime(&line).is_some();
+        let datestamp = timestamp::parse_datestamp(&line).is_some() || openj9::parse_wall_clock(&line).is_some();
+        if capabilities.format == LogFormat::Unknown && line.trim_start().starts_with("<verbosegc") {
+            capabilities.format = LogFormat::Xml;
+        }
+        if capabilities.format == LogFormat::Unknown && (uptime || datestamp) {
+            capabilities.format = if line.trim_start().starts_with('[') { LogFormat::Unified } else { LogFormat::Jdk8 };
+        }
+        capabilities.uptime |= uptime;
+        capabilities.datestamp |= datestamp;
+        capabilities.allocations |= match args.collector {
+            Collector::G1 => matches!(crate::parse_allocation(&line, &args.pattern), Some(Ok(_))),
+            Collector::Cms => cms::parse_cms_failure(&line).is_some_and(|x| !x.sizes.is_empty()),
+            Collector::Zgc => zgc::parse_page_count(&line).is_some_and(|x| x.page_type == zgc::LARGE && x.pages > 0),
+            Collector::Shenandoah => shenandoah.observe(&line).is_some(),
+            Collector::OpenJ9 => openj9.observe(&line).is_some(),
+            Collector::Zing => matches!(zing::parse_large_allocation(&line), Some(Some(_))) || matches!(crate::parse_allocation(&line, &args.pattern), Some(Ok(_))),
+        };
+        if capabilities.allocations && capabilities.uptime && capabilities.datestamp {
+            break;
+        }
+    }
+    // Shenandoah and OpenJ9's balanced policy log the region size at startup rather than in the flags
+    if let (Collector::Shenandoah | Collector::OpenJ9, Some(bytes)) = (args.collector, shenandoah.region_size().or(openj9.region_size())) {
+        capabilities.region_size = Ok(Some(format!("{}MB", bytes / crate::buckets::MB)));
+    }
+    capabilities
+}
+
+// --anonymize, the path also appears in the error messages
+pub fn anonymize(capabilities: &mut Capabilities) {
+    let label = anonymize::label(&capabilities.file);
+    if let Err(e) = &mut capabilities.region_size {
+        *e.path_mut() = label.clone().into();
+    }
+    if let Some(error) = &mut capabilities.error {
+        *error = error.replace(&capabilities.file, &label);
+    }
+    capabilities.file = label;
+}
@@ -0,0 +1,37 @@
+use crate::bins::display_size;
+use crate::numbers::grouped;
+
+// A humongous object occupies whole regions, the tail of its last region can't hold anything else until the object is reclaimed
+#[derive(Default)]
+pub struct HumongousWaste {
+    pub objects: u64,
+    pub allocated_bytes: u64,
+    pub wasted_bytes: u64,
+    // Regions the objects took, counted once per object even when it outlived several collections
+    pub regions: u64,
+}
+
+impl HumongousWaste {
+    pub fn observe(&mut self, bytes: u64, region_size: u64) {
+        self.objects += 1;
+        self.allocated_bytes += bytes;
+        self.wasted_bytes += bytes.div_ceil(region_size) * region_size - bytes;
+        self.regions += bytes.div_ceil(region_size);
+    }
+
+    // Share of the humongous regions' space left unused
+    pub fn percentage(&self) -> f64 {
+        let reserved = self.allocated_bytes + self.wasted_bytes;
+        if reserved == 0 { 0.0 } else { self.wasted_bytes as f64 * 100.0 / reserved as f64 }
+    }
+
+    pub fn stats(&self, raw_bytes: bool) -> Vec<(&'static str, String)> {
+        vec![
+            ("humongous objects", grouped(&self.objects)),
+            ("allocated bytes", display_size(self.allocated_bytes, raw_bytes)),
+            ("wasted bytes", display_size(self.wasted_bytes, raw_bytes)),
+            ("waste", format!("{:.2}%", self.percentage())),
+            ("avg wasted bytes per object", display_size(self.wasted_bytes / self.objects.max(1), raw_bytes)),
+        ]
+    }
+}
@@ -0,0 +1,75 @@
+// Only the stub at the bottom is left in the WebAssembly build
+#![cfg_attr(target_arch = "wasm32", allow(dead_code, unused_imports))]
+
+#[cfg(not(target_arch = "wasm32"))]
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+// A new file is only analyzed once its size stopped changing for this long, so copies have finished
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+// Dotfiles are usually partial uploads (rsync, scp) that get renamed once complete
+fn is_candidate(path: &Path) -> bool {
+    path.file_name().and_then(|x| x.to_str()).is_some_and(|x| !x.starts_with('.'))
+}
+
+// Calls `analyze` for every file created in (or moved into) the directory, once it is complete.
+// Files already in the directory are left alone, and each file is only analyzed once.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn watch_directory(dir: &Path, mut analyze: impl FnMut(&Path)) -> notify::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    log::info!("Watching {} for new gc logs", dir.display());
+
+    // Size and when it last changed of the files that are still being written
+    let mut pending: HashMap<PathBuf, (u64, Instant)> = HashMap::new();
+    let mut analyzed = HashSet::new();
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                for path in event.paths.into_iter().filter(|x| is_candidate(x) && !analyzed.contains(x)) {
+                    pending.entry(path).or_insert((0, Instant::now()));
+                }
+            }
+            Ok(Ok(_)) | Err(RecvTimeoutError::Timeout) => {}
+            Ok(Err(e)) => log::warn!("Error watching {}: {}", dir.display(), e),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        let now = Instant::now();
+        let mut ready = Vec::new();
+        pending.retain(|path, (size, changed)| match fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => {
+                if metadata.len() != *size {
+                    *size = metadata.len();
+                    *changed = now;
+                    true
+                } else if now.duration_since(*changed) >= SETTLE_TIME {
+                    ready.push(path.clone());
+                    false
+                } else {
+                    true
+                }
+            }
+            // Removed again, or a directory
+            _ => false,
+        });
+        ready.sort();
+        for path in ready {
+            analyze(&path);
+            analyzed.insert(path);
+        }
+    }
+}
+
+// There is nothing to watch without a filesystem
+#[cfg(target_arch = "wasm32")]
+pub fn watch_directory(_: &Path, _: impl FnMut(&Path)) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "watching is not available in the WebAssembly build"))
+}
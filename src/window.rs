@@ -0,0 +1,53 @@
+use crate::{timestamp, HumongousAllocation};
+
+// One end of a --compare-windows range, an uptime or a datestamp
+#[derive(Clone, Copy, Debug)]
+enum Bound {
+    Uptime(f64),
+    // The datestamp's time as written and its UTC offset, if any, as the --display-tz isn't known yet when the
+    // arguments are parsed
+    WallClock(i64, Option<i64>),
+}
+
+// "600", "13.175" and "10m" are uptimes, "2022-01-26T18:00:00" (or with a space) a datestamp
+fn parse_bound(text: &str) -> Result<Bound, String> {
+    match text.parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => return Ok(Bound::Uptime(seconds)),
+        _ => {}
+    }
+    if let Ok(seconds) = crate::parse_duration_arg(text) {
+        return Ok(Bound::Uptime(seconds as f64));
+    }
+    match text.len() >= 19 {
+        true => timestamp::parse_local(text).map(|(local, offset)| Bound::WallClock(local, offset)).ok_or_else(|| format!("{} is not a valid datestamp, expected e.g. 2022-01-26T18:00:00", text)),
+        false => Err(format!("{} is not a valid uptime or datestamp, expected e.g. 600, 10m or 2022-01-26T18:00:00", text)),
+    }
+}
+
+// A time range of the gc logs, open ended when a side is left out
+#[derive(Clone, Debug)]
+pub struct Window {
+    pub text: String,
+    start: Option<Bound>,
+    end: Option<Bound>,
+}
+
+// "FROM..TO", "FROM.." or "..TO"
+pub fn parse_window(text: &str) -> Result<Window, String> {
+    let (start, end) = text.split_once("..").ok_or_else(|| format!("{} is not a valid window, expected FROM..TO, e.g. 10m..1h", text))?;
+    let bound = |x: &str| (!x.trim().is_empty()).then(|| parse_bound(x.trim())).transpose();
+    Ok(Window { text: text.to_string(), start: bound(start)?, end: bound(end)? })
+}
+
+impl Window {
+    // From the start up to but excluding the end, allocations without the time a bound needs are left out
+    pub fn contains(&self, allocation: &HumongousAllocation) -> bool {
+        let compare = |bound: &Bound| match bound {
+            Bound::Uptime(seconds) => allocation.uptime.map(|x| x.partial_cmp(seconds)),
+            Bound::WallClock(local, offset) => allocation.wall_clock.map(|x| Some(x.cmp(&timestamp::to_utc(*local, *offset)))),
+        };
+        let after_start = self.start.as_ref().is_none_or(|x| compare(x).flatten().is_some_and(|x| x.is_ge()));
+        let before_end = self.end.as_ref().is_none_or(|x| compare(x).flatten().is_some_and(|x| x.is_lt()));
+        after_start && before_end
+    }
+}
@@ -0,0 +1,96 @@
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet, XlsxError};
+use std::path::Path;
+
+use crate::report::Report;
+use crate::timeseries;
+use crate::Cli;
+
+// A bold header row frozen above the data, with a Report column first when --group-by split the reports
+fn sheet<'a>(workbook: &'a mut Workbook, name: &str, grouped: bool, columns: &[&str]) -> Result<&'a mut Worksheet, XlsxError> {
+    let sheet = workbook.add_worksheet().set_name(name)?;
+    let bold = Format::new().set_bold();
+    let report = grouped.then_some("Report");
+    for (col, title) in report.iter().chain(columns).enumerate() {
+        sheet.write_with_format(0, col as u16, *title, &bold)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    Ok(sheet)
+}
+
+// Buckets, Percentiles, Files and Time Series sheets, sizes in bytes and percentages as fractions formatted as
+// percentages so they stay usable in formulas
+pub fn export(path: &Path, reports: &[Report], args: &Cli) -> Result<(), XlsxError> {
+    let grouped = reports.iter().any(|x| x.title.is_some());
+    let first = grouped as u16;
+    let percent = Format::new().set_num_format("0.00%");
+    let mut workbook = Workbook::new();
+
+    let buckets = sheet(&mut workbook, "Buckets", grouped, &["Region Size", "Max Allocation Size (50%)", "Allocations", "% of Allocations", "Cumulative %"])?;
+    let mut row = 1;
+    for report in reports {
+        let total: u32 = report.region_size_array.iter().map(|x| x.num_allocations).sum();
+        let mut cumulative = 0;
+        for bucket in &report.region_size_array {
+            cumulative += bucket.num_allocations;
+            if grouped {
+                buckets.write(row, 0, report.title.as_deref().unwrap_or_default())?;
+            }
+            buckets.write(row, first, &bucket.region_size)?;
+            buckets.write(row, first + 1, bucket.max_size)?;
+            buckets.write(row, first + 2, bucket.num_allocations)?;
+            buckets.write_with_format(row, first + 3, bucket.num_allocations as f64 / total.max(1) as f64, &percent)?;
+            buckets.write_with_format(row, first + 4, cumulative as f64 / total.max(1) as f64, &percent)?;
+            row += 1;
+        }
+    }
+    buckets.autofit();
+
+    let percentiles = sheet(&mut workbook, "Percentiles", grouped, &["Percentile", "Bytes"])?;
+    let mut row = 1;
+    for report in reports {
+        for (label, bytes) in report.percentiles(&args.percentiles) {
+            if grouped {
+                percentiles.write(row, 0, report.title.as_deref().unwrap_or_default())?;
+            }
+            percentiles.write(row, first, label)?;
+            percentiles.write(row, first + 1, bytes)?;
+            row += 1;
+        }
+    }
+    percentiles.autofit();
+
+    let files = sheet(&mut workbook, "Files", grouped, &["File", "Region Size (MB)", "Allocations", "Allocated Bytes", "Evacuation Failures", "Status", "Label"])?;
+    let mut row = 1;
+    for report in reports {
+        for summary in &report.file_summaries {
+            if grouped {
+                files.write(row, 0, report.title.as_deref().unwrap_or_default())?;
+            }
+            files.write(row, first, &summary.file)?;
+            if let Some(region_size) = &summary.region_size {
+                files.write(row, first + 1, region_size.mb)?;
+            }
+            files.write(row, first + 2, summary.allocations)?;
+            files.write(row, first + 3, summary.allocated_bytes)?;
+            files.write(row, first + 4, summary.evacuation_failures)?;
+            files.write(row, first + 5, summary.row().status)?;
+            if let Some(label) = &summary.label {
+                files.write(row, first + 6, label)?;
+            }
+            row += 1;
+        }
+    }
+    files.autofit();
+
+    // Across all reports like the timeseries export, the datestamps as Excel dates
+    let series = sheet(&mut workbook, "Time Series", false, &["Timestamp", "Allocations", "Bytes"])?;
+    let datetime = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+    for (row, (start, allocations, bytes)) in (1..).zip(timeseries::windows(reports, args.interval.unwrap_or(60))) {
+        series.write_datetime_with_format(row, 0, ExcelDateTime::from_timestamp(start)?, &datetime)?;
+        series.write(row, 1, allocations)?;
+        series.write(row, 2, bytes)?;
+    }
+    series.autofit();
+
+    workbook.save(path)
+}
@@ -0,0 +1,125 @@
+use cli_table::format::Justify;
+
+use crate::jvm_flags::parse_size;
+use crate::numbers::grouped;
+use crate::report::Record;
+
+const PAGE_TYPES: [&str; 3] = ["Small", "Medium", "Large"];
+// Large pages hold a single object each, anything above the medium page object size limit
+pub const LARGE: usize = 2;
+
+pub struct ZgcPageRow {
+    pub page_type: &'static str,
+    pub avg_pages: String,
+    pub max_pages: u64,
+    pub avg_size: String,
+    pub max_size: u64,
+}
+
+impl Record for ZgcPageRow {
+    const COLUMNS: &'static [(&'static str, Justify)] = &[
+        ("Page Type", Justify::Left),
+        ("Avg Pages", Justify::Right),
+        ("Max Pages", Justify::Right),
+        ("Avg Size (MB)", Justify::Right),
+        ("Max Size (MB)", Justify::Right),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.page_type.to_string(), self.avg_pages.clone(), grouped(&self.max_pages), self.avg_size.clone(), grouped(&self.max_size)]
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct PageStats {
+    collections: u64,
+    pages: u64,
+    max_pages: u64,
+    bytes: u64,
+    max_bytes: u64,
+}
+
+// ZGC's counterpart of humongous allocations: the per-collection page counts of each page type
+#[derive(Default)]
+pub struct ZgcPages {
+    stats: [PageStats; 3],
+}
+
+pub struct ZgcPageCount {
+    pub page_type: usize,
+    pub pages: u64,
+    pub bytes: u64,
+}
+
+// JDK 17+: "[info][gc,reloc] GC(3) Large Pages: 2 / 24M, Empty: 8M, Relocated: 0M, In-Place: 0"
+// JDK 11:  "[info][gc,reloc] GC(3) Large Pages: 2 / 24M(1%), Empty: 0M(0%), Compacting: 0M(0%)->0M(0%)"
+pub fn parse_page_count(line: &str) -> Option<ZgcPageCount> {
+    let (before, rest) = line.split_once(" Pages: ")?;
+    let page_type = PAGE_TYPES.iter().position(|x| before.ends_with(x))?;
+    let (pages, rest) = rest.split_once(" / ")?;
+    let size = rest.split([',', '(']).next()?;
+    Some(ZgcPageCount { page_type, pages: pages.trim().parse::<u64>().ok()?, bytes: parse_size(size)? })
+}
+
+impl ZgcPages {
+    pub fn record(&mut self, count: &ZgcPageCount) {
+        let stats = &mut self.stats[count.page_type];
+        stats.collections += 1;
+        stats.pages += count.pages;
+        stats.max_pages = stats.max_pages.max(count.pages);
+        stats.bytes += count.bytes;
+        stats.max_bytes = stats.max_bytes.max(count.bytes);
+    }
+
+    pub fn merge(&mut self, other: ZgcPages) {
+        for (stats, other) in self.stats.iter_mut().zip(other.stats) {
+            stats.collections += other.collections;
+            stats.pages += other.pages;
+            stats.max_pages = stats.max_pages.max(other.max_pages);
+            stats.bytes += other.bytes;
+            stats.max_bytes = stats.max_bytes.max(other.max_bytes);
+        }
+    }
+
+    pub fn collections(&self) -> u64 {
+        self.stats.iter().map(|x| x.collections).max().unwrap_or(0)
+    }
+
+    pub fn large_pages(&self) -> u64 {
+        self.stats[LARGE].pages
+    }
+
+    pub fn rows(&self) -> Vec<ZgcPageRow> {
+        PAGE_TYPES
+            .iter()
+            .zip(self.stats)
+            .filter(|(_, stats)| stats.collections > 0)
+            .map(|(page_type, stats)| ZgcPageRow {
+                page_type,
+                avg_pages: format!("{:.1}", stats.pages as f64 / stats.collections as f64),
+                max_pages: stats.max_pages,
+                avg_size: format!("{:.1}", stats.bytes as f64 / stats.collections as f64 / 1024.0 / 1024.0),
+                max_size: stats.max_bytes / 1024 / 1024,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buckets::MB;
+
+    fn parsed(line: &str) -> Option<(usize, u64, u64)> {
+        parse_page_count(line).map(|x| (x.page_type, x.pages, x.bytes))
+    }
+
+    #[test]
+    fn parses_the_page_counts() {
+        assert_eq!(parsed("[2024-03-01T10:15:00.123+0000][12.345s][info][gc,reloc] GC(3) Large Pages: 2 / 24M, Empty: 8M, Relocated: 0M, In-Place: 0"), Some((LARGE, 2, 24 * MB)));
+        assert_eq!(parsed("[12.345s][info][gc,reloc] GC(3) Small Pages: 1289 / 2578M, Empty: 1024M, Relocated: 12M, In-Place: 0"), Some((0, 1289, 2578 * MB)));
+        assert_eq!(parsed("[12.345s][info][gc,reloc] GC(3) Large Pages: 2 / 24M(1%), Empty: 0M(0%), Compacting: 0M(0%)->0M(0%)"), Some((LARGE, 2, 24 * MB)));
+        assert_eq!(parsed("[12.345s][info][gc,reloc] GC(3) Medium Pages: 3 / 96M(2%), Empty: 32M(1%), Compacting: 0M(0%)->0M(0%)"), Some((1, 3, 96 * MB)));
+        assert_eq!(parsed("[12.345s][info][gc,heap] GC(3) Large Pages: unknown"), None);
+    }
+}
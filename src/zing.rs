@@ -0,0 +1,102 @@
+use crate::jvm_flags::parse_size;
+use crate::numbers::grouped;
+
+// What the lines name a large object allocation by, lowercased. C4 in Zing / Prime has no regions, objects
+// above the large object threshold get a multi-page block of their own in the large object space.
+const LARGE_ALLOCATION: [&str; 3] = ["large object allocation", "large allocation", "allocating large object"];
+
+// Zing / Prime's counterpart of humongous allocations: the allocations into the large object space that its
+// log gives the size of
+#[derive(Default)]
+pub struct ZingAllocations {
+    pub large_allocations: u64,
+    pub largest: u64,
+    // Large object allocation lines whose size couldn't be read
+    pub without_size: u64,
+}
+
+// The first size after the words, "16777232 bytes", "16777232B", "16384K" or "16M", or "size: 16M" and
+// "(16M)" as a field
+fn size_after(rest: &str) -> Option<u64> {
+    let mut words = rest.split(|x: char| x.is_whitespace() || matches!(x, ',' | ':' | '=' | '(' | ')' | '[' | ']')).filter(|x| !x.is_empty()).peekable();
+    while let Some(word) = words.next() {
+        if !word.starts_with(|x: char| x.is_ascii_digit()) {
+            continue;
+        }
+        if words.peek().is_some_and(|x| x.eq_ignore_ascii_case("bytes")) {
+            return word.parse().ok();
+        }
+        return parse_size(word.strip_suffix(['B', 'b']).unwrap_or(word));
+    }
+    None
+}
+
+// The size of a large object allocation, e.g.
+// "[12.345s][info][gc,alloc] Large object allocation: 16777232 bytes" or "Allocating large object of 16M"
+pub fn parse_large_allocation(line: &str) -> Option<Option<u64>> {
+    let lowercase = line.to_ascii_lowercase();
+    let end = LARGE_ALLOCATION.iter().find_map(|x| lowercase.find(x).map(|start| start + x.len()))?;
+    Some(size_after(&line[end..]))
+}
+
+impl ZingAllocations {
+    // Returns the size of a large object allocation
+    pub fn observe(&mut self, line: &str) -> Option<u64> {
+        match parse_large_allocation(line)? {
+            Some(bytes) => {
+                self.large_allocations += 1;
+                self.largest = self.largest.max(bytes);
+                Some(bytes)
+            }
+            None => {
+                self.without_size += 1;
+                None
+            }
+        }
+    }
+
+    // Folds in the allocations of the lines that follow
+    pub fn merge(&mut self, other: ZingAllocations) {
+        self.large_allocations += other.large_allocations;
+        self.largest = self.largest.max(other.largest);
+        self.without_size += other.without_size;
+    }
+
+    pub fn stats(&self) -> Vec<(&'static str, String)> {
+        let mut stats = vec![("large object allocations", grouped(&self.large_allocations))];
+        if self.large_allocations > 0 {
+            stats.push(("largest", format!("{}K", grouped(&(self.largest / 1024)))));
+        }
+        if self.without_size > 0 {
+            stats.push(("without a size", grouped(&self.without_size)));
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_large_object_allocations() {
+        let lines = [
+            ("[12.345s][info][gc,alloc] Large object allocation: 16777232 bytes", 16777232),
+            ("[12.345s][info][gc,alloc] Large object allocation: 16777232B", 16777232),
+            ("2024-03-01T10:15:00.123+0000: 12.345: Allocating large object of 16M", 16 * 1024 * 1024),
+            ("[12.345s][info][gc] GC(12) Large allocation (size: 16384K) stalled", 16 * 1024 * 1024),
+        ];
+        for (line, bytes) in lines {
+            assert_eq!(parse_large_allocation(line), Some(Some(bytes)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn counts_allocations_without_a_size() {
+        let mut allocations = ZingAllocations::default();
+        assert_eq!(allocations.observe("[12.345s][info][gc,alloc] Large object allocation failed"), None);
+        assert_eq!(allocations.observe("[12.345s][info][gc] GC(12) New: 1024M->512M"), None);
+        assert_eq!(allocations.observe("[12.345s][info][gc,alloc] Large object allocation: 4194304 bytes"), Some(4194304));
+        assert_eq!((allocations.large_allocations, allocations.largest, allocations.without_size), (1, 4194304, 1));
+    }
+}
@@ -0,0 +1,8 @@
+use wasm_bindgen::prelude::*;
+
+// Analyzes a gc log and returns the json report, e.g. analyze("gc.log", text, ["--buckets", "1m,2m,4m"]).
+// Name is only used to label the report, options are the flags of the command line tool.
+#[wasm_bindgen]
+pub fn analyze(name: &str, text: &str, options: Vec<String>) -> Result<String, JsValue> {
+    rs_gc_ho_allocation_parser::analyze_text(name, text, &options).map_err(|e| JsValue::from_str(&e))
+}